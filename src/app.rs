@@ -1,9 +1,15 @@
-use clap::{App, Arg};
+use clap::{App, AppSettings, Arg, SubCommand};
 
 pub fn build() -> App<'static, 'static> {
     let app = App::new("lsd")
         .version(crate_version!())
         .about(crate_description!())
+        // The files named help are listed as the others, and the ones named like a command are
+        // not taken for a mistyped one: as the paths are taken first, there are no external
+        // commands.
+        .setting(AppSettings::DisableHelpSubcommand)
+        .setting(AppSettings::AllowExternalSubcommands)
+        .setting(AppSettings::VersionlessSubcommands)
         .arg(Arg::with_name("FILE").multiple(true).default_value("."))
         .arg(
             Arg::with_name("all")
@@ -72,6 +78,14 @@ pub fn build() -> App<'static, 'static> {
                 .long("ignore-config")
                 .help("Ignore the configuration file"),
         )
+        .arg(
+            Arg::with_name("config-file")
+                .long("config-file")
+                .value_name("path")
+                .takes_value(true)
+                .conflicts_with("ignore-config")
+                .help("Use the configuration file at path instead of searching for one"),
+        )
         .arg(
             Arg::with_name("oneline")
                 .short("1")
@@ -108,6 +122,12 @@ pub fn build() -> App<'static, 'static> {
                 .value_name("num")
                 .help("Stop recursing into directories after reaching specified depth"),
         )
+        .arg(
+            Arg::with_name("yes")
+                .long("yes")
+                .multiple(true)
+                .help("Recurse from the root of a filesystem without asking first"),
+        )
         .arg(
             Arg::with_name("directory-only")
                 .short("d")
@@ -135,6 +155,146 @@ pub fn build() -> App<'static, 'static> {
                 .multiple(true)
                 .help("Display the total size of directories"),
         )
+        .arg(
+            Arg::with_name("total-size-symlinks")
+                .long("total-size-symlinks")
+                .possible_value("link")
+                .possible_value("follow")
+                .possible_value("skip")
+                .multiple(true)
+                .number_of_values(1)
+                .help("With --total-size, count the symlinks found in the directories by the size of the link, of the file it references, or not at all"),
+        )
+        .arg(
+            Arg::with_name("count-links")
+                .long("count-links")
+                .multiple(true)
+                .help("With --total-size, count the hard linked files each time they are found, instead of once"),
+        )
+        .arg(
+            Arg::with_name("csv")
+                .long("csv")
+                .multiple(true)
+                .conflicts_with("tsv")
+                .help("Print one comma-separated record per file instead of the layout"),
+        )
+        .arg(
+            Arg::with_name("tsv")
+                .long("tsv")
+                .multiple(true)
+                .help("Print one tab-separated record per file instead of the layout"),
+        )
+        .arg(
+            Arg::with_name("watch")
+                .long("watch")
+                .multiple(true)
+                .help("List the files again each time they change, clearing the screen first"),
+        )
+        .arg(
+            Arg::with_name("info")
+                .long("info")
+                .multiple(true)
+                .conflicts_with("watch")
+                .help("Print a panel detailing the metadata, the dates, the git status, the extended attributes and the symlink chain of the single file given, instead of listing it"),
+        )
+        .arg(
+            Arg::with_name("checksum")
+                .long("checksum")
+                .multiple(true)
+                .requires("info")
+                .help("With --info, compute the SHA-256 checksum of the file"),
+        )
+        .arg(
+            Arg::with_name("debug-readdir")
+                .long("debug-readdir")
+                .multiple(true)
+                .conflicts_with_all(&["info", "watch"])
+                .help("Print the entries of the directories in the order the system reads them, with the type it reports, their inode and whether their metadata can be read, instead of listing them"),
+        )
+        .arg(
+            Arg::with_name("paginate")
+                .long("paginate")
+                .multiple(true)
+                .help("Pipe the output into the pager set in the PAGER environment variable, less -RFX by default, when it is a terminal"),
+        )
+        .arg(
+            Arg::with_name("raw")
+                .long("raw")
+                .multiple(true)
+                .help("Print the names as they are, without escaping their control characters nor the bytes which are not valid UTF-8"),
+        )
+        .arg(
+            Arg::with_name("summary")
+                .long("summary")
+                .multiple(true)
+                .help("Print a footer counting the files of each type and their total size after each directory, or after the whole tree"),
+        )
+        .arg(
+            Arg::with_name("names-only")
+                .long("names-only")
+                .multiple(true)
+                .help("Print the unstyled names only, without reading the metadata of the files"),
+        )
+        .arg(
+            Arg::with_name("header")
+                .long("header")
+                .multiple(true)
+                .help("Print a header row labelling the columns of the long format"),
+        )
+        .arg(
+            Arg::with_name("filesystem")
+                .long("filesystem")
+                .multiple(true)
+                .help("Show the filesystem block in the long format, marking the mount points"),
+        )
+        .arg(
+            Arg::with_name("count")
+                .long("count")
+                .takes_value(true)
+                .require_equals(true)
+                .min_values(0)
+                .multiple(true)
+                .possible_value("total")
+                .possible_value("type")
+                .value_name("by")
+                .help("Print the number of entries listed for each path instead of the listing, or one number per file type with --count=type"),
+        )
+        .arg(
+            Arg::with_name("keep-duplicates")
+                .long("keep-duplicates")
+                .multiple(true)
+                .help("List the paths as given, even the duplicated ones and the ones inside another listed directory"),
+        )
+        .arg(
+            Arg::with_name("glob")
+                .long("glob")
+                .multiple(true)
+                .help("Expand the glob patterns of the paths, as '*.rs' or 'src/**/*.toml', instead of relying on the shell"),
+        )
+        .arg(
+            Arg::with_name("no-glob-error")
+                .long("no-glob-error")
+                .multiple(true)
+                .help("With --glob, ignore the patterns matching no file instead of reporting them"),
+        )
+        .arg(
+            Arg::with_name("group-devices")
+                .long("group-devices")
+                .multiple(true)
+                .help("Group the devices of the listed directories by their subsystem, as tty or sd, under a header counting them"),
+        )
+        .arg(
+            Arg::with_name("elide-prefix")
+                .long("elide-prefix")
+                .multiple(true)
+                .help("Replace the listed path with … in the folder paths of the recursive listing, once it has been shown in full"),
+        )
+        .arg(
+            Arg::with_name("include-snapshots")
+                .long("include-snapshots")
+                .multiple(true)
+                .help("Recurse into filesystem snapshot directories (.snapshot, .zfs/snapshot)"),
+        )
         .arg(
             Arg::with_name("date")
                 .long("date")
@@ -142,7 +302,17 @@ pub fn build() -> App<'static, 'static> {
                 .default_value("date")
                 .multiple(true)
                 .number_of_values(1)
-                .help("How to display date [possible values: date, relative, +date-time-format]"),
+                .help("How to display date [possible values: date, relative, smart, +date-time-format]"),
+        )
+        .arg(
+            Arg::with_name("date-timezone")
+                .long("date-timezone")
+                .validator(validate_date_timezone_argument)
+                .allow_hyphen_values(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("zone")
+                .help("In which time zone to display the dates [possible values: local, utc, +hh:mm, -hh:mm]"),
         )
         .arg(
             Arg::with_name("timesort")
@@ -197,8 +367,13 @@ pub fn build() -> App<'static, 'static> {
                     "time",
                     "version",
                     "extension",
+                    "created",
+                    "inode",
+                    "links",
                     #[cfg(feature = "git")]
                         "git",
+                    #[cfg(feature = "git")]
+                        "repo",
                 ])
                 .takes_value(true)
                 .value_name("WORD")
@@ -215,6 +390,49 @@ pub fn build() -> App<'static, 'static> {
                 .multiple(true)
                 .help("Reverse the order of the sort"),
         )
+        .arg(
+            Arg::with_name("sort-case")
+                .long("sort-case")
+                .possible_value("sensitive")
+                .possible_value("insensitive")
+                .possible_value("smart")
+                .multiple(true)
+                .number_of_values(1)
+                .help("How the case is considered when sorting by name, smart ignoring it unless the names only differ by their case"),
+        )
+        .arg(
+            Arg::with_name("ignore-case")
+                .long("ignore-case")
+                .multiple(true)
+                .conflicts_with("sort-case")
+                .help("Ignore the case when sorting by name, as --sort-case insensitive"),
+        )
+        .arg(
+            Arg::with_name("sort-cmd")
+                .long("sort-cmd")
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("cmd")
+                .help("Sort by the keys the shell command prints for the paths it reads on its standard input, one per line"),
+        )
+        .arg(
+            Arg::with_name("path-order")
+                .long("path-order")
+                .possible_value("sorted")
+                .possible_value("given")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Sort the paths given as arguments as the entries of the directories, or list them in the order they are given"),
+        )
+        .arg(
+            Arg::with_name("collation")
+                .long("collation")
+                .possible_value("bytes")
+                .possible_value("locale")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Sort the names by the code points of their characters, or with the collation rules of the locale (LC_COLLATE)"),
+        )
         .arg(
             Arg::with_name("group-dirs")
                 .long("group-dirs")
@@ -241,8 +459,15 @@ pub fn build() -> App<'static, 'static> {
                     "name",
                     "inode",
                     "links",
+                    "security",
+                    "filesystem",
+                    "kind",
+                    "allocated",
+                    "created",
                     #[cfg(feature = "git")]
                         "git",
+                    #[cfg(feature = "git")]
+                        "repo",
                 ])
                 .help("Specify the blocks that will be displayed and in what order"),
         )
@@ -251,6 +476,38 @@ pub fn build() -> App<'static, 'static> {
                 .long("classic")
                 .help("Enable classic mode (no colors or icons)"),
         )
+        .arg(
+            Arg::with_name("no-user")
+                .long("no-user")
+                .multiple(true)
+                .help("Do not display the user block"),
+        )
+        .arg(
+            Arg::with_name("group")
+                .long("group")
+                .multiple(true)
+                .overrides_with("no-group")
+                .help("Display the group block in the long format, after the user block"),
+        )
+        .arg(
+            Arg::with_name("no-group")
+                .long("no-group")
+                .multiple(true)
+                .overrides_with("group")
+                .help("Do not display the group block"),
+        )
+        .arg(
+            Arg::with_name("no-permissions")
+                .long("no-permissions")
+                .multiple(true)
+                .help("Do not display the permissions block"),
+        )
+        .arg(
+            Arg::with_name("no-time")
+                .long("no-time")
+                .multiple(true)
+                .help("Do not display the date block"),
+        )
         .arg(
             Arg::with_name("no-symlink")
                 .long("no-symlink")
@@ -267,6 +524,28 @@ pub fn build() -> App<'static, 'static> {
                 .default_value("")
                 .help("Do not display files/directories with names matching the glob pattern(s). More than one can be specified by repeating the argument"),
         )
+        .arg(
+            Arg::with_name("exclude-from")
+                .long("exclude-from")
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("file")
+                .help("Do not display files/directories matching the gitignore style patterns read from file (- for the standard input). More than one can be specified by repeating the argument"),
+        )
+        .arg(
+            Arg::with_name("find")
+                .long("find")
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("regex")
+                .help("Only display the files/directories whose names match the regular expression within the listed directories, keeping the directories holding them in the tree and the recursive listings"),
+        )
+        .arg(
+            Arg::with_name("ignore-files")
+                .long("ignore-files")
+                .multiple(true)
+                .help("Do not display files/directories matching the gitignore style patterns of the .ignore and .lsdignore files of the listed directories and of their sub directories"),
+        )
         .arg(
             Arg::with_name("inode")
                 .short("i")
@@ -274,12 +553,200 @@ pub fn build() -> App<'static, 'static> {
                 .multiple(true)
                 .help("Display the index number of each file"),
         )
+        .arg(
+            Arg::with_name("numeric-uid-gid")
+                .short("n")
+                .long("numeric-uid-gid")
+                .multiple(true)
+                .help("Display the numeric user and group ids instead of their names"),
+        )
+        .arg(
+            Arg::with_name("verbose-permissions")
+                .long("verbose-permissions")
+                .multiple(true)
+                .help("Spell out the permissions of the owner, the group and the others after their symbolic notation"),
+        )
         .arg(
             Arg::with_name("dereference")
                 .short("L")
                 .long("dereference")
                 .multiple(true)
                 .help("When showing file information for a symbolic link, show information for the file the link references rather than for the link itself"),
+        )
+        .arg(
+            Arg::with_name("follow-symlinks")
+                .long("follow-symlinks")
+                .multiple(true)
+                .help("Descend into the symbolic links to directories when recursing, reporting the links leading back to a parent directory"),
+        )
+        .arg(
+            Arg::with_name("max-symlink-depth")
+                .long("max-symlink-depth")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validate_number_argument)
+                .value_name("num")
+                .help("Stop resolving a chain of symbolic links after following the specified number of links"),
+        )
+        .arg(
+            Arg::with_name("max-memory")
+                .long("max-memory")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validate_number_argument)
+                .value_name("MB")
+                .help("When collecting the files metadata uses more than the specified megabytes, list the remaining files unsorted instead"),
+        )
+        .arg(
+            Arg::with_name("max-entries")
+                .long("max-entries")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validate_number_argument)
+                .value_name("num")
+                .help("Do not list the directories having more than the specified entries, printing an error instead"),
+        )
+        .arg(
+            Arg::with_name("truncate-names")
+                .long("truncate-names")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validate_number_argument)
+                .value_name("num")
+                .help("Shorten the names longer than the specified columns with an ellipsis in the grid layout"),
+        )
+        .arg(
+            Arg::with_name("truncate-middle")
+                .long("truncate-middle")
+                .multiple(true)
+                .help("Shorten the names in the middle, keeping their extension, rather than at their end. Without --truncate-names, only the names too long for the grid layout to show two columns are shortened"),
+        )
+        .arg(
+            Arg::with_name("columns")
+                .long("columns")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validate_columns_argument)
+                .value_name("num")
+                .help("Display the grid layout in exactly the specified columns, whatever the width of the terminal is"),
+        )
+        .arg(
+            Arg::with_name("tree-limit")
+                .long("tree-limit")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validate_tree_limit_argument)
+                .value_name("num")
+                .help("Show at most the specified number of entries of each directory in the tree layout, counting the other ones on a last line"),
+        )
+        .arg(
+            Arg::with_name("fd")
+                .long("fd")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validate_fd_argument)
+                .value_name("num")
+                .help("List the directory open as the specified file descriptor, inherited from the parent process, looking up its files from it"),
+        )
+        .arg(
+            Arg::with_name("generate-completions")
+                .long("generate-completions")
+                .possible_values(&clap::Shell::variants())
+                .takes_value(true)
+                .value_name("shell")
+                .help("Print the completion script for the specified shell and exit"),
+        )
+        .arg(
+            Arg::with_name("init")
+                .long("init")
+                .possible_values(&["bash", "zsh", "fish"])
+                .takes_value(true)
+                .value_name("shell")
+                .help("Print the shell code listing each directory entered, to be evaluated at the shell startup, and exit"),
+        )
+        .arg(
+            Arg::with_name("time-style")
+                .long("time-style")
+                .validator(validate_time_style_argument)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("style")
+                .help("How to display date, as ls does [possible values: full-iso, long-iso, iso, locale, +date-time-format]"),
+        )
+        // The following ls arguments are accepted to ease using lsd as an alias of ls, but they
+        // are not supported, see the strictness configuration.
+        .arg(
+            Arg::with_name("full-time")
+                .long("full-time")
+                .multiple(true)
+                .hidden(true),
+        )
+        .arg(
+            Arg::with_name("by-columns")
+                .short("C")
+                .multiple(true)
+                .hidden(true),
+        )
+        .arg(
+            Arg::with_name("across")
+                .short("x")
+                .multiple(true)
+                .hidden(true),
+        )
+        .arg(
+            Arg::with_name("escape")
+                .short("b")
+                .long("escape")
+                .multiple(true)
+                .hidden(true),
+        )
+        .arg(
+            Arg::with_name("hide-control-chars")
+                .short("q")
+                .long("hide-control-chars")
+                .multiple(true)
+                .hidden(true),
+        )
+        .arg(
+            Arg::with_name("literal")
+                .short("N")
+                .long("literal")
+                .multiple(true)
+                .hidden(true),
+        )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about("List the entries of two directories, marking the ones only in one of them, the changed and the identical ones. The options are passed before diff, and a file named diff is listed as ./diff or after --")
+                .arg(
+                    Arg::with_name("DIR1")
+                        .required(true)
+                        .help("The first directory to compare"),
+                )
+                .arg(
+                    Arg::with_name("DIR2")
+                        .required(true)
+                        .help("The second directory to compare"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("stats")
+                .about("Count the files of the directories and their total size by extension or by top level directory, the largest first. The options are passed before stats, and a file named stats is listed as ./stats or after --")
+                .arg(
+                    Arg::with_name("by")
+                        .long("by")
+                        .possible_value("extension")
+                        .possible_value("directory")
+                        .default_value("extension")
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("How to group the files"),
+                )
+                .arg(
+                    Arg::with_name("DIR")
+                        .multiple(true)
+                        .default_value(".")
+                        .help("A directory to count the files of"),
+                ),
         );
     if cfg!(feature = "git") {
         app.arg(
@@ -288,6 +755,33 @@ pub fn build() -> App<'static, 'static> {
                 .multiple(true)
                 .help("Show git status on file and directory")
         )
+        .arg(
+            Arg::with_name("git-renames")
+                .long("git-renames")
+                .multiple(true)
+                .help("Print the previous name of the files renamed in the git index"),
+        )
+        .arg(
+            Arg::with_name("git-status-style")
+                .long("git-status-style")
+                .possible_value("two-char")
+                .possible_value("single")
+                .multiple(true)
+                .number_of_values(1)
+                .help("Show the index and workdir git statuses, or only the most severe one"),
+        )
+        .arg(
+            Arg::with_name("git-untracked-count")
+                .long("git-untracked-count")
+                .multiple(true)
+                .help("Count the untracked files of the directories in the git block"),
+        )
+        .arg(
+            Arg::with_name("git-change-counts")
+                .long("git-change-counts")
+                .multiple(true)
+                .help("Count the files of the directories with staged and unstaged changes in the git block"),
+        )
     } else {
         app
     }
@@ -296,11 +790,105 @@ pub fn build() -> App<'static, 'static> {
 fn validate_date_argument(arg: String) -> Result<(), String> {
     if arg.starts_with('+') {
         validate_time_format(&arg)
-    } else if &arg == "date" || &arg == "relative" {
+    } else if &arg == "date" || &arg == "relative" || &arg == "smart" {
         Result::Ok(())
     } else {
-        Result::Err("possible values: date, relative, +date-time-format".to_owned())
+        Result::Err("possible values: date, relative, smart, +date-time-format".to_owned())
+    }
+}
+
+pub fn validate_date_timezone_argument(arg: String) -> Result<(), String> {
+    if arg == "local"
+        || arg.eq_ignore_ascii_case("utc")
+        || parse_utc_offset(&arg).is_some()
+        || is_timezone_name(&arg)
+    {
+        Ok(())
+    } else if cfg!(feature = "tz") {
+        Err("possible values: local, utc, an offset from UTC as +hh:mm, or a time zone name as Europe/Paris".to_owned())
+    } else {
+        Err("possible values: local, utc, or an offset from UTC as +hh:mm".to_owned())
+    }
+}
+
+/// Whether `name` is a time zone of the tz database, such as "Europe/Paris".
+#[cfg(feature = "tz")]
+pub fn is_timezone_name(name: &str) -> bool {
+    name.parse::<chrono_tz::Tz>().is_ok()
+}
+
+#[cfg(not(feature = "tz"))]
+pub fn is_timezone_name(_name: &str) -> bool {
+    false
+}
+
+fn validate_time_style_argument(arg: String) -> Result<(), String> {
+    let style = arg.trim_start_matches("posix-");
+    if style.starts_with('+') {
+        validate_time_format(style)
+    } else if ["full-iso", "long-iso", "iso", "locale"].contains(&style) {
+        Result::Ok(())
+    } else {
+        Result::Err(
+            "possible values: full-iso, long-iso, iso, locale, +date-time-format".to_owned(),
+        )
+    }
+}
+
+fn validate_number_argument(arg: String) -> Result<(), String> {
+    match arg.parse::<usize>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err("a valid positive number is required".to_owned()),
+    }
+}
+
+fn validate_columns_argument(arg: String) -> Result<(), String> {
+    match arg.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(()),
+        _ => Err("a number of columns of at least 1 is required".to_owned()),
+    }
+}
+
+fn validate_fd_argument(arg: String) -> Result<(), String> {
+    match arg.parse::<i32>() {
+        Ok(fd) if fd >= 0 => Ok(()),
+        _ => Err("a file descriptor number is required".to_owned()),
+    }
+}
+
+fn validate_tree_limit_argument(arg: String) -> Result<(), String> {
+    match arg.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(()),
+        _ => Err("a number of entries of at least 1 is required".to_owned()),
+    }
+}
+
+/// Parses an offset from UTC such as "+02", "-0530" or "+05:30", in seconds east of it.
+pub fn parse_utc_offset(offset: &str) -> Option<i32> {
+    let sign = match offset.chars().next() {
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return None,
+    };
+    let offset = &offset[1..];
+    if !offset.is_ascii() {
+        return None;
+    }
+    let (hours, minutes) = match offset.len() {
+        2 => (offset, "00"),
+        4 => (&offset[..2], &offset[2..]),
+        5 if &offset[2..3] == ":" => (&offset[..2], &offset[3..]),
+        _ => return None,
+    };
+    let digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if !digits(hours) || !digits(minutes) {
+        return None;
+    }
+    let (hours, minutes) = (hours.parse::<i32>().ok()?, minutes.parse::<i32>().ok()?);
+    if hours > 23 || minutes > 59 {
+        return None;
     }
+    Some(sign * (hours * 3600 + minutes * 60))
 }
 
 pub fn validate_time_format(formatter: &str) -> Result<(), String> {
@@ -0,0 +1,64 @@
+//! The SHA-256 checksum of the files, computed for the detail panel of `--info` when asked for.
+
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// The hexadecimal SHA-256 digest of the content of the file at `path`, read by blocks.
+pub fn sha256(path: &Path) -> io::Result<String> {
+    digest(File::open(path)?)
+}
+
+/// The hexadecimal SHA-256 digest of everything read from `reader`.
+fn digest<R: Read>(mut reader: R) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => hasher.update(&buffer[..read]),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{digest, sha256};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_digest() {
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            digest(&b""[..]).unwrap()
+        );
+        assert_eq!(
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            digest(&b"abc"[..]).unwrap()
+        );
+        assert_eq!(
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            digest(&b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"[..]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sha256_large_file() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("file");
+        fs::write(&path, vec![b'a'; 1_000_000]).unwrap();
+        assert_eq!(
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0",
+            sha256(&path).unwrap()
+        );
+    }
+}
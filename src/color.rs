@@ -1,7 +1,13 @@
+use crate::flags::color::{
+    Background, BlockColors, ColorValue, SetidBackgrounds, SparseBackground,
+};
+use crate::flags::Block;
+use crate::meta::name::find_by_suffix;
 use ansi_term::{ANSIString, Colour, Style};
 use lscolors::{Indicator, LsColors};
 use std::collections::HashMap;
 use std::path::Path;
+use std::rc::Rc;
 
 #[allow(dead_code)]
 #[derive(Hash, Debug, Eq, PartialEq, Clone)]
@@ -14,25 +20,41 @@ pub enum Elem {
     SymLink,
     BrokenSymLink,
     Dir {
-        uid: bool,
+        setgid: bool,
     },
+    /// Directories with the sticky bit, writable by the others or both
+    StickyDir,
+    OtherWritableDir,
+    StickyOtherWritableDir,
     Pipe,
     BlockDevice,
     CharDevice,
     Socket,
     Special,
 
+    /// Backgrounds of the setuid files and the setgid directories
+    FileSetuid,
+    DirSetgid,
+
+    /// Background of the size of the sparse files
+    SparseFile,
+
     /// Permissions
     Read,
     Write,
     Exec,
     ExecSticky,
+    ExecDenied,
+    Capability,
+    /// The `+` of the files with an extended access control list.
+    Acl,
     NoAccess,
 
     /// Last Time Modified
     DayOld,
     HourOld,
     Older,
+    VeryOld,
 
     /// User / Group Name
     User,
@@ -40,10 +62,17 @@ pub enum Elem {
 
     /// File Size
     NonFile,
+    FileHuge,
     FileLarge,
     FileMedium,
     FileSmall,
 
+    /// Space allocated on the disk
+    AllocatedHuge,
+    AllocatedLarge,
+    AllocatedMedium,
+    AllocatedSmall,
+
     /// INode
     INode {
         valid: bool,
@@ -53,6 +82,45 @@ pub enum Elem {
         valid: bool,
     },
 
+    /// Filesystem type and mount points
+    Filesystem,
+    MountPoint,
+
+    /// Kind of file
+    Kind,
+
+    /// Column headers
+    Header,
+
+    /// Separators between the blocks and background of the shaded rows
+    TableSeparator,
+    RowShade,
+
+    /// Tree guides
+    TreeEdge,
+
+    /// Marks of the entries of the compared directories
+    DiffRemoved,
+    DiffAdded,
+    DiffChanged,
+    DiffSame,
+
+    /// Names of the entries which changed in the watch mode
+    WatchCreated,
+    WatchModified,
+    WatchDeleted,
+
+    /// Previous name of a file renamed in git
+    GitRenamedFrom,
+
+    /// Reason the content of a directory could not be read
+    ReadError,
+
+    /// Branch and state of a git repository listed as a directory
+    GitRepoBranch,
+    GitRepoClean,
+    GitRepoDirty,
+
     #[cfg(feature = "git")]
     GitStatus {
         status: crate::git::GitStatus
@@ -60,24 +128,68 @@ pub enum Elem {
 }
 
 impl Elem {
-    pub fn has_suid(&self) -> bool {
-        matches!(self, Elem::Dir { uid: true } | Elem::File { uid: true, .. })
+    /// The element of the background highlighting a setuid file or a setgid directory.
+    fn setid_background(&self) -> Option<Elem> {
+        match self {
+            Elem::File { uid: true, .. } => Some(Elem::FileSetuid),
+            Elem::Dir { setgid: true } => Some(Elem::DirSetgid),
+            _ => None,
+        }
     }
 }
 
 pub type ColoredString<'a> = ANSIString<'a>;
 
 #[allow(dead_code)]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Theme {
     NoColor,
     Default,
     NoLscolors,
+    /// The [Default](Theme::Default) theme, its colors turned to the 16 basic ones.
+    Colors16,
+    /// The [Default](Theme::Default) theme, its colors turned to the 8 basic ones.
+    Colors8,
+}
+
+impl Theme {
+    /// The [Default](Theme::Default) theme, or the one of the few colors the terminal named by
+    /// `term` shows: 8 for the Linux and BSD consoles and the terminals named after them, 16 for
+    /// the `-16color` ones.
+    pub fn for_term(term: Option<&str>) -> Self {
+        match term {
+            Some(term) if term.ends_with("-16color") => Self::Colors16,
+            Some("linux") | Some("cons25") | Some("ansi") | Some("xterm-color") => Self::Colors8,
+            Some(term) if term.ends_with("-8color") => Self::Colors8,
+            _ => Self::Default,
+        }
+    }
+}
+
+/// The styles `dircolors` gives to the archives, images, videos and audio files, for the
+/// extensions missing from the default database of `lscolors`.
+const EXTRA_EXTENSION_STYLES: &str = "*.apk=01;31:*.dmg=01;31:*.avif=01;35:*.heic=01;35:\
+                                      *.heif=01;35:*.ico=01;35:*.jxl=01;35:*.psd=01;35:\
+                                      *.webp=01;35:*.aiff=00;36:*.ape=00;36:*.wma=00;36";
+
+thread_local! {
+    /// The styles of `LS_COLORS`, only parsed once however many `Colors` are set up. Without it,
+    /// the files are still colored by their extension, as by `dircolors`.
+    static ENV_LSCOLORS: Rc<LsColors> = Rc::new(
+        LsColors::from_env().unwrap_or_else(|| LsColors::from_string(EXTRA_EXTENSION_STYLES)),
+    );
 }
 
 pub struct Colors {
     colors: Option<HashMap<Elem, Colour>>,
-    lscolors: Option<LsColors>,
+    lscolors: Option<Rc<LsColors>>,
+    tree_edges: Vec<Colour>,
+    colors_by_extension: HashMap<String, Colour>,
+    /// The blocks painted in a single colour, or unstyled when [None].
+    block_colours: HashMap<Block, Option<Colour>>,
+    /// Whether the terminal shows the true colors, which are otherwise turned to the closest
+    /// 256-color palette ones.
+    truecolor: bool,
 }
 
 impl Colors {
@@ -86,26 +198,216 @@ impl Colors {
             Theme::NoColor => None,
             Theme::Default => Some(Self::get_light_theme_colour_map()),
             Theme::NoLscolors => Some(Self::get_light_theme_colour_map()),
+            Theme::Colors16 => Some(Self::get_basic_colour_map(16)),
+            Theme::Colors8 => Some(Self::get_basic_colour_map(8)),
         };
-        let lscolors = match theme {
-            Theme::NoColor => None,
-            Theme::Default => Some(LsColors::from_env().unwrap_or_default()),
-            Theme::NoLscolors => None,
+
+        let colors = Self {
+            colors,
+            lscolors: None,
+            tree_edges: Vec::new(),
+            colors_by_extension: HashMap::new(),
+            block_colours: HashMap::new(),
+            truecolor: matches!(
+                std::env::var("COLORTERM").as_deref(),
+                Ok("truecolor") | Ok("24bit")
+            ),
+        };
+
+        match theme {
+            Theme::Default | Theme::Colors16 | Theme::Colors8 => {
+                colors.with_lscolors(ENV_LSCOLORS.with(Rc::clone))
+            }
+            _ => colors,
+        }
+    }
+
+    /// Styles the file names with `lscolors` rather than with the theme, as `LS_COLORS` does for
+    /// the [Default](Theme::Default) theme. The styles may be shared by several `Colors`, as they
+    /// are only read.
+    pub fn with_lscolors(mut self, lscolors: impl Into<Rc<LsColors>>) -> Self {
+        self.lscolors = Some(lscolors.into());
+        self
+    }
+
+    /// Colors the tree guides with the `palette`, a color per depth level, instead of the
+    /// [Elem::TreeEdge] one. The palette is reused from its start for the deeper levels.
+    pub fn with_tree_edges(mut self, palette: &[ColorValue]) -> Self {
+        self.tree_edges = palette.iter().map(|&color| self.colour_of(color)).collect();
+        self
+    }
+
+    /// Colors the files with the colors configured for their extension in `extensions`. They take
+    /// precedence over the `LS_COLORS` styles, and over the default ones used when it is unset.
+    pub fn with_extensions(mut self, extensions: &crate::flags::Extensions) -> Self {
+        self.colors_by_extension = extensions
+            .colors()
+            .map(|(ext, color)| (ext.to_string(), self.colour_of(color)))
+            .collect();
+        self
+    }
+
+    /// Highlights the setuid files and the setgid directories with the configured `backgrounds`
+    /// instead of the ones of the theme, [Background::None] turning them off.
+    pub fn with_setid_backgrounds(mut self, backgrounds: &SetidBackgrounds) -> Self {
+        if let Some(ref mut colors) = self.colors {
+            let configured = [
+                (Elem::FileSetuid, backgrounds.file_setuid),
+                (Elem::DirSetgid, backgrounds.dir_setgid),
+            ];
+            for (elem, background) in configured.iter() {
+                match background {
+                    Some(Background::Color(color)) => {
+                        colors.insert(elem.clone(), colour_of(*color, self.truecolor));
+                    }
+                    Some(Background::None) => {
+                        colors.remove(elem);
+                    }
+                    None => {}
+                }
+            }
+        }
+        self
+    }
+
+    /// Highlights the size of the sparse files with the configured `background` instead of the
+    /// one of the theme, [Background::None] turning it off.
+    pub fn with_sparse_background(mut self, background: &SparseBackground) -> Self {
+        if let Some(ref mut colors) = self.colors {
+            match background.0 {
+                Some(Background::Color(color)) => {
+                    colors.insert(Elem::SparseFile, colour_of(color, self.truecolor));
+                }
+                Some(Background::None) => {
+                    colors.remove(&Elem::SparseFile);
+                }
+                None => {}
+            }
+        }
+        self
+    }
+
+    /// Paints the blocks with the configured `block_colors` rather than with the colors of their
+    /// elements, [Background::None] leaving them unstyled.
+    pub fn with_block_colors(mut self, block_colors: &BlockColors) -> Self {
+        self.block_colours = block_colors
+            .0
+            .iter()
+            .map(|(block, color)| {
+                let colour = match color {
+                    Background::None => None,
+                    Background::Color(color) => Some(self.colour_of(*color)),
+                };
+                (*block, colour)
+            })
+            .collect();
+        self
+    }
+
+    /// The colour a configured `color` is shown with, see [colour_of].
+    fn colour_of(&self, color: ColorValue) -> Colour {
+        colour_of(color, self.truecolor)
+    }
+
+    /// Colors the tree guide `edge` drawn for an entry at `depth`, starting from 1.
+    pub fn colorize_tree_edge(&self, edge: &str, depth: usize) -> ColoredString<'static> {
+        if self.colors.is_some() && !self.tree_edges.is_empty() {
+            let colour = self.tree_edges[(depth - 1) % self.tree_edges.len()];
+            colour.paint(edge.to_string())
+        } else {
+            self.colorize(edge.to_string(), &Elem::TreeEdge)
+        }
+    }
+
+    /// Puts the already colored `line`, followed by `padding` spaces, on the [Elem::RowShade]
+    /// background, reapplying it after each reset of the style.
+    pub fn shade_row(&self, line: &str, padding: usize) -> String {
+        let colour = match self
+            .colors
+            .as_ref()
+            .and_then(|colors| colors.get(&Elem::RowShade))
+        {
+            Some(colour) => *colour,
+            None => return line.to_string(),
         };
+        let background = Style::new().on(colour);
+        let prefix = background.prefix().to_string();
+        let reset = background.suffix().to_string();
 
-        Self { colors, lscolors }
+        format!(
+            "{}{}{}{}",
+            prefix,
+            line.replace(&reset, &format!("{}{}", reset, prefix)),
+            " ".repeat(padding),
+            reset
+        )
+    }
+
+    /// Repaints the `rendered` block with the colour configured for the `block`, if any, the
+    /// styles of its elements being dropped.
+    pub fn colorize_block<'a>(
+        &self,
+        block: &Block,
+        rendered: ColoredString<'a>,
+    ) -> ColoredString<'a> {
+        match self.block_colours.get(block) {
+            Some(colour) if self.colors.is_some() => {
+                let text = strip_styles(&rendered.to_string());
+                match colour {
+                    Some(colour) => colour.paint(text),
+                    None => ColoredString::from(text),
+                }
+            }
+            _ => rendered,
+        }
+    }
+
+    /// Repaints the `rendered` name of an entry as the `elem`, the styles of its parts being
+    /// dropped.
+    pub fn colorize_name<'a>(&self, rendered: ColoredString<'a>, elem: &Elem) -> ColoredString<'a> {
+        if self.colors.is_some() {
+            self.colorize(strip_styles(&rendered.to_string()), elem)
+        } else {
+            rendered
+        }
+    }
+
+    /// Whether nothing is colored, so the styled strings can be skipped altogether.
+    pub fn is_plain(&self) -> bool {
+        self.colors.is_none() && self.lscolors.is_none()
     }
 
     pub fn colorize<'a>(&self, input: String, elem: &Elem) -> ColoredString<'a> {
         self.style(elem).paint(input)
     }
 
+    /// Colorizes `input` as `elem`, on the background of the sparse files if there is one.
+    pub fn colorize_sparse<'a>(&self, input: String, elem: &Elem) -> ColoredString<'a> {
+        let style = self.style(elem);
+        match self
+            .colors
+            .as_ref()
+            .and_then(|colors| colors.get(&Elem::SparseFile))
+        {
+            Some(background) => style.on(*background).paint(input),
+            None => style.paint(input),
+        }
+    }
+
     pub fn colorize_using_path<'a>(
         &self,
         input: String,
         path: &Path,
         elem: &Elem,
     ) -> ColoredString<'a> {
+        if let Some(style) = self.style_from_dir_permissions(elem) {
+            return style.paint(input);
+        }
+
+        if let Some(colour) = self.colour_from_extension(path, elem) {
+            return colour.paint(input);
+        }
+
         let style_from_path = self.style_from_path(path);
         match style_from_path {
             Some(style_from_path) => style_from_path.paint(input),
@@ -122,6 +424,34 @@ impl Colors {
         }
     }
 
+    /// The `LS_COLORS` style of the sticky, other-writable and setgid directories, which the
+    /// `LS_COLORS` lookup by path colors as any directory. When it has no entry for them, the
+    /// directory style applies as with `ls`.
+    fn style_from_dir_permissions(&self, elem: &Elem) -> Option<Style> {
+        let lscolors = self.lscolors.as_ref()?;
+        match elem {
+            Elem::StickyDir
+            | Elem::OtherWritableDir
+            | Elem::StickyOtherWritableDir
+            | Elem::Dir { setgid: true } => {
+                let indicator = self.get_indicator_from_elem(elem)?;
+                lscolors
+                    .style_for_indicator(indicator)
+                    .map(lscolors::Style::to_ansi_term_style)
+            }
+            _ => None,
+        }
+    }
+
+    /// The colour configured for the longest extension of a file, see [find_by_suffix].
+    fn colour_from_extension(&self, path: &Path, elem: &Elem) -> Option<Colour> {
+        if self.colors.is_none() || !matches!(elem, Elem::File { .. }) {
+            return None;
+        }
+        let file_name = path.file_name()?.to_str()?;
+        find_by_suffix(file_name, &self.colors_by_extension).copied()
+    }
+
     fn style(&self, elem: &Elem) -> Style {
         match &self.lscolors {
             Some(lscolors) => match self.get_indicator_from_elem(elem) {
@@ -140,8 +470,14 @@ impl Colors {
     fn style_default(&self, elem: &Elem) -> Style {
         if let Some(ref colors) = self.colors {
             let style_fg = Style::default().fg(colors[elem]);
-            if elem.has_suid() {
-                style_fg.on(Colour::Fixed(124)) // Red3
+            if let Some(background) = elem.setid_background().and_then(|bg| colors.get(&bg)) {
+                style_fg.on(*background)
+            } else if *elem == Elem::Header {
+                style_fg.underline()
+            } else if *elem == Elem::GitRenamedFrom {
+                style_fg.dimmed()
+            } else if *elem == Elem::WatchDeleted {
+                style_fg.strikethrough()
             } else {
                 style_fg
             }
@@ -157,19 +493,23 @@ impl Colors {
                 (true, false) => Some("ex"),
                 (false, false) => Some("fi"),
             },
-            Elem::Dir { uid } => {
-                if *uid {
-                    None
+            Elem::Dir { setgid } => {
+                if *setgid {
+                    Some("sg")
                 } else {
                     Some("di")
                 }
             }
+            Elem::StickyDir => Some("st"),
+            Elem::OtherWritableDir => Some("ow"),
+            Elem::StickyOtherWritableDir => Some("tw"),
             Elem::SymLink => Some("ln"),
             Elem::Pipe => Some("pi"),
             Elem::Socket => Some("so"),
             Elem::BlockDevice => Some("bd"),
             Elem::CharDevice => Some("cd"),
             Elem::BrokenSymLink => Some("or"),
+            Elem::Capability => Some("ca"),
             Elem::INode { valid } => match valid {
                 true => Some("so"),
                 false => Some("no"),
@@ -187,6 +527,20 @@ impl Colors {
         }
     }
 
+    /// The light theme with its colors turned to the closest of the `count` basic ones, 8 or 16,
+    /// which all the terminals show. The rows are not shaded with 8 colors, as the only dark one
+    /// is the black of the background.
+    fn get_basic_colour_map(count: u8) -> HashMap<Elem, Colour> {
+        let mut m: HashMap<Elem, Colour> = Self::get_light_theme_colour_map()
+            .into_iter()
+            .map(|(elem, colour)| (elem, basic_colour(colour, count)))
+            .collect();
+        if count == 8 {
+            m.remove(&Elem::RowShade);
+        }
+        m
+    }
+
     // You can find the table for each color, code, and display at:
     //
     //https://jonasjacek.github.io/colors/
@@ -201,6 +555,9 @@ impl Colors {
         m.insert(Elem::Write, Colour::Yellow);
         m.insert(Elem::Exec, Colour::Red);
         m.insert(Elem::ExecSticky, Colour::Purple);
+        m.insert(Elem::ExecDenied, Colour::Fixed(196)); // Red1
+        m.insert(Elem::Capability, Colour::Fixed(208)); // DarkOrange
+        m.insert(Elem::Acl, Colour::Fixed(75)); // SteelBlue1
         m.insert(Elem::NoAccess, Colour::Fixed(245)); // Grey
 
         // File Types
@@ -232,26 +589,40 @@ impl Colors {
             },
             Colour::Fixed(40),
         ); // Green3
-        m.insert(Elem::Dir { uid: true }, Colour::Fixed(33)); // DodgerBlue1
-        m.insert(Elem::Dir { uid: false }, Colour::Fixed(33)); // DodgerBlue1
+        m.insert(Elem::Dir { setgid: true }, Colour::Fixed(33)); // DodgerBlue1
+        m.insert(Elem::Dir { setgid: false }, Colour::Fixed(33)); // DodgerBlue1
+        m.insert(Elem::StickyDir, Colour::Fixed(75)); // SteelBlue1
+        m.insert(Elem::OtherWritableDir, Colour::Fixed(34)); // Green3
+        m.insert(Elem::StickyOtherWritableDir, Colour::Fixed(35)); // SpringGreen3
         m.insert(Elem::Pipe, Colour::Fixed(44)); // DarkTurquoise
         m.insert(Elem::SymLink, Colour::Fixed(44)); // DarkTurquoise
         m.insert(Elem::BrokenSymLink, Colour::Fixed(124)); // Red3
+        m.insert(Elem::ReadError, Colour::Fixed(196)); // Red1
         m.insert(Elem::BlockDevice, Colour::Fixed(44)); // DarkTurquoise
         m.insert(Elem::CharDevice, Colour::Fixed(172)); // Orange3
         m.insert(Elem::Socket, Colour::Fixed(44)); // DarkTurquoise
         m.insert(Elem::Special, Colour::Fixed(44)); // DarkTurquoise
+        m.insert(Elem::FileSetuid, Colour::Fixed(124)); // Red3
+        m.insert(Elem::SparseFile, Colour::Fixed(24)); // DeepSkyBlue4
 
         // Last Time Modified
         m.insert(Elem::HourOld, Colour::Fixed(40)); // Green3
         m.insert(Elem::DayOld, Colour::Fixed(42)); // SpringGreen2
         m.insert(Elem::Older, Colour::Fixed(36)); // DarkCyan
+        m.insert(Elem::VeryOld, Colour::Fixed(23)); // DeepSkyBlue4
 
-        // Last Time Modified
+        // File Size
         m.insert(Elem::NonFile, Colour::Fixed(245)); // Grey
         m.insert(Elem::FileSmall, Colour::Fixed(229)); // Wheat1
         m.insert(Elem::FileMedium, Colour::Fixed(216)); // LightSalmon1
         m.insert(Elem::FileLarge, Colour::Fixed(172)); // Orange3
+        m.insert(Elem::FileHuge, Colour::Fixed(196)); // Red1
+
+        // Allocated size
+        m.insert(Elem::AllocatedSmall, Colour::Fixed(189)); // LightSteelBlue1
+        m.insert(Elem::AllocatedMedium, Colour::Fixed(147)); // LightSteelBlue
+        m.insert(Elem::AllocatedLarge, Colour::Fixed(105)); // LightSlateBlue
+        m.insert(Elem::AllocatedHuge, Colour::Fixed(129)); // Purple1
 
         // INode
         m.insert(Elem::INode { valid: true }, Colour::Fixed(13)); // Pink
@@ -259,7 +630,39 @@ impl Colors {
         m.insert(Elem::Links { valid: true }, Colour::Fixed(13));
         m.insert(Elem::Links { valid: false }, Colour::Fixed(245));
 
+        // Filesystem
+        m.insert(Elem::Filesystem, Colour::Fixed(110)); // LightSkyBlue3
+        m.insert(Elem::MountPoint, Colour::Fixed(33)); // DodgerBlue1
+
+        // Kind
+        m.insert(Elem::Kind, Colour::Fixed(152)); // LightCyan3
+
+        // Header
+        m.insert(Elem::Header, Colour::Fixed(250)); // Grey74
+
+        // Table
+        m.insert(Elem::TableSeparator, Colour::Fixed(245)); // Grey
+        m.insert(Elem::RowShade, Colour::Fixed(236)); // Grey19
+
+        // Tree guides
+        m.insert(Elem::TreeEdge, Colour::Fixed(245)); // Grey
+
+        // Diff marks
+        m.insert(Elem::DiffRemoved, Colour::Fixed(160)); // Red3
+        m.insert(Elem::DiffAdded, Colour::Fixed(40)); // Green3
+        m.insert(Elem::DiffChanged, Colour::Fixed(172)); // Orange3
+        m.insert(Elem::DiffSame, Colour::Fixed(245)); // Grey
+
+        // Watch
+        m.insert(Elem::WatchCreated, Colour::Fixed(40)); // Green3
+        m.insert(Elem::WatchModified, Colour::Fixed(184)); // Yellow3
+        m.insert(Elem::WatchDeleted, Colour::Fixed(245)); // Grey
+
         // GitStatus
+        m.insert(Elem::GitRenamedFrom, Colour::Fixed(245)); // Grey
+        m.insert(Elem::GitRepoBranch, Colour::Fixed(109)); // LightSkyBlue4
+        m.insert(Elem::GitRepoClean, Colour::Fixed(40)); // Green3
+        m.insert(Elem::GitRepoDirty, Colour::Fixed(208)); // DarkOrange
         #[cfg(feature = "git")]
             {
                 m.insert(Elem::GitStatus { status: crate::git::GitStatus::Default }, Colour::White);
@@ -277,3 +680,220 @@ impl Colors {
         m
     }
 }
+
+/// Removes the escape sequences styling `styled`, keeping its text.
+fn strip_styles(styled: &str) -> String {
+    let mut text = String::with_capacity(styled.len());
+    let mut chars = styled.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // The control sequences end with a letter, as the `m` of `ESC[1;31m`.
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            text.push(c);
+        }
+    }
+    text
+}
+
+/// The colour a configured `color` is shown with: its true color when the terminal shows them, as
+/// told by `truecolor`, or else the closest color of the 256-color palette.
+fn colour_of(color: ColorValue, truecolor: bool) -> Colour {
+    match color {
+        ColorValue::Fixed(n) => Colour::Fixed(n),
+        ColorValue::Rgb(r, g, b) if truecolor => Colour::RGB(r, g, b),
+        ColorValue::Rgb(r, g, b) => Colour::Fixed(closest_fixed(r, g, b)),
+    }
+}
+
+/// The closest of the `count` basic colors, 8 or 16, to `colour`: the named colors for the first
+/// 8 and the first 16 colors of the palette for the bright ones.
+fn basic_colour(colour: Colour, count: u8) -> Colour {
+    const NAMED: [Colour; 8] = [
+        Colour::Black,
+        Colour::Red,
+        Colour::Green,
+        Colour::Yellow,
+        Colour::Blue,
+        Colour::Purple,
+        Colour::Cyan,
+        Colour::White,
+    ];
+    let (r, g, b) = match colour {
+        // The dark grey is turned to the white rather than to the black of the background.
+        Colour::Fixed(8) if count == 8 => return Colour::White,
+        Colour::Fixed(n) if n < 8 => return NAMED[n as usize],
+        Colour::Fixed(n) if n < 16 && count == 8 => return NAMED[n as usize - 8],
+        Colour::Fixed(n) if n < 16 => return colour,
+        Colour::Fixed(n) => fixed_rgb(n),
+        Colour::RGB(r, g, b) => (r, g, b),
+        named => return named,
+    };
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let bright = count == 16 && max > 200;
+    let index = if max - min < 40 {
+        // The greys, from the dark ones to the white.
+        match max {
+            200..=255 if count == 16 => 15,
+            140..=255 => 7,
+            _ if count == 16 => 8,
+            _ => 7,
+        }
+    } else {
+        // The channels well above the weakest one give the hue.
+        let threshold = u32::from(min) + (u32::from(max - min) * 3 / 5);
+        let on = |channel: u8| (u32::from(channel) > threshold) as u8;
+        let index = on(r) | on(g) << 1 | on(b) << 2;
+        if bright {
+            index + 8
+        } else {
+            index
+        }
+    };
+    if index < 8 {
+        NAMED[index as usize]
+    } else {
+        Colour::Fixed(index)
+    }
+}
+
+/// The red, green and blue of the color `n` of the 6x6x6 cube or of the grey ramp of the
+/// 256-color palette.
+fn fixed_rgb(n: u8) -> (u8, u8, u8) {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    if n >= 232 {
+        let grey = 8 + 10 * (n - 232);
+        (grey, grey, grey)
+    } else {
+        let n = (n - 16) as usize;
+        (LEVELS[n / 36], LEVELS[n / 6 % 6], LEVELS[n % 6])
+    }
+}
+
+/// The closest color to `r`, `g` and `b` among the 6x6x6 cube and the grey ramp of the 256-color
+/// palette, the first 16 colors being left out as the terminals change them.
+fn closest_fixed(r: u8, g: u8, b: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let closest_level = |value: u8| {
+        (0..LEVELS.len())
+            .min_by_key(|&idx| (i32::from(LEVELS[idx]) - i32::from(value)).abs())
+            .unwrap_or(0)
+    };
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+        d(r, r2) + d(g, g2) + d(b, b2)
+    };
+
+    let (ri, gi, bi) = (closest_level(r), closest_level(g), closest_level(b));
+    let cube = (16 + 36 * ri + 6 * gi + bi) as u8;
+    let cube_distance = distance((LEVELS[ri], LEVELS[gi], LEVELS[bi]));
+
+    // The grey ramp goes from 8 to 238 by steps of 10.
+    let average = (u32::from(r) + u32::from(g) + u32::from(b)) / 3;
+    let grey_idx = (average.saturating_sub(3) / 10).min(23) as u8;
+    let grey = 8 + 10 * grey_idx;
+    let grey_distance = distance((grey, grey, grey));
+
+    if grey_distance < cube_distance {
+        232 + grey_idx
+    } else {
+        cube
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{basic_colour, closest_fixed, colour_of, strip_styles};
+    use super::{Colors, Elem, Theme};
+    use crate::flags::color::ColorValue;
+    use ansi_term::Colour;
+
+    #[test]
+    fn test_closest_fixed() {
+        assert_eq!(16, closest_fixed(0, 0, 0));
+        assert_eq!(231, closest_fixed(255, 255, 255));
+        assert_eq!(208, closest_fixed(255, 135, 0));
+        assert_eq!(196, closest_fixed(250, 10, 5));
+        assert_eq!(244, closest_fixed(128, 128, 128));
+    }
+
+    #[test]
+    fn test_basic_colour() {
+        // DodgerBlue1, DarkTurquoise, Orange3, Grey and Grey74.
+        assert_eq!(Colour::Fixed(12), basic_colour(Colour::Fixed(33), 16));
+        assert_eq!(Colour::Blue, basic_colour(Colour::Fixed(33), 8));
+        assert_eq!(Colour::Fixed(14), basic_colour(Colour::Fixed(44), 16));
+        assert_eq!(Colour::Yellow, basic_colour(Colour::Fixed(172), 8));
+        assert_eq!(Colour::Fixed(8), basic_colour(Colour::Fixed(245), 16));
+        assert_eq!(Colour::White, basic_colour(Colour::Fixed(245), 8));
+        assert_eq!(Colour::White, basic_colour(Colour::Fixed(250), 16));
+        assert_eq!(Colour::Purple, basic_colour(Colour::Fixed(13), 8));
+        assert_eq!(Colour::Red, basic_colour(Colour::Red, 8));
+    }
+
+    #[test]
+    fn test_basic_themes() {
+        assert_eq!(Theme::Colors8, Theme::for_term(Some("linux")));
+        assert_eq!(Theme::Colors16, Theme::for_term(Some("xterm-16color")));
+        assert_eq!(Theme::Default, Theme::for_term(Some("xterm-256color")));
+        assert_eq!(Theme::Default, Theme::for_term(None));
+
+        for &(theme, count) in &[(Theme::Colors8, 8), (Theme::Colors16, 16)] {
+            let colors = Colors::new(theme);
+            let map = colors.colors.as_ref().unwrap();
+            // All the elements of the light theme are colored, but for the shaded rows.
+            let shaded = map.contains_key(&Elem::RowShade) as usize;
+            let light = Colors::new(Theme::NoLscolors).colors.unwrap();
+            assert_eq!(light.len(), map.len() + 1 - shaded);
+            for colour in map.values() {
+                match colour {
+                    Colour::Fixed(n) => assert!(*n >= 8 && *n < count, "{:?}", colour),
+                    Colour::RGB(..) => panic!("{:?}", colour),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_strip_styles() {
+        let styled = format!(
+            "{} {}",
+            Colour::Red.bold().paint("a"),
+            Colour::Fixed(33).paint("b")
+        );
+        assert_eq!("a b", strip_styles(&styled));
+        assert_eq!("plain", strip_styles("plain"));
+    }
+
+    #[test]
+    fn test_colorize_name() {
+        let colors = Colors::new(Theme::NoLscolors);
+        let name = Colour::Fixed(33).paint("a");
+        assert_eq!(
+            Colour::Fixed(245).strikethrough().paint("a").to_string(),
+            colors
+                .colorize_name(name.clone(), &Elem::WatchDeleted)
+                .to_string()
+        );
+
+        let colors = Colors::new(Theme::NoColor);
+        assert_eq!(
+            name.to_string(),
+            colors
+                .colorize_name(name.clone(), &Elem::WatchCreated)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_colour_of() {
+        assert_eq!(Colour::Fixed(33), colour_of(ColorValue::Fixed(33), false));
+        assert_eq!(Colour::Fixed(33), colour_of(ColorValue::Fixed(33), true));
+        let orange = ColorValue::Rgb(255, 135, 0);
+        assert_eq!(Colour::RGB(255, 135, 0), colour_of(orange, true));
+        assert_eq!(Colour::Fixed(208), colour_of(orange, false));
+    }
+}
@@ -1,22 +1,32 @@
 ///! This module provides methods to handle the program's config files and operations related to
 ///! this.
-use crate::flags::color::ColorOption;
+use crate::flags::color::{Background, ColorOption, ColorValue};
 use crate::flags::display::Display;
-use crate::flags::icons::{IconOption, IconTheme};
+use crate::flags::extensions::ExtensionStyle;
+use crate::flags::git_status_style::GitStatusStyle;
+use crate::flags::icons::{GitStatusIcons, IconOption, IconTheme};
 use crate::flags::layout::Layout;
 use crate::flags::size::SizeFlag;
-use crate::flags::sorting::{DirGrouping, SortColumn};
+use crate::flags::sorting::{Collation, DirGrouping, PathOrder, SortCase, SortColumn};
+use crate::flags::strictness::Strictness;
+use crate::flags::total_size_symlinks::TotalSizeSymlinks;
+use crate::flags::tree_connectors::TreeConnectors;
 use crate::print_error;
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::Marker;
 
 use std::fs;
 
 const CONF_DIR: &str = "lsd";
 const CONF_FILE_NAME: &str = "config";
 const YAML_LONG_EXT: &str = "yaml";
+const CONF_FILE_ENV: &str = "LSD_CONFIG_FILE";
+const PROJECT_CONF_FILE_NAME: &str = ".lsdrc";
 
 /// A struct to hold an optional configuration items, and provides methods
 /// around error handling in a config file.
@@ -40,18 +50,84 @@ pub struct Config {
     pub no_symlink: Option<bool>,
     pub total_size: Option<bool>,
     pub symlink_arrow: Option<String>,
+    pub max_symlink_depth: Option<usize>,
+    pub numeric_uid_gid: Option<bool>,
+    pub include_snapshots: Option<bool>,
+    pub strictness: Option<Strictness>,
+    pub max_memory: Option<usize>,
+    pub header: Option<bool>,
+    pub truncate_names: Option<usize>,
+    pub follow_symlinks: Option<bool>,
+    pub git_renames: Option<bool>,
+    pub git_status_style: Option<GitStatusStyle>,
+    pub names_only: Option<bool>,
+    pub git_untracked_count: Option<bool>,
+    pub extensions: Option<HashMap<String, ExtensionStyle>>,
+    pub sort_cmd: Option<String>,
+    pub table: Option<Table>,
+    pub keep_duplicates: Option<bool>,
+    pub group_devices: Option<bool>,
+    pub elide_prefix: Option<bool>,
+    pub summary: Option<bool>,
+    pub raw: Option<bool>,
+    pub paginate: Option<bool>,
+    pub glob: Option<bool>,
+    pub no_glob_error: Option<bool>,
+    pub max_entries: Option<usize>,
+    pub total_size_symlinks: Option<TotalSizeSymlinks>,
+    pub count_links: Option<bool>,
+    pub columns: Option<usize>,
+    pub tree_limit: Option<usize>,
+    pub tree_connectors: Option<TreeConnectors>,
+    pub verbose_permissions: Option<bool>,
+    pub ignore_files: Option<bool>,
+    pub truncate_middle: Option<bool>,
+    pub date_timezone: Option<String>,
+    pub git_change_counts: Option<bool>,
 }
 
 #[derive(Eq, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub struct Color {
-    pub when: ColorOption,
+    pub when: Option<ColorOption>,
+    pub size_thresholds: Option<SizeThresholds>,
+    pub date_thresholds: Option<DateThresholds>,
+    pub tree_edges: Option<Vec<ColorValue>>,
+    pub file_setuid: Option<Background>,
+    pub dir_setgid: Option<Background>,
+    pub sparse_file: Option<Background>,
+    pub blocks: Option<HashMap<String, Background>>,
 }
 
 #[derive(Eq, PartialEq, Debug, Deserialize)]
+pub struct SizeThresholds {
+    pub medium: Option<u64>,
+    pub large: Option<u64>,
+    pub huge: Option<u64>,
+}
+
+#[derive(Eq, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DateThresholds {
+    pub recent: Option<String>,
+    pub old: Option<String>,
+    pub very_old: Option<String>,
+}
+
+#[derive(Eq, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub struct Icons {
     pub when: Option<IconOption>,
     pub theme: Option<IconTheme>,
     pub separator: Option<String>,
+    pub git_status: Option<GitStatusIcons>,
+}
+
+#[derive(Eq, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Table {
+    pub separator: Option<String>,
+    pub shade_rows: Option<bool>,
 }
 
 #[derive(Eq, PartialEq, Debug, Deserialize)]
@@ -66,6 +142,10 @@ pub struct Sorting {
     pub column: Option<SortColumn>,
     pub reverse: Option<bool>,
     pub dir_grouping: Option<DirGrouping>,
+    pub case: Option<SortCase>,
+    pub collation: Option<Collation>,
+    pub group_dir_symlinks: Option<bool>,
+    pub path_order: Option<PathOrder>,
 }
 
 impl Config {
@@ -88,13 +168,47 @@ impl Config {
             no_symlink: None,
             total_size: None,
             symlink_arrow: None,
+            max_symlink_depth: None,
+            numeric_uid_gid: None,
+            include_snapshots: None,
+            strictness: None,
+            max_memory: None,
+            header: None,
+            truncate_names: None,
+            follow_symlinks: None,
+            git_renames: None,
+            git_status_style: None,
+            names_only: None,
+            git_untracked_count: None,
+            extensions: None,
+            sort_cmd: None,
+            table: None,
+            keep_duplicates: None,
+            group_devices: None,
+            elide_prefix: None,
+            summary: None,
+            raw: None,
+            paginate: None,
+            glob: None,
+            no_glob_error: None,
+            max_entries: None,
+            total_size_symlinks: None,
+            count_links: None,
+            columns: None,
+            tree_limit: None,
+            tree_connectors: None,
+            verbose_permissions: None,
+            ignore_files: None,
+            truncate_middle: None,
+            date_timezone: None,
+            git_change_counts: None,
         }
     }
 
     /// This constructs a Config struct with a passed file path [String].
     pub fn from_file(file: String) -> Option<Self> {
         match fs::read(&file) {
-            Ok(f) => match Self::from_yaml(&String::from_utf8_lossy(&f)) {
+            Ok(f) => match Self::from_checked_yaml(&file, &String::from_utf8_lossy(&f)) {
                 Ok(c) => Some(c),
                 Err(e) => {
                     print_error!("Configuration file {} format error, {}.", &file, e);
@@ -111,6 +225,103 @@ impl Config {
         }
     }
 
+    /// This constructs a Config struct from the first configuration file found, in this order:
+    /// - the `config_file` passed with the "config-file" argument,
+    /// - the file set in the `LSD_CONFIG_FILE` environment variable,
+    /// - a project-local `.lsdrc` file, in the current directory or one of its parents,
+    /// - the user configuration file, see [config_file_path](Config::config_file_path).
+    ///
+    /// If the file can not be read, the default configuration is used.
+    pub fn load(config_file: Option<&str>) -> Self {
+        let current_dir = std::env::current_dir().ok();
+        let found = Self::find_file(
+            config_file.map(PathBuf::from),
+            std::env::var_os(CONF_FILE_ENV).map(PathBuf::from),
+            current_dir.as_deref(),
+        );
+
+        if let Some((path, explicit)) = found {
+            if explicit && !path.exists() {
+                print_error!("Can not open config file {}: not found.", path.display());
+            } else if let Some(c) = Self::from_file(path.to_string_lossy().to_string()) {
+                return c;
+            }
+        }
+        Self::from_yaml(DEFAULT_CONFIG).unwrap()
+    }
+
+    /// This finds the path of the configuration file to use, see [load](Config::load) for the
+    /// order of precedence. The returned flag tells whether the file was explicitly requested,
+    /// from the argument or the environment variable, and thus is expected to exist.
+    fn find_file(
+        from_args: Option<PathBuf>,
+        from_env: Option<PathBuf>,
+        current_dir: Option<&Path>,
+    ) -> Option<(PathBuf, bool)> {
+        if let Some(p) = from_args {
+            return Some((p, true));
+        }
+        if let Some(p) = from_env.filter(|p| !p.as_os_str().is_empty()) {
+            return Some((p, true));
+        }
+        if let Some(dir) = current_dir {
+            let project_file = dir
+                .ancestors()
+                .map(|d| d.join(PROJECT_CONF_FILE_NAME))
+                .find(|p| p.is_file());
+            if let Some(p) = project_file {
+                return Some((p, false));
+            }
+        }
+        Self::config_file_path().map(|p| (p, false))
+    }
+
+    /// This constructs a Config struct with the [Yaml] str read from the `file`, printing an
+    /// error with its line and column for each problem found by [check](Config::check). The
+    /// options concerned by the problems are left unset, the others being kept.
+    fn from_checked_yaml(file: &str, yaml: &str) -> Result<Self, serde_yaml::Error> {
+        let (value, problems) = Self::check(yaml)?;
+        if !problems.is_empty() {
+            let positions = Positions::new(yaml);
+            for problem in problems {
+                match positions.markers.get(&problem.path) {
+                    Some(mark) => print_error!(
+                        "{}:{}:{}: {}",
+                        file,
+                        mark.line(),
+                        mark.col() + 1,
+                        problem.message
+                    ),
+                    None => print_error!("{}: {}", file, problem.message),
+                }
+            }
+        }
+        serde_yaml::from_value(value)
+    }
+
+    /// This checks a [Yaml] str against the expected keys and values of a configuration file,
+    /// returning its parsed value, without the unrecognized keys, the bad enum values and the
+    /// values of the wrong type, along with a [Problem] for each of them.
+    fn check(yaml: &str) -> Result<(serde_yaml::Value, Vec<Problem>), serde_yaml::Error> {
+        let mut value = serde_yaml::from_str::<serde_yaml::Value>(yaml)?;
+        let mut problems = Vec::new();
+        CONFIG_SCHEMA.validate(&mut value, "", &[], &mut problems);
+        Ok((value, problems))
+    }
+
+    /// This returns the message of each problem found by [check](Config::check) in a [Yaml] str.
+    /// Nothing is returned for a str which is not valid [Yaml], as the parsing reports it.
+    #[cfg(test)]
+    fn validate(yaml: &str) -> Vec<String> {
+        match Self::check(yaml) {
+            Ok((_, problems)) => problems
+                .into_iter()
+                .map(|problem| problem.message)
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
     /// This constructs a Config struct with a passed [Yaml] str.
     /// If error happened, return the [serde_yaml::Error].
     fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
@@ -150,12 +361,492 @@ impl Config {
 
 impl Default for Config {
     fn default() -> Self {
-        if let Some(p) = Self::config_file_path() {
-            if let Some(c) = Self::from_file(p.to_string_lossy().to_string()) {
-                return c;
+        Self::load(None)
+    }
+}
+
+/// The expected shape of a configuration value.
+enum Schema {
+    Bool,
+    /// A non-negative integer, as the sizes, the depths and the counts.
+    Number,
+    String,
+    /// One of the listed strings.
+    Enum(&'static [&'static str]),
+    /// One of the listed strings, or a free format starting with a `+`.
+    Format(&'static [&'static str]),
+    /// A string accepted by the validator of its argument, which describes the possible values.
+    Validated(fn(String) -> Result<(), String>),
+    /// A 256-color palette number, or a `#rrggbb` true color.
+    Color,
+    /// A [Schema::Color], or "none", as the backgrounds and the block colors.
+    Background,
+    List(&'static Schema),
+    /// A mapping from any key to values of the schema.
+    Dict(&'static Schema),
+    Map(&'static [(&'static str, Schema)]),
+}
+
+const CONFIG_SCHEMA: Schema = Schema::Map(&[
+    ("classic", Schema::Bool),
+    (
+        "blocks",
+        Schema::List(&Schema::Enum(&[
+            "permission",
+            "user",
+            "group",
+            "size",
+            "size_value",
+            "date",
+            "name",
+            "inode",
+            "links",
+            "security",
+            "filesystem",
+            "kind",
+            "allocated",
+            "created",
+            "git",
+            "repo",
+        ])),
+    ),
+    (
+        "color",
+        Schema::Map(&[
+            ("when", Schema::Enum(&["always", "auto", "never"])),
+            (
+                "size-thresholds",
+                Schema::Map(&[
+                    ("medium", Schema::Number),
+                    ("large", Schema::Number),
+                    ("huge", Schema::Number),
+                ]),
+            ),
+            (
+                "date-thresholds",
+                Schema::Map(&[
+                    ("recent", Schema::String),
+                    ("old", Schema::String),
+                    ("very-old", Schema::String),
+                ]),
+            ),
+            ("tree-edges", Schema::List(&Schema::Color)),
+            ("file-setuid", Schema::Background),
+            ("dir-setgid", Schema::Background),
+            ("sparse-file", Schema::Background),
+            (
+                "blocks",
+                Schema::Map(&[
+                    ("permission", Schema::Background),
+                    ("user", Schema::Background),
+                    ("group", Schema::Background),
+                    ("size", Schema::Background),
+                    ("size_value", Schema::Background),
+                    ("date", Schema::Background),
+                    ("name", Schema::Background),
+                    ("inode", Schema::Background),
+                    ("links", Schema::Background),
+                    ("security", Schema::Background),
+                    ("filesystem", Schema::Background),
+                    ("kind", Schema::Background),
+                    ("allocated", Schema::Background),
+                    ("created", Schema::Background),
+                    ("git", Schema::Background),
+                    ("repo", Schema::Background),
+                ]),
+            ),
+        ]),
+    ),
+    ("date", Schema::Format(&["date", "relative", "smart"])),
+    (
+        "date-timezone",
+        Schema::Validated(crate::app::validate_date_timezone_argument),
+    ),
+    ("dereference", Schema::Bool),
+    (
+        "display",
+        Schema::Enum(&["all", "almost-all", "directory-only", "visible-only"]),
+    ),
+    (
+        "icons",
+        Schema::Map(&[
+            ("when", Schema::Enum(&["always", "auto", "never"])),
+            ("theme", Schema::Enum(&["fancy", "unicode"])),
+            ("separator", Schema::String),
+            (
+                "git-status",
+                Schema::Map(&[
+                    ("preset", Schema::Enum(&["theme", "ascii"])),
+                    ("unmodified", Schema::String),
+                    ("new-in-index", Schema::String),
+                    ("new-in-workdir", Schema::String),
+                    ("deleted", Schema::String),
+                    ("modified", Schema::String),
+                    ("renamed", Schema::String),
+                    ("ignored", Schema::String),
+                    ("typechange", Schema::String),
+                    ("conflicted", Schema::String),
+                ]),
+            ),
+        ]),
+    ),
+    ("ignore-globs", Schema::List(&Schema::String)),
+    ("indicators", Schema::Bool),
+    ("layout", Schema::Enum(&["grid", "tree", "oneline"])),
+    (
+        "recursion",
+        Schema::Map(&[("enabled", Schema::Bool), ("depth", Schema::Number)]),
+    ),
+    ("size", Schema::Enum(&["default", "short", "bytes"])),
+    (
+        "sorting",
+        Schema::Map(&[
+            (
+                "column",
+                Schema::Enum(&[
+                    "extension",
+                    "name",
+                    "time",
+                    "created",
+                    "size",
+                    "version",
+                    "inode",
+                    "links",
+                    "git-status",
+                    "git-repo",
+                ]),
+            ),
+            ("reverse", Schema::Bool),
+            ("dir-grouping", Schema::Enum(&["none", "first", "last"])),
+            ("case", Schema::Enum(&["sensitive", "insensitive", "smart"])),
+            ("collation", Schema::Enum(&["bytes", "locale"])),
+            ("group-dir-symlinks", Schema::Bool),
+            ("path-order", Schema::Enum(&["sorted", "given"])),
+        ]),
+    ),
+    ("no-symlink", Schema::Bool),
+    ("total-size", Schema::Bool),
+    ("symlink-arrow", Schema::String),
+    ("max-symlink-depth", Schema::Number),
+    ("numeric-uid-gid", Schema::Bool),
+    ("include-snapshots", Schema::Bool),
+    ("strictness", Schema::Enum(&["lenient", "warn", "strict"])),
+    ("max-memory", Schema::Number),
+    ("header", Schema::Bool),
+    ("truncate-names", Schema::Number),
+    ("truncate-middle", Schema::Bool),
+    ("follow-symlinks", Schema::Bool),
+    ("git-renames", Schema::Bool),
+    ("git-status-style", Schema::Enum(&["two-char", "single"])),
+    ("names-only", Schema::Bool),
+    ("git-untracked-count", Schema::Bool),
+    ("git-change-counts", Schema::Bool),
+    ("sort-cmd", Schema::String),
+    (
+        "extensions",
+        Schema::Dict(&Schema::Map(&[
+            ("icon", Schema::String),
+            ("color", Schema::Color),
+        ])),
+    ),
+    (
+        "table",
+        Schema::Map(&[("separator", Schema::String), ("shade-rows", Schema::Bool)]),
+    ),
+    ("keep-duplicates", Schema::Bool),
+    ("group-devices", Schema::Bool),
+    ("elide-prefix", Schema::Bool),
+    ("summary", Schema::Bool),
+    ("raw", Schema::Bool),
+    ("paginate", Schema::Bool),
+    ("glob", Schema::Bool),
+    ("no-glob-error", Schema::Bool),
+    ("max-entries", Schema::Number),
+    ("columns", Schema::Number),
+    ("tree-limit", Schema::Number),
+    (
+        "tree-connectors",
+        Schema::Map(&[
+            ("preset", Schema::Enum(&["unicode", "ascii"])),
+            ("edge", Schema::String),
+            ("corner", Schema::String),
+            ("line", Schema::String),
+        ]),
+    ),
+    (
+        "total-size-symlinks",
+        Schema::Enum(&["link", "follow", "skip"]),
+    ),
+    ("count-links", Schema::Bool),
+    ("verbose-permissions", Schema::Bool),
+    ("ignore-files", Schema::Bool),
+]);
+
+/// A problem found by the [Schema] in a configuration file, with the path of the value it is
+/// about, made of the keys of the mappings and of the indexes of the list items, as `[0]`.
+struct Problem {
+    path: Vec<String>,
+    message: String,
+}
+
+impl Schema {
+    /// This checks the `value` found at `key`, pushing a [Problem] for each problem. The values
+    /// with a problem are removed from their mapping or list, so that their option keeps its
+    /// default, and this returns false if the `value` itself has to be removed.
+    /// Empty values are always accepted, as they leave the option unset.
+    fn validate(
+        &self,
+        value: &mut serde_yaml::Value,
+        key: &str,
+        path: &[String],
+        problems: &mut Vec<Problem>,
+    ) -> bool {
+        use serde_yaml::{Mapping, Value};
+
+        let message = match (self, &mut *value) {
+            (_, Value::Null)
+            | (Schema::Bool, Value::Bool(_))
+            | (Schema::String, Value::String(_)) => None,
+            (Schema::Number, Value::Number(n)) if n.as_u64().is_some() => None,
+            (Schema::Number, Value::Number(n)) => Some(format!(
+                "Config {} could only be a non-negative integer, got {}.",
+                key, n
+            )),
+            (Schema::Enum(values), Value::String(s)) if !values.contains(&s.as_str()) => {
+                Some(format!(
+                    "Config {} could only be one of {}, got {}.",
+                    key,
+                    values.join(", "),
+                    s
+                ))
+            }
+            (Schema::Enum(_), Value::String(_)) => None,
+            (Schema::Format(values), Value::String(s))
+                if !values.contains(&s.as_str()) && !s.starts_with('+') =>
+            {
+                Some(format!(
+                    "Config {} could only be one of {} or a +<format>, got {}.",
+                    key,
+                    values.join(", "),
+                    s
+                ))
+            }
+            (Schema::Format(_), Value::String(_)) => None,
+            (Schema::Validated(validator), Value::String(s)) => validator(s.clone())
+                .err()
+                .map(|values| format!("Config {} is not valid, {}, got {}.", key, values, s)),
+            (Schema::Color, Value::Number(_)) | (Schema::Background, Value::Number(_)) => None,
+            (Schema::Color, Value::String(s)) if ColorValue::from_hex(s).is_none() => {
+                Some(format!(
+                    "Config {} could only be a 256-color number or #rrggbb, got {}.",
+                    key, s
+                ))
             }
+            (Schema::Color, Value::String(_)) => None,
+            (Schema::Background, Value::String(s))
+                if s != "none" && ColorValue::from_hex(s).is_none() =>
+            {
+                Some(format!(
+                    "Config {} could only be a 256-color number, #rrggbb or none, got {}.",
+                    key, s
+                ))
+            }
+            (Schema::Background, Value::String(_)) => None,
+            (Schema::List(schema), Value::Sequence(items)) => {
+                let checked = std::mem::take(items).into_iter().enumerate();
+                for (index, mut item) in checked {
+                    let item_path = Self::child_path(path, format!("[{}]", index));
+                    if schema.validate(&mut item, key, &item_path, problems) {
+                        items.push(item);
+                    }
+                }
+                None
+            }
+            (Schema::Dict(schema), Value::Mapping(mapping)) => {
+                let mut checked = Mapping::new();
+                for (name, mut item) in std::mem::take(mapping) {
+                    let text = Self::key_name(&name);
+                    let item_key = format!("{}.{}", key, text);
+                    let item_path = Self::child_path(path, text);
+                    if schema.validate(&mut item, &item_key, &item_path, problems) {
+                        checked.insert(name, item);
+                    }
+                }
+                *mapping = checked;
+                None
+            }
+            (Schema::Map(fields), Value::Mapping(mapping)) => {
+                let mut checked = Mapping::new();
+                for (name, mut item) in std::mem::take(mapping) {
+                    let text = Self::key_name(&name);
+                    let item_key = if key.is_empty() {
+                        text.clone()
+                    } else {
+                        format!("{}.{}", key, text)
+                    };
+                    let item_path = Self::child_path(path, text.clone());
+                    let valid = match fields.iter().find(|(field, _)| *field == text) {
+                        Some((_, schema)) => {
+                            schema.validate(&mut item, &item_key, &item_path, problems)
+                        }
+                        None => {
+                            problems.push(Problem {
+                                path: item_path,
+                                message: format!("Config {} is not recognized.", item_key),
+                            });
+                            false
+                        }
+                    };
+                    if valid {
+                        checked.insert(name, item);
+                    }
+                }
+                *mapping = checked;
+                None
+            }
+            (schema, _) => Some(format!(
+                "Config {} should be {}.",
+                if key.is_empty() { "file" } else { key },
+                schema.description()
+            )),
+        };
+
+        match message {
+            Some(message) => {
+                problems.push(Problem {
+                    path: path.to_vec(),
+                    message,
+                });
+                false
+            }
+            None => true,
+        }
+    }
+
+    fn child_path(path: &[String], name: String) -> Vec<String> {
+        let mut child = path.to_vec();
+        child.push(name);
+        child
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Schema::Bool => "a boolean",
+            Schema::Number => "a number",
+            Schema::Color => "a number or #rrggbb",
+            Schema::Background => "a number, #rrggbb or none",
+            Schema::String | Schema::Enum(_) | Schema::Format(_) | Schema::Validated(_) => {
+                "a string"
+            }
+            Schema::List(_) => "a list",
+            Schema::Dict(_) | Schema::Map(_) => "a mapping",
+        }
+    }
+
+    /// The text of a mapping key, as written in the config file.
+    fn key_name(name: &serde_yaml::Value) -> String {
+        match name {
+            serde_yaml::Value::String(name) => name.clone(),
+            other => serde_yaml::to_string(other)
+                .unwrap_or_default()
+                .trim_start_matches("---")
+                .trim()
+                .to_string(),
+        }
+    }
+}
+
+/// The positions of the keys of the mappings and of the list items in a [Yaml] str, by their path
+/// as in a [Problem].
+#[derive(Default)]
+struct Positions {
+    markers: HashMap<Vec<String>, Marker>,
+    /// The path of each mapping and list being read, with the key of the value expected next in
+    /// a mapping, or the index of the next item in a list.
+    stack: Vec<(Vec<String>, Position)>,
+}
+
+enum Position {
+    Key,
+    Value(String),
+    Item(usize),
+}
+
+impl Positions {
+    /// This reads the positions in the first document of a [Yaml] str. The ones after an error
+    /// are missing, as the parsing reports it.
+    fn new(yaml: &str) -> Self {
+        let mut positions = Self::default();
+        let _ = Parser::new(yaml.chars()).load(&mut positions, false);
+        positions
+    }
+
+    /// This returns the path of the node starting at `mark`, recording its position when it is a
+    /// key or a list item, and notes in its parent that it was read if it is a `scalar`.
+    fn start(&mut self, mark: Marker, scalar: Option<&str>) -> Vec<String> {
+        let path = match self.stack.last_mut() {
+            None => Vec::new(),
+            Some((path, Position::Key)) => {
+                let key = scalar.unwrap_or_default().to_string();
+                let mut key_path = path.clone();
+                key_path.push(key);
+                self.markers.insert(key_path.clone(), mark);
+                key_path
+            }
+            Some((path, Position::Value(key))) => {
+                let mut value_path = path.clone();
+                value_path.push(key.clone());
+                value_path
+            }
+            Some((path, Position::Item(index))) => {
+                let mut item_path = path.clone();
+                item_path.push(format!("[{}]", index));
+                self.markers.insert(item_path.clone(), mark);
+                item_path
+            }
+        };
+        if scalar.is_some() {
+            self.end(scalar);
+        }
+        path
+    }
+
+    /// This notes in the parent of a node that it was read, the text of the `scalar` nodes being
+    /// the keys of the values which follow them in a mapping.
+    fn end(&mut self, scalar: Option<&str>) {
+        if let Some((_, position)) = self.stack.last_mut() {
+            *position = match position {
+                Position::Key => Position::Value(scalar.unwrap_or_default().to_string()),
+                Position::Value(_) => Position::Key,
+                Position::Item(index) => Position::Item(*index + 1),
+            };
+        }
+    }
+}
+
+impl MarkedEventReceiver for Positions {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        match event {
+            Event::Scalar(text, ..) => {
+                self.start(mark, Some(&text));
+            }
+            Event::Alias(_) => {
+                self.start(mark, Some(""));
+            }
+            Event::MappingStart(_) => {
+                let path = self.start(mark, None);
+                self.stack.push((path, Position::Key));
+            }
+            Event::SequenceStart(_) => {
+                let path = self.start(mark, None);
+                self.stack.push((path, Position::Item(0)));
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+                self.end(None);
+            }
+            _ => {}
         }
-        Self::from_yaml(DEFAULT_CONFIG).unwrap()
     }
 }
 
@@ -170,7 +861,8 @@ classic: false
 # == Blocks ==
 # This specifies the columns and their order when using the long and the tree
 # layout.
-# Possible values: permission, user, group, size, size_value, date, name, inode, git
+# Possible values: permission, user, group, size, size_value, date, name, inode,
+# links, security, filesystem, kind, allocated, created, git, repo
 blocks:
   - permission
   - user
@@ -184,14 +876,50 @@ blocks:
 color:
   # When to colorize the output.
   # When "classic" is set, this is set to "never".
+  # With auto, the NO_COLOR, CLICOLOR and CLICOLOR_FORCE environment variables
+  # are followed.
   # Possible values: never, auto, always
   when: auto
+  # The sizes, in bytes, from which a file size is colored as medium, large and
+  # huge. Each threshold must be greater than the previous one.
+  size-thresholds:
+    medium: 1048576
+    large: 1073741824
+    huge: 1099511627776
+  # The ages from which a date is no more colored as recent, as within the last
+  # day, and from which it is colored as very old, as numbers followed by s, m,
+  # h, d or w. When very-old is unset, the old dates are all colored alike.
+  date-thresholds:
+    recent: 1h
+    old: 1d
+    # very-old: 52w
+  # The colors below are 256-color palette numbers or true colors quoted as
+  # '#rrggbb', which are turned to the closest palette colors unless the
+  # COLORTERM environment variable is truecolor or 24bit.
+  # The colors of the tree guides, taken in turn for each depth level. When
+  # unset, all the guides are grey.
+  # tree-edges: [33, 40, 172, '#ff8700']
+  # The background colors of the setuid files and the setgid directories, or
+  # none to not highlight them. When unset, the theme highlights the setuid
+  # files in red and leaves the setgid directories as the other directories.
+  # file-setuid: 124
+  # dir-setgid: 22
+  # The background color of the size of the sparse files, which take less
+  # space on the disk than their size, or none to not highlight them.
+  # sparse-file: 24
+  # The blocks to show in a single color, or uncolored with none, instead of
+  # the colors of the theme.
+  # blocks:
+  #   date: none
+  #   size: 244
 
 # == Date ==
 # This specifies the date format for the date column. The freeform format
 # accepts an strftime like string.
+# The "smart" one shows the time for today, the month and the day for this year
+# and the year for the older dates.
 # When "classic" is set, this is set to "date".
-# Possible values: date, relative, +<date_format>
+# Possible values: date, relative, smart, +<date_format>
 # date: date
 
 # == Dereference ==
@@ -213,6 +941,20 @@ icons:
   # Which icon theme to use.
   # Possible values: fancy, unicode
   theme: fancy
+  # The symbols of the git statuses. The preset gives them all, either the ones
+  # of the icon theme or plain letters, then each status may be given its own.
+  # Possible presets: theme, ascii
+  # git-status:
+  #   preset: ascii
+  #   unmodified: "-"
+  #   new-in-index: "N"
+  #   new-in-workdir: "?"
+  #   deleted: "D"
+  #   modified: "M"
+  #   renamed: "R"
+  #   ignored: "!"
+  #   typechange: "T"
+  #   conflicted: "C"
   # The string between the icons and the name.
   # Possible values: any string (eg: " |")
   separator: " "
@@ -250,7 +992,7 @@ size: default
 # == Sorting ==
 sorting:
   # Specify what to sort by.
-  # Possible values: extension, name, time, size, version
+  # Possible values: extension, name, time, created, size, version, inode, links
   column: name
   # Whether to reverse the sorting.
   # Possible values: false, true
@@ -259,6 +1001,24 @@ sorting:
   # When "classic" is set, this is set to "none".
   # Possible values: first, last, none
   dir-grouping: none
+  # How the case of the names is considered when sorting by name. The "smart"
+  # sorting ignores it, unless the names only differ by their case.
+  # Possible values: sensitive, insensitive, smart
+  case: insensitive
+  # In which order the characters of the names are sorted, either by their code
+  # points or with the collation rules of the locale (LC_COLLATE), which sort
+  # the accented letters and the other scripts as the language does.
+  # Possible values: bytes, locale
+  collation: bytes
+  # Whether the symlinks to directories are grouped with the directories by
+  # dir-grouping, rather than with the files.
+  # Possible values: false, true
+  group-dir-symlinks: true
+  # Whether the paths given as arguments are sorted as the entries of the
+  # directories, or listed in the order they are given. The files are listed
+  # before the directories either way.
+  # Possible values: sorted, given
+  path-order: sorted
 
 # == No Symlink ==
 # Whether to omit showing symlink targets
@@ -273,6 +1033,219 @@ total-size: false
 # == Symlink arrow ==
 # Specifies how the symlink arrow display, chars in both ascii and utf8
 symlink-arrow: ⇒
+
+# == Max symlink depth ==
+# How many symbolic links to follow when resolving a chain of links. Longer
+# chains (or loops) are annotated with "[too many levels]".
+# Possible values: any positive integer
+max-symlink-depth: 40
+
+# == Numeric uid gid ==
+# Whether to show the numeric user and group ids instead of their names.
+# Possible values: false, true
+numeric-uid-gid: false
+
+# == Include snapshots ==
+# Whether to recurse into filesystem snapshot directories (NetApp ".snapshot",
+# ZFS ".zfs/snapshot"), which mirror the whole file system. This also affects
+# the total size of directories.
+# Possible values: false, true
+include-snapshots: false
+
+# == Strictness ==
+# How to handle the `ls` arguments which are not supported: "lenient" silently
+# uses an approximation, "warn" also prints a warning and "strict" exits with an
+# error.
+# Possible values: lenient, warn, strict
+strictness: warn
+
+# == Max memory ==
+# How many megabytes may be used to collect the files metadata. When exceeded,
+# the files are listed unsorted, one directory at a time. There is no limit by
+# default.
+# max-memory: 512
+
+# == Header ==
+# Whether to print a header row labelling the columns of the long format.
+# Possible values: false, true
+header: false
+
+# == Truncate names ==
+# How many columns a name may take in the grid layout. Longer names are shortened
+# with an ellipsis. Names are not shortened by default.
+# truncate-names: 32
+
+# == Follow symlinks ==
+# Whether to descend into the symbolic links to directories in the recursive and
+# tree layouts. A link leading back to one of its parent directories is reported
+# instead of being followed.
+# Possible values: false, true
+follow-symlinks: false
+
+# == Git renames ==
+# When the git block is shown, print the previous name of the files renamed in
+# the index after their name.
+git-renames: false
+
+# == Git status style ==
+# How many symbols the git block shows for a file: the index then the workdir
+# status, or only the most severe of them.
+# Possible values: two-char, single
+git-status-style: two-char
+
+# == Names only ==
+# Print the unstyled names only, without reading the metadata of the files.
+# The other display options are ignored.
+names-only: false
+
+# == Git untracked count ==
+# When the git block is shown, count the untracked files of the directories
+# next to their status, as in "?(42)".
+git-untracked-count: false
+
+# == Extensions ==
+# The icon and the color, as a 256-color palette number or a '#rrggbb' true
+# color, of the files by their extension. A compound extension is matched
+# before the last one, so the "tar.gz" style is taken for "backup.tar.gz"
+# rather than the "gz" one. The icons of the theme are kept for the extensions
+# not listed.
+# extensions:
+#   tar.gz:
+#     icon: ""
+#     color: 172
+#   rs:
+#     icon: ""
+#     color: '#dea584'
+
+# == Sort Command ==
+# A shell command printing a sort key for each path read on its standard input,
+# one per line and in the same order. The files are then sorted by these keys,
+# the directories staying grouped as set by "sorting"->"dir-grouping". Should
+# the command fail, the files are sorted as usual.
+# sort-cmd: ci-status --sort-key
+
+# == Table ==
+# The visual aids of the long and the tree layouts: a character printed between
+# the blocks, and a dim background shading every other row.
+table:
+  # separator: "│"
+  shade-rows: false
+
+# == Keep duplicates ==
+# List the paths as they are given, even the duplicated ones and the ones
+# already listed while recursing into another one.
+# Possible values: false, true
+keep-duplicates: false
+
+# == Group devices ==
+# Group the block and character devices of the listed directories by their
+# subsystem, as tty or sd, each group under a header counting its devices.
+# Possible values: false, true
+group-devices: false
+
+# == Elide prefix ==
+# Whether to replace the listed path with … in the folder paths of the
+# recursive listing, once it has been shown in full.
+# Possible values: false, true
+elide-prefix: false
+
+# == Summary ==
+# Whether to print a footer counting the files of each type and their total
+# size after each directory, or after the whole tree.
+# Possible values: false, true
+summary: false
+
+# == Raw ==
+# Whether to print the names as they are, rather than escaping their control
+# characters and the bytes which are not valid UTF-8.
+# Possible values: false, true
+raw: false
+
+# == Paginate ==
+# Whether to pipe the output into the pager set in the PAGER environment
+# variable, "less -RFX" by default, when it is a terminal.
+# Possible values: false, true
+paginate: false
+
+# == Glob ==
+# Expand the glob patterns of the listed paths, as '*.rs' or 'src/**/*.toml',
+# instead of relying on the shell, so they work the same everywhere.
+# Possible values: false, true
+glob: false
+
+# == No glob error ==
+# With glob, ignore the patterns matching no file instead of reporting them.
+# Possible values: false, true
+no-glob-error: false
+
+# == Max entries ==
+# Do not list the listed directories having more entries than this number, but
+# print an error instead. The shell code printed by --init uses this to skip
+# the huge directories.
+# max-entries: 1000
+
+# == Total size symlinks ==
+# How the symlinks found in the directories are counted in their total size:
+# by the size of the link itself, by the size of the file it references, or
+# not at all.
+# Possible values: link, follow, skip
+total-size-symlinks: link
+
+# == Count links ==
+# Count the hard linked files in the total size of the directories each time
+# they are found, instead of once.
+# Possible values: false, true
+count-links: false
+
+# == Columns ==
+# How many columns the grid layout has, whatever the width of the terminal is.
+# The columns are fitted into the terminal by default.
+# columns: 4
+
+# == Tree limit ==
+# How many entries of each directory the tree layout shows at most, the other
+# ones being counted on a last line. All of them are shown by default.
+# tree-limit: 100
+
+# == Tree connectors ==
+# The characters the edges of the tree layout are drawn with: the box drawing
+# ones of the unicode preset, or the pipes and dashes of the ascii one. Each
+# connector set replaces the one of the preset.
+tree-connectors:
+  # Possible values: unicode, ascii
+  preset: unicode
+  # edge: "├──"
+  # corner: "└──"
+  # line: "│  "
+
+# == Verbose permissions ==
+# Spell out the permissions of each class after their symbolic notation, as in
+# "rw-r----- (owner: read/write, group: read, others: none)".
+# Possible values: false, true
+verbose-permissions: false
+
+# == Ignore files ==
+# Leave out the files matched by the gitignore style patterns of the .ignore and
+# .lsdignore files of the listed directories and of their sub directories.
+# Possible values: false, true
+ignore-files: false
+
+# == Truncate middle ==
+# Whether to shorten the names in the middle, keeping their extension, rather
+# than at their end. Without truncate-names, only the names too long for the
+# grid layout to show two columns are shortened.
+# Possible values: false, true
+truncate-middle: false
+
+# == Date timezone ==
+# In which time zone the dates are displayed: local, utc, an offset from UTC
+# such as +05:30, or with the tz feature a time zone name such as Europe/Paris.
+date-timezone: local
+
+# == Git change counts ==
+# When the git block is shown, count the files of the directories with staged
+# changes and with changes not staged, as in "3±/2?".
+git-change-counts: false
 "#;
 
 #[cfg(test)]
@@ -284,13 +1257,20 @@ impl Config {
 
 #[cfg(test)]
 mod tests {
-    use super::Config;
+    use super::{Config, Positions};
     use crate::config_file;
-    use crate::flags::color::ColorOption;
+    use crate::flags::color::{Background, ColorOption, ColorValue};
+    use crate::flags::extensions::ExtensionStyle;
+    use crate::flags::git_status_style::GitStatusStyle;
     use crate::flags::icons::{IconOption, IconTheme};
     use crate::flags::layout::Layout;
     use crate::flags::size::SizeFlag;
-    use crate::flags::sorting::{DirGrouping, SortColumn};
+    use crate::flags::sorting::{Collation, DirGrouping, PathOrder, SortCase, SortColumn};
+    use crate::flags::strictness::Strictness;
+    use crate::flags::total_size_symlinks::TotalSizeSymlinks;
+    use crate::flags::tree_connectors::TreeConnectors;
+    use assert_fs::prelude::*;
+    use std::path::PathBuf;
 
     #[test]
     fn test_read_default() {
@@ -310,7 +1290,22 @@ mod tests {
                     .into()
                 ),
                 color: Some(config_file::Color {
-                    when: ColorOption::Auto,
+                    when: Some(ColorOption::Auto),
+                    size_thresholds: Some(config_file::SizeThresholds {
+                        medium: Some(1_048_576),
+                        large: Some(1_073_741_824),
+                        huge: Some(1_099_511_627_776),
+                    }),
+                    date_thresholds: Some(config_file::DateThresholds {
+                        recent: Some("1h".to_string()),
+                        old: Some("1d".to_string()),
+                        very_old: None,
+                    }),
+                    tree_edges: None,
+                    file_setuid: None,
+                    dir_setgid: None,
+                    sparse_file: None,
+                    blocks: None,
                 }),
                 date: None,
                 dereference: Some(false),
@@ -319,6 +1314,7 @@ mod tests {
                     when: Some(IconOption::Auto),
                     theme: Some(IconTheme::Fancy),
                     separator: Some(" ".to_string()),
+                    git_status: None,
                 }),
                 ignore_globs: None,
                 indicators: Some(false),
@@ -332,10 +1328,51 @@ mod tests {
                     column: Some(SortColumn::Name),
                     reverse: Some(false),
                     dir_grouping: Some(DirGrouping::None),
+                    case: Some(SortCase::Insensitive),
+                    collation: Some(Collation::Bytes),
+                    group_dir_symlinks: Some(true),
+                    path_order: Some(PathOrder::Sorted),
                 }),
                 no_symlink: Some(false),
                 total_size: Some(false),
                 symlink_arrow: Some("⇒".into()),
+                max_symlink_depth: Some(40),
+                numeric_uid_gid: Some(false),
+                include_snapshots: Some(false),
+                strictness: Some(Strictness::Warn),
+                max_memory: None,
+                header: Some(false),
+                truncate_names: None,
+                follow_symlinks: Some(false),
+                git_renames: Some(false),
+                git_status_style: Some(GitStatusStyle::TwoChar),
+                names_only: Some(false),
+                git_untracked_count: Some(false),
+                extensions: None,
+                sort_cmd: None,
+                table: Some(config_file::Table {
+                    separator: None,
+                    shade_rows: Some(false),
+                }),
+                keep_duplicates: Some(false),
+                group_devices: Some(false),
+                elide_prefix: Some(false),
+                summary: Some(false),
+                raw: Some(false),
+                paginate: Some(false),
+                glob: Some(false),
+                no_glob_error: Some(false),
+                max_entries: None,
+                total_size_symlinks: Some(TotalSizeSymlinks::Link),
+                count_links: Some(false),
+                columns: None,
+                tree_limit: None,
+                tree_connectors: Some(TreeConnectors::default()),
+                verbose_permissions: Some(false),
+                ignore_files: Some(false),
+                truncate_middle: Some(false),
+                date_timezone: Some("local".into()),
+                git_change_counts: Some(false),
             },
             c
         );
@@ -347,6 +1384,12 @@ mod tests {
         assert!(c.classic.unwrap())
     }
 
+    #[test]
+    fn test_read_sorting_column_inode() {
+        let c = Config::from_yaml("sorting:\n  column: inode").unwrap();
+        assert_eq!(Some(SortColumn::INode), c.sorting.unwrap().column);
+    }
+
     #[test]
     fn test_read_config_bad_bool() {
         let c = Config::from_yaml("classic: notbool");
@@ -363,4 +1406,277 @@ mod tests {
     fn test_read_bad_display() {
         assert!(Config::from_yaml("display: bad").is_err())
     }
+
+    #[test]
+    fn test_find_file_precedence() {
+        let project = assert_fs::TempDir::new().unwrap();
+        project.child(".lsdrc").touch().unwrap();
+        project.child("sub").create_dir_all().unwrap();
+        let sub = project.path().join("sub");
+
+        let found = Config::find_file(
+            Some(PathBuf::from("cli.yaml")),
+            Some(PathBuf::from("env.yaml")),
+            Some(&sub),
+        );
+        assert_eq!(Some((PathBuf::from("cli.yaml"), true)), found);
+
+        let found = Config::find_file(None, Some(PathBuf::from("env.yaml")), Some(&sub));
+        assert_eq!(Some((PathBuf::from("env.yaml"), true)), found);
+
+        let found = Config::find_file(None, Some(PathBuf::new()), Some(&sub));
+        assert_eq!(Some((project.path().join(".lsdrc"), false)), found);
+    }
+
+    #[test]
+    fn test_find_file_without_project_file() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        assert_eq!(
+            Config::config_file_path().map(|p| (p, false)),
+            Config::find_file(None, None, Some(dir.path()))
+        );
+    }
+
+    #[test]
+    fn test_validate_default() {
+        assert_eq!(
+            Vec::<String>::new(),
+            Config::validate(config_file::DEFAULT_CONFIG)
+        );
+    }
+
+    #[test]
+    fn test_validate_unknown_keys() {
+        let warnings = Config::validate("unknown: true\ncolor:\n  when: auto\n  theme: dark\n");
+        assert_eq!(
+            vec![
+                "Config unknown is not recognized.".to_string(),
+                "Config color.theme is not recognized.".to_string(),
+            ],
+            warnings
+        );
+    }
+
+    #[test]
+    fn test_validate_bad_values() {
+        let warnings = Config::validate(
+            "layout: list\nblocks:\n  - name\n  - owner\ndate: iso\nsorting:\n  reverse: 1\n",
+        );
+        assert_eq!(
+            vec![
+                "Config layout could only be one of grid, tree, oneline, got list.".to_string(),
+                "Config blocks could only be one of permission, user, group, size, size_value, date, name, inode, links, security, filesystem, kind, allocated, created, git, repo, got owner.".to_string(),
+                "Config date could only be one of date, relative, smart or a +<format>, got iso."
+                    .to_string(),
+                "Config sorting.reverse should be a boolean.".to_string(),
+            ],
+            warnings
+        );
+    }
+
+    #[test]
+    fn test_validate_malformed_color() {
+        let warnings = Config::validate("color: always\nicons:\n  when: sometimes\n");
+        assert_eq!(
+            vec![
+                "Config color should be a mapping.".to_string(),
+                "Config icons.when could only be one of always, auto, never, got sometimes."
+                    .to_string(),
+            ],
+            warnings
+        );
+    }
+
+    #[test]
+    fn test_read_true_colors() {
+        let c =
+            Config::from_yaml("color:\n  tree-edges: [33, '#FF8700']\n  dir-setgid: '#005f00'\n")
+                .unwrap();
+        let color = c.color.unwrap();
+        assert_eq!(
+            Some(vec![ColorValue::Fixed(33), ColorValue::Rgb(255, 135, 0)]),
+            color.tree_edges
+        );
+        assert_eq!(
+            Some(Background::Color(ColorValue::Rgb(0, 95, 0))),
+            color.dir_setgid
+        );
+
+        assert!(Config::from_yaml("color:\n  tree-edges: ['#ff87']\n").is_err());
+        assert_eq!(
+            vec![
+                "Config color.tree-edges could only be a 256-color number or #rrggbb, got #ff87."
+                    .to_string()
+            ],
+            Config::validate("color:\n  tree-edges: ['#ff87']\n")
+        );
+    }
+
+    #[test]
+    fn test_read_block_colors() {
+        let c = Config::from_yaml("color:\n  blocks:\n    date: none\n    size: 244\n").unwrap();
+        let blocks = c.color.unwrap().blocks.unwrap();
+        assert_eq!(Some(&Background::None), blocks.get("date"));
+        assert_eq!(
+            Some(&Background::Color(ColorValue::Fixed(244))),
+            blocks.get("size")
+        );
+
+        assert_eq!(
+            vec!["Config color.blocks.dates is not recognized.".to_string()],
+            Config::validate("color:\n  blocks:\n    dates: none\n")
+        );
+    }
+
+    #[test]
+    fn test_read_setid_backgrounds() {
+        let c = Config::from_yaml("color:\n  file-setuid: none\n  dir-setgid: 22\n").unwrap();
+        let color = c.color.unwrap();
+        assert_eq!(Some(Background::None), color.file_setuid);
+        assert_eq!(
+            Some(Background::Color(ColorValue::Fixed(22))),
+            color.dir_setgid
+        );
+
+        assert!(Config::from_yaml("color:\n  file-setuid: red\n").is_err());
+        assert_eq!(
+            vec!["Config color.file-setuid could only be a 256-color number, #rrggbb or none, got red.".to_string()],
+            Config::validate("color:\n  file-setuid: red\n")
+        );
+    }
+
+    #[test]
+    fn test_read_sparse_file_background() {
+        let c = Config::from_yaml("color:\n  sparse-file: '#005f87'\n").unwrap();
+        assert_eq!(
+            Some(Background::Color(ColorValue::Rgb(0, 0x5f, 0x87))),
+            c.color.unwrap().sparse_file
+        );
+        assert!(Config::validate("color:\n  sparse-file: none\n").is_empty());
+    }
+
+    #[test]
+    fn test_read_extensions() {
+        let c = Config::from_yaml(
+            "extensions: { \"rs\": { icon: \"\u{e7a8}\", color: \"#dea584\" }, md: { icon: M } }\n",
+        )
+        .unwrap();
+        let extensions = c.extensions.unwrap();
+        assert_eq!(
+            Some(&ExtensionStyle {
+                icon: Some("\u{e7a8}".to_string()),
+                color: Some(ColorValue::Rgb(0xde, 0xa5, 0x84)),
+            }),
+            extensions.get("rs")
+        );
+        assert_eq!(
+            Some(&ExtensionStyle {
+                icon: Some("M".to_string()),
+                color: None,
+            }),
+            extensions.get("md")
+        );
+    }
+
+    #[test]
+    fn test_validate_extensions() {
+        let warnings = Config::validate(
+            "extensions:\n  tar.gz:\n    icon: x\n    color: red\n  rs:\n    colour: 1\n",
+        );
+        assert_eq!(
+            vec![
+                "Config extensions.tar.gz.color could only be a 256-color number or #rrggbb, got red."
+                    .to_string(),
+                "Config extensions.rs.colour is not recognized.".to_string(),
+            ],
+            warnings
+        );
+    }
+
+    #[test]
+    fn test_validate_empty_values() {
+        assert_eq!(
+            Vec::<String>::new(),
+            Config::validate("date:\nrecursion:\n  depth:\n")
+        );
+    }
+
+    #[test]
+    fn test_validate_numbers() {
+        assert_eq!(
+            vec![
+                "Config max-memory could only be a non-negative integer, got -5.".to_string(),
+                "Config truncate-names could only be a non-negative integer, got 2.5.".to_string(),
+            ],
+            Config::validate("max-memory: -5\ntruncate-names: 2.5\ncolumns: 3\n")
+        );
+
+        let c =
+            Config::from_checked_yaml("config.yaml", "max-memory: -5\nblocks: [name]\n").unwrap();
+        assert_eq!(None, c.max_memory);
+        assert_eq!(Some(vec!["name".to_string()]), c.blocks);
+    }
+
+    #[test]
+    fn test_validate_date_timezone() {
+        assert_eq!(
+            Vec::<String>::new(),
+            Config::validate("date-timezone: +05:30\n")
+        );
+
+        let warnings = Config::validate("date-timezone: mars\n");
+        assert_eq!(1, warnings.len());
+        assert!(warnings[0].starts_with("Config date-timezone is not valid, possible values: "));
+        assert!(warnings[0].ends_with(", got mars."));
+    }
+
+    #[test]
+    fn test_validate_date_format() {
+        assert_eq!(Vec::<String>::new(), Config::validate("date: +%F %R\n"));
+    }
+
+    #[test]
+    fn test_read_checked_default() {
+        assert_eq!(
+            Config::from_yaml(config_file::DEFAULT_CONFIG).unwrap(),
+            Config::from_checked_yaml("default", config_file::DEFAULT_CONFIG).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_read_checked_keeps_valid_values() {
+        let c = Config::from_checked_yaml(
+            "config.yaml",
+            "sorting:\n  column: sizes\n  reverse: true\nblocks: [name, owner, size]\nicons: 1\n",
+        )
+        .unwrap();
+        let sorting = c.sorting.unwrap();
+        assert_eq!(None, sorting.column);
+        assert_eq!(Some(true), sorting.reverse);
+        assert_eq!(Some(vec!["name".to_string(), "size".to_string()]), c.blocks);
+        assert_eq!(None, c.icons);
+
+        assert!(Config::from_checked_yaml("config.yaml", "unknown: true\n").is_ok());
+        assert!(Config::from_checked_yaml("config.yaml", "sorting: [\n").is_err());
+    }
+
+    #[test]
+    fn test_positions() {
+        let positions = Positions::new(
+            "# Sorting\nsorting:\n  column: sizes\nblocks:\n  - name\n  - owner\ncolor: { when: auto }\n",
+        );
+        let position = |path: &[&str]| {
+            let path: Vec<String> = path.iter().map(|key| key.to_string()).collect();
+            positions
+                .markers
+                .get(&path)
+                .map(|mark| (mark.line(), mark.col()))
+        };
+
+        assert_eq!(Some((2, 0)), position(&["sorting"]));
+        assert_eq!(Some((3, 2)), position(&["sorting", "column"]));
+        assert_eq!(Some((6, 4)), position(&["blocks", "[1]"]));
+        assert_eq!(Some((7, 9)), position(&["color", "when"]));
+        assert_eq!(None, position(&["sizes"]));
+    }
 }
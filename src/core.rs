@@ -1,17 +1,26 @@
 use crate::color::{self, Colors};
+use crate::diff;
 use crate::display;
-use crate::flags::{Block, ColorOption, Display, Flags, IconOption, IconTheme, Layout, SortOrder};
+use crate::flags::{
+    Block, ColorOption, Count, Display, Flags, IconOption, IconTheme, Layout, PathOrder, SortOrder,
+};
 #[cfg(feature = "git")]
-use crate::git::GitCache;
+use crate::git::{GitCaches, GitStatusProvider};
 #[cfg(not(feature = "git"))]
-use crate::git_stub::GitCache;
+use crate::git_stub::{GitCaches, GitStatusProvider};
 
 use crate::icon::{self, Icons};
-use crate::meta::Meta;
-use crate::{print_error, print_output, sort};
-use std::path::PathBuf;
+use crate::meta::name::{os_str_text, raw_bytes};
+use crate::meta::{self, ignore_files, FileType, Hidden, MemoryBudget, Meta, SymLink};
+use crate::{glob, info, print_error, readdir, sort, stats, watch};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU8, Ordering};
 
-#[cfg(not(target_os = "windows"))]
 use std::io;
 #[cfg(not(target_os = "windows"))]
 use std::os::unix::io::AsRawFd;
@@ -19,12 +28,50 @@ use std::os::unix::io::AsRawFd;
 #[cfg(target_os = "windows")]
 use terminal_size::terminal_size;
 
+/// The code of the most serious problem reported, see [ExitCode::report].
+static EXIT_CODE: AtomicU8 = AtomicU8::new(ExitCode::Success as u8);
+
+/// How serious the problems met while listing are, lsd exiting with the code of the most serious
+/// one reported, as GNU ls does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExitCode {
+    /// Everything was listed.
+    Success = 0,
+    /// A file found while listing could not be, such as an unreadable sub directory.
+    MinorIssue = 1,
+    /// A path given as argument could not be listed, or the arguments are invalid.
+    MajorIssue = 2,
+}
+
+impl ExitCode {
+    /// Records a problem of this seriousness, once its error is printed.
+    pub fn report(self) {
+        if self as u8 > EXIT_CODE.load(Ordering::Relaxed) {
+            EXIT_CODE.store(self as u8, Ordering::Relaxed);
+        }
+    }
+
+    /// The code of the most serious problem reported so far.
+    pub fn reported() -> i32 {
+        i32::from(EXIT_CODE.load(Ordering::Relaxed))
+    }
+}
+
 pub struct Core {
     flags: Flags,
     icons: Icons,
     //display: Display,
     colors: Colors,
     sorters: Vec<(SortOrder, sort::SortFn)>,
+    /// Whether the standard output is a terminal.
+    tty: bool,
+    /// The pager the output is written to, when paginating.
+    pager: RefCell<Option<Child>>,
+    /// Whether the sort command has failed, the files being then sorted as usual for the rest
+    /// of the run.
+    sort_cmd_failed: Cell<bool>,
+    /// The entries which changed between the listings of the watch mode.
+    changes: RefCell<watch::Changes>,
 }
 
 impl Core {
@@ -47,7 +94,7 @@ impl Core {
 
         let color_theme = match (tty_available && console_color_ok, flags.color.when) {
             (_, ColorOption::Never) | (false, ColorOption::Auto) => color::Theme::NoColor,
-            _ => color::Theme::Default,
+            _ => color::Theme::for_term(std::env::var("TERM").ok().as_deref()),
         };
 
         let icon_theme = match (tty_available, flags.icons.when, flags.icons.theme) {
@@ -66,43 +113,508 @@ impl Core {
             inner_flags.layout = Layout::OneLine;
         };
 
+        // The watch mode never ends, so its output can not be paginated.
+        let pager = if flags.paginate.0 && !flags.watch.0 && tty_available {
+            spawn_pager()
+        } else {
+            None
+        };
+
         let sorters = sort::assemble_sorters(&flags);
+        let colors = Colors::new(color_theme)
+            .with_tree_edges(&flags.color.tree_edges.0)
+            .with_setid_backgrounds(&flags.color.setid_backgrounds)
+            .with_sparse_background(&flags.color.sparse_background)
+            .with_block_colors(&flags.color.block_colors)
+            .with_extensions(&flags.extensions);
+        let icons = Icons::new(icon_theme, icon_separator)
+            .with_git_status(&flags.icons.git_status)
+            .with_extensions(&flags.extensions);
 
         Self {
             flags,
             //display: Display::new(inner_flags),
-            colors: Colors::new(color_theme),
-            icons: Icons::new(icon_theme, icon_separator),
+            colors,
+            icons,
             sorters,
+            tty: tty_available,
+            pager: RefCell::new(pager),
+            sort_cmd_failed: Cell::new(false),
+            changes: RefCell::new(watch::Changes::default()),
         }
     }
 
     pub fn run(self, paths: Vec<PathBuf>) {
-        let mut meta_list = self.fetch(paths);
+        if self.flags.info.0 {
+            self.info(&paths);
+            return;
+        }
+        if self.flags.debug_readdir.0 {
+            self.debug_readdir(&paths);
+            return;
+        }
+        if !self.confirms_root_recursion(&paths) {
+            ExitCode::MajorIssue.report();
+            return;
+        }
+        if !self.flags.watch.0 {
+            self.list(paths);
+            return;
+        }
 
-        self.sort(&mut meta_list);
-        self.display(&meta_list)
+        let depth = self.depth();
+        loop {
+            let watcher = watch::Watcher::new(&paths, depth);
+            if self.tty {
+                // Clear the screen and move the cursor to its top left corner.
+                self.print("\x1b[2J\x1b[H");
+            }
+            self.list(paths.clone());
+            watcher.wait();
+        }
     }
 
-    fn fetch(&self, paths: Vec<PathBuf>) -> Vec<Meta> {
-        let mut meta_list = Vec::with_capacity(paths.len());
-        let depth = match self.flags.layout {
+    fn list(&self, paths: Vec<PathBuf>) {
+        let paths = if self.flags.glob.0 {
+            glob::expand(paths, &self.flags)
+        } else {
+            paths
+        };
+        let paths = if self.flags.keep_duplicates.0 {
+            paths
+        } else {
+            self.deduplicate(paths)
+        };
+        let paths = match self.flags.max_entries.0 {
+            Some(limit) => skip_large_dirs(paths, limit),
+            None => paths,
+        };
+
+        if self.flags.names_only.0 {
+            self.list_names(&paths);
+            return;
+        }
+
+        if self.flags.count != Count::None {
+            self.count(&paths);
+            return;
+        }
+
+        if self.streams_tree() {
+            self.stream_tree(paths);
+            return;
+        }
+
+        let (mut meta_list, remaining) = self.fetch(paths);
+
+        if self.flags.watch.0 {
+            // The deleted entries can only be told apart by being struck through.
+            let show_deleted = !self.colors.is_plain() && !self.flags.output_format.is_records();
+            self.changes
+                .borrow_mut()
+                .annotate(&mut meta_list, show_deleted);
+        }
+
+        if self.flags.sorting.path_order == PathOrder::Given {
+            for meta in &mut meta_list {
+                if let Some(content) = &mut meta.content {
+                    self.sort(content);
+                }
+            }
+        } else {
+            self.sort(&mut meta_list);
+        }
+        self.display(&meta_list);
+
+        if self.flags.layout == Layout::Tree && !remaining.is_empty() {
+            self.stream_tree(remaining);
+            return;
+        }
+        for path in remaining {
+            if self.flags.output_format.is_records() {
+                self.stream_records(&path);
+            } else {
+                self.stream(&path, self.depth());
+            }
+        }
+    }
+
+    /// Prints the names of the `paths`, then the names of the entries of the directories, one per
+    /// line. No metadata is read but to tell the directories, so this is as fast as a listing
+    /// gets.
+    fn list_names(&self, paths: &[PathBuf]) {
+        let mut output = String::new();
+        let (directories, files): (Vec<&PathBuf>, Vec<&PathBuf>) = paths.iter().partition(|path| {
+            self.flags.display != Display::DirectoryOnly
+                && matches!(readdir::metadata(path), Ok(metadata) if metadata.is_dir())
+        });
+
+        for path in files {
+            match readdir::symlink_metadata(path) {
+                Ok(_) => {
+                    output += &os_str_text(path.as_os_str(), self.flags.raw.0);
+                    output.push('\n');
+                }
+                Err(err) => {
+                    print_error!("{}: {}.", path.display(), err);
+                    ExitCode::MajorIssue.report();
+                }
+            }
+        }
+
+        for path in directories {
+            let entries = match readdir::read_dir(path) {
+                Ok(entries) => entries,
+                Err(err) => {
+                    print_error!("{}: {}.", path.display(), err);
+                    ExitCode::MajorIssue.report();
+                    continue;
+                }
+            };
+            let mut names: Vec<OsString> = Vec::new();
+            let hidden = match self.flags.display {
+                Display::VisibleOnly => Some(Hidden::for_directory(path)),
+                _ => None,
+            };
+            if self.flags.display == Display::All {
+                names.push(".".into());
+                names.push("..".into());
+            }
+            for entry in entries {
+                let name = match entry {
+                    Ok(entry) => entry.file_name(),
+                    Err(err) => {
+                        print_error!("{}: {}.", path.display(), err);
+                        ExitCode::MinorIssue.report();
+                        continue;
+                    }
+                };
+                let is_hidden = match &hidden {
+                    Some(hidden) => hidden.is_hidden(&path.join(&name), &name),
+                    None => false,
+                };
+                if is_hidden || self.flags.ignore_globs.0.is_match(&name) {
+                    continue;
+                }
+                names.push(name);
+            }
+
+            names.sort();
+            if self.flags.sorting.order == SortOrder::Reverse {
+                names.reverse();
+            }
+
+            if paths.len() > 1 {
+                output.push('\n');
+                output += &os_str_text(path.as_os_str(), self.flags.raw.0);
+                output += ":\n";
+            }
+            for name in names {
+                output += &os_str_text(&name, self.flags.raw.0);
+                output.push('\n');
+            }
+        }
+
+        self.print(&output);
+    }
+
+    /// Prints the number of entries listed for each of the `paths`, or their number by file
+    /// type, as filtered and walked for the listing. A path listed itself, such as a file, counts
+    /// as one entry.
+    fn count(&self, paths: &[PathBuf]) {
+        let recurse =
+            self.flags.layout == Layout::Tree || self.flags.display != Display::DirectoryOnly;
+        let depth = if recurse { self.depth() } else { 0 };
+
+        let mut output = String::new();
+        for path in paths {
+            if let Err(err) = path.symlink_metadata() {
+                print_error!("{}: {}.", path.display(), err);
+                ExitCode::MajorIssue.report();
+                continue;
+            }
+
+            let mut counts: Vec<(&str, usize)> = Vec::new();
+            for meta in meta::walk(path, depth, &self.flags) {
+                let kind = match meta {
+                    Ok(meta) => meta.file_type.kind(),
+                    Err((path, err)) => {
+                        print_error!("{}: {}.", path.display(), err);
+                        ExitCode::MinorIssue.report();
+                        continue;
+                    }
+                };
+                match counts.iter_mut().find(|(counted, _)| *counted == kind) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((kind, 1)),
+                }
+            }
+
+            if self.flags.count == Count::ByType {
+                if paths.len() > 1 {
+                    output += &format!("\n{}:\n", os_str_text(path.as_os_str(), self.flags.raw.0));
+                }
+                counts.sort_unstable();
+                for (kind, count) in counts {
+                    output += &format!("{} {}\n", count, kind);
+                }
+            } else {
+                let total: usize = counts.iter().map(|(_, count)| count).sum();
+                if paths.len() > 1 {
+                    output += &format!(
+                        "{} {}\n",
+                        total,
+                        os_str_text(path.as_os_str(), self.flags.raw.0)
+                    );
+                } else {
+                    output += &format!("{}\n", total);
+                }
+            }
+        }
+
+        self.print(&output);
+    }
+
+    /// Prints the comparison of the `left` and `right` directories, see [diff](diff::diff). They
+    /// are only recursed into with `--recursive` or `--tree`.
+    pub fn diff(self, left: &Path, right: &Path) {
+        for dir in &[left, right] {
+            match dir.metadata() {
+                Ok(metadata) if metadata.is_dir() => {}
+                Ok(_) => {
+                    print_error!("{}: Not a directory.", dir.display());
+                    ExitCode::MajorIssue.report();
+                    return;
+                }
+                Err(err) => {
+                    print_error!("{}: {}.", dir.display(), err);
+                    ExitCode::MajorIssue.report();
+                    return;
+                }
+            }
+        }
+
+        let output = diff::diff(
+            left,
+            right,
+            self.depth(),
+            &self.flags,
+            &self.colors,
+            &self.icons,
+        );
+        self.print(&output);
+    }
+
+    /// Prints the entries of the directories of `paths` as the system reads them, see
+    /// [debug_readdir](readdir::debug_readdir), each after its path when there are several.
+    fn debug_readdir(&self, paths: &[PathBuf]) {
+        for (idx, path) in paths.iter().enumerate() {
+            match readdir::debug_readdir(path, self.flags.raw.0) {
+                Ok(entries) if paths.len() > 1 => {
+                    let separator = if idx > 0 { "\n" } else { "" };
+                    let header = os_str_text(path.as_os_str(), self.flags.raw.0);
+                    self.print(&format!("{}{}:\n{}", separator, header, entries));
+                }
+                Ok(entries) => self.print(&entries),
+                Err(err) => {
+                    print_error!("{}: {}.", path.display(), err);
+                    ExitCode::MajorIssue.report();
+                }
+            }
+        }
+    }
+
+    /// Prints the detail panel of the single path of `paths`, see [info](info::info). The git
+    /// status is only looked for when the git feature is enabled.
+    fn info(&self, paths: &[PathBuf]) {
+        let path = match paths {
+            [path] => path,
+            _ => {
+                print_error!(
+                    "--info takes a single path, but {} were given.",
+                    paths.len()
+                );
+                ExitCode::MajorIssue.report();
+                return;
+            }
+        };
+
+        let dereference =
+            self.flags.dereference.0 && SymLink::resolves_within(path, self.flags.symlink_depth.0);
+        #[allow(unused_mut)]
+        let mut meta = match Meta::from_path(path, dereference) {
+            Ok(meta) => meta,
+            Err(err) => {
+                print_error!("{}: {}.", path.display(), err);
+                ExitCode::MajorIssue.report();
+                return;
+            }
+        };
+        #[cfg(feature = "git")]
+        meta.set_git_status(
+            &*GitCaches::new(self.flags.git_renames.0).get(path),
+            true,
+            &self.flags,
+        );
+
+        let output = info::info(&meta, &self.flags, &self.colors, &self.icons);
+        self.print(&output);
+    }
+
+    /// Prints the statistics of the files of each directory of `dirs`, see [stats](stats::stats),
+    /// after its path when there are several. The walk is as deep as `--depth`, or whole.
+    pub fn stats(self, dirs: &[PathBuf], grouping: stats::Grouping) {
+        for (idx, dir) in dirs.iter().enumerate() {
+            if let Err(err) = dir.metadata() {
+                print_error!("{}: {}.", dir.display(), err);
+                ExitCode::MajorIssue.report();
+                continue;
+            }
+
+            let output = stats::stats(
+                dir,
+                self.flags.recursion.depth,
+                grouping,
+                &self.flags,
+                &self.colors,
+            );
+            if dirs.len() > 1 {
+                let separator = if idx > 0 { "\n" } else { "" };
+                let header = os_str_text(dir.as_os_str(), self.flags.raw.0);
+                self.print(&format!("{}{}:\n{}", separator, header, output));
+            } else {
+                self.print(&output);
+            }
+        }
+    }
+
+    /// Removes the paths which would be listed twice: the ones given several times, and the ones
+    /// found while recursing into another given directory. The paths are compared once resolved,
+    /// see [resolve], and the ones which can not be are kept for their error to be printed.
+    fn deduplicate(&self, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        let resolved: Vec<Option<PathBuf>> = paths.iter().map(|path| resolve(path)).collect();
+        let recursing = self.depth() > 1
+            && (self.flags.layout == Layout::Tree || self.flags.display != Display::DirectoryOnly);
+
+        let mut kept = Vec::with_capacity(paths.len());
+        for (idx, path) in paths.into_iter().enumerate() {
+            let resolved_path = match &resolved[idx] {
+                Some(resolved_path) => resolved_path,
+                None => {
+                    kept.push(path);
+                    continue;
+                }
+            };
+            let duplicated = resolved[..idx].contains(&resolved[idx]);
+            let nested = recursing
+                && resolved
+                    .iter()
+                    .flatten()
+                    .any(|dir| dir.is_dir() && self.is_listed_within(resolved_path, dir));
+            if duplicated || nested {
+                log::debug!("{}: already listed, skipping it.", path.display());
+            } else {
+                kept.push(path);
+            }
+        }
+
+        kept
+    }
+
+    /// Whether `path` is listed when recursing into `dir`: it is below `dir`, within the depth
+    /// of the recursion, and none of the directories leading to it is hidden or ignored.
+    fn is_listed_within(&self, path: &Path, dir: &Path) -> bool {
+        let relative = match path.strip_prefix(dir) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative,
+            _ => return false,
+        };
+        if relative.components().count() >= self.depth() {
+            return false;
+        }
+
+        let mut parent = dir.to_path_buf();
+        let mut layers = Vec::new();
+        for name in relative.iter() {
+            let child = parent.join(name);
+            let is_hidden = self.flags.display == Display::VisibleOnly
+                && Hidden::for_directory(&parent).is_hidden(&child, name);
+            if self.flags.ignore_files.0 {
+                layers.extend(ignore_files::read(&parent));
+            }
+            if is_hidden
+                || self.flags.ignore_globs.0.is_match(name)
+                || self.flags.exclude_from.is_excluded(&child, child.is_dir())
+                || ignore_files::is_ignored(layers.iter(), &child, child.is_dir())
+            {
+                return false;
+            }
+            parent = child;
+        }
+
+        true
+    }
+
+    /// Whether to go on listing the `paths`. Recursing from the root of a filesystem without a
+    /// depth limit nor a filter lists every file of the system, which takes minutes and floods the
+    /// terminal, so a confirmation is asked for first when the listing is interactive, unless
+    /// `--yes` is passed. The listing is given up when it is declined.
+    fn confirms_root_recursion(&self, paths: &[PathBuf]) -> bool {
+        if self.flags.yes.0
+            || self.depth() != usize::MAX
+            || self.flags.find.0.is_some()
+            || !self.tty
+            || !stdin_is_tty()
+        {
+            return true;
+        }
+        let root = match paths.iter().find(|path| is_filesystem_root(path)) {
+            Some(root) => root,
+            None => return true,
+        };
+
+        eprint!(
+            "lsd: list every file under {} recursively? [y/N] ",
+            root.display()
+        );
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer).is_err() {
+            return false;
+        }
+        matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+
+    fn depth(&self) -> usize {
+        match self.flags.layout {
             Layout::Tree { .. } => self.flags.recursion.depth,
             _ if self.flags.recursion.enabled => self.flags.recursion.depth,
             _ => 1,
-        };
+        }
+    }
 
-        for path in paths {
-            let mut meta = match Meta::from_path(&path, self.flags.dereference.0) {
+    /// Collects the metadata of the `paths`. If this exceeds the memory limit, the paths which
+    /// could not be collected are returned along the collected ones, to be streamed.
+    fn fetch(&self, paths: Vec<PathBuf>) -> (Vec<Meta>, Vec<PathBuf>) {
+        let mut meta_list = Vec::with_capacity(paths.len());
+        let mut remaining = Vec::new();
+        let depth = self.depth();
+        let budget = MemoryBudget::new(self.flags.max_memory.bytes());
+        let mut caches = GitCaches::new(self.flags.git_renames.0);
+
+        let mut paths = paths.into_iter();
+        while let Some(path) = paths.next() {
+            let dereference = self.flags.dereference.0
+                && SymLink::resolves_within(&path, self.flags.symlink_depth.0);
+            let mut meta = match Meta::from_listed_path(&path, dereference, &self.flags) {
                 Ok(meta) => meta,
                 Err(err) => {
                     print_error!("{}: {}.", path.display(), err);
+                    ExitCode::MajorIssue.report();
                     continue;
                 }
             };
 
             let cache = if self.flags.blocks.0.contains(&Block::GitStatus) {
-                Some(GitCache::new(&path))
+                Some(caches.get(&path))
             } else {
                 None
             };
@@ -110,53 +622,315 @@ impl Core {
             let recurse =
                 self.flags.layout == Layout::Tree || self.flags.display != Display::DirectoryOnly;
             if recurse {
-                match meta.recurse_into(depth, &self.flags, cache.as_ref()) {
+                let provider = cache.as_ref().map(|c| &**c as &dyn GitStatusProvider);
+                match meta.recurse_into(depth, &self.flags, provider, &budget) {
                     Ok(content) => {
                         meta.content = content;
                         #[cfg(feature = "git")]
                         if let Some(cache) = cache {
-                            let is_directory = true;
-                            meta.git_status = match std::fs::canonicalize(&meta.path) {
-                                Ok(filename) => Some(cache.get(&filename, is_directory)),
-                                Err(err) => {
-                                    log::debug!("error {}", err);
-                                    None
-                                }
-                            };
+                            meta.set_git_status(&*cache, true, &self.flags);
                         };
+                        #[cfg(feature = "git")]
+                        meta.set_git_repo(&self.flags);
                         meta_list.push(meta);
                     }
+                    Err(_) if budget.is_exceeded() => {
+                        print_error!(
+                            "{}: memory limit of {} MB exceeded, listing the remaining files unsorted.",
+                            path.display(),
+                            self.flags.max_memory.0.unwrap_or_default()
+                        );
+                        remaining.push(path);
+                        remaining.extend(paths);
+                        break;
+                    }
                     Err(err) => {
-                        print_error!("lsd: {}: {}\n", path.display(), err);
+                        print_error!("{}: {}.", path.display(), err);
+                        ExitCode::MajorIssue.report();
                         continue;
                     }
                 };
             } else {
                 #[cfg(feature = "git")]
                 if let Some(cache) = cache {
-                    let is_directory = true;
-                    meta.git_status = match std::fs::canonicalize(&meta.path) {
-                        Ok(filename) => Some(cache.get(&filename, is_directory)),
-                        Err(err) => {
-                            log::debug!("error {}", err);
-                            None
-                        }
-                    };
+                    meta.set_git_status(&*cache, true, &self.flags);
                 };
+                #[cfg(feature = "git")]
+                meta.set_git_repo(&self.flags);
                 meta_list.push(meta);
             };
         }
         if self.flags.total_size.0 {
             for meta in &mut meta_list.iter_mut() {
-                meta.calculate_total_size();
+                meta.calculate_total_size(&self.flags);
+            }
+        }
+        // The entries are left out once the totals are summed up, which count them all.
+        if self.flags.find.0.is_some() {
+            for meta in &mut meta_list {
+                meta.retain_found(&self.flags);
+            }
+        }
+
+        (meta_list, remaining)
+    }
+
+    /// Lists `path` without sorting it nor collecting its whole content: each directory is
+    /// displayed before its sub directories are read, so only one is held in memory at a time.
+    fn stream(&self, path: &Path, depth: usize) {
+        let dereference =
+            self.flags.dereference.0 && SymLink::resolves_within(path, self.flags.symlink_depth.0);
+        let mut meta = match Meta::from_listed_path(path, dereference, &self.flags) {
+            Ok(meta) => meta,
+            Err(err) => {
+                print_error!("{}: {}.", path.display(), err);
+                ExitCode::MajorIssue.report();
+                return;
+            }
+        };
+
+        let mut content = match meta.recurse_into(1, &self.flags, None, &MemoryBudget::new(None)) {
+            Ok(Some(content)) => content,
+            Ok(None) => return self.display_streamed(&meta, ""),
+            Err(err) => {
+                print_error!("{}: {}.", path.display(), err);
+                ExitCode::MajorIssue.report();
+                return;
+            }
+        };
+
+        self.retain_streamed(&mut content);
+
+        let directories: Vec<PathBuf> = content
+            .iter()
+            .filter(|m| matches!(m.file_type, FileType::Directory { .. }))
+            .filter(|m| m.name.name != "." && m.name.name != "..")
+            .map(|m| m.path.clone())
+            .collect();
+
+        // The sub directories are separated from the listing of their parent.
+        let separator = if depth < self.depth() { "\n" } else { "" };
+        let header = format!(
+            "{}{}:\n",
+            separator,
+            os_str_text(meta.path.as_os_str(), self.flags.raw.0)
+        );
+        meta.content = Some(content);
+        self.display_streamed(&meta, &header);
+
+        if depth > 1 {
+            for directory in directories {
+                self.stream(&directory, depth - 1);
+            }
+        }
+    }
+
+    /// Whether the tree is printed as it is read, see [stream_tree](Core::stream_tree). It is
+    /// collected whole when the total sizes of the directories are needed, the width of its
+    /// widest row to shade them, which directories hold the entries found, or the entries which
+    /// changed in the watch mode. The directories left when the memory limit is exceeded are
+    /// streamed whatever this returns.
+    fn streams_tree(&self) -> bool {
+        self.flags.layout == Layout::Tree
+            && !self.flags.output_format.is_records()
+            && !self.flags.watch.0
+            && !self.flags.total_size.0
+            && !self.flags.table.shade_rows.0
+            && self.flags.find.0.is_none()
+    }
+
+    /// Keeps in the `content` of a directory listed as it is read only the entries found and
+    /// the directories, which may hold some but can not be left out before being read.
+    fn retain_streamed(&self, content: &mut Vec<Meta>) {
+        if self.flags.find.0.is_some() {
+            content.retain(|meta| {
+                matches!(meta.file_type, FileType::Directory { .. })
+                    || self.flags.find.is_match(&meta.name.name)
+            });
+        }
+    }
+
+    /// Lists the `paths` as a tree printed depth first as its directories are read, instead of
+    /// being collected whole, so that only the entries of the directories from the listed ones
+    /// down to the one being read are held in memory. Each directory is sorted and its blocks
+    /// are aligned on their own, as they are when the tree is collected.
+    fn stream_tree(&self, paths: Vec<PathBuf>) {
+        let mut caches = GitCaches::new(self.flags.git_renames.0);
+        let mut metas = Vec::with_capacity(paths.len());
+        for path in paths {
+            let dereference = self.flags.dereference.0
+                && SymLink::resolves_within(&path, self.flags.symlink_depth.0);
+            match Meta::from_listed_path(&path, dereference, &self.flags) {
+                #[cfg_attr(not(feature = "git"), allow(unused_mut))]
+                Ok(mut meta) => {
+                    #[cfg(feature = "git")]
+                    {
+                        if self.flags.blocks.0.contains(&Block::GitStatus) {
+                            meta.set_git_status(&*caches.get(&path), true, &self.flags);
+                        }
+                        meta.set_git_repo(&self.flags);
+                    }
+                    metas.push(meta);
+                }
+                Err(err) => {
+                    print_error!("{}: {}.", path.display(), err);
+                    ExitCode::MajorIssue.report();
+                }
+            }
+        }
+        if self.flags.sorting.path_order != PathOrder::Given {
+            self.sort(&mut metas);
+        }
+
+        let mut totals = display::Totals::default();
+        let (header, rows) = display::tree_rows(&metas, &self.flags, &self.colors, &self.icons, 0);
+        if let Some(header) = header {
+            self.print(&(header + "\n"));
+        }
+        for (meta, row) in metas.iter().zip(rows) {
+            self.print(&display::tree_row(
+                &row,
+                "",
+                0,
+                false,
+                &self.flags,
+                &self.colors,
+            ));
+
+            let cache = if self.flags.blocks.0.contains(&Block::GitStatus) {
+                Some(caches.get(&meta.path))
+            } else {
+                None
+            };
+            let provider = cache.as_ref().map(|c| &**c as &dyn GitStatusProvider);
+            let listed = self.stream_tree_level(
+                meta,
+                provider,
+                &meta::Ancestors::default(),
+                1,
+                "",
+                &mut totals,
+            );
+            // As for `tree`, the listed directories are not counted, only their content.
+            if !listed {
+                totals.add(meta);
             }
         }
 
-        meta_list
+        if self.flags.summary.0 {
+            self.print(&format!("\n{}\n", totals.render(&self.flags)));
+        }
+    }
+
+    /// Prints the content of `dir`, a level `depth` down a streamed tree under the `prefix` of
+    /// its edges, then the content of each directory of it right after its row, counting the
+    /// entries into the `totals`. Returns whether `dir` is a directory listed.
+    fn stream_tree_level(
+        &self,
+        dir: &Meta,
+        cache: Option<&dyn GitStatusProvider>,
+        ancestors: &meta::Ancestors,
+        depth: usize,
+        prefix: &str,
+        totals: &mut display::Totals,
+    ) -> bool {
+        let entered = depth < self.depth();
+        let (mut content, ancestors) = match dir.walk_level(&self.flags, cache, ancestors, entered)
+        {
+            Ok(Some(level)) => level,
+            Ok(None) => return false,
+            // As when the tree is collected, only the errors of the listed directories are
+            // serious, the ones of their sub directories being minor.
+            Err(err) => {
+                print_error!("{}: {}.", dir.path.display(), err);
+                if depth == 1 {
+                    ExitCode::MajorIssue.report();
+                } else {
+                    ExitCode::MinorIssue.report();
+                }
+                return false;
+            }
+        };
+        // Only reached with `--find` for the directories left past the memory limit.
+        self.retain_streamed(&mut content);
+        self.sort(&mut content);
+
+        let shown = &content[..display::tree_shown(content.len(), depth, &self.flags)];
+        let (_, rows) = display::tree_rows(shown, &self.flags, &self.colors, &self.icons, depth);
+        let last_idx = content.len();
+        for (idx, (meta, row)) in shown.iter().zip(rows).enumerate() {
+            let is_last = idx + 1 == last_idx;
+            self.print(&display::tree_row(
+                &row,
+                prefix,
+                depth,
+                is_last,
+                &self.flags,
+                &self.colors,
+            ));
+            totals.add(meta);
+
+            if entered && meta.is_entered(&self.flags) && meta.content_error.is_none() {
+                let prefix =
+                    display::tree_prefix(prefix, depth, is_last, &self.flags, &self.colors);
+                self.stream_tree_level(meta, cache, &ancestors, depth + 1, &prefix, totals);
+            }
+        }
+        // The entries left out are counted, but not their content.
+        for meta in &content[shown.len()..] {
+            totals.add(meta);
+        }
+        if shown.len() < content.len() {
+            let omitted = content.len() - shown.len();
+            self.print(&display::tree_omitted_row(
+                omitted,
+                prefix,
+                depth,
+                &self.flags,
+                &self.colors,
+            ));
+        }
+        true
+    }
+
+    /// Prints the records of the files of `path` as they are walked, as they do not need to be
+    /// grouped by directory.
+    fn stream_records(&self, path: &Path) {
+        for meta in meta::walk(path, self.depth(), &self.flags) {
+            match meta {
+                Ok(meta) if !self.flags.find.is_match(&meta.name.name) => {}
+                Ok(meta) => {
+                    let metas = std::slice::from_ref(&meta);
+                    self.print(&display::records(metas, &self.flags, false));
+                }
+                Err((path, err)) => {
+                    print_error!("{}: {}.", path.display(), err);
+                    ExitCode::MinorIssue.report();
+                }
+            }
+        }
     }
 
     fn sort(&self, metas: &mut Vec<Meta>) {
-        metas.sort_unstable_by(|a, b| sort::by_meta(&self.sorters, a, b));
+        let keys = match &self.flags.sort_cmd.0 {
+            // A single file is not worth running the command.
+            Some(command) if metas.len() > 1 && !self.sort_cmd_failed.get() => {
+                match sort::command_keys(command, metas) {
+                    Ok(keys) => Some(keys),
+                    Err(reason) => {
+                        print_error!("sort command `{}` failed: {}.", command, reason);
+                        ExitCode::MinorIssue.report();
+                        self.sort_cmd_failed.set(true);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        match keys {
+            Some(keys) => sort::by_keys(&self.flags, &self.sorters, metas, keys),
+            None => metas.sort_unstable_by(|a, b| sort::by_meta(&self.sorters, a, b)),
+        }
 
         for meta in metas {
             if let Some(ref mut content) = meta.content {
@@ -165,13 +939,158 @@ impl Core {
         }
     }
 
+    /// Displays a `meta` listed by [stream](Core::stream), after the `header` naming it. The
+    /// records do not need it, as they hold the whole path of each file.
+    fn display_streamed(&self, meta: &Meta, header: &str) {
+        let metas = std::slice::from_ref(meta);
+        let output = if self.flags.output_format.is_records() {
+            display::records(metas, &self.flags, false)
+        } else {
+            header.to_string() + &display::grid(metas, &self.flags, &self.colors, &self.icons)
+        };
+
+        self.print(&output);
+    }
+
     fn display(&self, metas: &[Meta]) {
-        let output = if self.flags.layout == Layout::Tree {
+        let output = if self.flags.output_format.is_records() {
+            display::records(metas, &self.flags, true)
+        } else if self.flags.layout == Layout::Tree {
             display::tree(&metas, &self.flags, &self.colors, &self.icons)
         } else {
             display::grid(&metas, &self.flags, &self.colors, &self.icons)
         };
 
-        print_output!("{}", output);
+        self.print(&output);
+    }
+
+    /// Prints `output`, to the pager if there is one, the bytes of the names which are not valid
+    /// UTF-8 being written back as they are in the raw mode.
+    fn print(&self, output: &str) {
+        use std::io::Write;
+
+        let bytes = if self.flags.raw.0 {
+            raw_bytes(output)
+        } else {
+            Cow::Borrowed(output.as_bytes())
+        };
+        let mut pager = self.pager.borrow_mut();
+        let written = match pager.as_mut().and_then(|pager| pager.stdin.as_mut()) {
+            Some(stdin) => stdin.write_all(&bytes),
+            None => std::io::stdout().lock().write_all(&bytes),
+        };
+        // As with print_output, a closed output, or a pager which was quit, ends the listing
+        // successfully.
+        if written.is_err() {
+            std::process::exit(0);
+        }
+    }
+}
+
+impl Drop for Core {
+    /// Waits for the user to quit the pager, once all the output has been written to it.
+    fn drop(&mut self) {
+        if let Some(mut pager) = self.pager.get_mut().take() {
+            drop(pager.stdin.take());
+            let _ = pager.wait();
+        }
+    }
+}
+
+/// Removes the directories of the `paths` having more than `limit` entries, printing an error for
+/// each of them. The entries are only counted up to the limit, so this is fast even for the huge
+/// directories.
+fn skip_large_dirs(paths: Vec<PathBuf>, limit: usize) -> Vec<PathBuf> {
+    paths
+        .into_iter()
+        .filter(|path| {
+            let count = match fs::read_dir(path) {
+                Ok(entries) => entries.take(limit + 1).count(),
+                Err(_) => return true,
+            };
+            if count > limit {
+                print_error!(
+                    "{}: more than {} entries, not listed.",
+                    path.display(),
+                    limit
+                );
+                ExitCode::MinorIssue.report();
+            }
+            count <= limit
+        })
+        .collect()
+}
+
+/// Whether `path` is the root of a filesystem, as `/` or a mount point, whose parent is on
+/// another device or is the directory itself.
+#[cfg(unix)]
+fn is_filesystem_root(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (fs::metadata(path), fs::metadata(path.join(".."))) {
+        (Ok(meta), Ok(parent)) => meta.dev() != parent.dev() || meta.ino() == parent.ino(),
+        _ => false,
+    }
+}
+
+/// Whether `path` is the root of a drive on Windows.
+#[cfg(windows)]
+fn is_filesystem_root(path: &Path) -> bool {
+    match fs::canonicalize(path) {
+        Ok(path) => path.parent().is_none(),
+        Err(_) => false,
+    }
+}
+
+/// Whether the standard input is a terminal the answer to a question can be read from. On
+/// Windows, this is told by the standard output being one.
+#[cfg(not(target_os = "windows"))]
+fn stdin_is_tty() -> bool {
+    unsafe { libc::isatty(io::stdin().as_raw_fd()) == 1 }
+}
+
+#[cfg(target_os = "windows")]
+fn stdin_is_tty() -> bool {
+    true
+}
+
+/// Runs the pager set in the `PAGER` environment variable, or `less -RFX`, reading the output on
+/// its standard input. Should it fail, an error is printed and the output is not paginated.
+fn spawn_pager() -> Option<Child> {
+    let command = std::env::var("PAGER")
+        .ok()
+        .filter(|command| !command.trim().is_empty())
+        .unwrap_or_else(|| "less -RFX".to_string());
+    let mut words = command.split_whitespace();
+    let program = words.next()?;
+    match Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(pager) => Some(pager),
+        Err(err) => {
+            print_error!("Can not run the pager {}: {}.", command, err);
+            None
+        }
+    }
+}
+
+/// The absolute path of the file listed for `path`: a symlink is listed itself, unless `path` ends
+/// with a separator and it is followed.
+fn resolve(path: &Path) -> Option<PathBuf> {
+    let followed = path.to_string_lossy().ends_with(std::path::is_separator);
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !followed => {
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            let resolved = fs::canonicalize(parent).ok()?.join(name);
+            resolved.symlink_metadata().ok()?;
+            Some(resolved)
+        }
+        _ => fs::canonicalize(path).ok(),
     }
 }
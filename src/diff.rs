@@ -0,0 +1,220 @@
+//! The comparison of two directories, listed together with a mark telling for each entry whether
+//! it is only in one of them, differs between them or is identical.
+
+use crate::color::{Colors, Elem};
+use crate::core::ExitCode;
+use crate::flags::Flags;
+use crate::icon::Icons;
+use crate::meta::name::DisplayOption;
+use crate::meta::{self, FileType, Meta};
+use crate::print_error;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// How an entry of the compared directories differs between them.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Difference {
+    /// The entry is only in the first directory.
+    Removed,
+    /// The entry is only in the second directory.
+    Added,
+    /// The entry is in both directories, with the listed attributes differing.
+    Changed(Vec<&'static str>),
+    Same,
+}
+
+impl Difference {
+    /// Compares the entries found at the same path in both directories, by their type, then by
+    /// their permissions and, for the ones which are not directories, their size and modification
+    /// date.
+    pub fn new(left: &Meta, right: &Meta) -> Self {
+        if left.file_type.kind() != right.file_type.kind() {
+            return Self::Changed(vec!["type"]);
+        }
+
+        let mut changes = Vec::new();
+        if !matches!(left.file_type, FileType::Directory { .. }) {
+            if left.size.get_bytes() != right.size.get_bytes() {
+                changes.push("size");
+            }
+            if left.date != right.date {
+                changes.push("modified");
+            }
+        }
+        if left.permissions != right.permissions {
+            changes.push("permissions");
+        }
+
+        if changes.is_empty() {
+            Self::Same
+        } else {
+            Self::Changed(changes)
+        }
+    }
+
+    fn mark(&self) -> (&'static str, Elem) {
+        match self {
+            Self::Removed => ("-", Elem::DiffRemoved),
+            Self::Added => ("+", Elem::DiffAdded),
+            Self::Changed(_) => ("~", Elem::DiffChanged),
+            Self::Same => ("=", Elem::DiffSame),
+        }
+    }
+}
+
+/// Lists the entries of the `left` and `right` directories, walked up to `depth` levels down as
+/// for the listing, sorted by their path relative to the directories. Each entry is preceded by
+/// its [Difference] mark, `-` for the entries only in `left`, `+` for the ones only in `right`,
+/// `~` for the changed ones, followed by their changes, and `=` for the identical ones. The
+/// content of the directories which are only on one side is not listed.
+pub fn diff(
+    left: &Path,
+    right: &Path,
+    depth: usize,
+    flags: &Flags,
+    colors: &Colors,
+    icons: &Icons,
+) -> String {
+    let mut sides: BTreeMap<PathBuf, (Option<Meta>, Option<Meta>)> = BTreeMap::new();
+    for (path, meta) in entries(left, depth, flags) {
+        sides.entry(path).or_default().0 = Some(meta);
+    }
+    for (path, meta) in entries(right, depth, flags) {
+        sides.entry(path).or_default().1 = Some(meta);
+    }
+
+    let mut output = String::new();
+    let mut one_sided_dir: Option<PathBuf> = None;
+    for (path, side) in sides {
+        if let Some(dir) = &one_sided_dir {
+            if path.starts_with(dir) {
+                continue;
+            }
+        }
+
+        let (difference, meta, base_path) = match side {
+            (Some(left_meta), Some(right_meta)) => {
+                (Difference::new(&left_meta, &right_meta), right_meta, right)
+            }
+            (Some(left_meta), None) => (Difference::Removed, left_meta, left),
+            (None, Some(right_meta)) => (Difference::Added, right_meta, right),
+            (None, None) => continue,
+        };
+        let one_sided = matches!(difference, Difference::Removed | Difference::Added);
+        if one_sided && matches!(meta.file_type, FileType::Directory { .. }) {
+            one_sided_dir = Some(path);
+        }
+
+        let (mark, elem) = difference.mark();
+        output += &colors.colorize(mark.to_string(), &elem).to_string();
+        output.push(' ');
+        output += &meta
+            .name
+            .render_truncated(
+                colors,
+                icons,
+                &DisplayOption::Relative { base_path },
+                None,
+                flags.raw.0,
+            )
+            .to_string();
+        output += &meta.indicator.render(flags).to_string();
+        if let Difference::Changed(changes) = &difference {
+            let changes = format!("  ({})", changes.join(", "));
+            output += &colors.colorize(changes, &elem).to_string();
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+/// The entries of the directory `dir` by their path relative to it, without the `.` and `..`
+/// ones.
+fn entries(dir: &Path, depth: usize, flags: &Flags) -> Vec<(PathBuf, Meta)> {
+    let mut entries = Vec::new();
+    for meta in meta::walk(dir, depth, flags) {
+        match meta {
+            Ok(meta) => {
+                if meta.name.name == "." || meta.name.name == ".." {
+                    continue;
+                }
+                if let Ok(path) = meta.path.strip_prefix(dir) {
+                    entries.push((path.to_path_buf(), meta));
+                }
+            }
+            Err((path, err)) => {
+                print_error!("{}: {}.", path.display(), err);
+                ExitCode::MinorIssue.report();
+            }
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod test {
+    use super::{diff, Difference};
+    use crate::app;
+    use crate::color::{self, Colors};
+    use crate::config_file::Config;
+    use crate::flags::Flags;
+    use crate::icon::{self, Icons};
+    use crate::meta::Meta;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_diff() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let left = tmp_dir.path().join("left");
+        let right = tmp_dir.path().join("right");
+        for dir in &[&left, &right] {
+            fs::create_dir_all(dir.join("both")).unwrap();
+            fs::write(dir.join("same"), "same").unwrap();
+        }
+        fs::write(left.join("changed"), "left").unwrap();
+        fs::write(right.join("changed"), "right side").unwrap();
+        fs::write(left.join("removed"), "").unwrap();
+        fs::create_dir_all(right.join("added/sub")).unwrap();
+
+        let matches = app::build()
+            .get_matches_from_safe(vec!["lsd", "--recursive"])
+            .unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+        let output = diff(
+            &left,
+            &right,
+            flags.recursion.depth,
+            &flags,
+            &Colors::new(color::Theme::NoColor),
+            &Icons::new(icon::Theme::NoIcon, " ".to_string()),
+        );
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!("+ added", lines[0]);
+        assert_eq!("= both", lines[1]);
+        assert!(lines[2].starts_with("~ changed  (size"));
+        assert_eq!("- removed", lines[3]);
+        // The files written one after the other may have different dates.
+        assert!(lines[4] == "= same" || lines[4] == "~ same  (modified)");
+        assert_eq!(5, lines.len());
+    }
+
+    #[test]
+    fn test_difference() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file = tmp_dir.path().join("file");
+        fs::write(&file, "content").unwrap();
+        fs::create_dir(tmp_dir.path().join("dir")).unwrap();
+
+        let file = Meta::from_path(&file, false).unwrap();
+        let dir = Meta::from_path(&tmp_dir.path().join("dir"), false).unwrap();
+        assert_eq!(Difference::Same, Difference::new(&file, &file));
+        assert_eq!(
+            Difference::Changed(vec!["type"]),
+            Difference::new(&file, &dir)
+        );
+    }
+}
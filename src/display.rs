@@ -1,29 +1,40 @@
-use crate::color::{ColoredString, Colors};
-use crate::flags::{Block, Display, Flags, Layout};
+use crate::color::{ColoredString, Colors, Elem};
+use crate::flags::{Block, Display, Flags, Layout, OutputFormat, SizeFlag, TruncateNames};
 use crate::icon::Icons;
-use crate::meta::name::DisplayOption;
-use crate::meta::{FileType, Meta};
+use crate::meta::name::{os_str_text, DisplayOption, Truncation};
+use crate::meta::{Date, ExecPolicy, FileType, Filesystem, Kind, Meta, Size};
 use ansi_term::{ANSIString, ANSIStrings};
 use std::collections::HashMap;
+use std::path::Path;
 use term_grid::{Cell, Direction, Filling, Grid, GridOptions};
 use terminal_size::terminal_size;
 use unicode_width::UnicodeWidthStr;
 
-const EDGE: &str = "\u{251c}\u{2500}\u{2500}";
-// "├──"
-const LINE: &str = "\u{2502}  ";
-// "├  "
-const CORNER: &str = "\u{2514}\u{2500}\u{2500}";
-// "└──"
-const BLANK: &str = "   ";
-
 pub fn grid(metas: &[Meta], flags: &Flags, colors: &Colors, icons: &Icons) -> String {
     let term_width = match terminal_size() {
         Some((w, _)) => Some(w.0 as usize),
         None => None,
     };
 
-    inner_display_grid(
+    // Without a width to shorten the names to, the ones so long that they would leave a single
+    // column are shortened in the middle to fit two, with their icon and indicator.
+    let auto_truncated;
+    let flags = match term_width {
+        Some(term_width)
+            if flags.truncate_middle.0
+                && flags.truncate_names.0.is_none()
+                && flags.columns.0.is_none() =>
+        {
+            auto_truncated = Flags {
+                truncate_names: TruncateNames(Some((term_width / 2).saturating_sub(4))),
+                ..flags.clone()
+            };
+            &auto_truncated
+        }
+        _ => flags,
+    };
+
+    let mut output = inner_display_grid(
         &DisplayOption::None,
         metas,
         &flags,
@@ -31,13 +42,150 @@ pub fn grid(metas: &[Meta], flags: &Flags, colors: &Colors, icons: &Icons) -> St
         icons,
         0,
         term_width,
-    )
+        None,
+    );
+    // The folder paths are separated from what is listed before them, but the first one when
+    // nothing is.
+    if output.starts_with('\n') {
+        output.remove(0);
+    }
+    output
 }
 
 pub fn tree(metas: &[Meta], flags: &Flags, colors: &Colors, icons: &Icons) -> String {
-    inner_display_tree(metas, &flags, colors, icons, 0, "")
+    let mut output = inner_display_tree(metas, &flags, colors, icons, 0, "");
+    if flags.summary.0 {
+        let mut totals = Totals::default();
+        for meta in metas {
+            // As for `tree`, the listed directories are not counted, only their content.
+            match &meta.content {
+                Some(content) => totals.add_tree(content, 1, flags),
+                None => totals.add(meta),
+            }
+        }
+        output += &format!("\n{}\n", totals.render(flags));
+    }
+
+    if flags.table.shade_rows.0 {
+        let header_rows = if flags.header.0 && !metas.is_empty() {
+            1
+        } else {
+            0
+        };
+        shade_rows(&output, header_rows, colors)
+    } else {
+        output
+    }
+}
+
+/// The columns of the records, in the order they are printed.
+const RECORD_COLUMNS: &[&str] = &[
+    "path",
+    "type",
+    "mode",
+    "permissions",
+    "uid",
+    "user",
+    "gid",
+    "group",
+    "links",
+    "inode",
+    "size",
+    "modified",
+    "modified-epoch",
+    "created",
+    "created-epoch",
+    "target",
+];
+
+/// Prints one record per listed file, in the CSV or TSV format, preceded by a row naming the
+/// columns when `with_columns` is set.
+pub fn records(metas: &[Meta], flags: &Flags, with_columns: bool) -> String {
+    let mut output = String::new();
+
+    if with_columns {
+        output += &record_line(RECORD_COLUMNS.iter().map(|c| c.to_string()), flags);
+    }
+    inner_display_records(&mut output, metas, flags, 0);
+
+    output
+}
+
+fn inner_display_records(output: &mut String, metas: &[Meta], flags: &Flags, depth: usize) {
+    // As for the layouts, the directories given by the user are replaced by their content.
+    let skip_dirs = (depth == 0) && (flags.display != Display::DirectoryOnly);
+
+    for meta in metas {
+        // The records hold the whole path, so the directories holding the entries found are not
+        // needed to show them in context.
+        let found = depth == 0 || flags.find.is_match(&meta.name.name);
+        if found && !(skip_dirs && meta.file_type.is_dirlike() && meta.content.is_some()) {
+            output.push_str(&record_line(record_fields(meta, flags.raw.0), flags));
+        }
+
+        if let Some(content) = &meta.content {
+            inner_display_records(output, content, flags, depth + 1);
+        }
+    }
 }
 
+fn record_fields(meta: &Meta, raw: bool) -> impl Iterator<Item = String> {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+
+    vec![
+        os_str_text(meta.path.as_os_str(), raw),
+        meta.file_type.kind().to_string(),
+        format!("{:04o}", meta.permissions.mode()),
+        meta.permissions
+            .render(&Colors::new(crate::color::Theme::NoColor))
+            .to_string(),
+        optional(meta.owner.uid().map(|uid| uid.to_string())),
+        meta.owner.user(),
+        optional(meta.owner.gid().map(|gid| gid.to_string())),
+        meta.owner.group(),
+        optional(meta.links.count().map(|count| count.to_string())),
+        optional(meta.inode.index().map(|index| index.to_string())),
+        meta.size.get_bytes().to_string(),
+        meta.date.rfc3339(),
+        meta.date.epoch(),
+        optional(meta.created.as_ref().map(Date::rfc3339)),
+        optional(meta.created.as_ref().map(Date::epoch)),
+        optional(meta.symlink.symlink_string()),
+    ]
+    .into_iter()
+}
+
+fn record_line(fields: impl Iterator<Item = String>, flags: &Flags) -> String {
+    let separator = match flags.output_format {
+        OutputFormat::Tsv => "\t",
+        _ => ",",
+    };
+    let fields: Vec<String> = fields
+        .map(|field| escape_field(&field, flags.output_format))
+        .collect();
+
+    fields.join(separator) + "\n"
+}
+
+/// Escapes a field of a record. CSV fields are quoted as described by RFC 4180, while the TSV
+/// fields have their tabs, line breaks and backslashes escaped with a backslash.
+fn escape_field(field: &str, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Tsv => field
+            .replace('\\', "\\\\")
+            .replace('\t', "\\t")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r"),
+        _ if field.contains(&[',', '"', '\n', '\r'][..]) => {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        }
+        _ => field.to_string(),
+    }
+}
+
+/// Displays the `metas` in a grid, followed by the content of the directories under their path,
+/// `elided_prefix` being replaced with an ellipsis in these paths.
+#[allow(clippy::too_many_arguments)]
 fn inner_display_grid(
     display_option: &DisplayOption,
     metas: &[Meta],
@@ -46,13 +194,133 @@ fn inner_display_grid(
     icons: &Icons,
     depth: usize,
     term_width: Option<usize>,
+    elided_prefix: Option<&Path>,
 ) -> String {
     let mut output = String::new();
 
     let padding_rules = get_padding_rules(&metas, flags);
+
+    // The first iteration (depth == 0) corresponds to the inputs given by the
+    // user. We defer displaying directories given by the user unless we've been
+    // asked to display the directory itself (rather than its contents).
+    let skip_dirs = (depth == 0) && (flags.display != Display::DirectoryOnly);
+    let is_skipped = |meta: &Meta| {
+        skip_dirs
+            && (matches!(meta.file_type, FileType::Directory { .. })
+                || (matches!(meta.file_type, FileType::SymLink { is_dir: true })
+                    && flags.layout != Layout::OneLine))
+    };
+
+    let with_header =
+        flags.header.0 && flags.layout != Layout::Grid && metas.iter().any(|m| !is_skipped(m));
+
+    // print the files first.
+    let files: Vec<&Meta> = metas.iter().filter(|meta| !is_skipped(meta)).collect();
+    if flags.group_devices.0 && depth > 0 {
+        let (others, groups) = group_devices(files);
+        output += &display_files(
+            display_option,
+            &others,
+            flags,
+            colors,
+            icons,
+            &padding_rules,
+            with_header,
+            term_width,
+        );
+        for (subsystem, devices) in groups {
+            output += &format!("\n{}* ({}):\n", subsystem, devices.len());
+            output += &display_files(
+                display_option,
+                &devices,
+                flags,
+                colors,
+                icons,
+                &padding_rules,
+                false,
+                term_width,
+            );
+        }
+    } else {
+        output += &display_files(
+            display_option,
+            &files,
+            flags,
+            colors,
+            icons,
+            &padding_rules,
+            with_header,
+            term_width,
+        );
+    }
+
+    if flags.summary.0 && depth > 0 {
+        let mut totals = Totals::default();
+        metas.iter().for_each(|meta| totals.add(meta));
+        output += &totals.render(flags);
+        output += "\n";
+    }
+
+    let should_display_folder_path = should_display_folder_path(depth, &metas, &flags);
+
+    // print the folder content
+    let mut elided_prefix = elided_prefix;
+    for meta in metas {
+        if meta.content.is_some() {
+            if should_display_folder_path {
+                output += &display_folder_path(&meta, elided_prefix, flags.raw.0);
+
+                // The path listed by the user is shown in full in the first folder path only.
+                if flags.elide_prefix.0 && elided_prefix.is_none() {
+                    elided_prefix = match depth {
+                        0 => Some(&meta.path),
+                        _ => meta.path.parent(),
+                    };
+                }
+            }
+
+            let display_option = DisplayOption::Relative {
+                base_path: &meta.path,
+            };
+
+            output += &inner_display_grid(
+                &display_option,
+                meta.content.as_ref().unwrap(),
+                &flags,
+                colors,
+                icons,
+                depth + 1,
+                term_width,
+                elided_prefix,
+            );
+
+            if depth == 0 {
+                elided_prefix = None;
+            }
+        }
+    }
+
+    output
+}
+
+/// Displays the `metas` in a grid, after the header when `with_header` is set, without the
+/// content of the directories.
+#[allow(clippy::too_many_arguments)]
+fn display_files(
+    display_option: &DisplayOption,
+    metas: &[&Meta],
+    flags: &Flags,
+    colors: &Colors,
+    icons: &Icons,
+    padding_rules: &HashMap<Block, usize>,
+    with_header: bool,
+    term_width: Option<usize>,
+) -> String {
+    let mut output = String::new();
+
     let mut grid = match flags.layout {
         Layout::OneLine => Grid::new(GridOptions {
-            filling: Filling::Spaces(1),
+            filling: block_filling(flags, colors),
             direction: Direction::LeftToRight,
         }),
         _ => Grid::new(GridOptions {
@@ -61,19 +329,30 @@ fn inner_display_grid(
         }),
     };
 
-    // The first iteration (depth == 0) corresponds to the inputs given by the
-    // user. We defer displaying directories given by the user unless we've been
-    // asked to display the directory itself (rather than its contents).
-    let skip_dirs = (depth == 0) && (flags.display != Display::DirectoryOnly);
+    if with_header {
+        add_header(&mut grid, colors, flags);
+    }
+
+    // Without colors nor icons, a listing of the names alone is made of plain strings, which
+    // are printed line by line when there is a single column and no header in the grid.
+    let plain = colors.is_plain() && icons.is_plain() && flags.blocks.0 == [Block::Name];
+    let fits_columns = flags.columns.0.is_some() || term_width.is_some();
+    let plain_lines = plain && !with_header && (flags.layout != Layout::Grid || !fits_columns);
 
-    // print the files first.
     for meta in metas {
-        // Maybe skip showing the directory meta now; show its contents later.
-        if skip_dirs
-            && (matches!(meta.file_type, FileType::Directory { .. })
-            || (matches!(meta.file_type, FileType::SymLink { is_dir: true })
-            && flags.layout != Layout::OneLine))
-        {
+        if plain {
+            let name = plain_name(meta, colors, flags, display_option);
+
+            if plain_lines {
+                output += &name;
+                output.push('\n');
+                continue;
+            }
+
+            grid.add(Cell {
+                width: UnicodeWidthStr::width(&*name),
+                contents: name,
+            });
             continue;
         }
 
@@ -83,7 +362,7 @@ fn inner_display_grid(
             &icons,
             &flags,
             &display_option,
-            &padding_rules,
+            padding_rules,
         );
 
         for block in blocks {
@@ -96,8 +375,13 @@ fn inner_display_grid(
         }
     }
 
-    if flags.layout == Layout::Grid {
-        if let Some(tw) = term_width {
+    if plain_lines {
+        // The names have already been printed.
+    } else if flags.layout == Layout::Grid {
+        if let Some(columns) = flags.columns.0 {
+            // The lines longer than the terminal are left for it to wrap.
+            output += &grid.fit_into_columns(columns).to_string();
+        } else if let Some(tw) = term_width {
             if let Some(gridded_output) = grid.fit_into_width(tw) {
                 output += &gridded_output.to_string();
             } else {
@@ -110,55 +394,155 @@ fn inner_display_grid(
             output += &grid.fit_into_columns(1).to_string();
         }
     } else {
-        output += &grid.fit_into_columns(flags.blocks.0.len()).to_string();
+        let content = grid.fit_into_columns(flags.blocks.0.len()).to_string();
+        if flags.table.shade_rows.0 {
+            output += &shade_rows(&content, if with_header { 1 } else { 0 }, colors);
+        } else {
+            output += &content;
+        }
     }
 
-    let should_display_folder_path = should_display_folder_path(depth, &metas, &flags);
+    output
+}
 
-    // print the folder content
-    for meta in metas {
-        if meta.content.is_some() {
-            if should_display_folder_path {
-                output += &display_folder_path(&meta);
+/// Splits the devices out of the `metas`, grouped by their subsystem in the order they are
+/// listed. The other files are kept in their order, with the devices alone of their subsystem.
+fn group_devices(metas: Vec<&Meta>) -> (Vec<&Meta>, Vec<(&str, Vec<&Meta>)>) {
+    fn subsystem(meta: &Meta) -> Option<&str> {
+        match meta.file_type {
+            FileType::BlockDevice | FileType::CharDevice => Some(device_subsystem(&meta.name.name)),
+            _ => None,
+        }
+    }
+
+    let mut groups: Vec<(&str, Vec<&Meta>)> = Vec::new();
+    for meta in &metas {
+        if let Some(name) = subsystem(meta) {
+            match groups.iter_mut().find(|(group, _)| *group == name) {
+                Some((_, devices)) => devices.push(meta),
+                None => groups.push((name, vec![meta])),
             }
+        }
+    }
+    groups.retain(|(_, devices)| devices.len() > 1);
 
-            let display_option = DisplayOption::Relative {
-                base_path: &meta.path,
-            };
+    let others = metas
+        .into_iter()
+        .filter(|meta| match subsystem(meta) {
+            Some(name) => !groups.iter().any(|(group, _)| *group == name),
+            None => true,
+        })
+        .collect();
 
-            output += &inner_display_grid(
-                &display_option,
-                meta.content.as_ref().unwrap(),
-                &flags,
+    (others, groups)
+}
+
+/// The subsystems naming their devices with a number or a capital letter after them, as `tty1`
+/// or `ttyS0`, and with partition numbers which would otherwise be taken for the device names.
+const DEVICE_SUBSYSTEMS: &[&str] = &["loop", "mmcblk", "nbd", "nvme", "tty"];
+
+/// The subsystems of the disks, naming them with letters after them, as `sda`.
+const DISK_SUBSYSTEMS: &[&str] = &["hd", "sd", "vd", "xvd"];
+
+/// The subsystem of a device, as `sd` for `sda1`: one of the known subsystems starting its name,
+/// or else its name without its trailing number, as `hidraw` for `hidraw0`.
+fn device_subsystem(name: &str) -> &str {
+    let known = |subsystems: &[&'static str], is_suffix_start: fn(char) -> bool| {
+        subsystems.iter().copied().find(|subsystem| {
+            name.starts_with(subsystem)
+                && name[subsystem.len()..].chars().next().map(is_suffix_start) == Some(true)
+        })
+    };
+
+    known(DEVICE_SUBSYSTEMS, |c| {
+        c.is_ascii_digit() || c.is_ascii_uppercase()
+    })
+    .or_else(|| known(DISK_SUBSYSTEMS, |c| c.is_ascii_lowercase()))
+    .unwrap_or_else(|| name.trim_end_matches(|c: char| c.is_ascii_digit()))
+}
+
+fn inner_display_tree(
+    metas: &[Meta],
+    flags: &Flags,
+    colors: &Colors,
+    icons: &Icons,
+    depth: usize,
+    prefix: &str,
+) -> String {
+    let mut output = String::new();
+    let shown = &metas[..tree_shown(metas.len(), depth, flags)];
+    let (header, rows) = tree_rows(shown, flags, colors, icons, depth);
+    if let Some(header) = header {
+        output += &header;
+        output += "\n";
+    }
+
+    for (idx, (meta, row)) in shown.iter().zip(rows).enumerate() {
+        let is_last = idx + 1 == metas.len();
+        output += &tree_row(&row, prefix, depth, is_last, flags, colors);
+
+        if let Some(content) = &meta.content {
+            output += &inner_display_tree(
+                content,
+                flags,
                 colors,
                 icons,
                 depth + 1,
-                term_width,
+                &tree_prefix(prefix, depth, is_last, flags, colors),
             );
         }
     }
+    if shown.len() < metas.len() {
+        output += &tree_omitted_row(metas.len() - shown.len(), prefix, depth, flags, colors);
+    }
 
     output
 }
 
-fn inner_display_tree(
+/// How many of the `count` entries of a directory `depth` levels down a tree are shown, as set
+/// by the tree limit flag. The listed files are all shown.
+pub fn tree_shown(count: usize, depth: usize, flags: &Flags) -> usize {
+    match flags.tree_limit.0 {
+        Some(limit) if depth > 0 => count.min(limit),
+        _ => count,
+    }
+}
+
+/// The last line of a directory `depth` levels down a tree, under the `prefix` of its edges,
+/// counting its `omitted` entries.
+pub fn tree_omitted_row(
+    omitted: usize,
+    prefix: &str,
+    depth: usize,
+    flags: &Flags,
+    colors: &Colors,
+) -> String {
+    let text = format!("\u{2026} and {} more", omitted);
+    let row = colors.colorize(text, &Elem::TreeEdge).to_string();
+    tree_row(&row, prefix, depth, true, flags, colors)
+}
+
+/// The rows of the `metas` of a directory `depth` levels down a tree, their blocks aligned, and
+/// the header row above them when they are the listed files and it is asked for.
+pub fn tree_rows(
     metas: &[Meta],
     flags: &Flags,
     colors: &Colors,
     icons: &Icons,
     depth: usize,
-    prefix: &str,
-) -> String {
-    let mut output = String::new();
-    let last_idx = metas.len();
-
-    let padding_rules = get_padding_rules(&metas, flags);
+) -> (Option<String>, Vec<String>) {
+    let padding_rules = get_padding_rules(metas, flags);
 
     let mut grid = Grid::new(GridOptions {
-        filling: Filling::Spaces(1),
+        filling: block_filling(flags, colors),
         direction: Direction::LeftToRight,
     });
 
+    let with_header = flags.header.0 && depth == 0 && !metas.is_empty();
+    if with_header {
+        add_header(&mut grid, colors, flags);
+    }
+
     for meta in metas.iter() {
         for block in get_output(
             &meta,
@@ -178,48 +562,146 @@ fn inner_display_tree(
     }
 
     let content = grid.fit_into_columns(flags.blocks.0.len()).to_string();
-    let mut lines = content.lines();
+    let mut lines = content.lines().map(String::from);
+    let header = if with_header { lines.next() } else { None };
+    (header, lines.collect())
+}
+
+/// The line of a `row` of a tree `depth` levels down, after the `prefix` of the edges of its
+/// parents and its own edge, the corner one if `is_last` of its directory.
+pub fn tree_row(
+    row: &str,
+    prefix: &str,
+    depth: usize,
+    is_last: bool,
+    flags: &Flags,
+    colors: &Colors,
+) -> String {
+    let mut output = String::new();
+    if depth > 0 {
+        output += prefix;
 
-    for (idx, meta) in metas.iter().enumerate() {
-        let is_last_folder_elem = idx + 1 != last_idx;
+        let connectors = &flags.tree_connectors;
+        let edge = if is_last {
+            connectors.corner()
+        } else {
+            connectors.edge()
+        };
+        output += &colors.colorize_tree_edge(edge, depth).to_string();
+        output += " ";
+    }
 
-        if depth > 0 {
-            output += prefix;
+    output += row;
+    output += "\n";
+    output
+}
 
-            if is_last_folder_elem {
-                output += EDGE;
-            } else {
-                output += CORNER;
-            }
-            output += " ";
+/// The prefix of the edges of the content of a directory `depth` levels down a tree, under the
+/// `prefix` of its own row: its edge goes on down unless it `is_last` of its parent.
+pub fn tree_prefix(
+    prefix: &str,
+    depth: usize,
+    is_last: bool,
+    flags: &Flags,
+    colors: &Colors,
+) -> String {
+    let mut new_prefix = String::from(prefix);
+
+    if depth > 0 {
+        let connectors = &flags.tree_connectors;
+        if is_last {
+            new_prefix += &connectors.blank();
+        } else {
+            new_prefix += &colors
+                .colorize_tree_edge(connectors.line(), depth)
+                .to_string();
         }
+    }
+    new_prefix
+}
 
-        output += &String::from(lines.next().unwrap());
-        output += "\n";
+/// The names of the file types in the summary footers, in the singular and the plural, in the
+/// order they are counted there.
+const SUMMARY_TYPES: &[(&str, &str)] = &[
+    ("directory", "directories"),
+    ("file", "files"),
+    ("symlink", "symlinks"),
+    ("pipe", "pipes"),
+    ("socket", "sockets"),
+    ("block device", "block devices"),
+    ("character device", "character devices"),
+    ("special file", "special files"),
+];
+
+/// The number of files of each type of [SUMMARY_TYPES] and the sum of their sizes, printed in
+/// the footers of the `--summary` flag.
+#[derive(Default)]
+pub struct Totals {
+    counts: [usize; 8],
+    bytes: u64,
+}
 
-        if meta.content.is_some() {
-            let mut new_prefix = String::from(prefix);
+impl Totals {
+    /// Counts `meta`, unless it is the `.` or `..` entry.
+    pub fn add(&mut self, meta: &Meta) {
+        if meta.name.name == "." || meta.name.name == ".." {
+            return;
+        }
+        let index = match meta.file_type {
+            FileType::Directory { .. } => 0,
+            FileType::File { .. } => 1,
+            FileType::SymLink { .. } => 2,
+            FileType::Pipe => 3,
+            FileType::Socket => 4,
+            FileType::BlockDevice => 5,
+            FileType::CharDevice => 6,
+            FileType::Special => 7,
+        };
+        self.counts[index] += 1;
+        self.bytes += meta.size.get_bytes();
+    }
 
-            if depth > 0 {
-                if is_last_folder_elem {
-                    new_prefix += LINE;
-                } else {
-                    new_prefix += BLANK;
-                }
+    /// Counts the `metas` of a directory `depth` levels down a tree, and the content of the ones
+    /// shown. The ones left out by the tree limit are counted, but not their content.
+    fn add_tree(&mut self, metas: &[Meta], depth: usize, flags: &Flags) {
+        let shown = tree_shown(metas.len(), depth, flags);
+        for (idx, meta) in metas.iter().enumerate() {
+            self.add(meta);
+            if let Some(content) = meta.content.as_ref().filter(|_| idx < shown) {
+                self.add_tree(content, depth + 1, flags);
             }
-
-            output += &inner_display_tree(
-                &meta.content.as_ref().unwrap(),
-                &flags,
-                colors,
-                icons,
-                depth + 1,
-                &new_prefix,
-            );
         }
     }
 
-    output
+    /// The counts of the types found, as "2 directories, 1 file", followed by the total size.
+    pub fn render(&self, flags: &Flags) -> String {
+        let mut parts: Vec<String> = SUMMARY_TYPES
+            .iter()
+            .zip(self.counts.iter())
+            .filter(|(_, &count)| count > 0)
+            .map(|(&(singular, plural), &count)| {
+                format!("{} {}", count, if count == 1 { singular } else { plural })
+            })
+            .collect();
+        if parts.is_empty() {
+            parts.push("0 files".to_string());
+        }
+
+        let size = Size::new(self.bytes);
+        let separator = if flags.size == SizeFlag::Default {
+            " "
+        } else {
+            ""
+        };
+        parts.push(format!(
+            "{}{}{} in total",
+            size.value_string(flags),
+            separator,
+            size.unit_string(flags)
+        ));
+
+        parts.join(", ")
+    }
 }
 
 fn should_display_folder_path(depth: usize, metas: &[Meta], flags: &Flags) -> bool {
@@ -239,10 +721,13 @@ fn should_display_folder_path(depth: usize, metas: &[Meta], flags: &Flags) -> bo
     }
 }
 
-fn display_folder_path(meta: &Meta) -> String {
+fn display_folder_path(meta: &Meta, elided_prefix: Option<&Path>, raw: bool) -> String {
     let mut output = String::new();
     output.push('\n');
-    output += &meta.path.to_string_lossy();
+    match elided_prefix.and_then(|prefix| meta.path.strip_prefix(prefix).ok()) {
+        Some(path) => output += &os_str_text(Path::new("\u{2026}").join(path).as_os_str(), raw),
+        None => output += &os_str_text(meta.path.as_os_str(), raw),
+    }
     output += ":\n";
 
     output
@@ -258,42 +743,102 @@ fn get_output<'a>(
 ) -> Vec<ANSIString<'a>> {
     let mut strings: Vec<ANSIString> = Vec::new();
     for block in flags.blocks.0.iter() {
+        let rendered = strings.len();
         match block {
             Block::INode => strings.push(meta.inode.render(colors)),
             Block::Links => strings.push(meta.links.render(colors)),
+            Block::Security => strings.push(ExecPolicy::new(meta).render(colors)),
+            Block::Filesystem => strings.push(Filesystem::new(&meta.path).render(colors)),
+            Block::Kind => strings.push(Kind::new(meta).render(colors)),
             Block::Permission => {
                 let s: &[ColoredString] = &[
                     meta.file_type.render(colors),
                     meta.permissions.render(colors),
                 ];
-                let res = ANSIStrings(s).to_string();
+                let mut res = ANSIStrings(s).to_string();
+                if flags.verbose_permissions.0 {
+                    res = format!("{} {}", res, meta.permissions.summary());
+                }
                 strings.push(ColoredString::from(res));
             }
-            Block::User => strings.push(meta.owner.render_user(colors)),
-            Block::Group => strings.push(meta.owner.render_group(colors)),
+            Block::User => strings.push(meta.owner.render_user(colors, flags)),
+            Block::Group => strings.push(meta.owner.render_group(colors, flags)),
             Block::Size => strings.push(meta.size.render(
                 colors,
                 &flags,
                 padding_rules[&Block::SizeValue],
             )),
             Block::SizeValue => strings.push(meta.size.render_value(colors, flags)),
-            Block::Date => strings.push(meta.date.render(colors, &flags)),
+            Block::Allocated => strings.push(meta.allocated.render(
+                colors,
+                flags,
+                padding_rules[&Block::Allocated],
+            )),
+            Block::Date => {
+                let width = UnicodeWidthStr::width(&*meta.date.date_string(flags));
+                let s = ANSIStrings(&[
+                    ColoredString::from(figure_padding(width, padding_rules[&Block::Date])),
+                    meta.date.render(colors, &flags),
+                ])
+                .to_string();
+                strings.push(ColoredString::from(s));
+            }
+            // The files whose birth time is not recorded get a `-`, aligned as the dates.
+            Block::Created => {
+                let (width, date) = match &meta.created {
+                    Some(created) => (
+                        UnicodeWidthStr::width(&*created.date_string(flags)),
+                        created.render(colors, &flags),
+                    ),
+                    None => (1, ColoredString::from("-")),
+                };
+                let s = ANSIStrings(&[
+                    ColoredString::from(figure_padding(width, padding_rules[&Block::Created])),
+                    date,
+                ])
+                .to_string();
+                strings.push(ColoredString::from(s));
+            }
             Block::Name => {
-                let s: String =
-                    if flags.no_symlink.0 || flags.dereference.0 || flags.layout == Layout::Grid {
-                        ANSIStrings(&[
-                            meta.name.render(colors, icons, &display_option),
-                            meta.indicator.render(&flags),
-                        ])
-                            .to_string()
+                let shows_target = shows_symlink_target(meta, flags);
+                let name = meta.name.render_truncated(
+                    colors,
+                    icons,
+                    &display_option,
+                    if shows_target {
+                        None
                     } else {
-                        ANSIStrings(&[
-                            meta.name.render(colors, icons, &display_option),
-                            meta.indicator.render(&flags),
-                            meta.symlink.render(colors, &flags),
-                        ])
-                            .to_string()
-                    };
+                        truncation(flags)
+                    },
+                    flags.raw.0,
+                );
+                // The entries which changed are highlighted in the watch mode.
+                let name = match meta.change {
+                    Some(change) => colors.colorize_name(name, &change.elem()),
+                    None => name,
+                };
+                let mut s: String = if !shows_target {
+                    ANSIStrings(&[name, meta.indicator.render(&flags)]).to_string()
+                } else {
+                    ANSIStrings(&[
+                        name,
+                        meta.indicator.render(&flags),
+                        meta.symlink.render(colors, &flags),
+                    ])
+                    .to_string()
+                };
+                if let Some(old_path) = shown_renamed_from(meta, flags) {
+                    let annotation = format!(" \u{2190} {}", old_path.display());
+                    s.push_str(
+                        &colors
+                            .colorize(annotation, &Elem::GitRenamedFrom)
+                            .to_string(),
+                    );
+                }
+                if let Some(reason) = &meta.content_error {
+                    let annotation = format!(" [{}]", reason.describe());
+                    s.push_str(&colors.colorize(annotation, &Elem::ReadError).to_string());
+                }
 
                 strings.push(ColoredString::from(s));
             }
@@ -302,19 +847,137 @@ fn get_output<'a>(
                     #[cfg(not(feature = "git"))]
                     panic!("git feature is disabled");
                     #[cfg(feature = "git")]
-                    strings.push(_s.render(colors, icons));
+                    strings.push(_s.render(
+                        colors,
+                        icons,
+                        flags.git_status_style,
+                        meta.git_untracked_count,
+                        meta.git_change_counts,
+                    ));
                 }
             }
+            // The files which are not a repository get an empty cell, to keep the rows aligned.
+            Block::GitRepo => match &meta.git_repo {
+                Some(repo) => strings.push(repo.render(colors)),
+                None => strings.push(ColoredString::from("")),
+            },
         };
+
+        // A block is not rendered for the files without a git status.
+        if strings.len() > rendered {
+            let string = strings.remove(rendered);
+            strings.push(colors.colorize_block(block, string));
+        }
     }
 
     strings
 }
 
-fn get_visible_width(input: &str) -> usize {
-    let mut nb_invisible_char = 0;
+/// The previous path of `meta` to print after its name, if it has been renamed in the git index
+/// and this is asked for along the git block.
+fn shown_renamed_from<'a>(meta: &'a Meta, flags: &Flags) -> Option<&'a Path> {
+    if flags.git_renames.0 && flags.blocks.0.contains(&Block::GitStatus) {
+        meta.git_renamed_from.as_deref()
+    } else {
+        None
+    }
+}
 
-    // If the input has color, do not compute the length contributed by the color to the actual length
+/// Add a row labelling each block to the `grid`.
+fn add_header(grid: &mut Grid, colors: &Colors, flags: &Flags) {
+    for block in flags.blocks.0.iter() {
+        let label = colors
+            .colorize(block.label().to_string(), &Elem::Header)
+            .to_string();
+
+        grid.add(Cell {
+            width: get_visible_width(&label),
+            contents: label,
+        });
+    }
+}
+
+/// The filling between the blocks of a row: the configured separator surrounded by spaces, or a
+/// single space.
+fn block_filling(flags: &Flags, colors: &Colors) -> Filling {
+    match &flags.table.separator.0 {
+        Some(separator) => {
+            let separator = colors.colorize(separator.clone(), &Elem::TableSeparator);
+            Filling::Text(format!(" {} ", separator))
+        }
+        None => Filling::Spaces(1),
+    }
+}
+
+/// Shades every other row of `content` after its `header_rows` first ones, the shaded rows being
+/// padded to the width of the widest row.
+fn shade_rows(content: &str, header_rows: usize, colors: &Colors) -> String {
+    let width = content.lines().map(get_visible_width).max().unwrap_or(0);
+
+    content
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            if idx >= header_rows && (idx - header_rows) % 2 == 1 {
+                colors.shade_row(line, width - get_visible_width(line)) + "\n"
+            } else {
+                line.to_string() + "\n"
+            }
+        })
+        .collect()
+}
+
+/// How the names are shortened, as they are only in the grid layout.
+fn truncation(flags: &Flags) -> Option<Truncation> {
+    if flags.layout != Layout::Grid {
+        return None;
+    }
+    let width = flags.truncate_names.0?;
+    if flags.truncate_middle.0 {
+        Some(Truncation::Middle(width))
+    } else {
+        Some(Truncation::End(width))
+    }
+}
+
+/// Whether the target of the symlink, if `meta` is one, is displayed after its name.
+fn shows_symlink_target(meta: &Meta, flags: &Flags) -> bool {
+    // A dereferenced link which could not be resolved is still shown as a link.
+    let dereferenced = flags.dereference.0
+        && !meta.symlink.exceeds_depth(flags.symlink_depth.0)
+        && meta.symlink.broken_reason().is_none();
+
+    !(flags.no_symlink.0 || dereferenced || flags.layout == Layout::Grid)
+}
+
+/// The name block of `meta` built without any styled string, when there is neither color nor
+/// icon to display.
+fn plain_name(
+    meta: &Meta,
+    colors: &Colors,
+    flags: &Flags,
+    display_option: &DisplayOption,
+) -> String {
+    let mut name = meta.name.plain(display_option, flags.raw.0);
+    if let Some(truncation) = truncation(flags) {
+        name = truncation.apply(name);
+    }
+    name += meta.indicator.plain(flags);
+
+    if shows_symlink_target(meta, flags) {
+        name += &meta.symlink.render(colors, flags).to_string();
+    }
+    if let Some(reason) = &meta.content_error {
+        name += &format!(" [{}]", reason.describe());
+    }
+
+    name
+}
+
+fn get_visible_width(input: &str) -> usize {
+    let mut nb_invisible_char = 0;
+
+    // If the input has color, do not compute the length contributed by the color to the actual length
     for (idx, _) in input.match_indices("\u{1b}[") {
         let (_, s) = input.split_at(idx);
 
@@ -327,11 +990,18 @@ fn get_visible_width(input: &str) -> usize {
     UnicodeWidthStr::width(input) - nb_invisible_char
 }
 
-fn detect_size_lengths(metas: &[Meta], flags: &Flags) -> usize {
+/// The padding right-aligning a content which is `width` columns wide on `alignment` columns.
+///
+/// Figure spaces are used as they are as wide as a digit, keeping numbers aligned in any font.
+fn figure_padding(width: usize, alignment: usize) -> String {
+    "\u{2007}".repeat(alignment.saturating_sub(width))
+}
+
+fn detect_size_lengths(metas: &[Meta], flags: &Flags, size: fn(&Meta) -> &Size) -> usize {
     let mut max_value_length: usize = 0;
 
     for meta in metas {
-        let value_len = meta.size.value_string(flags).len();
+        let value_len = UnicodeWidthStr::width(&*size(meta).value_string(flags));
 
         if value_len > max_value_length {
             max_value_length = value_len;
@@ -341,15 +1011,52 @@ fn detect_size_lengths(metas: &[Meta], flags: &Flags) -> usize {
     max_value_length
 }
 
+fn detect_date_lengths(metas: &[Meta], flags: &Flags) -> usize {
+    metas
+        .iter()
+        .map(|meta| UnicodeWidthStr::width(&*meta.date.date_string(flags)))
+        .max()
+        .unwrap_or(0)
+}
+
+fn detect_created_lengths(metas: &[Meta], flags: &Flags) -> usize {
+    metas
+        .iter()
+        .map(|meta| match &meta.created {
+            Some(created) => UnicodeWidthStr::width(&*created.date_string(flags)),
+            None => 1,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
 fn get_padding_rules(metas: &[Meta], flags: &Flags) -> HashMap<Block, usize> {
     let mut padding_rules: HashMap<Block, usize> = HashMap::new();
 
     if flags.blocks.0.contains(&Block::Size) {
-        let size_val = detect_size_lengths(&metas, &flags);
+        let size_val = detect_size_lengths(&metas, &flags, |meta| &meta.size);
 
         padding_rules.insert(Block::SizeValue, size_val);
     }
 
+    if flags.blocks.0.contains(&Block::Allocated) {
+        let allocated_val = detect_size_lengths(metas, flags, |meta| &meta.allocated);
+
+        padding_rules.insert(Block::Allocated, allocated_val);
+    }
+
+    if flags.blocks.0.contains(&Block::Date) {
+        let date_width = detect_date_lengths(metas, flags);
+
+        padding_rules.insert(Block::Date, date_width);
+    }
+
+    if flags.blocks.0.contains(&Block::Created) {
+        let created_width = detect_created_lengths(metas, flags);
+
+        padding_rules.insert(Block::Created, created_width);
+    }
+
     padding_rules
 }
 
@@ -359,10 +1066,14 @@ mod tests {
     use crate::app;
     use crate::color;
     use crate::color::Colors;
+    use crate::flags::color::{Background, BlockColors, ColorValue};
+    use crate::flags::table::TableSeparator;
+    use crate::flags::tree_connectors::{TreeConnectorPreset, TreeConnectors};
     use crate::icon;
     use crate::icon::Icons;
-    use crate::meta::{FileType, Name};
+    use crate::meta::{BrokenReason, FileType, MemoryBudget, Name};
     use crate::Config;
+    use ansi_term::Colour;
     use assert_fs::prelude::*;
     use std::path::Path;
 
@@ -500,6 +1211,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_display_figure_padding() {
+        for (s, alignment, padding) in &[
+            ("42", 4, 2),
+            ("１２", 4, 0),
+            ("12", 4, 2),
+            ("3月", 4, 1),
+            ("12月", 4, 0),
+            ("2020-01-01", 4, 0),
+        ] {
+            let pad = figure_padding(UnicodeWidthStr::width(*s), *alignment);
+
+            assert_eq!(pad.chars().count(), *padding);
+            assert!(pad.chars().all(|c| c == '\u{2007}'));
+            assert_eq!(
+                get_visible_width(&format!("{}{}", pad, s)),
+                std::cmp::max(*alignment, get_visible_width(s))
+            );
+        }
+    }
+
     #[test]
     fn test_display_tree_with_all() {
         let argv = vec!["lsd", "--tree", "--all"];
@@ -512,7 +1244,7 @@ mod tests {
         dir.child("one.d/.hidden").touch().unwrap();
         let metas = Meta::from_path(Path::new(dir.path()), false)
             .unwrap()
-            .recurse_into(42, &flags, None)
+            .recurse_into(42, &flags, None, &MemoryBudget::new(None))
             .unwrap()
             .unwrap();
         let output = inner_display_tree(
@@ -526,4 +1258,506 @@ mod tests {
 
         assert_eq!("one.d\n├── .hidden\n└── two\n", output);
     }
+
+    #[test]
+    fn test_display_tree_ascii_connectors() {
+        let argv = vec!["lsd", "--tree"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let config = Config {
+            tree_connectors: Some(TreeConnectors {
+                preset: TreeConnectorPreset::Ascii,
+                ..TreeConnectors::default()
+            }),
+            ..Config::with_none()
+        };
+        let flags = Flags::configure_from(&matches, &config).unwrap();
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("one.d/two.d").create_dir_all().unwrap();
+        dir.child("one.d/two.d/three").touch().unwrap();
+        dir.child("one.d/zero").touch().unwrap();
+        let mut metas = Meta::from_path(Path::new(dir.path()), false)
+            .unwrap()
+            .recurse_into(42, &flags, None, &MemoryBudget::new(None))
+            .unwrap()
+            .unwrap();
+        if let Some(content) = metas[0].content.as_mut() {
+            content.sort_by(|a, b| a.name.name.cmp(&b.name.name));
+        }
+        let output = inner_display_tree(
+            &metas,
+            &flags,
+            &Colors::new(color::Theme::NoColor),
+            &Icons::new(icon::Theme::NoIcon, " ".to_string()),
+            0,
+            "",
+        );
+
+        assert_eq!("one.d\n|-- two.d\n|  `-- three\n`-- zero\n", output);
+    }
+
+    #[test]
+    fn test_display_tree_edges_palette() {
+        let argv = vec!["lsd", "--tree"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("one.d/two.d").create_dir_all().unwrap();
+        dir.child("one.d/two.d/three").touch().unwrap();
+        dir.child("one.d/zero").touch().unwrap();
+        let mut metas = Meta::from_path(Path::new(dir.path()), false)
+            .unwrap()
+            .recurse_into(42, &flags, None, &MemoryBudget::new(None))
+            .unwrap()
+            .unwrap();
+        // The guides depend on the order, which is the one of the file system until sorted.
+        if let Some(content) = metas[0].content.as_mut() {
+            content.sort_by(|a, b| a.name.name.cmp(&b.name.name));
+        }
+        let palette = [ColorValue::Fixed(1), ColorValue::Fixed(2)];
+        let output = inner_display_tree(
+            &metas,
+            &flags,
+            &Colors::new(color::Theme::NoLscolors).with_tree_edges(&palette),
+            &Icons::new(icon::Theme::NoIcon, " ".to_string()),
+            0,
+            "",
+        );
+
+        let first = Colour::Fixed(1);
+        let second = Colour::Fixed(2);
+        let connectors = &flags.tree_connectors;
+        let (edge, line, corner) = (connectors.edge(), connectors.line(), connectors.corner());
+        assert!(output.contains(&format!("{} ", first.paint(edge))));
+        assert!(output.contains(&format!("{}{} ", first.paint(line), second.paint(corner))));
+        assert!(output.contains(&format!("{} ", first.paint(corner))));
+    }
+
+    #[test]
+    fn test_display_header_long() {
+        let argv = vec!["lsd", "--header", "--blocks", "size,name"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("one").touch().unwrap();
+        let metas = Meta::from_path(Path::new(dir.path()), false)
+            .unwrap()
+            .recurse_into(1, &flags, None, &MemoryBudget::new(None))
+            .unwrap()
+            .unwrap();
+        let output = inner_display_grid(
+            &DisplayOption::FileName,
+            &metas,
+            &flags,
+            &Colors::new(color::Theme::NoColor),
+            &Icons::new(icon::Theme::NoIcon, " ".to_string()),
+            1,
+            None,
+            None,
+        );
+
+        assert_eq!("Size Name\n0 B  one\n", output);
+    }
+
+    #[test]
+    fn test_display_summary() {
+        let argv = vec!["lsd", "--summary", "--oneline", "--all", "--size", "bytes"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("one").write_str("12345").unwrap();
+        dir.child("two").write_str("678").unwrap();
+        let metas = Meta::from_path(Path::new(dir.path()), false)
+            .unwrap()
+            .recurse_into(1, &flags, None, &MemoryBudget::new(None))
+            .unwrap()
+            .unwrap();
+        let output = inner_display_grid(
+            &DisplayOption::FileName,
+            &metas,
+            &flags,
+            &Colors::new(color::Theme::NoColor),
+            &Icons::new(icon::Theme::NoIcon, " ".to_string()),
+            1,
+            None,
+            None,
+        );
+
+        // The . and .. entries are listed but not counted.
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(5, lines.len());
+        assert!(lines.contains(&".."));
+        assert_eq!("2 files, 8 in total", lines[4]);
+    }
+
+    #[test]
+    fn test_totals_render() {
+        let flags = Flags::default();
+        assert_eq!("0 files, 0 B in total", Totals::default().render(&flags));
+
+        let totals = Totals {
+            counts: [2, 1, 0, 0, 0, 0, 3, 0],
+            bytes: 2048,
+        };
+        assert_eq!(
+            "2 directories, 1 file, 3 character devices, 2.0 KB in total",
+            totals.render(&flags)
+        );
+    }
+
+    #[test]
+    fn test_display_table_separator() {
+        let argv = vec!["lsd", "--blocks", "size,name"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let mut flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+        flags.table.separator = TableSeparator(Some("|".to_string()));
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("one").touch().unwrap();
+        let metas = Meta::from_path(Path::new(dir.path()), false)
+            .unwrap()
+            .recurse_into(1, &flags, None, &MemoryBudget::new(None))
+            .unwrap()
+            .unwrap();
+        let output = inner_display_grid(
+            &DisplayOption::FileName,
+            &metas,
+            &flags,
+            &Colors::new(color::Theme::NoColor),
+            &Icons::new(icon::Theme::NoIcon, " ".to_string()),
+            1,
+            None,
+            None,
+        );
+
+        assert_eq!("0 B | one\n", output);
+    }
+
+    #[test]
+    fn test_display_block_colors() {
+        let argv = vec!["lsd", "--blocks", "size,name"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("one").touch().unwrap();
+        let metas = Meta::from_path(&dir.path().join("one"), false).unwrap();
+        let render = |block_colors: &[(Block, Background)]| {
+            let colors = Colors::new(color::Theme::NoLscolors)
+                .with_block_colors(&BlockColors(block_colors.iter().cloned().collect()));
+            get_output(
+                &metas,
+                &colors,
+                &Icons::new(icon::Theme::NoIcon, " ".to_string()),
+                &flags,
+                &DisplayOption::FileName,
+                &get_padding_rules(std::slice::from_ref(&metas), &flags),
+            )
+            .iter()
+            .map(|string| string.to_string())
+            .collect::<Vec<_>>()
+        };
+
+        let colored = render(&[]);
+        let uncolored = render(&[(Block::Size, Background::None)]);
+        assert_eq!("0 B", uncolored[0]);
+        assert_eq!(colored[1], uncolored[1]);
+        assert_ne!(colored[0], uncolored[0]);
+
+        let grey = render(&[(Block::Size, Background::Color(ColorValue::Fixed(244)))]);
+        assert_eq!(Colour::Fixed(244).paint("0 B").to_string(), grey[0]);
+    }
+
+    #[test]
+    fn test_display_shade_rows() {
+        let colors = Colors::new(color::Theme::NoLscolors);
+        let user = colors.colorize("user".to_string(), &Elem::User);
+        let content = format!("Name\na\n{} b\nc\nd\n", user);
+        let output = shade_rows(&content, 1, &colors);
+
+        assert_eq!(
+            format!(
+                "Name\na\n\u{1b}[48;5;236m{}\u{1b}[48;5;236m b\u{1b}[0m\nc\n\u{1b}[48;5;236md     \u{1b}[0m\n",
+                user
+            ),
+            output
+        );
+    }
+
+    #[test]
+    fn test_display_device_subsystem() {
+        assert_eq!("tty", device_subsystem("tty1"));
+        assert_eq!("tty", device_subsystem("ttyS0"));
+        assert_eq!("tty", device_subsystem("tty"));
+        assert_eq!("sd", device_subsystem("sda1"));
+        assert_eq!("nvme", device_subsystem("nvme0n1p2"));
+        assert_eq!("loop", device_subsystem("loop0p1"));
+        assert_eq!("hidraw", device_subsystem("hidraw0"));
+        assert_eq!("random", device_subsystem("random"));
+    }
+
+    #[test]
+    fn test_display_group_devices() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let names = [
+            "file", "null", "sda", "sda1", "tty1", "tty2", "ttyS0", "zero",
+        ];
+        let metas: Vec<Meta> = names
+            .iter()
+            .map(|name| {
+                dir.child(name).touch().unwrap();
+                let mut meta = Meta::from_path(&dir.path().join(name), false).unwrap();
+                if *name != "file" {
+                    meta.file_type = FileType::CharDevice;
+                }
+                meta
+            })
+            .collect();
+
+        let (others, groups) = group_devices(metas.iter().collect());
+        let names_of = |metas: &[&Meta]| -> Vec<String> {
+            metas.iter().map(|meta| meta.name.name.clone()).collect()
+        };
+        assert_eq!(vec!["file", "null", "zero"], names_of(&others));
+        assert_eq!(2, groups.len());
+        assert_eq!("sd", groups[0].0);
+        assert_eq!(vec!["sda", "sda1"], names_of(&groups[0].1));
+        assert_eq!("tty", groups[1].0);
+        assert_eq!(vec!["tty1", "tty2", "ttyS0"], names_of(&groups[1].1));
+    }
+
+    #[test]
+    fn test_display_header_grid() {
+        let argv = vec!["lsd", "--header"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("one").touch().unwrap();
+        let metas = Meta::from_path(Path::new(dir.path()), false)
+            .unwrap()
+            .recurse_into(1, &flags, None, &MemoryBudget::new(None))
+            .unwrap()
+            .unwrap();
+        let output = inner_display_grid(
+            &DisplayOption::FileName,
+            &metas,
+            &flags,
+            &Colors::new(color::Theme::NoColor),
+            &Icons::new(icon::Theme::NoIcon, " ".to_string()),
+            1,
+            None,
+            None,
+        );
+
+        assert_eq!("one\n", output);
+    }
+
+    #[test]
+    fn test_display_columns() {
+        let argv = vec!["lsd", "--columns", "2"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        for name in &["a", "bb", "c", "d"] {
+            dir.child(name).touch().unwrap();
+        }
+        let mut metas = Meta::from_path(Path::new(dir.path()), false)
+            .unwrap()
+            .recurse_into(1, &flags, None, &MemoryBudget::new(None))
+            .unwrap()
+            .unwrap();
+        metas.sort_by(|a, b| a.name.cmp(&b.name));
+        let display = |term_width| {
+            inner_display_grid(
+                &DisplayOption::FileName,
+                &metas,
+                &flags,
+                &Colors::new(color::Theme::NoColor),
+                &Icons::new(icon::Theme::NoIcon, " ".to_string()),
+                1,
+                term_width,
+                None,
+            )
+        };
+
+        assert_eq!("a   c\nbb  d\n", display(Some(200)));
+        assert_eq!("a   c\nbb  d\n", display(Some(3)));
+        assert_eq!("a   c\nbb  d\n", display(None));
+    }
+
+    #[test]
+    fn test_display_header_tree() {
+        let argv = vec!["lsd", "--tree", "--header", "--blocks", "size,name"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("one.d").create_dir_all().unwrap();
+        dir.child("one.d/two").touch().unwrap();
+        let metas = Meta::from_path(&dir.path().join("one.d"), false)
+            .unwrap()
+            .recurse_into(42, &flags, None, &MemoryBudget::new(None))
+            .unwrap()
+            .unwrap();
+        let output = inner_display_tree(
+            &metas,
+            &flags,
+            &Colors::new(color::Theme::NoColor),
+            &Icons::new(icon::Theme::NoIcon, " ".to_string()),
+            0,
+            "",
+        );
+
+        assert_eq!("Size Name\n0 B  two\n", output);
+    }
+
+    #[test]
+    fn test_display_escape_field() {
+        for (field, csv, tsv) in &[
+            ("plain", "plain", "plain"),
+            ("a,b", "\"a,b\"", "a,b"),
+            ("say \"hi\"", "\"say \"\"hi\"\"\"", "say \"hi\""),
+            ("a\tb", "a\tb", "a\\tb"),
+            ("a\nb", "\"a\nb\"", "a\\nb"),
+            ("a\\b", "a\\b", "a\\\\b"),
+        ] {
+            assert_eq!(*csv, escape_field(field, OutputFormat::Csv));
+            assert_eq!(*tsv, escape_field(field, OutputFormat::Tsv));
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_display_records() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let argv = vec!["lsd", "--csv"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("one,two").write_str("12345").unwrap();
+        let path = dir.path().join("one,two");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o4751)).unwrap();
+        let metas = Meta::from_path(Path::new(dir.path()), false)
+            .unwrap()
+            .recurse_into(1, &flags, None, &MemoryBudget::new(None))
+            .unwrap()
+            .unwrap();
+        let output = records(&metas, &flags, true);
+        let mut lines = output.lines();
+
+        assert_eq!(
+            Some(concat!(
+                "path,type,mode,permissions,uid,user,gid,group,links,inode,size,modified,",
+                "modified-epoch,created,created-epoch,target"
+            )),
+            lines.next()
+        );
+        let record = lines.next().unwrap();
+        assert!(record.starts_with(&format!("\"{}\",file,4751,rwsr-x--x,", path.display())));
+        assert!(record.contains(",1,") && record.contains(",5,"));
+        assert!(record.ends_with(','));
+        assert_eq!(None, lines.next());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_display_plain_name_matches_render() {
+        let argv = vec!["lsd", "--classify", "--oneline"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+        let colors = Colors::new(color::Theme::NoColor);
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("one.d").create_dir_all().unwrap();
+        dir.child("two").touch().unwrap();
+        std::os::unix::fs::symlink("two", dir.path().join("three")).unwrap();
+        let metas = Meta::from_path(Path::new(dir.path()), false)
+            .unwrap()
+            .recurse_into(1, &flags, None, &MemoryBudget::new(None))
+            .unwrap()
+            .unwrap();
+
+        for meta in &metas {
+            let rendered = get_output(
+                meta,
+                &colors,
+                &icons,
+                &flags,
+                &DisplayOption::FileName,
+                &HashMap::new(),
+            );
+            assert_eq!(
+                rendered[0].to_string(),
+                plain_name(meta, &colors, &flags, &DisplayOption::FileName)
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn test_display_git_renamed_from() {
+        let colors = Colors::new(color::Theme::NoColor);
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("new").touch().unwrap();
+        let mut meta = Meta::from_path(&dir.path().join("new"), false).unwrap();
+        meta.git_status = Some(Default::default());
+        meta.git_renamed_from = Some("sub/old".into());
+
+        for (argv, expected) in &[
+            (
+                vec!["lsd", "--blocks", "name,git", "--git-renames"],
+                "new \u{2190} sub/old",
+            ),
+            (vec!["lsd", "--blocks", "name,git"], "new"),
+            (vec!["lsd", "--blocks", "name", "--git-renames"], "new"),
+        ] {
+            let matches = app::build().get_matches_from_safe(argv).unwrap();
+            let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+            let rendered = get_output(
+                &meta,
+                &colors,
+                &icons,
+                &flags,
+                &DisplayOption::FileName,
+                &HashMap::new(),
+            );
+            assert_eq!(*expected, rendered[0].to_string());
+        }
+    }
+
+    #[test]
+    fn test_display_content_error() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+        let colors = Colors::new(color::Theme::NoColor);
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("sub").create_dir_all().unwrap();
+        let mut meta = Meta::from_path(&dir.path().join("sub"), false).unwrap();
+        meta.content_error = Some(BrokenReason::PermissionDenied);
+
+        let rendered = get_output(
+            &meta,
+            &colors,
+            &icons,
+            &flags,
+            &DisplayOption::FileName,
+            &HashMap::new(),
+        );
+        assert_eq!("sub [permission denied]", rendered[0].to_string());
+        assert_eq!(
+            "sub [permission denied]",
+            plain_name(&meta, &colors, &flags, &DisplayOption::FileName)
+        );
+    }
 }
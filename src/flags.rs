@@ -1,44 +1,134 @@
 pub mod blocks;
+pub mod checksum;
 pub mod color;
+pub mod columns;
+pub mod count;
+pub mod count_links;
 pub mod date;
+pub mod date_timezone;
+pub mod debug_readdir;
 pub mod dereference;
 pub mod display;
+pub mod elide_prefix;
+pub mod exclude_from;
+pub mod extensions;
+pub mod find;
+pub mod follow_symlinks;
+pub mod git_change_counts;
+pub mod git_renames;
+pub mod git_status_style;
+pub mod git_untracked_count;
+pub mod glob;
+pub mod group_devices;
+pub mod header;
 pub mod icons;
+pub mod ignore_files;
 pub mod ignore_globs;
+pub mod include_snapshots;
 pub mod indicators;
+pub mod info;
+pub mod keep_duplicates;
 pub mod layout;
+pub mod max_entries;
+pub mod max_memory;
+pub mod names_only;
+pub mod no_glob_error;
+pub mod numeric_uid_gid;
+pub mod output_format;
+pub mod paginate;
+pub mod raw;
 pub mod recursion;
 pub mod size;
+pub mod sort_cmd;
 pub mod sorting;
+pub mod strictness;
+pub mod summary;
 pub mod symlink_arrow;
+pub mod symlink_depth;
 pub mod symlinks;
+pub mod table;
 pub mod total_size;
+pub mod total_size_symlinks;
+pub mod tree_connectors;
+pub mod tree_limit;
+pub mod truncate_middle;
+pub mod truncate_names;
+pub mod verbose_permissions;
+pub mod watch;
+pub mod yes;
 #[cfg(feature = "git")]
 pub mod git_icons;
 
 pub use blocks::Block;
 pub use blocks::Blocks;
+pub use checksum::Checksum;
 pub use color::Color;
 pub use color::ColorOption;
+pub use columns::Columns;
+pub use count::Count;
+pub use count_links::CountLinks;
 pub use date::DateFlag;
+pub use date_timezone::DateTimezone;
+pub use debug_readdir::DebugReaddir;
 pub use dereference::Dereference;
 pub use display::Display;
+pub use elide_prefix::ElidePrefix;
+pub use exclude_from::ExcludeFrom;
+pub use extensions::Extensions;
+pub use find::Find;
+pub use follow_symlinks::FollowSymlinks;
+pub use git_change_counts::GitChangeCounts;
+pub use git_renames::GitRenames;
+pub use git_status_style::GitStatusStyle;
+pub use git_untracked_count::GitUntrackedCount;
+pub use glob::Glob;
+pub use group_devices::GroupDevices;
+pub use header::Header;
+pub use icons::GitStatusIcons;
 pub use icons::IconOption;
 pub use icons::IconSeparator;
 pub use icons::IconTheme;
 pub use icons::Icons;
+pub use ignore_files::IgnoreFiles;
 pub use ignore_globs::IgnoreGlobs;
+pub use include_snapshots::IncludeSnapshots;
 pub use indicators::Indicators;
+pub use info::Info;
+pub use keep_duplicates::KeepDuplicates;
 pub use layout::Layout;
+pub use max_entries::MaxEntries;
+pub use max_memory::MaxMemory;
+pub use names_only::NamesOnly;
+pub use no_glob_error::NoGlobError;
+pub use numeric_uid_gid::NumericUidGid;
+pub use output_format::OutputFormat;
+pub use paginate::Paginate;
+pub use raw::Raw;
 pub use recursion::Recursion;
 pub use size::SizeFlag;
+pub use sort_cmd::SortCmd;
+pub use sorting::Collation;
 pub use sorting::DirGrouping;
+pub use sorting::PathOrder;
+pub use sorting::SortCase;
 pub use sorting::SortColumn;
 pub use sorting::SortOrder;
 pub use sorting::Sorting;
+pub use strictness::Strictness;
+pub use summary::Summary;
 pub use symlink_arrow::SymlinkArrow;
+pub use symlink_depth::SymlinkDepth;
 pub use symlinks::NoSymlink;
+pub use table::Table;
 pub use total_size::TotalSize;
+pub use total_size_symlinks::TotalSizeSymlinks;
+pub use tree_connectors::TreeConnectors;
+pub use tree_limit::TreeLimit;
+pub use truncate_middle::TruncateMiddle;
+pub use truncate_names::TruncateNames;
+pub use verbose_permissions::VerbosePermissions;
+pub use watch::Watch;
+pub use yes::Yes;
 
 use crate::config_file::Config;
 
@@ -65,6 +155,51 @@ pub struct Flags {
     pub sorting: Sorting,
     pub total_size: TotalSize,
     pub symlink_arrow: SymlinkArrow,
+    pub symlink_depth: SymlinkDepth,
+    pub numeric_uid_gid: NumericUidGid,
+    pub include_snapshots: IncludeSnapshots,
+    pub exclude_from: ExcludeFrom,
+    pub max_memory: MaxMemory,
+    pub header: Header,
+    pub output_format: OutputFormat,
+    pub truncate_names: TruncateNames,
+    pub follow_symlinks: FollowSymlinks,
+    pub git_renames: GitRenames,
+    #[cfg_attr(not(feature = "git"), allow(dead_code))]
+    pub git_status_style: GitStatusStyle,
+    pub names_only: NamesOnly,
+    #[cfg_attr(not(feature = "git"), allow(dead_code))]
+    pub git_untracked_count: GitUntrackedCount,
+    pub extensions: Extensions,
+    pub sort_cmd: SortCmd,
+    pub table: Table,
+    pub keep_duplicates: KeepDuplicates,
+    pub group_devices: GroupDevices,
+    pub count: Count,
+    pub elide_prefix: ElidePrefix,
+    pub summary: Summary,
+    pub raw: Raw,
+    pub paginate: Paginate,
+    pub watch: Watch,
+    pub info: Info,
+    pub checksum: Checksum,
+    pub glob: Glob,
+    pub no_glob_error: NoGlobError,
+    pub max_entries: MaxEntries,
+    pub total_size_symlinks: TotalSizeSymlinks,
+    pub count_links: CountLinks,
+    pub columns: Columns,
+    pub tree_limit: TreeLimit,
+    pub debug_readdir: DebugReaddir,
+    pub tree_connectors: TreeConnectors,
+    pub verbose_permissions: VerbosePermissions,
+    pub ignore_files: IgnoreFiles,
+    pub find: Find,
+    pub truncate_middle: TruncateMiddle,
+    pub date_timezone: DateTimezone,
+    #[cfg_attr(not(feature = "git"), allow(dead_code))]
+    pub git_change_counts: GitChangeCounts,
+    pub yes: Yes,
 }
 
 impl Flags {
@@ -73,8 +208,12 @@ impl Flags {
     /// # Errors
     ///
     /// This can return an [Error], when either the building of the ignore globs or the parsing of
-    /// the recursion depth parameter fails.
+    /// the recursion depth parameter fails, or when an unsupported `ls` argument is passed in the
+    /// strict mode.
     pub fn configure_from(matches: &ArgMatches, config: &Config) -> Result<Self, Error> {
+        let strictness = Strictness::configure_from(matches, config);
+        strictness.check_unsupported(matches)?;
+
         Ok(Self {
             blocks: Blocks::configure_from(matches, config)?,
             color: Color::configure_from(matches, config),
@@ -91,6 +230,48 @@ impl Flags {
             sorting: Sorting::configure_from(matches, config),
             total_size: TotalSize::configure_from(matches, config),
             symlink_arrow: SymlinkArrow::configure_from(matches, config),
+            symlink_depth: SymlinkDepth::configure_from(matches, config),
+            numeric_uid_gid: NumericUidGid::configure_from(matches, config),
+            include_snapshots: IncludeSnapshots::configure_from(matches, config),
+            exclude_from: ExcludeFrom::configure_from(matches)?,
+            max_memory: MaxMemory::configure_from(matches, config),
+            header: Header::configure_from(matches, config),
+            output_format: OutputFormat::configure_from(matches, config),
+            truncate_names: TruncateNames::configure_from(matches, config),
+            follow_symlinks: FollowSymlinks::configure_from(matches, config),
+            git_renames: GitRenames::configure_from(matches, config),
+            git_status_style: GitStatusStyle::configure_from(matches, config),
+            names_only: NamesOnly::configure_from(matches, config),
+            git_untracked_count: GitUntrackedCount::configure_from(matches, config),
+            extensions: Extensions::configure_from(matches, config),
+            sort_cmd: SortCmd::configure_from(matches, config),
+            table: Table::configure_from(matches, config),
+            keep_duplicates: KeepDuplicates::configure_from(matches, config),
+            group_devices: GroupDevices::configure_from(matches, config),
+            count: Count::configure_from(matches, config),
+            elide_prefix: ElidePrefix::configure_from(matches, config),
+            summary: Summary::configure_from(matches, config),
+            raw: Raw::configure_from(matches, config),
+            paginate: Paginate::configure_from(matches, config),
+            watch: Watch::configure_from(matches, config),
+            info: Info::configure_from(matches, config),
+            checksum: Checksum::configure_from(matches, config),
+            glob: Glob::configure_from(matches, config),
+            no_glob_error: NoGlobError::configure_from(matches, config),
+            max_entries: MaxEntries::configure_from(matches, config),
+            total_size_symlinks: TotalSizeSymlinks::configure_from(matches, config),
+            count_links: CountLinks::configure_from(matches, config),
+            columns: Columns::configure_from(matches, config),
+            tree_limit: TreeLimit::configure_from(matches, config),
+            debug_readdir: DebugReaddir::configure_from(matches, config),
+            tree_connectors: TreeConnectors::configure_from(matches, config),
+            verbose_permissions: VerbosePermissions::configure_from(matches, config),
+            ignore_files: IgnoreFiles::configure_from(matches, config),
+            find: Find::configure_from(matches)?,
+            truncate_middle: TruncateMiddle::configure_from(matches, config),
+            date_timezone: DateTimezone::configure_from(matches, config),
+            git_change_counts: GitChangeCounts::configure_from(matches, config),
+            yes: Yes::configure_from(matches, config),
         })
     }
 }
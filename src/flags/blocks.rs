@@ -8,6 +8,14 @@ use std::convert::TryFrom;
 
 use clap::{ArgMatches, Error, ErrorKind};
 
+/// The arguments removing a [Block] from the ones displayed.
+const NO_BLOCK_ARGS: &[(&str, Block)] = &[
+    ("no-user", Block::User),
+    ("no-group", Block::Group),
+    ("no-permissions", Block::Permission),
+    ("no-time", Block::Date),
+];
+
 /// A struct to hold a [Vec] of [Block]s and to provide methods to create it.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Blocks(pub Vec<Block>);
@@ -22,12 +30,14 @@ impl Blocks {
     ///
     /// No matter if the "long" argument was passed, if the "inode" argument is passed and the
     /// `Blocks` does not contain a [Block] of variant [INode](Block::INode) yet, one is prepended
-    /// to the returned value.
+    /// to the returned value. With the "long" argument, the "group" argument adds a missing
+    /// [Group](Block::Group) block after the [User](Block::User) one. The blocks of the "no-user",
+    /// "no-group", "no-permissions" and "no-time" arguments passed are then removed from it.
     ///
     /// # Errors
     ///
     /// This errors if any of the [ArgMatches] parameter arguments causes [Block]'s implementation
-    /// of [TryFrom::try_from] to return an [Err].
+    /// of [TryFrom::try_from] to return an [Err], or if the removed blocks leave none to display.
     pub fn configure_from(matches: &ArgMatches, config: &Config) -> Result<Self, Error> {
         let mut result: Result<Self, Error> = if matches.is_present("long") {
             Ok(Self::long())
@@ -53,7 +63,33 @@ impl Blocks {
 
         if matches.is_present("git") && matches.is_present("long") {
             if let Ok(blocks) = result.as_mut() {
-                blocks.optional_add_git_status();
+                blocks.optional_add_before_name(Block::GitStatus);
+            }
+        }
+
+        if matches.is_present("filesystem") && matches.is_present("long") {
+            if let Ok(blocks) = result.as_mut() {
+                blocks.optional_add_before_name(Block::Filesystem);
+            }
+        }
+
+        if matches.is_present("group") && matches.is_present("long") {
+            if let Ok(blocks) = result.as_mut() {
+                blocks.optional_add_group();
+            }
+        }
+
+        if let Ok(blocks) = result.as_mut() {
+            for (arg, block) in NO_BLOCK_ARGS {
+                if matches.is_present(arg) {
+                    blocks.0.retain(|b| b != block);
+                }
+            }
+            if blocks.0.is_empty() {
+                return Err(Error::with_description(
+                    "The --no-* arguments can not remove every block to display.",
+                    ErrorKind::ArgumentConflict,
+                ));
             }
         }
 
@@ -151,25 +187,28 @@ impl Blocks {
         }
     }
 
-    /// Checks whether `self` already contains a [Block] of variant [GitStatus](Block::GitSatus).
-    fn contains_git_status(&self) -> bool {
-        self.0.contains(&Block::GitStatus)
+    /// Puts a [Group](Block::Group) block on the right of the [User](Block::User) block of `self`,
+    /// or else on the left of its [Name](Block::Name) block, if `self` does not already contain a
+    /// Block of that variant.
+    fn optional_add_group(&mut self) {
+        match self.0.iter().position(|&b| b == Block::User) {
+            Some(position) if !self.0.contains(&Block::Group) => {
+                self.0.insert(position + 1, Block::Group)
+            }
+            _ => self.optional_add_before_name(Block::Group),
+        }
     }
 
-    /// Put a [Block] of variant [GitStatus](Block::GitSatus) on the left of [GitStatus](Block::Name) to `self`.
-    fn add_git_status(&mut self) {
+    /// Puts `block` on the left of the [Name](Block::Name) block of `self`, if `self` does not
+    /// already contain a Block of that variant.
+    fn optional_add_before_name(&mut self, block: Block) {
+        if self.0.contains(&block) {
+            return;
+        }
         if let Some(position) = self.0.iter().position(|&b| b == Block::Name) {
-            self.0.insert(position, Block::GitStatus);
+            self.0.insert(position, block);
         } else {
-            self.0.push(Block::GitStatus);
-        }
-    }
-
-    /// Prepends a [Block] of variant [GitStatus](Block::GitSatus), if `self` does not already contain a
-    /// Block of that variant.
-    fn optional_add_git_status(&mut self) {
-        if !self.contains_git_status() {
-            self.add_git_status()
+            self.0.push(block);
         }
     }
 }
@@ -193,7 +232,37 @@ pub enum Block {
     Name,
     INode,
     Links,
+    Security,
+    Filesystem,
+    Kind,
+    Allocated,
+    Created,
     GitStatus,
+    GitRepo,
+}
+
+impl Block {
+    /// The label of the block in the header row.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Permission => "Permissions",
+            Self::User => "User",
+            Self::Group => "Group",
+            Self::Size => "Size",
+            Self::SizeValue => "Size",
+            Self::Date => "Date",
+            Self::Name => "Name",
+            Self::INode => "INode",
+            Self::Links => "Links",
+            Self::Security => "Security",
+            Self::Filesystem => "Filesystem",
+            Self::Kind => "Kind",
+            Self::Allocated => "Allocated",
+            Self::Created => "Created",
+            Self::GitStatus => "Git",
+            Self::GitRepo => "Repo",
+        }
+    }
 }
 
 impl TryFrom<&str> for Block {
@@ -210,7 +279,13 @@ impl TryFrom<&str> for Block {
             "name" => Ok(Self::Name),
             "inode" => Ok(Self::INode),
             "links" => Ok(Self::Links),
+            "security" => Ok(Self::Security),
+            "filesystem" => Ok(Self::Filesystem),
+            "kind" => Ok(Self::Kind),
+            "allocated" => Ok(Self::Allocated),
+            "created" => Ok(Self::Created),
             "git" => Ok(Self::GitStatus),
+            "repo" => Ok(Self::GitRepo),
             _ => Err(format!("Not a valid block name: {}", &string)),
         }
     }
@@ -224,7 +299,7 @@ mod test_blocks {
     use crate::app;
     use crate::config_file::Config;
 
-    use clap::Error;
+    use clap::{Error, ErrorKind};
 
     // The following tests are implemented using match expressions instead of the assert_eq macro,
     // because clap::Error does not implement PartialEq.
@@ -421,6 +496,98 @@ mod test_blocks {
         });
     }
 
+    #[test]
+    fn test_configure_from_no_blocks() {
+        let argv = vec!["lsd", "--long", "--no-user", "--no-time"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let test_blocks = Blocks(vec![
+            Block::Permission,
+            Block::Group,
+            Block::Size,
+            Block::Name,
+        ]);
+        assert!(matches!(
+            Blocks::configure_from(&matches, &Config::with_none()),
+            Ok(blocks) if blocks == test_blocks
+        ));
+
+        let argv = vec!["lsd", "--blocks", "group,name", "--no-group"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert!(matches!(
+            Blocks::configure_from(&matches, &Config::with_none()),
+            Ok(blocks) if blocks == Blocks(vec![Block::Name])
+        ));
+    }
+
+    #[test]
+    fn test_configure_from_no_blocks_left() {
+        let argv = vec!["lsd", "--long", "--blocks", "date", "--no-time"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert!(matches!(
+            Blocks::configure_from(&matches, &Config::with_none()),
+            Err(Error {
+                kind: ErrorKind::ArgumentConflict,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_from_arg_matches_implicit_add_filesystem_block() {
+        let argv = vec![
+            "lsd",
+            "--blocks",
+            "permission,name,group,date",
+            "--filesystem",
+            "--long",
+        ];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let test_blocks = Blocks(vec![
+            Block::Permission,
+            Block::Filesystem,
+            Block::Name,
+            Block::Group,
+            Block::Date,
+        ]);
+        assert!(matches!(
+            Blocks::configure_from(&matches, &Config::with_none()),
+            Ok(blocks) if blocks == test_blocks
+        ));
+    }
+
+    #[test]
+    fn test_from_arg_matches_implicit_add_group_block() {
+        let argv = vec!["lsd", "--blocks", "user,date,name", "--group", "--long"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let test_blocks = Blocks(vec![Block::User, Block::Group, Block::Date, Block::Name]);
+        assert!(matches!(
+            Blocks::configure_from(&matches, &Config::with_none()),
+            Ok(blocks) if blocks == test_blocks
+        ));
+
+        let argv = vec!["lsd", "--blocks", "size,name", "--group", "--long"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let test_blocks = Blocks(vec![Block::Size, Block::Group, Block::Name]);
+        assert!(matches!(
+            Blocks::configure_from(&matches, &Config::with_none()),
+            Ok(blocks) if blocks == test_blocks
+        ));
+
+        // The last of --group and --no-group is the one used.
+        let argv = vec!["lsd", "--long", "--group", "--no-group"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert!(matches!(
+            Blocks::configure_from(&matches, &Config::with_none()),
+            Ok(blocks) if !blocks.0.contains(&Block::Group)
+        ));
+        let argv = vec!["lsd", "--long", "--no-group", "--group"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert!(matches!(
+            Blocks::configure_from(&matches, &Config::with_none()),
+            Ok(blocks) if blocks.0.contains(&Block::Group)
+        ));
+    }
+
     #[cfg(feature = "git")]
     #[test]
     fn test_from_arg_matches_implicit_add_git_block() {
@@ -613,8 +780,38 @@ mod test_block {
         assert_eq!(Ok(Block::Links), Block::try_from("links"));
     }
 
+    #[test]
+    fn test_security() {
+        assert_eq!(Ok(Block::Security), Block::try_from("security"));
+    }
+
+    #[test]
+    fn test_filesystem() {
+        assert_eq!(Ok(Block::Filesystem), Block::try_from("filesystem"));
+    }
+
+    #[test]
+    fn test_kind() {
+        assert_eq!(Ok(Block::Kind), Block::try_from("kind"));
+    }
+
+    #[test]
+    fn test_allocated() {
+        assert_eq!(Ok(Block::Allocated), Block::try_from("allocated"));
+    }
+
+    #[test]
+    fn test_created() {
+        assert_eq!(Ok(Block::Created), Block::try_from("created"));
+    }
+
     #[test]
     fn test_git_status() {
         assert_eq!(Ok(Block::GitStatus), Block::try_from("git"));
     }
+
+    #[test]
+    fn test_git_repo() {
+        assert_eq!(Ok(Block::GitRepo), Block::try_from("repo"));
+    }
 }
@@ -0,0 +1,60 @@
+//! This module defines the [Checksum] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to compute the checksum of the file shown by the detail panel.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Checksum(pub bool);
+
+impl Configurable<Self> for Checksum {
+    /// Get a potential `Checksum` value from [ArgMatches].
+    ///
+    /// If the "checksum" argument is passed, this returns a `Checksum` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("checksum") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// The checksum is only computed on demand, as it reads the whole file, so it can not be
+    /// configured in the [Config]. This always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Checksum;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, Checksum::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--info", "--checksum"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(Checksum(true)), Checksum::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, Checksum::from_config(&Config::with_none()));
+    }
+}
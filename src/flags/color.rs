@@ -4,25 +4,56 @@
 use super::Configurable;
 
 use crate::config_file::Config;
+use crate::flags::Block;
 use crate::print_error;
 
 use clap::ArgMatches;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::convert::TryFrom;
 
 /// A collection of flags on how to use colors.
-#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct Color {
     /// When to use color.
     pub when: ColorOption,
+    /// From which sizes a file size is colored as medium, large or huge.
+    pub size_thresholds: SizeThresholds,
+    /// From which ages a date is colored as within the last day, older and very old.
+    pub date_thresholds: DateThresholds,
+    /// The colors of the tree guides for each depth level.
+    pub tree_edges: TreeEdges,
+    /// The background colors of the setuid files and the setgid directories.
+    pub setid_backgrounds: SetidBackgrounds,
+    /// The background color of the size of the sparse files.
+    pub sparse_background: SparseBackground,
+    /// The blocks shown uncolored or in a single color.
+    pub block_colors: BlockColors,
 }
 
 impl Color {
     /// Get a `Color` struct from [ArgMatches], a [Config] or the [Default] values.
     ///
-    /// The [ColorOption] is configured with their respective [Configurable] implementation.
+    /// The [ColorOption], [SizeThresholds], [DateThresholds], [TreeEdges], [SetidBackgrounds],
+    /// [SparseBackground] and [BlockColors] are configured with their respective [Configurable]
+    /// implementation.
     pub fn configure_from(matches: &ArgMatches, config: &Config) -> Self {
         let when = ColorOption::configure_from(matches, config);
-        Self { when }
+        let size_thresholds = SizeThresholds::configure_from(matches, config);
+        let date_thresholds = DateThresholds::configure_from(matches, config);
+        let tree_edges = TreeEdges::configure_from(matches, config);
+        let setid_backgrounds = SetidBackgrounds::configure_from(matches, config);
+        let sparse_background = SparseBackground::configure_from(matches, config);
+        let block_colors = BlockColors::configure_from(matches, config);
+        Self {
+            when,
+            size_thresholds,
+            date_thresholds,
+            tree_edges,
+            setid_backgrounds,
+            sparse_background,
+            block_colors,
+        }
     }
 }
 
@@ -54,6 +85,20 @@ impl ColorOption {
 }
 
 impl Configurable<Self> for ColorOption {
+    /// Get the `ColorOption` from [ArgMatches], a [Config] or the [Default] value, the first one
+    /// to have a value winning. Only when it is [ColorOption::Auto], the environment is then
+    /// checked with [from_environment](Configurable::from_environment), as `--color always` or
+    /// `never` and their configured counterparts are explicit choices prevailing over it.
+    fn configure_from(matches: &ArgMatches, config: &Config) -> Self {
+        let when = Self::from_arg_matches(matches)
+            .or_else(|| Self::from_config(config))
+            .unwrap_or_default();
+        match when {
+            Self::Auto => Self::from_environment().unwrap_or(Self::Auto),
+            when => when,
+        }
+    }
+
     /// Get a potential `ColorOption` variant from [ArgMatches].
     ///
     /// If the "classic" argument is passed, then this returns the [ColorOption::Never] variant in
@@ -84,7 +129,25 @@ impl Configurable<Self> for ColorOption {
         }
 
         if let Some(color) = &config.color {
-            Some(color.when)
+            color.when
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `ColorOption` variant from the environment.
+    ///
+    /// If `CLICOLOR_FORCE` is set to anything but "0", this returns [ColorOption::Always] in a
+    /// [Some]. Otherwise if `NO_COLOR` is set and not empty, or `CLICOLOR` is set to "0", this
+    /// returns [ColorOption::Never] in a [Some]. Otherwise this returns [None].
+    fn from_environment() -> Option<Self> {
+        let var = |name| std::env::var_os(name).map(|value| value.to_string_lossy().to_string());
+        if matches!(var("CLICOLOR_FORCE"), Some(force) if force != "0") {
+            Some(Self::Always)
+        } else if matches!(var("NO_COLOR"), Some(no_color) if !no_color.is_empty())
+            || var("CLICOLOR").as_deref() == Some("0")
+        {
+            Some(Self::Never)
         } else {
             None
         }
@@ -98,6 +161,341 @@ impl Default for ColorOption {
     }
 }
 
+/// The sizes, in bytes, from which a file size is colored as medium, large and huge. Smaller
+/// sizes are colored as small.
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub struct SizeThresholds {
+    pub medium: u64,
+    pub large: u64,
+    pub huge: u64,
+}
+
+impl Configurable<Self> for SizeThresholds {
+    /// The size thresholds can not be passed as arguments, so this always returns [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `SizeThresholds` value from a [Config].
+    ///
+    /// If the `Config::color::size-thresholds` has value, this returns its values in a [Some],
+    /// any missing threshold keeping its default value. If the thresholds are not increasing, an
+    /// error is printed and this returns [None]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        let thresholds = config.color.as_ref()?.size_thresholds.as_ref()?;
+        let default = Self::default();
+        let result = Self {
+            medium: thresholds.medium.unwrap_or(default.medium),
+            large: thresholds.large.unwrap_or(default.large),
+            huge: thresholds.huge.unwrap_or(default.huge),
+        };
+
+        if result.medium < result.large && result.large < result.huge {
+            Some(result)
+        } else {
+            print_error!(
+                "Config color.size-thresholds must be increasing, got medium: {}, large: {} and huge: {}.",
+                result.medium,
+                result.large,
+                result.huge
+            );
+            None
+        }
+    }
+}
+
+/// The default values for `SizeThresholds` are 1 MiB, 1 GiB and 1 TiB.
+impl Default for SizeThresholds {
+    fn default() -> Self {
+        Self {
+            medium: 1024 * 1024,
+            large: 1024 * 1024 * 1024,
+            huge: 1024 * 1024 * 1024 * 1024,
+        }
+    }
+}
+
+/// The ages, in seconds, from which a date is no more colored as within the last hour and as
+/// within the last day, then from which it is colored as very old, the older dates being all
+/// colored alike when [None].
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub struct DateThresholds {
+    pub recent: u64,
+    pub old: u64,
+    pub very_old: Option<u64>,
+}
+
+impl DateThresholds {
+    /// Parses an age written as a number followed by its unit: `s`, `m`, `h`, `d` or `w`.
+    fn parse_age(age: &str) -> Option<u64> {
+        let unit = age.chars().last()?;
+        let number: u64 = age[..age.len() - unit.len_utf8()].parse().ok()?;
+        let seconds = match unit {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+        // The dates can not be compared with ages overflowing the range of the dates.
+        number
+            .checked_mul(seconds)
+            .filter(|&seconds| seconds < 1 << 40)
+    }
+}
+
+impl Configurable<Self> for DateThresholds {
+    /// The date thresholds can not be passed as arguments, so this always returns [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `DateThresholds` value from a [Config].
+    ///
+    /// If the `Config::color::date-thresholds` has value, this returns its values in a [Some],
+    /// any missing threshold keeping its default value. If an age is not valid or the thresholds
+    /// are not increasing, an error is printed and this returns [None]. Otherwise this returns
+    /// [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        let thresholds = config.color.as_ref()?.date_thresholds.as_ref()?;
+        let parse = |age: &Option<String>| match age {
+            Some(age) => match Self::parse_age(age) {
+                Some(seconds) => Ok(Some(seconds)),
+                None => Err(age.clone()),
+            },
+            None => Ok(None),
+        };
+        let ages = (
+            parse(&thresholds.recent),
+            parse(&thresholds.old),
+            parse(&thresholds.very_old),
+        );
+        let (recent, old, very_old) = match ages {
+            (Ok(recent), Ok(old), Ok(very_old)) => (recent, old, very_old),
+            (Err(age), _, _) | (_, Err(age), _) | (_, _, Err(age)) => {
+                print_error!(
+                    "Config color.date-thresholds has an invalid age {}, expected a number followed by s, m, h, d or w.",
+                    age
+                );
+                return None;
+            }
+        };
+        let default = Self::default();
+        let result = Self {
+            recent: recent.unwrap_or(default.recent),
+            old: old.unwrap_or(default.old),
+            very_old,
+        };
+
+        let increasing = match result.very_old {
+            Some(very_old) => result.old < very_old,
+            None => true,
+        };
+        if result.recent < result.old && increasing {
+            Some(result)
+        } else {
+            print_error!("Config color.date-thresholds must be increasing.");
+            None
+        }
+    }
+}
+
+/// The default values for `DateThresholds` are an hour and a day, without very old dates.
+impl Default for DateThresholds {
+    fn default() -> Self {
+        Self {
+            recent: 60 * 60,
+            old: 24 * 60 * 60,
+            very_old: None,
+        }
+    }
+}
+
+/// The colors the tree guides are colored with, one after another as the
+/// tree gets deeper. When empty, the guides all have the same color.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct TreeEdges(pub Vec<ColorValue>);
+
+impl Configurable<Self> for TreeEdges {
+    /// The tree edges colors can not be passed as arguments, so this always returns [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `TreeEdges` value from a [Config].
+    ///
+    /// If the `Config::color::tree-edges` has value, this returns it in a [Some]. Otherwise this
+    /// returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        let tree_edges = config.color.as_ref()?.tree_edges.as_ref()?;
+        Some(Self(tree_edges.clone()))
+    }
+}
+
+/// A color as written in the config file.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawColorValue")]
+pub enum ColorValue {
+    /// A 256-color palette number.
+    Fixed(u8),
+    /// A true color, from its red, green and blue components.
+    Rgb(u8, u8, u8),
+}
+
+impl ColorValue {
+    /// Get a true color from its `#rrggbb` hexadecimal notation.
+    pub fn from_hex(value: &str) -> Option<Self> {
+        if value.len() != 7 || !value.starts_with('#') {
+            return None;
+        }
+        let hex = &value[1..];
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let component = |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).ok();
+        Some(Self::Rgb(component(0)?, component(2)?, component(4)?))
+    }
+}
+
+/// A [ColorValue] as written in the config file, either a number or a `#rrggbb` string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawColorValue {
+    Fixed(u8),
+    Hex(String),
+}
+
+impl TryFrom<RawColorValue> for ColorValue {
+    type Error = String;
+
+    fn try_from(raw: RawColorValue) -> Result<Self, Self::Error> {
+        match raw {
+            RawColorValue::Fixed(n) => Ok(Self::Fixed(n)),
+            RawColorValue::Hex(hex) => Self::from_hex(&hex).ok_or_else(|| {
+                format!(
+                    "a color could only be a 256-color number or #rrggbb, got {}",
+                    hex
+                )
+            }),
+        }
+    }
+}
+
+/// A background color, or the color of all the elements of a block replacing the ones of the
+/// theme, which can be turned off.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawOptionalColor")]
+pub enum Background {
+    /// No color at all.
+    None,
+    /// A 256-color palette number or a true color.
+    Color(ColorValue),
+}
+
+/// A [Background] as written in the config file, either a number, a `#rrggbb` string or "none".
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawOptionalColor {
+    Fixed(u8),
+    Keyword(String),
+}
+
+impl TryFrom<RawOptionalColor> for Background {
+    type Error = String;
+
+    fn try_from(raw: RawOptionalColor) -> Result<Self, Self::Error> {
+        match raw {
+            RawOptionalColor::Fixed(n) => Ok(Self::Color(ColorValue::Fixed(n))),
+            RawOptionalColor::Keyword(keyword) if keyword == "none" => Ok(Self::None),
+            RawOptionalColor::Keyword(keyword) => match ColorValue::from_hex(&keyword) {
+                Some(color) => Ok(Self::Color(color)),
+                None => Err(format!(
+                    "a color could only be a 256-color number, #rrggbb or none, got {}",
+                    keyword
+                )),
+            },
+        }
+    }
+}
+
+/// The flag holding the blocks colored otherwise than by the theme, as the date block left
+/// uncolored while the names keep their colors.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct BlockColors(pub HashMap<Block, Background>);
+
+impl Configurable<Self> for BlockColors {
+    /// The block colors can not be passed as arguments, so this always returns [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `BlockColors` value from a [Config].
+    ///
+    /// If the `Config::color::blocks` has value, this returns the colors of the valid block names
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        let blocks = config.color.as_ref()?.blocks.as_ref()?;
+        Some(Self(
+            blocks
+                .iter()
+                .filter_map(|(name, color)| Some((Block::try_from(name.as_str()).ok()?, *color)))
+                .collect(),
+        ))
+    }
+}
+
+/// The backgrounds the setuid files and the setgid directories are highlighted with, the ones
+/// of the theme being kept when [None].
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct SetidBackgrounds {
+    pub file_setuid: Option<Background>,
+    pub dir_setgid: Option<Background>,
+}
+
+impl Configurable<Self> for SetidBackgrounds {
+    /// The backgrounds can not be passed as arguments, so this always returns [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `SetidBackgrounds` value from a [Config].
+    ///
+    /// If either the `Config::color::file-setuid` or the `Config::color::dir-setgid` has value,
+    /// this returns them in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        let color = config.color.as_ref()?;
+        if color.file_setuid.is_none() && color.dir_setgid.is_none() {
+            return None;
+        }
+        Some(Self {
+            file_setuid: color.file_setuid,
+            dir_setgid: color.dir_setgid,
+        })
+    }
+}
+
+/// The background the size of the sparse files is highlighted with, the one of the theme being
+/// kept when [None].
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct SparseBackground(pub Option<Background>);
+
+impl Configurable<Self> for SparseBackground {
+    /// The background can not be passed as an argument, so this always returns [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `SparseBackground` value from a [Config].
+    ///
+    /// If the `Config::color::sparse-file` has value, this returns it in a [Some]. Otherwise this
+    /// returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        let background = config.color.as_ref()?.sparse_file?;
+        Some(Self(Some(background)))
+    }
+}
+
 #[cfg(test)]
 mod test_color_option {
     use super::ColorOption;
@@ -172,7 +570,14 @@ mod test_color_option {
     fn test_from_config_always() {
         let mut c = Config::with_none();
         c.color = Some(config_file::Color {
-            when: ColorOption::Always,
+            when: Some(ColorOption::Always),
+            size_thresholds: None,
+            date_thresholds: None,
+            tree_edges: None,
+            file_setuid: None,
+            dir_setgid: None,
+            sparse_file: None,
+            blocks: None,
         });
 
         assert_eq!(Some(ColorOption::Always), ColorOption::from_config(&c));
@@ -182,7 +587,14 @@ mod test_color_option {
     fn test_from_config_auto() {
         let mut c = Config::with_none();
         c.color = Some(config_file::Color {
-            when: ColorOption::Auto,
+            when: Some(ColorOption::Auto),
+            size_thresholds: None,
+            date_thresholds: None,
+            tree_edges: None,
+            file_setuid: None,
+            dir_setgid: None,
+            sparse_file: None,
+            blocks: None,
         });
         assert_eq!(Some(ColorOption::Auto), ColorOption::from_config(&c));
     }
@@ -191,7 +603,14 @@ mod test_color_option {
     fn test_from_config_never() {
         let mut c = Config::with_none();
         c.color = Some(config_file::Color {
-            when: ColorOption::Never,
+            when: Some(ColorOption::Never),
+            size_thresholds: None,
+            date_thresholds: None,
+            tree_edges: None,
+            file_setuid: None,
+            dir_setgid: None,
+            sparse_file: None,
+            blocks: None,
         });
         assert_eq!(Some(ColorOption::Never), ColorOption::from_config(&c));
     }
@@ -200,9 +619,377 @@ mod test_color_option {
     fn test_from_config_classic_mode() {
         let mut c = Config::with_none();
         c.color = Some(config_file::Color {
-            when: ColorOption::Always,
+            when: Some(ColorOption::Always),
+            size_thresholds: None,
+            date_thresholds: None,
+            tree_edges: None,
+            file_setuid: None,
+            dir_setgid: None,
+            sparse_file: None,
+            blocks: None,
         });
         c.classic = Some(true);
         assert_eq!(Some(ColorOption::Never), ColorOption::from_config(&c));
     }
+
+    fn with_environment(vars: &[(&str, &str)], argv: Vec<&str>) -> ColorOption {
+        for name in &["NO_COLOR", "CLICOLOR", "CLICOLOR_FORCE"] {
+            std::env::remove_var(name);
+        }
+        for (name, value) in vars {
+            std::env::set_var(name, value);
+        }
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let when = ColorOption::configure_from(&matches, &Config::with_none());
+        for (name, _) in vars {
+            std::env::remove_var(name);
+        }
+        when
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_from_environment() {
+        assert_eq!(ColorOption::Auto, with_environment(&[], vec!["lsd"]));
+        assert_eq!(
+            ColorOption::Never,
+            with_environment(&[("NO_COLOR", "1")], vec!["lsd"])
+        );
+        assert_eq!(
+            ColorOption::Auto,
+            with_environment(&[("NO_COLOR", "")], vec!["lsd"])
+        );
+        assert_eq!(
+            ColorOption::Never,
+            with_environment(&[("CLICOLOR", "0")], vec!["lsd"])
+        );
+        assert_eq!(
+            ColorOption::Auto,
+            with_environment(&[("CLICOLOR", "1")], vec!["lsd"])
+        );
+        assert_eq!(
+            ColorOption::Always,
+            with_environment(&[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")], vec!["lsd"])
+        );
+        assert_eq!(
+            ColorOption::Auto,
+            with_environment(&[("CLICOLOR_FORCE", "0")], vec!["lsd"])
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_from_environment_with_explicit_color() {
+        assert_eq!(
+            ColorOption::Always,
+            with_environment(&[("NO_COLOR", "1")], vec!["lsd", "--color", "always"])
+        );
+        assert_eq!(
+            ColorOption::Never,
+            with_environment(&[("CLICOLOR_FORCE", "1")], vec!["lsd", "--color", "never"])
+        );
+        assert_eq!(
+            ColorOption::Never,
+            with_environment(&[("NO_COLOR", "1")], vec!["lsd", "--color", "auto"])
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_size_thresholds {
+    use super::SizeThresholds;
+
+    use crate::app;
+    use crate::config_file::{self, Config};
+    use crate::flags::Configurable;
+
+    fn config_with(medium: Option<u64>, large: Option<u64>, huge: Option<u64>) -> Config {
+        let mut c = Config::with_none();
+        c.color = Some(config_file::Color {
+            when: None,
+            size_thresholds: Some(config_file::SizeThresholds {
+                medium,
+                large,
+                huge,
+            }),
+            date_thresholds: None,
+            tree_edges: None,
+            file_setuid: None,
+            dir_setgid: None,
+            sparse_file: None,
+            blocks: None,
+        });
+        c
+    }
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, SizeThresholds::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, SizeThresholds::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_all() {
+        let c = config_with(Some(10), Some(20), Some(30));
+        assert_eq!(
+            Some(SizeThresholds {
+                medium: 10,
+                large: 20,
+                huge: 30,
+            }),
+            SizeThresholds::from_config(&c)
+        );
+    }
+
+    #[test]
+    fn test_from_config_partial() {
+        let c = config_with(None, Some(2048 * 1024), None);
+        assert_eq!(
+            Some(SizeThresholds {
+                large: 2048 * 1024,
+                ..SizeThresholds::default()
+            }),
+            SizeThresholds::from_config(&c)
+        );
+    }
+
+    #[test]
+    fn test_from_config_not_increasing() {
+        let c = config_with(Some(30), Some(20), Some(10));
+        assert_eq!(None, SizeThresholds::from_config(&c));
+    }
+}
+
+#[cfg(test)]
+mod test_date_thresholds {
+    use super::DateThresholds;
+
+    use crate::config_file::{self, Config};
+    use crate::flags::Configurable;
+
+    fn config_with(recent: Option<&str>, old: Option<&str>, very_old: Option<&str>) -> Config {
+        let mut c = Config::with_none();
+        c.color = Some(config_file::Color {
+            when: None,
+            size_thresholds: None,
+            date_thresholds: Some(config_file::DateThresholds {
+                recent: recent.map(String::from),
+                old: old.map(String::from),
+                very_old: very_old.map(String::from),
+            }),
+            tree_edges: None,
+            file_setuid: None,
+            dir_setgid: None,
+            sparse_file: None,
+            blocks: None,
+        });
+        c
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, DateThresholds::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_all() {
+        let c = config_with(Some("15m"), Some("30d"), Some("52w"));
+        assert_eq!(
+            Some(DateThresholds {
+                recent: 15 * 60,
+                old: 30 * 24 * 60 * 60,
+                very_old: Some(52 * 7 * 24 * 60 * 60),
+            }),
+            DateThresholds::from_config(&c)
+        );
+    }
+
+    #[test]
+    fn test_from_config_partial() {
+        let c = config_with(None, None, Some("90d"));
+        assert_eq!(
+            Some(DateThresholds {
+                very_old: Some(90 * 24 * 60 * 60),
+                ..DateThresholds::default()
+            }),
+            DateThresholds::from_config(&c)
+        );
+    }
+
+    #[test]
+    fn test_from_config_invalid() {
+        assert_eq!(
+            None,
+            DateThresholds::from_config(&config_with(Some("15"), None, None))
+        );
+        assert_eq!(
+            None,
+            DateThresholds::from_config(&config_with(None, Some("1y"), None))
+        );
+        assert_eq!(
+            None,
+            DateThresholds::from_config(&config_with(Some("2d"), None, None))
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_tree_edges {
+    use super::{ColorValue, TreeEdges};
+
+    use crate::app;
+    use crate::config_file::{self, Config};
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, TreeEdges::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, TreeEdges::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_palette() {
+        let mut c = Config::with_none();
+        c.color = Some(config_file::Color {
+            when: None,
+            size_thresholds: None,
+            date_thresholds: None,
+            tree_edges: Some(vec![ColorValue::Fixed(33), ColorValue::Rgb(255, 0, 0)]),
+            file_setuid: None,
+            dir_setgid: None,
+            sparse_file: None,
+            blocks: None,
+        });
+        assert_eq!(
+            Some(TreeEdges(vec![
+                ColorValue::Fixed(33),
+                ColorValue::Rgb(255, 0, 0)
+            ])),
+            TreeEdges::from_config(&c)
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_setid_backgrounds {
+    use super::{Background, ColorValue, SetidBackgrounds};
+
+    use crate::app;
+    use crate::config_file::{self, Config};
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, SetidBackgrounds::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, SetidBackgrounds::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_backgrounds() {
+        let mut c = Config::with_none();
+        c.color = Some(config_file::Color {
+            when: None,
+            size_thresholds: None,
+            date_thresholds: None,
+            tree_edges: None,
+            file_setuid: Some(Background::None),
+            dir_setgid: Some(Background::Color(ColorValue::Fixed(22))),
+            sparse_file: None,
+            blocks: None,
+        });
+        assert_eq!(
+            Some(SetidBackgrounds {
+                file_setuid: Some(Background::None),
+                dir_setgid: Some(Background::Color(ColorValue::Fixed(22))),
+            }),
+            SetidBackgrounds::from_config(&c)
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_sparse_background {
+    use super::{Background, SparseBackground};
+
+    use crate::config_file::{self, Config};
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, SparseBackground::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_background() {
+        let mut c = Config::with_none();
+        c.color = Some(config_file::Color {
+            when: None,
+            size_thresholds: None,
+            date_thresholds: None,
+            tree_edges: None,
+            file_setuid: None,
+            dir_setgid: None,
+            sparse_file: Some(Background::None),
+            blocks: None,
+        });
+        assert_eq!(
+            Some(SparseBackground(Some(Background::None))),
+            SparseBackground::from_config(&c)
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_block_colors {
+    use super::{Background, BlockColors};
+
+    use crate::config_file::{self, Config};
+    use crate::flags::{Block, Configurable};
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, BlockColors::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_blocks() {
+        let mut blocks = HashMap::new();
+        blocks.insert("date".to_string(), Background::None);
+        blocks.insert("unknown".to_string(), Background::None);
+        let mut c = Config::with_none();
+        c.color = Some(config_file::Color {
+            when: None,
+            size_thresholds: None,
+            date_thresholds: None,
+            tree_edges: None,
+            file_setuid: None,
+            dir_setgid: None,
+            sparse_file: None,
+            blocks: Some(blocks),
+        });
+
+        let mut expected = HashMap::new();
+        expected.insert(Block::Date, Background::None);
+        assert_eq!(Some(BlockColors(expected)), BlockColors::from_config(&c));
+    }
 }
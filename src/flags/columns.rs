@@ -0,0 +1,84 @@
+//! This module defines the [Columns] flag. To set it up from [ArgMatches], a [Config] and
+//! its [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag holding how many columns the grid layout has, whatever the width of the terminal is.
+/// The columns are fitted into the terminal when it is [None].
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Columns(pub Option<usize>);
+
+impl Configurable<Self> for Columns {
+    /// Get a potential `Columns` value from [ArgMatches].
+    ///
+    /// If the "columns" argument is passed, this returns a `Columns` with its parameter as value
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.occurrences_of("columns") > 0 {
+            let count = matches.values_of("columns")?.next_back()?;
+            count.parse::<usize>().ok().map(|n| Self(Some(n)))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `Columns` value from a [Config].
+    ///
+    /// If the `Config::columns` has a value other than 0, this returns it as the value of the
+    /// `Columns`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.columns.filter(|&n| n > 0).map(|n| Self(Some(n)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Columns;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, Columns::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_count() {
+        let argv = vec!["lsd", "--columns", "3"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(Columns(Some(3))), Columns::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_invalid() {
+        let argv = vec!["lsd", "--columns", "0"];
+        assert!(app::build().get_matches_from_safe(argv).is_err());
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, Columns::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_count() {
+        let mut c = Config::with_none();
+        c.columns = Some(3);
+        assert_eq!(Some(Columns(Some(3))), Columns::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_zero() {
+        let mut c = Config::with_none();
+        c.columns = Some(0);
+        assert_eq!(None, Columns::from_config(&c));
+    }
+}
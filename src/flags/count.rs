@@ -0,0 +1,87 @@
+//! This module defines the [Count] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use its [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to print the number of listed entries instead of the listing.
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum Count {
+    /// The entries are listed.
+    None,
+    /// A single number is printed for each path.
+    Total,
+    /// A number is printed for each file type found in each path.
+    ByType,
+}
+
+impl Configurable<Self> for Count {
+    /// Get a potential `Count` variant from [ArgMatches].
+    ///
+    /// If the "count" argument is passed, this returns [Count::ByType] in a [Some] when its last
+    /// value is "type", and [Count::Total] otherwise. If it is not passed, this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if !matches.is_present("count") {
+            return None;
+        }
+        match matches
+            .values_of("count")
+            .and_then(|mut values| values.next_back())
+        {
+            Some("type") => Some(Self::ByType),
+            _ => Some(Self::Total),
+        }
+    }
+
+    /// The count can not be configured in the [Config], as it would replace all the listings.
+    /// This always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
+
+/// The default value for `Count` is [Count::None].
+impl Default for Count {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Count;
+
+    use crate::app;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, Count::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_total() {
+        let argv = vec!["lsd", "--count", "dir"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(Count::Total), Count::from_arg_matches(&matches));
+        assert_eq!(Some("dir"), matches.value_of("FILE"));
+    }
+
+    #[test]
+    fn test_from_arg_matches_by_type() {
+        let argv = vec!["lsd", "--count=type"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(Count::ByType), Count::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_bad_value() {
+        let argv = vec!["lsd", "--count=size"];
+        assert!(app::build().get_matches_from_safe(argv).is_err());
+    }
+}
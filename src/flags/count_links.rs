@@ -0,0 +1,80 @@
+//! This module defines the [CountLinks] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to count the hard linked files in the total size of the directories
+/// each time they are found, rather than once, as `du -l` does.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct CountLinks(pub bool);
+
+impl Configurable<Self> for CountLinks {
+    /// Get a potential `CountLinks` value from [ArgMatches].
+    ///
+    /// If the "count-links" argument is passed, this returns a `CountLinks` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("count-links") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `CountLinks` value from a [Config].
+    ///
+    /// If the `Config::count-links` has value, this returns its value as the value of the
+    /// `CountLinks`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.count_links.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CountLinks;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, CountLinks::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--count-links"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(CountLinks(true)),
+            CountLinks::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, CountLinks::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.count_links = Some(true);
+        assert_eq!(Some(CountLinks(true)), CountLinks::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.count_links = Some(false);
+        assert_eq!(Some(CountLinks(false)), CountLinks::from_config(&c));
+    }
+}
@@ -16,6 +16,8 @@ pub enum DateFlag {
     Relative,
     ISO,
     Formatted(String),
+    /// The time for today, the month and the day for this year, and the year before.
+    Smart,
 }
 
 impl DateFlag {
@@ -30,11 +32,28 @@ impl DateFlag {
         }
     }
 
+    /// Get a value from a time style, as passed to ls with "--time-style" or the `TIME_STYLE`
+    /// environment variable. The "posix-" prefix is ignored.
+    fn from_time_style(value: &str) -> Option<Self> {
+        match value.trim_start_matches("posix-") {
+            "full-iso" => Some(Self::Formatted("%F %T.%f %z".into())),
+            "long-iso" => Some(Self::Formatted("%F %R".into())),
+            "iso" => Some(Self::ISO),
+            "locale" => Some(Self::Date),
+            style if style.starts_with('+') => Self::from_format_string(style),
+            _ => {
+                print_error!("Not a valid date value: {}.", value);
+                None
+            }
+        }
+    }
+
     /// Get a value from a str.
     fn from_str(value: &str) -> Option<Self> {
         match value {
             "date" => Some(Self::Date),
             "relative" => Some(Self::Relative),
+            "smart" => Some(Self::Smart),
             _ if value.starts_with('+') => Self::from_format_string(&value),
             _ => {
                 print_error!("Not a valid date value: {}.", value);
@@ -57,11 +76,16 @@ impl Configurable<Self> for DateFlag {
             match matches.values_of("date")?.last() {
                 Some("date") => Some(Self::Date),
                 Some("relative") => Some(Self::Relative),
+                Some("smart") => Some(Self::Smart),
                 Some(format) if format.starts_with('+') => {
                     Some(Self::Formatted(format[1..].to_owned()))
                 }
                 _ => panic!("This should not be reachable!"),
             }
+        } else if matches.occurrences_of("time-style") > 0 {
+            Self::from_time_style(matches.values_of("time-style")?.next_back()?)
+        } else if matches.is_present("full-time") {
+            Self::from_time_style("full-iso")
         } else {
             None
         }
@@ -70,7 +94,7 @@ impl Configurable<Self> for DateFlag {
     /// Get a potential `DateFlag` variant from a [Config].
     ///
     /// If the `Config::classic` is `true` then this returns the Some(DateFlag::Date),
-    /// Otherwise if the `Config::date` has value and is one of "date" or "relative",
+    /// Otherwise if the `Config::date` has value and is one of "date", "relative" or "smart",
     /// this returns its corresponding variant in a [Some].
     /// Otherwise this returns [None].
     fn from_config(config: &Config) -> Option<Self> {
@@ -88,16 +112,7 @@ impl Configurable<Self> for DateFlag {
     /// Get a potential `DateFlag` variant from the environment.
     fn from_environment() -> Option<Self> {
         if let Ok(value) = std::env::var("TIME_STYLE") {
-            match value.as_str() {
-                "full-iso" => Some(Self::Formatted("%F %T.%f %z".into())),
-                "long-iso" => Some(Self::Formatted("%F %R".into())),
-                "iso" => Some(Self::ISO),
-                _ if value.starts_with('+') => Self::from_format_string(&value),
-                _ => {
-                    print_error!("Not a valid date value: {}.", value);
-                    None
-                }
-            }
+            Self::from_time_style(&value)
         } else {
             None
         }
@@ -143,6 +158,13 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_from_arg_matches_smart() {
+        let argv = vec!["lsd", "--date", "smart"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(DateFlag::Smart), DateFlag::from_arg_matches(&matches));
+    }
+
     #[test]
     fn test_from_arg_matches_format() {
         let argv = vec!["lsd", "--date", "+%F"];
@@ -175,6 +197,48 @@ mod test {
         assert_eq!(Some(DateFlag::Date), DateFlag::from_arg_matches(&matches));
     }
 
+    #[test]
+    fn test_from_arg_matches_time_style() {
+        for (style, expected) in &[
+            ("full-iso", DateFlag::Formatted("%F %T.%f %z".to_string())),
+            ("long-iso", DateFlag::Formatted("%F %R".to_string())),
+            ("posix-long-iso", DateFlag::Formatted("%F %R".to_string())),
+            ("iso", DateFlag::ISO),
+            ("locale", DateFlag::Date),
+            ("+%F", DateFlag::Formatted("%F".to_string())),
+        ] {
+            let argv = vec!["lsd", "--time-style", style];
+            let matches = app::build().get_matches_from_safe(argv).unwrap();
+            assert_eq!(Some(expected.clone()), DateFlag::from_arg_matches(&matches));
+        }
+    }
+
+    #[test]
+    fn test_from_arg_matches_time_style_invalid() {
+        let argv = vec!["lsd", "--time-style", "short"];
+        assert!(app::build().get_matches_from_safe(argv).is_err());
+    }
+
+    #[test]
+    fn test_from_arg_matches_date_over_time_style() {
+        let argv = vec!["lsd", "--time-style", "iso", "--date", "relative"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(DateFlag::Relative),
+            DateFlag::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_arg_matches_full_time() {
+        let argv = vec!["lsd", "--full-time"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(DateFlag::Formatted("%F %T.%f %z".to_string())),
+            DateFlag::from_arg_matches(&matches)
+        );
+    }
+
     #[test]
     fn test_from_config_none() {
         assert_eq!(None, DateFlag::from_config(&Config::with_none()));
@@ -195,6 +259,13 @@ mod test {
         assert_eq!(Some(DateFlag::Relative), DateFlag::from_config(&c));
     }
 
+    #[test]
+    fn test_from_config_smart() {
+        let mut c = Config::with_none();
+        c.date = Some("smart".into());
+        assert_eq!(Some(DateFlag::Smart), DateFlag::from_config(&c));
+    }
+
     #[test]
     fn test_from_config_format() {
         let mut c = Config::with_none();
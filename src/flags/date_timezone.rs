@@ -0,0 +1,169 @@
+//! This module defines the [DateTimezone] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::app;
+use crate::config_file::Config;
+use crate::print_error;
+
+use chrono::{DateTime, FixedOffset, Local};
+#[cfg(feature = "tz")]
+use chrono::{Offset, TimeZone};
+use clap::ArgMatches;
+
+/// The flag showing in which time zone the dates are displayed.
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum DateTimezone {
+    /// The local time zone of the system.
+    Local,
+    Utc,
+    /// A fixed offset from UTC, in seconds east of it.
+    Offset(i32),
+    /// A time zone of the tz database, whose offset depends on the date.
+    #[cfg(feature = "tz")]
+    Named(chrono_tz::Tz),
+}
+
+impl DateTimezone {
+    /// Get a value from "local", "utc" or an offset from UTC such as "+02", "-0530" or "+05:30",
+    /// or with the `tz` feature a time zone name such as "Europe/Paris".
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "local" => Some(Self::Local),
+            _ if value.eq_ignore_ascii_case("utc") => Some(Self::Utc),
+            #[cfg(feature = "tz")]
+            _ if app::is_timezone_name(value) => value.parse().ok().map(Self::Named),
+            _ => app::parse_utc_offset(value).map(Self::Offset),
+        }
+    }
+
+    /// The offset from UTC of `date` in this time zone.
+    pub fn offset_of(self, date: &DateTime<Local>) -> FixedOffset {
+        match self {
+            Self::Local => *date.offset(),
+            Self::Utc => FixedOffset::east(0),
+            Self::Offset(seconds) => FixedOffset::east(seconds),
+            #[cfg(feature = "tz")]
+            Self::Named(tz) => tz.offset_from_utc_datetime(&date.naive_utc()).fix(),
+        }
+    }
+}
+
+impl Configurable<Self> for DateTimezone {
+    /// Get a potential `DateTimezone` variant from [ArgMatches].
+    ///
+    /// If the "date-timezone" argument is passed, the variant corresponding to its last value is
+    /// returned in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        Self::from_str(matches.values_of("date-timezone")?.next_back()?)
+    }
+
+    /// Get a potential `DateTimezone` variant from a [Config].
+    ///
+    /// If the `Config::date_timezone` has a valid value, this returns its corresponding variant
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        let value = config.date_timezone.as_ref()?;
+        let timezone = Self::from_str(value);
+        if timezone.is_none() {
+            print_error!("Not a valid date timezone: {}.", value);
+        }
+        timezone
+    }
+}
+
+/// The default value for `DateTimezone` is [DateTimezone::Local].
+impl Default for DateTimezone {
+    fn default() -> Self {
+        Self::Local
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DateTimezone;
+    #[cfg(feature = "tz")]
+    use chrono::FixedOffset;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(Some(DateTimezone::Local), DateTimezone::from_str("local"));
+        assert_eq!(Some(DateTimezone::Utc), DateTimezone::from_str("UTC"));
+        assert_eq!(
+            Some(DateTimezone::Offset(7200)),
+            DateTimezone::from_str("+02")
+        );
+        assert_eq!(
+            Some(DateTimezone::Offset(-19800)),
+            DateTimezone::from_str("-0530")
+        );
+        assert_eq!(
+            Some(DateTimezone::Offset(19800)),
+            DateTimezone::from_str("+05:30")
+        );
+        for value in &["Mars/Olympus", "+2", "+24", "+0560", "+0:530", "02"] {
+            assert_eq!(None, DateTimezone::from_str(value), "{}", value);
+        }
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn test_from_str_named() {
+        use chrono::TimeZone;
+
+        let paris = DateTimezone::from_str("Europe/Paris").unwrap();
+        assert_eq!(DateTimezone::Named(chrono_tz::Europe::Paris), paris);
+        // The offset follows the daylight saving time of the date.
+        let winter = chrono::Local.timestamp(1_577_880_000, 0);
+        let summer = chrono::Local.timestamp(1_593_604_800, 0);
+        assert_eq!(FixedOffset::east(3600), paris.offset_of(&winter));
+        assert_eq!(FixedOffset::east(7200), paris.offset_of(&summer));
+    }
+
+    #[cfg(not(feature = "tz"))]
+    #[test]
+    fn test_from_str_named() {
+        assert_eq!(None, DateTimezone::from_str("Europe/Paris"));
+    }
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, DateTimezone::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_multi() {
+        let argv = vec!["lsd", "--date-timezone", "-01", "--date-timezone", "utc"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(DateTimezone::Utc),
+            DateTimezone::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_arg_matches_invalid() {
+        let argv = vec!["lsd", "--date-timezone", "Mars/Olympus"];
+        assert!(app::build().get_matches_from_safe(argv).is_err());
+    }
+
+    #[test]
+    fn test_from_config() {
+        let mut c = Config::with_none();
+        assert_eq!(None, DateTimezone::from_config(&c));
+        c.date_timezone = Some("-08:00".into());
+        assert_eq!(
+            Some(DateTimezone::Offset(-28800)),
+            DateTimezone::from_config(&c)
+        );
+        c.date_timezone = Some("nowhere".into());
+        assert_eq!(None, DateTimezone::from_config(&c));
+    }
+}
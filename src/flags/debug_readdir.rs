@@ -0,0 +1,64 @@
+//! This module defines the [DebugReaddir] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to print the entries of the directories as they are read from the
+/// system, with their type as it is reported there, instead of listing them.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct DebugReaddir(pub bool);
+
+impl Configurable<Self> for DebugReaddir {
+    /// Get a potential `DebugReaddir` value from [ArgMatches].
+    ///
+    /// If the "debug-readdir" argument is passed, this returns a `DebugReaddir` with value `true`
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("debug-readdir") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// The raw entries are only printed to diagnose a file system, so this can not be configured
+    /// in the [Config]. This always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DebugReaddir;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, DebugReaddir::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--debug-readdir"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(DebugReaddir(true)),
+            DebugReaddir::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, DebugReaddir::from_config(&Config::with_none()));
+    }
+}
@@ -0,0 +1,79 @@
+//! This module defines the [ElidePrefix] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to elide the listed path from the folder paths following the first one.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct ElidePrefix(pub bool);
+
+impl Configurable<Self> for ElidePrefix {
+    /// Get a potential `ElidePrefix` value from [ArgMatches].
+    ///
+    /// If the "elide-prefix" argument is passed, this returns a `ElidePrefix` with value `true` in
+    /// a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("elide-prefix") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `ElidePrefix` value from a [Config].
+    ///
+    /// If the `Config::elide-prefix` has value, this returns its value as the value of the
+    /// `ElidePrefix`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.elide_prefix.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ElidePrefix;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, ElidePrefix::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--elide-prefix"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(ElidePrefix(true)),
+            ElidePrefix::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, ElidePrefix::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.elide_prefix = Some(true);
+        assert_eq!(Some(ElidePrefix(true)), ElidePrefix::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.elide_prefix = Some(false);
+        assert_eq!(Some(ElidePrefix(false)), ElidePrefix::from_config(&c));
+    }
+}
@@ -0,0 +1,138 @@
+//! This module defines the [ExcludeFrom] flag. To set it up from [ArgMatches] and its [Default]
+//! value, use the [configure_from](ExcludeFrom::configure_from) method.
+
+use clap::{ArgMatches, Error, ErrorKind};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// The struct holding the gitignore style patterns read from the "exclude-from" files.
+#[derive(Clone, Debug)]
+pub struct ExcludeFrom(pub Gitignore);
+
+impl ExcludeFrom {
+    /// Returns a value from either [ArgMatches] or a [Default] value.
+    ///
+    /// The patterns are anchored to the current working directory, as if they were written in a
+    /// `.gitignore` file located there.
+    ///
+    /// # Errors
+    ///
+    /// If one of the files can not be read or one of its patterns is not valid.
+    pub fn configure_from(matches: &ArgMatches) -> Result<Self, Error> {
+        match matches.values_of("exclude-from") {
+            Some(files) => {
+                let root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+                let mut builder = GitignoreBuilder::new(root);
+                for file in files {
+                    let patterns = Self::read_patterns(file).map_err(|err| {
+                        Error::with_description(
+                            &format!("Can not read exclude file {}: {}", file, err),
+                            ErrorKind::ValueValidation,
+                        )
+                    })?;
+                    for line in patterns.lines() {
+                        if let Err(err) = builder.add_line(None, line) {
+                            return Err(Error::with_description(
+                                &err.to_string(),
+                                ErrorKind::ValueValidation,
+                            ));
+                        }
+                    }
+                }
+                match builder.build() {
+                    Ok(gitignore) => Ok(Self(gitignore)),
+                    Err(err) => Err(Error::with_description(
+                        &err.to_string(),
+                        ErrorKind::ValueValidation,
+                    )),
+                }
+            }
+            None => Ok(Default::default()),
+        }
+    }
+
+    /// Read the content of the file at `file`, or of the standard input when `file` is `-`.
+    fn read_patterns(file: &str) -> io::Result<String> {
+        if file == "-" {
+            let mut patterns = String::new();
+            io::stdin().read_to_string(&mut patterns)?;
+            Ok(patterns)
+        } else {
+            fs::read_to_string(file)
+        }
+    }
+
+    /// Checks whether `path` is excluded by the patterns. A negated pattern (`!pattern`) matching
+    /// after an excluding one keeps the path.
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        self.0.matched(path, is_dir).is_ignore()
+    }
+}
+
+/// The default value of `ExcludeFrom` does not exclude anything.
+impl Default for ExcludeFrom {
+    fn default() -> Self {
+        Self(Gitignore::empty())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ExcludeFrom;
+
+    use crate::app;
+
+    use std::io::Write;
+    use std::path::Path;
+    use tempfile::NamedTempFile;
+
+    fn configure_with_patterns(patterns: &str) -> ExcludeFrom {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "{}", patterns).unwrap();
+        let argv = vec![
+            "lsd".to_string(),
+            "--exclude-from".to_string(),
+            file.path().to_string_lossy().to_string(),
+        ];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        ExcludeFrom::configure_from(&matches).unwrap()
+    }
+
+    #[test]
+    fn test_configuration_from_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let exclude = ExcludeFrom::configure_from(&matches).unwrap();
+        assert!(!exclude.is_excluded(Path::new("target"), true));
+    }
+
+    #[test]
+    fn test_configuration_from_missing_file() {
+        let argv = vec!["lsd", "--exclude-from", "not-existed"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert!(ExcludeFrom::configure_from(&matches).is_err());
+    }
+
+    #[test]
+    fn test_exclude_patterns() {
+        let exclude = configure_with_patterns("# build output\n*.o\ntarget/\n!keep.o\n");
+
+        assert!(exclude.is_excluded(Path::new("main.o"), false));
+        assert!(exclude.is_excluded(Path::new("src/lib.o"), false));
+        assert!(!exclude.is_excluded(Path::new("keep.o"), false));
+        assert!(exclude.is_excluded(Path::new("target"), true));
+        assert!(!exclude.is_excluded(Path::new("target"), false));
+        assert!(!exclude.is_excluded(Path::new("main.rs"), false));
+    }
+
+    #[test]
+    fn test_exclude_anchored_pattern() {
+        let exclude = configure_with_patterns("/build\n");
+
+        assert!(exclude.is_excluded(Path::new("build"), true));
+        assert!(!exclude.is_excluded(Path::new("src/build"), true));
+    }
+}
@@ -0,0 +1,104 @@
+//! This module defines the [Extensions] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+use crate::flags::color::ColorValue;
+
+use clap::ArgMatches;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The icon and the color of the files with an extension.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ExtensionStyle {
+    pub icon: Option<String>,
+    pub color: Option<ColorValue>,
+}
+
+/// The flag holding the styles of the files by their extension, the keys being the lower case
+/// extensions without their leading dot, such as `rs` or the compound `tar.gz`.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Extensions(pub HashMap<String, ExtensionStyle>);
+
+impl Extensions {
+    /// The icons by extension.
+    pub fn icons(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .filter_map(|(ext, style)| Some((ext.as_str(), style.icon.as_deref()?)))
+    }
+
+    /// The colors by extension.
+    pub fn colors(&self) -> impl Iterator<Item = (&str, ColorValue)> {
+        self.0
+            .iter()
+            .filter_map(|(ext, style)| Some((ext.as_str(), style.color?)))
+    }
+}
+
+impl Configurable<Self> for Extensions {
+    /// The extension styles can not be passed as arguments, so this always returns [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `Extensions` value from a [Config].
+    ///
+    /// If the `Config::extensions` has value, this returns it in a [Some], with the extensions in
+    /// lower case and without a leading dot. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        let extensions = config.extensions.as_ref()?;
+        Some(Self(
+            extensions
+                .iter()
+                .map(|(ext, style)| {
+                    let ext = ext.trim_start_matches('.').to_lowercase();
+                    (ext, style.clone())
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ExtensionStyle, Extensions};
+
+    use crate::flags::color::ColorValue;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, Extensions::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, Extensions::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_normalized() {
+        let style = ExtensionStyle {
+            icon: Some("i".into()),
+            color: Some(ColorValue::Rgb(255, 135, 0)),
+        };
+        let mut extensions = HashMap::new();
+        extensions.insert(".TAR.GZ".to_string(), style.clone());
+        let mut c = Config::with_none();
+        c.extensions = Some(extensions);
+
+        let mut expected = HashMap::new();
+        expected.insert("tar.gz".to_string(), style);
+        assert_eq!(Some(Extensions(expected)), Extensions::from_config(&c));
+    }
+}
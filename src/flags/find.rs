@@ -0,0 +1,74 @@
+//! This module defines the [Find] flag. To set it up from [ArgMatches] and its [Default] value,
+//! use the [configure_from](Find::configure_from) method.
+
+use clap::{ArgMatches, Error, ErrorKind};
+use regex::Regex;
+
+/// The flag holding the regular expression the names of the entries listed within the directories
+/// are to match, all of them being listed when it is [None].
+#[derive(Clone, Debug, Default)]
+pub struct Find(pub Option<Regex>);
+
+impl Find {
+    /// Returns a value from either [ArgMatches] or a [Default] value. When the "find" argument
+    /// is passed several times, the last one is used.
+    ///
+    /// # Errors
+    ///
+    /// If the regular expression is not valid.
+    pub fn configure_from(matches: &ArgMatches) -> Result<Self, Error> {
+        match matches
+            .values_of("find")
+            .and_then(|mut values| values.next_back())
+        {
+            Some(pattern) => match Regex::new(pattern) {
+                Ok(regex) => Ok(Self(Some(regex))),
+                Err(err) => Err(Error::with_description(
+                    &format!("Invalid regex {}: {}", pattern, err),
+                    ErrorKind::ValueValidation,
+                )),
+            },
+            None => Ok(Self(None)),
+        }
+    }
+
+    /// Whether the entry named `name` is found, any entry being when there is no regular
+    /// expression.
+    pub fn is_match(&self, name: &str) -> bool {
+        match &self.0 {
+            Some(regex) => regex.is_match(name),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Find;
+
+    use crate::app;
+
+    #[test]
+    fn test_configure_from_none() {
+        let matches = app::build().get_matches_from_safe(vec!["lsd"]).unwrap();
+        let find = Find::configure_from(&matches).unwrap();
+        assert!(find.0.is_none());
+        assert!(find.is_match("anything"));
+    }
+
+    #[test]
+    fn test_configure_from_last() {
+        let argv = vec!["lsd", "--find", "^a", "--find", r"\.rs$"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let find = Find::configure_from(&matches).unwrap();
+        assert!(find.is_match("main.rs"));
+        assert!(!find.is_match("a.txt"));
+    }
+
+    #[test]
+    fn test_configure_from_invalid() {
+        let argv = vec!["lsd", "--find", "(unclosed"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert!(Find::configure_from(&matches).is_err());
+    }
+}
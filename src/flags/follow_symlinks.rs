@@ -0,0 +1,79 @@
+//! This module defines the [FollowSymlinks] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to descend into the symbolic links to directories when recursing.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct FollowSymlinks(pub bool);
+
+impl Configurable<Self> for FollowSymlinks {
+    /// Get a potential `FollowSymlinks` value from [ArgMatches].
+    ///
+    /// If the "follow-symlinks" argument is passed, this returns a `FollowSymlinks` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("follow-symlinks") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `FollowSymlinks` value from a [Config].
+    ///
+    /// If the `Config::follow-symlinks` has value, this returns its value as the value of the
+    /// `FollowSymlinks`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.follow_symlinks.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FollowSymlinks;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, FollowSymlinks::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--follow-symlinks"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(FollowSymlinks(true)),
+            FollowSymlinks::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, FollowSymlinks::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.follow_symlinks = Some(true);
+        assert_eq!(Some(FollowSymlinks(true)), FollowSymlinks::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.follow_symlinks = Some(false);
+        assert_eq!(Some(FollowSymlinks(false)), FollowSymlinks::from_config(&c));
+    }
+}
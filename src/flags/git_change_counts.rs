@@ -0,0 +1,87 @@
+//! This module defines the [GitChangeCounts] flag. To set it up from [ArgMatches], a [Config] and
+//! its [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to count the files of the directories with staged and unstaged
+/// changes in the git block.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct GitChangeCounts(pub bool);
+
+impl Configurable<Self> for GitChangeCounts {
+    /// Get a potential `GitChangeCounts` value from [ArgMatches].
+    ///
+    /// If the "git-change-counts" argument is passed, this returns a `GitChangeCounts` with value
+    /// `true` in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("git-change-counts") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `GitChangeCounts` value from a [Config].
+    ///
+    /// If the `Config::git-change-counts` has value, this returns its value as the value of the
+    /// `GitChangeCounts`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.git_change_counts.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GitChangeCounts;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, GitChangeCounts::from_arg_matches(&matches));
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--git-change-counts"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(GitChangeCounts(true)),
+            GitChangeCounts::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, GitChangeCounts::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.git_change_counts = Some(true);
+        assert_eq!(
+            Some(GitChangeCounts(true)),
+            GitChangeCounts::from_config(&c)
+        );
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.git_change_counts = Some(false);
+        assert_eq!(
+            Some(GitChangeCounts(false)),
+            GitChangeCounts::from_config(&c)
+        );
+    }
+}
@@ -1,22 +1,48 @@
+use crate::flags::icons::{GitStatusIcons, GitStatusPreset};
 use crate::icon::Theme;
 use crate::git::GitStatus;
 
 pub struct GitIcons {
-    theme: Theme
+    theme: Theme,
+    symbols: GitStatusIcons,
 }
 
 impl GitIcons {
     pub fn new(theme: Theme) -> GitIcons {
         GitIcons {
-            theme
+            theme,
+            symbols: GitStatusIcons::default(),
         }
     }
 
+    /// Shows the statuses with the configured `symbols` instead of the ones of the theme.
+    pub fn with_symbols(self, symbols: GitStatusIcons) -> GitIcons {
+        GitIcons { symbols, ..self }
+    }
+
     pub fn get(&self, status: &GitStatus) -> String {
-        match self.theme {
-            Theme::NoIcon => self.get_text(status),
-            Theme::Fancy => self.get_icon(status),
-            Theme::Unicode => self.get_unicode(status),
+        if let Some(symbol) = self.get_configured(status) {
+            return symbol.clone();
+        }
+        match (self.symbols.preset, self.theme) {
+            (GitStatusPreset::Ascii, _) | (_, Theme::NoIcon) => self.get_text(status),
+            (_, Theme::Fancy) => self.get_icon(status),
+            (_, Theme::Unicode) => self.get_unicode(status),
+        }
+    }
+
+    fn get_configured(&self, status: &GitStatus) -> Option<&String> {
+        let symbols = &self.symbols;
+        match status {
+            GitStatus::Default | GitStatus::Unmodified => symbols.unmodified.as_ref(),
+            GitStatus::NewInIndex => symbols.new_in_index.as_ref(),
+            GitStatus::NewInWorkdir => symbols.new_in_workdir.as_ref(),
+            GitStatus::Deleted => symbols.deleted.as_ref(),
+            GitStatus::Modified => symbols.modified.as_ref(),
+            GitStatus::Renamed => symbols.renamed.as_ref(),
+            GitStatus::Ignored => symbols.ignored.as_ref(),
+            GitStatus::Typechange => symbols.typechange.as_ref(),
+            GitStatus::Conflicted => symbols.conflicted.as_ref(),
         }
     }
 
@@ -53,4 +79,31 @@ impl GitIcons {
     fn get_unicode(&self, status: &GitStatus) -> String {
         self.get_text(status)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::GitIcons;
+    use crate::flags::icons::{GitStatusIcons, GitStatusPreset};
+    use crate::git::GitStatus;
+    use crate::icon::Theme;
+
+    #[test]
+    fn test_theme_symbols() {
+        let icons = GitIcons::new(Theme::Fancy);
+        assert_eq!("\u{f8ea}", icons.get(&GitStatus::Modified));
+        assert_eq!("M", GitIcons::new(Theme::NoIcon).get(&GitStatus::Modified));
+    }
+
+    #[test]
+    fn test_configured_symbols() {
+        let icons = GitIcons::new(Theme::Fancy).with_symbols(GitStatusIcons {
+            preset: GitStatusPreset::Ascii,
+            unmodified: Some("\u{2713}".to_string()),
+            ..GitStatusIcons::default()
+        });
+        assert_eq!("M", icons.get(&GitStatus::Modified));
+        assert_eq!("\u{2713}", icons.get(&GitStatus::Default));
+        assert_eq!("\u{2713}", icons.get(&GitStatus::Unmodified));
+    }
+}
@@ -0,0 +1,80 @@
+//! This module defines the [GitRenames] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to show the previous name of the files renamed in the git index.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct GitRenames(pub bool);
+
+impl Configurable<Self> for GitRenames {
+    /// Get a potential `GitRenames` value from [ArgMatches].
+    ///
+    /// If the "git-renames" argument is passed, this returns a `GitRenames` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("git-renames") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `GitRenames` value from a [Config].
+    ///
+    /// If the `Config::git-renames` has value, this returns its value as the value of the
+    /// `GitRenames`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.git_renames.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GitRenames;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, GitRenames::from_arg_matches(&matches));
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--git-renames"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(GitRenames(true)),
+            GitRenames::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, GitRenames::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.git_renames = Some(true);
+        assert_eq!(Some(GitRenames(true)), GitRenames::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.git_renames = Some(false);
+        assert_eq!(Some(GitRenames(false)), GitRenames::from_config(&c));
+    }
+}
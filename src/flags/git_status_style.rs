@@ -0,0 +1,123 @@
+//! This module defines the [GitStatusStyle] flag. To set it up from [ArgMatches], a [Config] and
+//! its [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+use serde::Deserialize;
+
+/// The flag showing how many symbols the git block shows for a file.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitStatusStyle {
+    /// The variant to show the index status then the workdir status.
+    TwoChar,
+    /// The variant to show the most severe of the index and workdir statuses.
+    Single,
+}
+
+impl GitStatusStyle {
+    fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "two-char" => Some(Self::TwoChar),
+            "single" => Some(Self::Single),
+            _ => {
+                panic!(
+                    "Git status style can only be one of two-char or single, but got {}.",
+                    value
+                );
+            }
+        }
+    }
+}
+
+impl Configurable<Self> for GitStatusStyle {
+    /// Get a potential `GitStatusStyle` variant from [ArgMatches].
+    ///
+    /// If the "git-status-style" argument is passed, the variant corresponding to its last value
+    /// is returned in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.occurrences_of("git-status-style") > 0 {
+            if let Some(style) = matches.values_of("git-status-style")?.next_back() {
+                return Self::from_str(style);
+            }
+        }
+        None
+    }
+
+    /// Get a potential `GitStatusStyle` variant from a [Config].
+    ///
+    /// If the `Config::git-status-style` has value, this returns it in a [Some]. Otherwise this
+    /// returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.git_status_style
+    }
+}
+
+/// The default value for `GitStatusStyle` is [GitStatusStyle::TwoChar].
+impl Default for GitStatusStyle {
+    fn default() -> Self {
+        Self::TwoChar
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GitStatusStyle;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, GitStatusStyle::from_arg_matches(&matches));
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn test_from_arg_matches_single() {
+        let argv = vec!["lsd", "--git-status-style", "single"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(GitStatusStyle::Single),
+            GitStatusStyle::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn test_from_arg_matches_multi() {
+        let argv = vec![
+            "lsd",
+            "--git-status-style",
+            "single",
+            "--git-status-style",
+            "two-char",
+        ];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(GitStatusStyle::TwoChar),
+            GitStatusStyle::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, GitStatusStyle::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_single() {
+        let mut c = Config::with_none();
+        c.git_status_style = Some(GitStatusStyle::Single);
+        assert_eq!(
+            Some(GitStatusStyle::Single),
+            GitStatusStyle::from_config(&c)
+        );
+    }
+}
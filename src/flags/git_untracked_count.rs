@@ -0,0 +1,86 @@
+//! This module defines the [GitUntrackedCount] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to count the untracked files of the directories in the git block.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct GitUntrackedCount(pub bool);
+
+impl Configurable<Self> for GitUntrackedCount {
+    /// Get a potential `GitUntrackedCount` value from [ArgMatches].
+    ///
+    /// If the "git-untracked-count" argument is passed, this returns a `GitUntrackedCount` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("git-untracked-count") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `GitUntrackedCount` value from a [Config].
+    ///
+    /// If the `Config::git-untracked-count` has value, this returns its value as the value of the
+    /// `GitUntrackedCount`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.git_untracked_count.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GitUntrackedCount;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, GitUntrackedCount::from_arg_matches(&matches));
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--git-untracked-count"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(GitUntrackedCount(true)),
+            GitUntrackedCount::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, GitUntrackedCount::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.git_untracked_count = Some(true);
+        assert_eq!(
+            Some(GitUntrackedCount(true)),
+            GitUntrackedCount::from_config(&c)
+        );
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.git_untracked_count = Some(false);
+        assert_eq!(
+            Some(GitUntrackedCount(false)),
+            GitUntrackedCount::from_config(&c)
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! This module defines the [Glob] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to expand the glob patterns of the listed paths.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Glob(pub bool);
+
+impl Configurable<Self> for Glob {
+    /// Get a potential `Glob` value from [ArgMatches].
+    ///
+    /// If the "glob" argument is passed, this returns a `Glob` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("glob") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `Glob` value from a [Config].
+    ///
+    /// If the `Config::glob` has value, this returns its value as the value of the
+    /// `Glob`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.glob.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Glob;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, Glob::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--glob"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(Glob(true)), Glob::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, Glob::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.glob = Some(true);
+        assert_eq!(Some(Glob(true)), Glob::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.glob = Some(false);
+        assert_eq!(Some(Glob(false)), Glob::from_config(&c));
+    }
+}
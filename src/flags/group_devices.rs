@@ -0,0 +1,80 @@
+//! This module defines the [GroupDevices] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to group the devices of a directory by their subsystem, as the
+/// terminals or the disks.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct GroupDevices(pub bool);
+
+impl Configurable<Self> for GroupDevices {
+    /// Get a potential `GroupDevices` value from [ArgMatches].
+    ///
+    /// If the "group-devices" argument is passed, this returns a `GroupDevices` with value `true` in
+    /// a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("group-devices") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `GroupDevices` value from a [Config].
+    ///
+    /// If the `Config::group-devices` has value, this returns its value as the value of the
+    /// `GroupDevices`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.group_devices.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GroupDevices;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, GroupDevices::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--group-devices"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(GroupDevices(true)),
+            GroupDevices::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, GroupDevices::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.group_devices = Some(true);
+        assert_eq!(Some(GroupDevices(true)), GroupDevices::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.group_devices = Some(false);
+        assert_eq!(Some(GroupDevices(false)), GroupDevices::from_config(&c));
+    }
+}
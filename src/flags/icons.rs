@@ -17,21 +17,25 @@ pub struct Icons {
     pub theme: IconTheme,
     /// String between icon and name.
     pub separator: IconSeparator,
+    /// The symbols of the git statuses.
+    pub git_status: GitStatusIcons,
 }
 
 impl Icons {
     /// Get an `Icons` struct from [ArgMatches], a [Config] or the [Default] values.
     ///
-    /// The [IconOption] and [IconTheme] are configured with their respective [Configurable]
-    /// implementation.
+    /// The [IconOption], [IconTheme], [IconSeparator] and [GitStatusIcons] are configured with
+    /// their respective [Configurable] implementation.
     pub fn configure_from(matches: &ArgMatches, config: &Config) -> Self {
         let when = IconOption::configure_from(matches, config);
         let theme = IconTheme::configure_from(matches, config);
         let separator = IconSeparator::configure_from(matches, config);
+        let git_status = GitStatusIcons::configure_from(matches, config);
         Self {
             when,
             theme,
             separator,
+            git_status,
         }
     }
 }
@@ -173,6 +177,56 @@ impl Default for IconSeparator {
     }
 }
 
+/// The symbols the git statuses are shown with, unless overridden.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitStatusPreset {
+    /// The symbols of the icon theme.
+    Theme,
+    /// Letters and punctuation only, whatever the icon theme.
+    Ascii,
+}
+
+/// The default value for `GitStatusPreset` is [GitStatusPreset::Theme].
+impl Default for GitStatusPreset {
+    fn default() -> Self {
+        Self::Theme
+    }
+}
+
+/// The symbols of the git statuses, each one set replacing the symbol of the preset. The
+/// unmodified symbol is also the one of the files without status.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct GitStatusIcons {
+    #[serde(default)]
+    pub preset: GitStatusPreset,
+    pub unmodified: Option<String>,
+    pub new_in_index: Option<String>,
+    pub new_in_workdir: Option<String>,
+    pub deleted: Option<String>,
+    pub modified: Option<String>,
+    pub renamed: Option<String>,
+    pub ignored: Option<String>,
+    pub typechange: Option<String>,
+    pub conflicted: Option<String>,
+}
+
+impl Configurable<Self> for GitStatusIcons {
+    /// The git status symbols can not be passed as arguments, so this always returns [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `GitStatusIcons` value from a [Config].
+    ///
+    /// If the `Config::icons::git-status` has value, this returns it in a [Some]. Otherwise this
+    /// returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.icons.as_ref()?.git_status.clone()
+    }
+}
+
 #[cfg(test)]
 mod test_icon_option {
     use super::IconOption;
@@ -250,6 +304,7 @@ mod test_icon_option {
             when: Some(IconOption::Always),
             theme: None,
             separator: None,
+            git_status: None,
         });
         assert_eq!(Some(IconOption::Always), IconOption::from_config(&c));
     }
@@ -261,6 +316,7 @@ mod test_icon_option {
             when: Some(IconOption::Auto),
             theme: None,
             separator: None,
+            git_status: None,
         });
         assert_eq!(Some(IconOption::Auto), IconOption::from_config(&c));
     }
@@ -272,6 +328,7 @@ mod test_icon_option {
             when: Some(IconOption::Never),
             theme: None,
             separator: None,
+            git_status: None,
         });
         assert_eq!(Some(IconOption::Never), IconOption::from_config(&c));
     }
@@ -284,6 +341,7 @@ mod test_icon_option {
             when: Some(IconOption::Always),
             theme: None,
             separator: None,
+            git_status: None,
         });
         assert_eq!(Some(IconOption::Never), IconOption::from_config(&c));
     }
@@ -346,6 +404,7 @@ mod test_icon_theme {
             when: None,
             theme: Some(IconTheme::Fancy),
             separator: None,
+            git_status: None,
         });
         assert_eq!(Some(IconTheme::Fancy), IconTheme::from_config(&c));
     }
@@ -357,6 +416,7 @@ mod test_icon_theme {
             when: None,
             theme: Some(IconTheme::Unicode),
             separator: None,
+            git_status: None,
         });
         assert_eq!(Some(IconTheme::Unicode), IconTheme::from_config(&c));
     }
@@ -376,6 +436,7 @@ mod test_icon_separator {
             when: None,
             theme: None,
             separator: Some(" ".to_string()),
+            git_status: None,
         });
         let expected = Some(IconSeparator(" ".to_string()));
         assert_eq!(expected, IconSeparator::from_config(&c));
@@ -388,8 +449,51 @@ mod test_icon_separator {
             when: None,
             theme: None,
             separator: Some(" |".to_string()),
+            git_status: None,
         });
         let expected = Some(IconSeparator(" |".to_string()));
         assert_eq!(expected, IconSeparator::from_config(&c));
     }
 }
+
+#[cfg(test)]
+mod test_git_status_icons {
+    use super::{GitStatusIcons, GitStatusPreset};
+
+    use crate::config_file::{Config, Icons};
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, GitStatusIcons::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let icons: GitStatusIcons =
+            serde_yaml::from_str("preset: ascii\nunmodified: \"\u{2713}\"\n").unwrap();
+        let expected = GitStatusIcons {
+            preset: GitStatusPreset::Ascii,
+            unmodified: Some("\u{2713}".to_string()),
+            ..GitStatusIcons::default()
+        };
+        assert_eq!(expected, icons);
+    }
+
+    #[test]
+    fn test_from_config_default_preset() {
+        let mut c = Config::with_none();
+        c.icons = Some(Icons {
+            when: None,
+            theme: None,
+            separator: None,
+            git_status: Some(GitStatusIcons {
+                modified: Some("~".to_string()),
+                ..GitStatusIcons::default()
+            }),
+        });
+        let icons = GitStatusIcons::from_config(&c).unwrap();
+        assert_eq!(GitStatusPreset::Theme, icons.preset);
+        assert_eq!(Some("~".to_string()), icons.modified);
+    }
+}
@@ -0,0 +1,80 @@
+//! This module defines the [IgnoreFiles] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to leave out the files matched by the `.ignore` and `.lsdignore` files
+/// of the listed directories, see [ignore_files](crate::meta::ignore_files).
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct IgnoreFiles(pub bool);
+
+impl Configurable<Self> for IgnoreFiles {
+    /// Get a potential `IgnoreFiles` value from [ArgMatches].
+    ///
+    /// If the "ignore-files" argument is passed, this returns a `IgnoreFiles` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("ignore-files") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `IgnoreFiles` value from a [Config].
+    ///
+    /// If the `Config::ignore-files` has value, this returns its value as the value of the
+    /// `IgnoreFiles`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.ignore_files.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::IgnoreFiles;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, IgnoreFiles::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--ignore-files"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(IgnoreFiles(true)),
+            IgnoreFiles::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, IgnoreFiles::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.ignore_files = Some(true);
+        assert_eq!(Some(IgnoreFiles(true)), IgnoreFiles::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.ignore_files = Some(false);
+        assert_eq!(Some(IgnoreFiles(false)), IgnoreFiles::from_config(&c));
+    }
+}
@@ -0,0 +1,85 @@
+//! This module defines the [IncludeSnapshots] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to recurse into filesystem snapshot directories.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct IncludeSnapshots(pub bool);
+
+impl Configurable<Self> for IncludeSnapshots {
+    /// Get a potential `IncludeSnapshots` value from [ArgMatches].
+    ///
+    /// If the "include-snapshots" argument is passed, this returns a `IncludeSnapshots` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("include-snapshots") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `IncludeSnapshots` value from a [Config].
+    ///
+    /// If the `Config::include-snapshots` has value, this returns its value as the value of the
+    /// `IncludeSnapshots`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.include_snapshots.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::IncludeSnapshots;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, IncludeSnapshots::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--include-snapshots"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(IncludeSnapshots(true)),
+            IncludeSnapshots::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, IncludeSnapshots::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.include_snapshots = Some(true);
+        assert_eq!(
+            Some(IncludeSnapshots(true)),
+            IncludeSnapshots::from_config(&c)
+        );
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.include_snapshots = Some(false);
+        assert_eq!(
+            Some(IncludeSnapshots(false)),
+            IncludeSnapshots::from_config(&c)
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! This module defines the [Info] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to print the detail panel of the single file given, instead of
+/// listing it.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Info(pub bool);
+
+impl Configurable<Self> for Info {
+    /// Get a potential `Info` value from [ArgMatches].
+    ///
+    /// If the "info" argument is passed, this returns an `Info` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("info") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// The detail panel is asked for one file at a time, so it can not be configured in the
+    /// [Config]. This always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Info;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, Info::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--info"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(Info(true)), Info::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, Info::from_config(&Config::with_none()));
+    }
+}
@@ -0,0 +1,80 @@
+//! This module defines the [KeepDuplicates] flag. To set it up from [ArgMatches], a [Config] and
+//! its [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to list the paths as they are given, even the duplicated ones and the
+/// ones already listed within another.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct KeepDuplicates(pub bool);
+
+impl Configurable<Self> for KeepDuplicates {
+    /// Get a potential `KeepDuplicates` value from [ArgMatches].
+    ///
+    /// If the "keep-duplicates" argument is passed, this returns a `KeepDuplicates` with value
+    /// `true` in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("keep-duplicates") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `KeepDuplicates` value from a [Config].
+    ///
+    /// If the `Config::keep-duplicates` has value, this returns its value as the value of the
+    /// `KeepDuplicates`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.keep_duplicates.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::KeepDuplicates;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, KeepDuplicates::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--keep-duplicates"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(KeepDuplicates(true)),
+            KeepDuplicates::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, KeepDuplicates::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.keep_duplicates = Some(true);
+        assert_eq!(Some(KeepDuplicates(true)), KeepDuplicates::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.keep_duplicates = Some(false);
+        assert_eq!(Some(KeepDuplicates(false)), KeepDuplicates::from_config(&c));
+    }
+}
@@ -0,0 +1,80 @@
+//! This module defines the [MaxEntries] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag holding how many entries a listed directory may have before it is skipped, with an
+/// error. The directories are always listed when it is [None].
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct MaxEntries(pub Option<usize>);
+
+impl Configurable<Self> for MaxEntries {
+    /// Get a potential `MaxEntries` value from [ArgMatches].
+    ///
+    /// If the "max-entries" argument is passed, this returns a `MaxEntries` with its
+    /// parameter as value in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.occurrences_of("max-entries") > 0 {
+            let count = matches.values_of("max-entries")?.next_back()?;
+            count.parse::<usize>().ok().map(|c| Self(Some(c)))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `MaxEntries` value from a [Config].
+    ///
+    /// If the `Config::max-entries` has value, this returns its value as the value of the
+    /// `MaxEntries`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.max_entries.map(|c| Self(Some(c)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MaxEntries;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, MaxEntries::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_count() {
+        let argv = vec!["lsd", "--max-entries", "1000"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(MaxEntries(Some(1000))),
+            MaxEntries::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_arg_matches_invalid() {
+        let argv = vec!["lsd", "--max-entries", "many"];
+        assert!(app::build().get_matches_from_safe(argv).is_err());
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, MaxEntries::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_count() {
+        let mut c = Config::with_none();
+        c.max_entries = Some(500);
+        assert_eq!(Some(MaxEntries(Some(500))), MaxEntries::from_config(&c));
+    }
+}
@@ -0,0 +1,94 @@
+//! This module defines the [MaxMemory] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag holding how many megabytes may be used to collect the files metadata before falling
+/// back to an unsorted streaming output. There is no limit when it is [None].
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct MaxMemory(pub Option<usize>);
+
+impl MaxMemory {
+    /// The limit in bytes, if any.
+    pub fn bytes(self) -> Option<usize> {
+        self.0
+            .map(|megabytes| megabytes.saturating_mul(1024 * 1024))
+    }
+}
+
+impl Configurable<Self> for MaxMemory {
+    /// Get a potential `MaxMemory` value from [ArgMatches].
+    ///
+    /// If the "max-memory" argument is passed, this returns a `MaxMemory` with its parameter as
+    /// value in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.occurrences_of("max-memory") > 0 {
+            let megabytes = matches.values_of("max-memory")?.next_back()?;
+            megabytes.parse::<usize>().ok().map(|m| Self(Some(m)))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `MaxMemory` value from a [Config].
+    ///
+    /// If the `Config::max-memory` has value, this returns its value as the value of the
+    /// `MaxMemory`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.max_memory.map(|m| Self(Some(m)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MaxMemory;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, MaxMemory::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_megabytes() {
+        let argv = vec!["lsd", "--max-memory", "512"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(MaxMemory(Some(512))),
+            MaxMemory::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_arg_matches_invalid() {
+        let argv = vec!["lsd", "--max-memory", "lots"];
+        assert!(app::build().get_matches_from_safe(argv).is_err());
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, MaxMemory::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_megabytes() {
+        let mut c = Config::with_none();
+        c.max_memory = Some(64);
+        assert_eq!(Some(MaxMemory(Some(64))), MaxMemory::from_config(&c));
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(None, MaxMemory::default().bytes());
+        assert_eq!(Some(2 * 1024 * 1024), MaxMemory(Some(2)).bytes());
+    }
+}
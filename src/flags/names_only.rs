@@ -0,0 +1,76 @@
+//! This module defines the [NamesOnly] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to print the unstyled names only, without reading the metadata of the files.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct NamesOnly(pub bool);
+
+impl Configurable<Self> for NamesOnly {
+    /// Get a potential `NamesOnly` value from [ArgMatches].
+    ///
+    /// If the "names-only" argument is passed, this returns a `NamesOnly` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("names-only") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `NamesOnly` value from a [Config].
+    ///
+    /// If the `Config::names-only` has value, this returns its value as the value of the
+    /// `NamesOnly`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.names_only.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::NamesOnly;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, NamesOnly::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--names-only"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(NamesOnly(true)), NamesOnly::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, NamesOnly::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.names_only = Some(true);
+        assert_eq!(Some(NamesOnly(true)), NamesOnly::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.names_only = Some(false);
+        assert_eq!(Some(NamesOnly(false)), NamesOnly::from_config(&c));
+    }
+}
@@ -0,0 +1,79 @@
+//! This module defines the [NoGlobError] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to ignore the glob patterns matching no file instead of reporting them.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct NoGlobError(pub bool);
+
+impl Configurable<Self> for NoGlobError {
+    /// Get a potential `NoGlobError` value from [ArgMatches].
+    ///
+    /// If the "no-glob-error" argument is passed, this returns a `NoGlobError` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("no-glob-error") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `NoGlobError` value from a [Config].
+    ///
+    /// If the `Config::no-glob-error` has value, this returns its value as the value of the
+    /// `NoGlobError`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.no_glob_error.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::NoGlobError;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, NoGlobError::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--no-glob-error"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(NoGlobError(true)),
+            NoGlobError::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, NoGlobError::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.no_glob_error = Some(true);
+        assert_eq!(Some(NoGlobError(true)), NoGlobError::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.no_glob_error = Some(false);
+        assert_eq!(Some(NoGlobError(false)), NoGlobError::from_config(&c));
+    }
+}
@@ -0,0 +1,89 @@
+//! This module defines the [NumericUidGid] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to show the numeric user and group ids instead of their names.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct NumericUidGid(pub bool);
+
+impl Configurable<Self> for NumericUidGid {
+    /// Get a potential `NumericUidGid` value from [ArgMatches].
+    ///
+    /// If the "numeric-uid-gid" argument is passed, this returns a `NumericUidGid` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("numeric-uid-gid") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `NumericUidGid` value from a [Config].
+    ///
+    /// If the `Config::numeric-uid-gid` has value, this returns its value as the value of the
+    /// `NumericUidGid`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.numeric_uid_gid.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::NumericUidGid;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, NumericUidGid::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--numeric-uid-gid"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(NumericUidGid(true)),
+            NumericUidGid::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_arg_matches_short() {
+        let argv = vec!["lsd", "-n"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(NumericUidGid(true)),
+            NumericUidGid::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, NumericUidGid::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.numeric_uid_gid = Some(true);
+        assert_eq!(Some(NumericUidGid(true)), NumericUidGid::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.numeric_uid_gid = Some(false);
+        assert_eq!(Some(NumericUidGid(false)), NumericUidGid::from_config(&c));
+    }
+}
@@ -0,0 +1,99 @@
+//! This module defines the [OutputFormat] flag. To set it up from [ArgMatches], a [Config] and
+//! its [Default] value, use its [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to print the listing for humans or as records for other programs.
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Display,
+    Csv,
+    Tsv,
+}
+
+impl OutputFormat {
+    /// Whether `self` prints one record per entry rather than the layout.
+    pub fn is_records(self) -> bool {
+        self != Self::Display
+    }
+}
+
+impl Configurable<Self> for OutputFormat {
+    /// Get a potential `OutputFormat` variant from [ArgMatches].
+    ///
+    /// If the "csv" or the "tsv" argument is passed, this returns the corresponding variant in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("csv") {
+            Some(Self::Csv)
+        } else if matches.is_present("tsv") {
+            Some(Self::Tsv)
+        } else {
+            None
+        }
+    }
+
+    /// The output format can not be configured in the [Config], as it would break the listings
+    /// for humans. This always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
+
+/// The default value for `OutputFormat` is [OutputFormat::Display].
+impl Default for OutputFormat {
+    fn default() -> Self {
+        Self::Display
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::OutputFormat;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, OutputFormat::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_csv() {
+        let argv = vec!["lsd", "--csv"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(OutputFormat::Csv),
+            OutputFormat::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_arg_matches_tsv() {
+        let argv = vec!["lsd", "--tsv"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(OutputFormat::Tsv),
+            OutputFormat::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_arg_matches_csv_and_tsv() {
+        let argv = vec!["lsd", "--csv", "--tsv"];
+        assert!(app::build().get_matches_from_safe(argv).is_err());
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, OutputFormat::from_config(&Config::with_none()));
+    }
+}
@@ -0,0 +1,76 @@
+//! This module defines the [Paginate] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to pipe the output into a pager when it is a terminal.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Paginate(pub bool);
+
+impl Configurable<Self> for Paginate {
+    /// Get a potential `Paginate` value from [ArgMatches].
+    ///
+    /// If the "paginate" argument is passed, this returns a `Paginate` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("paginate") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `Paginate` value from a [Config].
+    ///
+    /// If the `Config::paginate` has value, this returns its value as the value of the
+    /// `Paginate`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.paginate.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Paginate;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, Paginate::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--paginate"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(Paginate(true)), Paginate::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, Paginate::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.paginate = Some(true);
+        assert_eq!(Some(Paginate(true)), Paginate::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.paginate = Some(false);
+        assert_eq!(Some(Paginate(false)), Paginate::from_config(&c));
+    }
+}
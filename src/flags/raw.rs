@@ -0,0 +1,77 @@
+//! This module defines the [Raw] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to print the names as they are, their control characters and invalid
+/// bytes included.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Raw(pub bool);
+
+impl Configurable<Self> for Raw {
+    /// Get a potential `Raw` value from [ArgMatches].
+    ///
+    /// If the "raw" argument is passed, this returns a `Raw` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("raw") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `Raw` value from a [Config].
+    ///
+    /// If the `Config::raw` has value, this returns its value as the value of the
+    /// `Raw`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.raw.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Raw;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, Raw::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--raw"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(Raw(true)), Raw::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, Raw::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.raw = Some(true);
+        assert_eq!(Some(Raw(true)), Raw::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.raw = Some(false);
+        assert_eq!(Some(Raw(false)), Raw::from_config(&c));
+    }
+}
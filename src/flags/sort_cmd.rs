@@ -0,0 +1,81 @@
+//! This module defines the [SortCmd] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag holding the shell command printing the sort keys of the files, one per line for each
+/// path it reads on its standard input. The files are sorted as set by the other flags when it is
+/// [None].
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct SortCmd(pub Option<String>);
+
+impl Configurable<Self> for SortCmd {
+    /// Get a potential `SortCmd` value from [ArgMatches].
+    ///
+    /// If the "sort-cmd" argument is passed, this returns a `SortCmd` with its parameter as value
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.occurrences_of("sort-cmd") > 0 {
+            let command = matches.values_of("sort-cmd")?.next_back()?;
+            Some(Self(Some(command.to_string())))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `SortCmd` value from a [Config].
+    ///
+    /// If the `Config::sort-cmd` has value, this returns its value as the value of the `SortCmd`,
+    /// in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config
+            .sort_cmd
+            .as_ref()
+            .map(|command| Self(Some(command.clone())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SortCmd;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, SortCmd::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_command() {
+        let argv = vec!["lsd", "--sort-cmd", "wc -c"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(SortCmd(Some("wc -c".to_string()))),
+            SortCmd::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, SortCmd::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_command() {
+        let mut c = Config::with_none();
+        c.sort_cmd = Some("ci-status --key".to_string());
+        assert_eq!(
+            Some(SortCmd(Some("ci-status --key".to_string()))),
+            SortCmd::from_config(&c)
+        );
+    }
+}
@@ -14,21 +14,33 @@ pub struct Sorting {
     pub column: SortColumn,
     pub order: SortOrder,
     pub dir_grouping: DirGrouping,
+    pub case: SortCase,
+    pub collation: Collation,
+    pub group_dir_symlinks: GroupDirSymlinks,
+    pub path_order: PathOrder,
 }
 
 impl Sorting {
     /// Get a `Sorting` struct from [ArgMatches], a [Config] or the [Default] values.
     ///
-    /// The [SortColumn], [SortOrder] and [DirGrouping] are configured with their respective
-    /// [Configurable] implementation.
+    /// The [SortColumn], [SortOrder], [DirGrouping], [SortCase], [Collation], [GroupDirSymlinks]
+    /// and [PathOrder] are configured with their respective [Configurable] implementation.
     pub fn configure_from(matches: &ArgMatches, config: &Config) -> Self {
         let column = SortColumn::configure_from(matches, config);
         let order = SortOrder::configure_from(matches, config);
         let dir_grouping = DirGrouping::configure_from(matches, config);
+        let case = SortCase::configure_from(matches, config);
+        let collation = Collation::configure_from(matches, config);
+        let group_dir_symlinks = GroupDirSymlinks::configure_from(matches, config);
+        let path_order = PathOrder::configure_from(matches, config);
         Self {
             column,
             order,
             dir_grouping,
+            case,
+            collation,
+            group_dir_symlinks,
+            path_order,
         }
     }
 }
@@ -40,9 +52,14 @@ pub enum SortColumn {
     Extension,
     Name,
     Time,
+    Created,
     Size,
     Version,
-    GitStatus
+    #[serde(rename = "inode")]
+    INode,
+    Links,
+    GitStatus,
+    GitRepo,
 }
 
 impl Configurable<Self> for SortColumn {
@@ -63,8 +80,16 @@ impl Configurable<Self> for SortColumn {
             Some(Self::Extension)
         } else if matches.is_present("versionsort") || sort == Some("version") {
             Some(Self::Version)
+        } else if sort == Some("created") {
+            Some(Self::Created)
+        } else if sort == Some("inode") {
+            Some(Self::INode)
+        } else if sort == Some("links") {
+            Some(Self::Links)
         } else if sort == Some("git") {
             Some(Self::GitStatus)
+        } else if sort == Some("repo") {
+            Some(Self::GitRepo)
         } else {
             None
         }
@@ -207,6 +232,204 @@ impl Default for DirGrouping {
     }
 }
 
+/// The flag showing how the case of the names is considered when sorting by name.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortCase {
+    /// The variant comparing the names as they are, the upper case letters coming first.
+    Sensitive,
+    /// The variant ignoring the case of the names.
+    Insensitive,
+    /// The variant ignoring the case, unless the names only differ by their case.
+    Smart,
+}
+
+impl SortCase {
+    fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "sensitive" => Some(Self::Sensitive),
+            "insensitive" => Some(Self::Insensitive),
+            "smart" => Some(Self::Smart),
+            _ => panic!(
+                "Sort case can only be one of sensitive, insensitive or smart, but got {}.",
+                value
+            ),
+        }
+    }
+}
+
+impl Configurable<Self> for SortCase {
+    /// Get a potential `SortCase` variant from [ArgMatches].
+    ///
+    /// If the "ignore-case" argument is passed, this returns the [SortCase::Insensitive] variant
+    /// in a [Some]. Otherwise if the "sort-case" argument is passed, this returns the variant
+    /// corresponding to its parameter in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("ignore-case") {
+            return Some(Self::Insensitive);
+        }
+
+        if matches.occurrences_of("sort-case") > 0 {
+            if let Some(case) = matches.values_of("sort-case")?.next_back() {
+                return Self::from_str(case);
+            }
+        }
+        None
+    }
+
+    /// Get a potential `SortCase` variant from a [Config].
+    ///
+    /// If the `Config::sorting::case` has value, this returns it in a [Some]. Otherwise this
+    /// returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.sorting.as_ref()?.case
+    }
+}
+
+/// The default value for `SortCase` is [SortCase::Insensitive].
+impl Default for SortCase {
+    fn default() -> Self {
+        Self::Insensitive
+    }
+}
+
+/// The flag showing in which order the characters of the names are sorted.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Collation {
+    /// The variant comparing the characters by their code points.
+    Bytes,
+    /// The variant comparing the names with the collation rules of the `LC_COLLATE` locale, so
+    /// the accented letters are sorted next to their base letter.
+    Locale,
+}
+
+impl Collation {
+    fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "bytes" => Some(Self::Bytes),
+            "locale" => Some(Self::Locale),
+            _ => panic!(
+                "Collation can only be one of bytes or locale, but got {}.",
+                value
+            ),
+        }
+    }
+}
+
+impl Configurable<Self> for Collation {
+    /// Get a potential `Collation` variant from [ArgMatches].
+    ///
+    /// If the "collation" argument is passed, this returns the variant corresponding to its
+    /// parameter in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.occurrences_of("collation") > 0 {
+            if let Some(collation) = matches.values_of("collation")?.next_back() {
+                return Self::from_str(collation);
+            }
+        }
+        None
+    }
+
+    /// Get a potential `Collation` variant from a [Config].
+    ///
+    /// If the `Config::sorting::collation` has value, this returns it in a [Some]. Otherwise this
+    /// returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.sorting.as_ref()?.collation
+    }
+}
+
+/// The default value for `Collation` is [Collation::Bytes].
+impl Default for Collation {
+    fn default() -> Self {
+        Self::Bytes
+    }
+}
+
+/// The flag showing whether the symlinks to directories are grouped with the directories, rather
+/// than with the files, by the [DirGrouping].
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub struct GroupDirSymlinks(pub bool);
+
+impl Configurable<Self> for GroupDirSymlinks {
+    /// The grouping of the symlinks can not be set with an argument, so this always returns
+    /// [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `GroupDirSymlinks` value from a [Config].
+    ///
+    /// If the `Config::sorting::group-dir-symlinks` has value, this returns its value as the value
+    /// of the `GroupDirSymlinks`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.sorting.as_ref()?.group_dir_symlinks.map(Self)
+    }
+}
+
+/// The default value for `GroupDirSymlinks` is `true`, the symlinks to directories being listed
+/// with the directories.
+impl Default for GroupDirSymlinks {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// The flag showing in which order the paths given as arguments are listed.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PathOrder {
+    /// The variant sorting the paths as the entries of the directories are.
+    Sorted,
+    /// The variant listing the paths in the order they are given, the files still being listed
+    /// before the directories.
+    Given,
+}
+
+impl PathOrder {
+    fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "sorted" => Some(Self::Sorted),
+            "given" => Some(Self::Given),
+            _ => panic!(
+                "Path order can only be one of sorted or given, but got {}.",
+                value
+            ),
+        }
+    }
+}
+
+impl Configurable<Self> for PathOrder {
+    /// Get a potential `PathOrder` variant from [ArgMatches].
+    ///
+    /// If the "path-order" argument is passed, this returns the variant corresponding to its
+    /// parameter in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.occurrences_of("path-order") > 0 {
+            if let Some(order) = matches.values_of("path-order")?.next_back() {
+                return Self::from_str(order);
+            }
+        }
+        None
+    }
+
+    /// Get a potential `PathOrder` variant from a [Config].
+    ///
+    /// If the `Config::sorting::path-order` has value, this returns it in a [Some]. Otherwise
+    /// this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.sorting.as_ref()?.path_order
+    }
+}
+
+/// The default value for `PathOrder` is [PathOrder::Sorted].
+impl Default for PathOrder {
+    fn default() -> Self {
+        Self::Sorted
+    }
+}
+
 #[cfg(test)]
 mod test_sort_column {
     use super::SortColumn;
@@ -291,6 +514,27 @@ mod test_sort_column {
             Some(SortColumn::Version),
             SortColumn::from_arg_matches(&matches)
         );
+
+        let argv = vec!["lsd", "--sort", "created"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(SortColumn::Created),
+            SortColumn::from_arg_matches(&matches)
+        );
+
+        let argv = vec!["lsd", "--sort", "inode"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(SortColumn::INode),
+            SortColumn::from_arg_matches(&matches)
+        );
+
+        let argv = vec!["lsd", "--sort", "links"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(SortColumn::Links),
+            SortColumn::from_arg_matches(&matches)
+        );
     }
 
     #[test]
@@ -325,6 +569,10 @@ mod test_sort_column {
             column: None,
             reverse: None,
             dir_grouping: None,
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
 
         assert_eq!(None, SortColumn::from_config(&c));
@@ -337,6 +585,10 @@ mod test_sort_column {
             column: Some(SortColumn::Extension),
             reverse: None,
             dir_grouping: None,
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
         assert_eq!(Some(SortColumn::Extension), SortColumn::from_config(&c));
     }
@@ -348,6 +600,10 @@ mod test_sort_column {
             column: Some(SortColumn::Name),
             reverse: None,
             dir_grouping: None,
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
         assert_eq!(Some(SortColumn::Name), SortColumn::from_config(&c));
     }
@@ -359,6 +615,10 @@ mod test_sort_column {
             column: Some(SortColumn::Time),
             reverse: None,
             dir_grouping: None,
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
         assert_eq!(Some(SortColumn::Time), SortColumn::from_config(&c));
     }
@@ -370,6 +630,10 @@ mod test_sort_column {
             column: Some(SortColumn::Size),
             reverse: None,
             dir_grouping: None,
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
         assert_eq!(Some(SortColumn::Size), SortColumn::from_config(&c));
     }
@@ -381,6 +645,10 @@ mod test_sort_column {
             column: Some(SortColumn::Version),
             reverse: None,
             dir_grouping: None,
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
         assert_eq!(Some(SortColumn::Version), SortColumn::from_config(&c));
     }
@@ -431,6 +699,10 @@ mod test_sort_order {
             column: None,
             reverse: None,
             dir_grouping: None,
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
         assert_eq!(None, SortOrder::from_config(&c));
     }
@@ -442,6 +714,10 @@ mod test_sort_order {
             column: None,
             reverse: Some(true),
             dir_grouping: None,
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
         assert_eq!(Some(SortOrder::Reverse), SortOrder::from_config(&c));
     }
@@ -453,6 +729,10 @@ mod test_sort_order {
             column: None,
             reverse: Some(false),
             dir_grouping: None,
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
         assert_eq!(Some(SortOrder::Default), SortOrder::from_config(&c));
     }
@@ -543,6 +823,10 @@ mod test_dir_grouping {
             column: None,
             reverse: None,
             dir_grouping: Some(DirGrouping::First),
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
         assert_eq!(Some(DirGrouping::First), DirGrouping::from_config(&c));
     }
@@ -554,6 +838,10 @@ mod test_dir_grouping {
             column: None,
             reverse: None,
             dir_grouping: Some(DirGrouping::Last),
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
         assert_eq!(Some(DirGrouping::Last), DirGrouping::from_config(&c));
     }
@@ -565,6 +853,10 @@ mod test_dir_grouping {
             column: None,
             reverse: None,
             dir_grouping: None,
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
         assert_eq!(None, DirGrouping::from_config(&c));
     }
@@ -576,8 +868,184 @@ mod test_dir_grouping {
             column: None,
             reverse: None,
             dir_grouping: Some(DirGrouping::Last),
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
         });
         c.classic = Some(true);
         assert_eq!(Some(DirGrouping::None), DirGrouping::from_config(&c));
     }
 }
+
+#[cfg(test)]
+mod test_sort_case {
+    use super::SortCase;
+
+    use crate::app;
+    use crate::config_file::{Config, Sorting};
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, SortCase::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_smart() {
+        let argv = vec!["lsd", "--sort-case", "smart"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(SortCase::Smart), SortCase::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_ignore_case() {
+        let argv = vec!["lsd", "--ignore-case"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(SortCase::Insensitive),
+            SortCase::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, SortCase::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_sensitive() {
+        let mut c = Config::with_none();
+        c.sorting = Some(Sorting {
+            column: None,
+            reverse: None,
+            dir_grouping: None,
+            case: Some(SortCase::Sensitive),
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: None,
+        });
+        assert_eq!(Some(SortCase::Sensitive), SortCase::from_config(&c));
+    }
+}
+
+#[cfg(test)]
+mod test_collation {
+    use super::Collation;
+
+    use crate::app;
+    use crate::config_file::{Config, Sorting};
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, Collation::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_locale() {
+        let argv = vec!["lsd", "--collation", "locale"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(Collation::Locale),
+            Collation::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, Collation::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_locale() {
+        let mut c = Config::with_none();
+        c.sorting = Some(Sorting {
+            column: None,
+            reverse: None,
+            dir_grouping: None,
+            case: None,
+            collation: Some(Collation::Locale),
+            group_dir_symlinks: None,
+            path_order: None,
+        });
+        assert_eq!(Some(Collation::Locale), Collation::from_config(&c));
+    }
+}
+
+#[cfg(test)]
+mod test_group_dir_symlinks {
+    use super::GroupDirSymlinks;
+
+    use crate::config_file::{Config, Sorting};
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, GroupDirSymlinks::from_config(&Config::with_none()));
+        assert_eq!(GroupDirSymlinks(true), GroupDirSymlinks::default());
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.sorting = Some(Sorting {
+            column: None,
+            reverse: None,
+            dir_grouping: None,
+            case: None,
+            collation: None,
+            group_dir_symlinks: Some(false),
+            path_order: None,
+        });
+        assert_eq!(
+            Some(GroupDirSymlinks(false)),
+            GroupDirSymlinks::from_config(&c)
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_path_order {
+    use super::PathOrder;
+
+    use crate::app;
+    use crate::config_file::{Config, Sorting};
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, PathOrder::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_given() {
+        let argv = vec!["lsd", "--path-order", "given"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(PathOrder::Given),
+            PathOrder::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_given() {
+        let mut c = Config::with_none();
+        c.sorting = Some(Sorting {
+            column: None,
+            reverse: None,
+            dir_grouping: None,
+            case: None,
+            collation: None,
+            group_dir_symlinks: None,
+            path_order: Some(PathOrder::Given),
+        });
+        assert_eq!(Some(PathOrder::Given), PathOrder::from_config(&c));
+    }
+}
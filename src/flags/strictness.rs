@@ -0,0 +1,133 @@
+//! This module defines the [Strictness] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+use crate::print_error;
+
+use clap::{ArgMatches, Error, ErrorKind};
+use serde::Deserialize;
+
+/// The `ls` arguments accepted but not supported by lsd, with how they are shown to the user and
+/// the approximation used instead.
+const UNSUPPORTED_ARGS: &[(&str, &str, &str)] = &[
+    ("by-columns", "-C", "the grid layout is used"),
+    ("across", "-x", "the grid layout is used"),
+    ("escape", "-b", "the file names are shown as they are"),
+    (
+        "hide-control-chars",
+        "-q",
+        "the file names are shown as they are",
+    ),
+    ("literal", "-N", "the file names are shown as they are"),
+    (
+        "full-time",
+        "--full-time",
+        "only the full-iso time style is applied",
+    ),
+];
+
+/// The flag showing how to handle the `ls` arguments which are not supported by lsd.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strictness {
+    /// Silently use an approximation.
+    Lenient,
+    /// Print a warning and use an approximation.
+    Warn,
+    /// Exit with an error.
+    Strict,
+}
+
+impl Strictness {
+    /// Checks the unsupported `ls` arguments passed in [ArgMatches].
+    ///
+    /// # Errors
+    ///
+    /// If the `Strictness` is [Strictness::Strict] and one of them is passed.
+    pub fn check_unsupported(self, matches: &ArgMatches) -> Result<(), Error> {
+        for (name, arg, approximation) in UNSUPPORTED_ARGS {
+            if !matches.is_present(name) {
+                continue;
+            }
+            match self {
+                Self::Lenient => {}
+                Self::Warn => print_error!("{} is not supported, {}.", arg, approximation),
+                Self::Strict => {
+                    return Err(Error::with_description(
+                        &format!("The argument '{}' is not supported by lsd", arg),
+                        ErrorKind::UnknownArgument,
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Configurable<Self> for Strictness {
+    /// The strictness can not be passed as an argument, so this always returns [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `Strictness` variant from a [Config].
+    ///
+    /// If the `Config::strictness` has value, this returns it in a [Some]. Otherwise this returns
+    /// [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.strictness
+    }
+}
+
+/// The default value for `Strictness` is [Strictness::Warn].
+impl Default for Strictness {
+    fn default() -> Self {
+        Self::Warn
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Strictness;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd", "-C"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, Strictness::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, Strictness::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_strict() {
+        let mut c = Config::with_none();
+        c.strictness = Some(Strictness::Strict);
+        assert_eq!(Some(Strictness::Strict), Strictness::from_config(&c));
+    }
+
+    #[test]
+    fn test_check_unsupported() {
+        let argv = vec!["lsd", "-C", "-b"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert!(Strictness::Lenient.check_unsupported(&matches).is_ok());
+        assert!(Strictness::Warn.check_unsupported(&matches).is_ok());
+        assert!(Strictness::Strict.check_unsupported(&matches).is_err());
+    }
+
+    #[test]
+    fn test_check_supported() {
+        let argv = vec!["lsd", "-l"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert!(Strictness::Strict.check_unsupported(&matches).is_ok());
+    }
+}
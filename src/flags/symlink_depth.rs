@@ -0,0 +1,107 @@
+//! This module defines the [SymlinkDepth] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag holding how many symbolic links may be followed when resolving a chain of links.
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub struct SymlinkDepth(pub usize);
+
+impl Configurable<Self> for SymlinkDepth {
+    /// Get a potential `SymlinkDepth` value from [ArgMatches].
+    ///
+    /// If the "max-symlink-depth" argument is passed, this returns a `SymlinkDepth` with its
+    /// parameter as value in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.occurrences_of("max-symlink-depth") > 0 {
+            let depth = matches.values_of("max-symlink-depth")?.next_back()?;
+            depth.parse::<usize>().ok().map(Self)
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `SymlinkDepth` value from a [Config].
+    ///
+    /// If the `Config::max-symlink-depth` has value, this returns its value as the value of the
+    /// `SymlinkDepth`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.max_symlink_depth.map(Self)
+    }
+}
+
+/// The default value for `SymlinkDepth` is 40, the limit used by the Linux kernel.
+impl Default for SymlinkDepth {
+    fn default() -> Self {
+        Self(40)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SymlinkDepth;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, SymlinkDepth::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_depth() {
+        let argv = vec!["lsd", "--max-symlink-depth", "3"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(SymlinkDepth(3)),
+            SymlinkDepth::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_arg_matches_depth_multi() {
+        let argv = vec![
+            "lsd",
+            "--max-symlink-depth",
+            "3",
+            "--max-symlink-depth",
+            "5",
+        ];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(SymlinkDepth(5)),
+            SymlinkDepth::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_arg_matches_depth_invalid() {
+        let argv = vec!["lsd", "--max-symlink-depth", "foo"];
+        assert!(app::build().get_matches_from_safe(argv).is_err());
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, SymlinkDepth::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_depth() {
+        let mut c = Config::with_none();
+        c.max_symlink_depth = Some(8);
+        assert_eq!(Some(SymlinkDepth(8)), SymlinkDepth::from_config(&c));
+    }
+
+    #[test]
+    fn test_default() {
+        assert_eq!(SymlinkDepth(40), SymlinkDepth::default());
+    }
+}
@@ -0,0 +1,104 @@
+//! This module defines the [Table] flags. To set them up from [ArgMatches], a [Config] and their
+//! [Default] values, use the [configure_from](Table::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// A collection of flags on the visual aids of the long and the tree layouts.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Table {
+    /// The string printed between the blocks.
+    pub separator: TableSeparator,
+    /// Whether every other row is shaded.
+    pub shade_rows: ShadeRows,
+}
+
+impl Table {
+    /// Get a `Table` struct from [ArgMatches], a [Config] or the [Default] values.
+    ///
+    /// The [TableSeparator] and [ShadeRows] are configured with their respective [Configurable]
+    /// implementation.
+    pub fn configure_from(matches: &ArgMatches, config: &Config) -> Self {
+        let separator = TableSeparator::configure_from(matches, config);
+        let shade_rows = ShadeRows::configure_from(matches, config);
+        Self {
+            separator,
+            shade_rows,
+        }
+    }
+}
+
+/// The flag holding the character printed between the blocks, [None] keeping them separated by
+/// a single space.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct TableSeparator(pub Option<String>);
+
+impl Configurable<Self> for TableSeparator {
+    /// The separator can not be passed as an argument, so this always returns [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `TableSeparator` value from a [Config].
+    ///
+    /// If the `Config::table::separator` has value, this returns it in a [Some]. Otherwise this
+    /// returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        let separator = config.table.as_ref()?.separator.as_ref()?;
+        Some(Self(Some(separator.clone())))
+    }
+}
+
+/// The flag showing whether every other row has a dim background.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct ShadeRows(pub bool);
+
+impl Configurable<Self> for ShadeRows {
+    /// The shading can not be turned on with an argument, so this always returns [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `ShadeRows` value from a [Config].
+    ///
+    /// If the `Config::table::shade-rows` has value, this returns its value as the value of the
+    /// `ShadeRows`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.table.as_ref()?.shade_rows.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ShadeRows, Table, TableSeparator};
+
+    use crate::app;
+    use crate::config_file::{self, Config};
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_config_none() {
+        let matches = app::build().get_matches_from_safe(vec!["lsd"]).unwrap();
+        assert_eq!(
+            Table::default(),
+            Table::configure_from(&matches, &Config::with_none())
+        );
+    }
+
+    #[test]
+    fn test_from_config() {
+        let mut c = Config::with_none();
+        c.table = Some(config_file::Table {
+            separator: Some("│".to_string()),
+            shade_rows: Some(true),
+        });
+        assert_eq!(
+            Some(TableSeparator(Some("│".to_string()))),
+            TableSeparator::from_config(&c)
+        );
+        assert_eq!(Some(ShadeRows(true)), ShadeRows::from_config(&c));
+    }
+}
@@ -0,0 +1,107 @@
+//! This module defines the [TotalSizeSymlinks] flag. To set it up from [ArgMatches], a [Config]
+//! and its [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+use serde::Deserialize;
+
+/// The flag showing how the symlinks found in the directories are counted in their total size.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TotalSizeSymlinks {
+    /// Count the size of the link itself, as `du` does by default.
+    Link,
+    /// Count the size of the file the link references, the content of a directory being counted
+    /// once however many links lead to it, as `du -L` does.
+    Follow,
+    /// Do not count the links.
+    Skip,
+}
+
+impl TotalSizeSymlinks {
+    fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "link" => Some(Self::Link),
+            "follow" => Some(Self::Follow),
+            "skip" => Some(Self::Skip),
+            _ => panic!(
+                "Total size symlinks can only be one of link, follow or skip, but got {}.",
+                value
+            ),
+        }
+    }
+}
+
+impl Configurable<Self> for TotalSizeSymlinks {
+    /// Get a potential `TotalSizeSymlinks` variant from [ArgMatches].
+    ///
+    /// If the "total-size-symlinks" argument is passed, this returns the variant corresponding to
+    /// its parameter in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.occurrences_of("total-size-symlinks") > 0 {
+            if let Some(value) = matches.values_of("total-size-symlinks")?.next_back() {
+                return Self::from_str(value);
+            }
+        }
+        None
+    }
+
+    /// Get a potential `TotalSizeSymlinks` variant from a [Config].
+    ///
+    /// If the `Config::total-size-symlinks` has value, this returns it in a [Some]. Otherwise
+    /// this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.total_size_symlinks
+    }
+}
+
+/// The default value for `TotalSizeSymlinks` is [TotalSizeSymlinks::Link].
+impl Default for TotalSizeSymlinks {
+    fn default() -> Self {
+        Self::Link
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TotalSizeSymlinks;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, TotalSizeSymlinks::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_follow() {
+        let argv = vec!["lsd", "--total-size-symlinks", "follow"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(TotalSizeSymlinks::Follow),
+            TotalSizeSymlinks::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, TotalSizeSymlinks::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_skip() {
+        let mut c = Config::with_none();
+        c.total_size_symlinks = Some(TotalSizeSymlinks::Skip);
+        assert_eq!(
+            Some(TotalSizeSymlinks::Skip),
+            TotalSizeSymlinks::from_config(&c)
+        );
+    }
+}
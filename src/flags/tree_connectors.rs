@@ -0,0 +1,125 @@
+//! This module defines the [TreeConnectors] flag. To set it up from [ArgMatches], a [Config] and
+//! its [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+use serde::Deserialize;
+use unicode_width::UnicodeWidthStr;
+
+/// The characters the edges of the tree layout are drawn with, unless overridden.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TreeConnectorPreset {
+    /// The box drawing characters.
+    Unicode,
+    /// Pipes, dashes and backquotes, for the terminals and fonts without box drawing characters.
+    Ascii,
+}
+
+/// The default value for `TreeConnectorPreset` is [TreeConnectorPreset::Unicode].
+impl Default for TreeConnectorPreset {
+    fn default() -> Self {
+        Self::Unicode
+    }
+}
+
+/// The flag holding the connectors of the tree layout, each one set replacing the one of the
+/// preset: the edge leading to an entry, the corner leading to the last entry of a directory and
+/// the line going on down past the entries of a directory which is not the last one.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TreeConnectors {
+    #[serde(default)]
+    pub preset: TreeConnectorPreset,
+    pub edge: Option<String>,
+    pub corner: Option<String>,
+    pub line: Option<String>,
+}
+
+impl TreeConnectors {
+    /// The connector leading to an entry which is not the last one of its directory.
+    pub fn edge(&self) -> &str {
+        match (&self.edge, self.preset) {
+            (Some(edge), _) => edge,
+            (None, TreeConnectorPreset::Unicode) => "\u{251c}\u{2500}\u{2500}",
+            (None, TreeConnectorPreset::Ascii) => "|--",
+        }
+    }
+
+    /// The connector leading to the last entry of its directory.
+    pub fn corner(&self) -> &str {
+        match (&self.corner, self.preset) {
+            (Some(corner), _) => corner,
+            (None, TreeConnectorPreset::Unicode) => "\u{2514}\u{2500}\u{2500}",
+            (None, TreeConnectorPreset::Ascii) => "`--",
+        }
+    }
+
+    /// The connector going on down by the content of a directory which is not the last one.
+    pub fn line(&self) -> &str {
+        match (&self.line, self.preset) {
+            (Some(line), _) => line,
+            (None, TreeConnectorPreset::Unicode) => "\u{2502}  ",
+            (None, TreeConnectorPreset::Ascii) => "|  ",
+        }
+    }
+
+    /// The blank put by the content of the last directory instead of the line, as wide as it.
+    pub fn blank(&self) -> String {
+        " ".repeat(self.line().width())
+    }
+}
+
+impl Configurable<Self> for TreeConnectors {
+    /// The tree connectors can not be passed as arguments, so this always returns [None].
+    fn from_arg_matches(_matches: &ArgMatches) -> Option<Self> {
+        None
+    }
+
+    /// Get a potential `TreeConnectors` value from a [Config].
+    ///
+    /// If the `Config::tree_connectors` has value, this returns it in a [Some]. Otherwise this
+    /// returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.tree_connectors.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TreeConnectorPreset, TreeConnectors};
+
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, TreeConnectors::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_default_connectors() {
+        let connectors = TreeConnectors::default();
+        assert_eq!("\u{251c}\u{2500}\u{2500}", connectors.edge());
+        assert_eq!("\u{2514}\u{2500}\u{2500}", connectors.corner());
+        assert_eq!("\u{2502}  ", connectors.line());
+        assert_eq!("   ", connectors.blank());
+    }
+
+    #[test]
+    fn test_from_config_ascii() {
+        let config = Config {
+            tree_connectors: Some(serde_yaml::from_str("preset: ascii\nline: \"| \"\n").unwrap()),
+            ..Config::with_none()
+        };
+        let connectors = TreeConnectors::from_config(&config).unwrap();
+        assert_eq!(TreeConnectorPreset::Ascii, connectors.preset);
+        assert_eq!("|--", connectors.edge());
+        assert_eq!("`--", connectors.corner());
+        assert_eq!("| ", connectors.line());
+        assert_eq!("  ", connectors.blank());
+    }
+}
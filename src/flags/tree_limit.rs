@@ -0,0 +1,87 @@
+//! This module defines the [TreeLimit] flag. To set it up from [ArgMatches], a [Config] and
+//! its [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag holding how many entries of each directory the tree layout shows at most, the other
+/// ones being counted on a last line. All the entries are shown when it is [None].
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct TreeLimit(pub Option<usize>);
+
+impl Configurable<Self> for TreeLimit {
+    /// Get a potential `TreeLimit` value from [ArgMatches].
+    ///
+    /// If the "tree-limit" argument is passed, this returns a `TreeLimit` with its parameter as
+    /// value in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.occurrences_of("tree-limit") > 0 {
+            let count = matches.values_of("tree-limit")?.next_back()?;
+            count.parse::<usize>().ok().map(|n| Self(Some(n)))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `TreeLimit` value from a [Config].
+    ///
+    /// If the `Config::tree_limit` has a value other than 0, this returns it as the value of the
+    /// `TreeLimit`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.tree_limit.filter(|&n| n > 0).map(|n| Self(Some(n)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TreeLimit;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, TreeLimit::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_count() {
+        let argv = vec!["lsd", "--tree-limit", "3"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(TreeLimit(Some(3))),
+            TreeLimit::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_arg_matches_invalid() {
+        let argv = vec!["lsd", "--tree-limit", "0"];
+        assert!(app::build().get_matches_from_safe(argv).is_err());
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, TreeLimit::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_count() {
+        let mut c = Config::with_none();
+        c.tree_limit = Some(3);
+        assert_eq!(Some(TreeLimit(Some(3))), TreeLimit::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_zero() {
+        let mut c = Config::with_none();
+        c.tree_limit = Some(0);
+        assert_eq!(None, TreeLimit::from_config(&c));
+    }
+}
@@ -0,0 +1,81 @@
+//! This module defines the [TruncateMiddle] flag. To set it up from [ArgMatches], a [Config] and
+//! its [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to shorten the names in the middle, keeping their extension, rather
+/// than at their end. Without a [TruncateNames](super::TruncateNames) width, the names too long
+/// for the grid layout to show two columns are shortened.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct TruncateMiddle(pub bool);
+
+impl Configurable<Self> for TruncateMiddle {
+    /// Get a potential `TruncateMiddle` value from [ArgMatches].
+    ///
+    /// If the "truncate-middle" argument is passed, this returns a `TruncateMiddle` with value
+    /// `true` in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("truncate-middle") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `TruncateMiddle` value from a [Config].
+    ///
+    /// If the `Config::truncate-middle` has value, this returns its value as the value of the
+    /// `TruncateMiddle`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.truncate_middle.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TruncateMiddle;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, TruncateMiddle::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--truncate-middle"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(TruncateMiddle(true)),
+            TruncateMiddle::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, TruncateMiddle::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.truncate_middle = Some(true);
+        assert_eq!(Some(TruncateMiddle(true)), TruncateMiddle::from_config(&c));
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.truncate_middle = Some(false);
+        assert_eq!(Some(TruncateMiddle(false)), TruncateMiddle::from_config(&c));
+    }
+}
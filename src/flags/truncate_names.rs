@@ -0,0 +1,83 @@
+//! This module defines the [TruncateNames] flag. To set it up from [ArgMatches], a [Config] and
+//! its [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag holding how many columns a name may take in the grid layout before being shortened
+/// with an ellipsis. Names are never shortened when it is [None].
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct TruncateNames(pub Option<usize>);
+
+impl Configurable<Self> for TruncateNames {
+    /// Get a potential `TruncateNames` value from [ArgMatches].
+    ///
+    /// If the "truncate-names" argument is passed, this returns a `TruncateNames` with its
+    /// parameter as value in a [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.occurrences_of("truncate-names") > 0 {
+            let width = matches.values_of("truncate-names")?.next_back()?;
+            width.parse::<usize>().ok().map(|w| Self(Some(w)))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `TruncateNames` value from a [Config].
+    ///
+    /// If the `Config::truncate-names` has value, this returns its value as the value of the
+    /// `TruncateNames`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.truncate_names.map(|w| Self(Some(w)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TruncateNames;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, TruncateNames::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_width() {
+        let argv = vec!["lsd", "--truncate-names", "12"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(TruncateNames(Some(12))),
+            TruncateNames::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_arg_matches_invalid() {
+        let argv = vec!["lsd", "--truncate-names", "short"];
+        assert!(app::build().get_matches_from_safe(argv).is_err());
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, TruncateNames::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_width() {
+        let mut c = Config::with_none();
+        c.truncate_names = Some(20);
+        assert_eq!(
+            Some(TruncateNames(Some(20))),
+            TruncateNames::from_config(&c)
+        );
+    }
+}
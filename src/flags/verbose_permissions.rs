@@ -0,0 +1,85 @@
+//! This module defines the [VerbosePermissions] flag. To set it up from [ArgMatches], a [Config]
+//! and its [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to spell out the permissions after their symbolic notation.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct VerbosePermissions(pub bool);
+
+impl Configurable<Self> for VerbosePermissions {
+    /// Get a potential `VerbosePermissions` value from [ArgMatches].
+    ///
+    /// If the "verbose-permissions" argument is passed, this returns a `VerbosePermissions` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("verbose-permissions") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// Get a potential `VerbosePermissions` value from a [Config].
+    ///
+    /// If the `Config::verbose-permissions` has value, this returns its value as the value of the
+    /// `VerbosePermissions`, in a [Some]. Otherwise this returns [None].
+    fn from_config(config: &Config) -> Option<Self> {
+        config.verbose_permissions.map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::VerbosePermissions;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, VerbosePermissions::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--verbose-permissions"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            Some(VerbosePermissions(true)),
+            VerbosePermissions::from_arg_matches(&matches)
+        );
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, VerbosePermissions::from_config(&Config::with_none()));
+    }
+
+    #[test]
+    fn test_from_config_true() {
+        let mut c = Config::with_none();
+        c.verbose_permissions = Some(true);
+        assert_eq!(
+            Some(VerbosePermissions(true)),
+            VerbosePermissions::from_config(&c)
+        );
+    }
+
+    #[test]
+    fn test_from_config_false() {
+        let mut c = Config::with_none();
+        c.verbose_permissions = Some(false);
+        assert_eq!(
+            Some(VerbosePermissions(false)),
+            VerbosePermissions::from_config(&c)
+        );
+    }
+}
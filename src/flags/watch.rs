@@ -0,0 +1,60 @@
+//! This module defines the [Watch] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to list the files again each time they change.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Watch(pub bool);
+
+impl Configurable<Self> for Watch {
+    /// Get a potential `Watch` value from [ArgMatches].
+    ///
+    /// If the "watch" argument is passed, this returns a `Watch` with value `true` in a
+    /// [Some]. Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("watch") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// The watch mode can not be configured in the [Config], as no listing would end. This
+    /// always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Watch;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, Watch::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--watch"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(Watch(true)), Watch::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, Watch::from_config(&Config::with_none()));
+    }
+}
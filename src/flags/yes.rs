@@ -0,0 +1,60 @@
+//! This module defines the [Yes] flag. To set it up from [ArgMatches], a [Config] and its
+//! [Default] value, use the [configure_from](Configurable::configure_from) method.
+
+use super::Configurable;
+
+use crate::config_file::Config;
+
+use clap::ArgMatches;
+
+/// The flag showing whether to recurse from the root of a filesystem without asking first.
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Default)]
+pub struct Yes(pub bool);
+
+impl Configurable<Self> for Yes {
+    /// Get a potential `Yes` value from [ArgMatches].
+    ///
+    /// If the "yes" argument is passed, this returns a `Yes` with value `true` in a [Some].
+    /// Otherwise this returns [None].
+    fn from_arg_matches(matches: &ArgMatches) -> Option<Self> {
+        if matches.is_present("yes") {
+            Some(Self(true))
+        } else {
+            None
+        }
+    }
+
+    /// The confirmation can not be skipped in the [Config], as it would then never be asked.
+    /// This always returns [None].
+    fn from_config(_: &Config) -> Option<Self> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Yes;
+
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Configurable;
+
+    #[test]
+    fn test_from_arg_matches_none() {
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(None, Yes::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_arg_matches_true() {
+        let argv = vec!["lsd", "--yes"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(Some(Yes(true)), Yes::from_arg_matches(&matches));
+    }
+
+    #[test]
+    fn test_from_config_none() {
+        assert_eq!(None, Yes::from_config(&Config::with_none()));
+    }
+}
@@ -1,6 +1,8 @@
 use log::{debug, info, warn};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use crate::meta::git_file_status::GitFileStatus;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -27,32 +29,187 @@ pub enum GitStatus {
     Conflicted,
 }
 
+/// A source of Git statuses for the files of a listing.
+pub trait GitStatusProvider {
+    /// The known statuses, with the absolute path of their file.
+    fn statuses(&self) -> &[(PathBuf, GitFileStatus)];
+
+    /// The path, relative to the workdir, `filepath` had before being renamed in the index.
+    fn renamed_from(&self, _filepath: &Path) -> Option<&Path> {
+        None
+    }
+
+    /// The number of untracked files below the directory `dirpath`.
+    fn untracked_count(&self, dirpath: &Path) -> usize {
+        self.statuses()
+            .iter()
+            .filter(|&x| x.0.starts_with(dirpath) && x.1.workdir == GitStatus::NewInWorkdir)
+            .count()
+    }
+
+    /// The numbers of files below the directory `dirpath` with changes staged in the index, and
+    /// with changes not staged in the workdir, the untracked files included.
+    fn change_counts(&self, dirpath: &Path) -> (usize, usize) {
+        let mut counts = (0, 0);
+        for (_, status) in self.statuses().iter().filter(|x| x.0.starts_with(dirpath)) {
+            if status.index > GitStatus::Unmodified {
+                counts.0 += 1;
+            }
+            if status.workdir > GitStatus::Ignored {
+                counts.1 += 1;
+            }
+        }
+        counts
+    }
+
+    /// The status of `filepath`. The status of a directory is the most important index and
+    /// workdir statuses of the files it contains.
+    fn get(&self, filepath: &Path, is_directory: bool) -> GitFileStatus {
+        debug!("Look for [recurse={}] {:?}", is_directory, filepath);
+
+        if is_directory {
+            self.statuses()
+                .iter()
+                .filter(|&x| x.0.starts_with(filepath))
+                .inspect(|&x| debug!("\t{:?}", x.0))
+                .map(|x| x.1)
+                .fold(GitFileStatus::default(), |acc, x| GitFileStatus {
+                    index: std::cmp::max(acc.index, x.index),
+                    workdir: std::cmp::max(acc.workdir, x.workdir),
+                })
+        } else {
+            self.statuses()
+                .iter()
+                .find(|&x| filepath == x.0)
+                .map(|e| e.1)
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// The [GitStatusProvider] reading the statuses of a repository with libgit2.
 pub struct GitCache {
-    statuses: Vec<(PathBuf, git2::Status)>,
+    statuses: Vec<(PathBuf, GitFileStatus)>,
+    /// The previous paths of the renamed files, keyed by their absolute path.
+    renames: HashMap<PathBuf, PathBuf>,
     _cached_dir: Option<PathBuf>,
 }
 
 impl GitCache {
-    pub fn new(path: &Path) -> GitCache {
-        let cachedir = fs::canonicalize(&path).unwrap();
-        info!("Trying to retrieve Git statuses for {:?}", cachedir);
+    /// The repository of `path`, if any. As for git, the `GIT_DIR`, `GIT_WORK_TREE` and
+    /// `GIT_INDEX_FILE` environment variables override the repository, its work tree and its
+    /// index, as set by the hooks and the scripts committing from a temporary index.
+    fn discover(path: &Path) -> Option<git2::Repository> {
+        Self::open(
+            path,
+            std::env::var_os("GIT_DIR").as_deref().map(Path::new),
+            std::env::var_os("GIT_WORK_TREE").as_deref().map(Path::new),
+            std::env::var_os("GIT_INDEX_FILE").as_deref().map(Path::new),
+        )
+    }
 
-        let repo = match git2::Repository::discover(&path) {
+    /// The repository at `git_dir`, or else the one containing `path`, its work tree being
+    /// `work_tree`, or else the configured one, and its index the one at `index_file`.
+    fn open(
+        path: &Path,
+        git_dir: Option<&Path>,
+        work_tree: Option<&Path>,
+        index_file: Option<&Path>,
+    ) -> Option<git2::Repository> {
+        let result = match git_dir {
+            Some(git_dir) => git2::Repository::open(git_dir),
+            None => git2::Repository::discover(&path),
+        };
+        let repo = match result {
             Ok(r) => r,
+            // Most paths are not in a repository, which is not worth a warning.
+            Err(e) if e.code() == git2::ErrorCode::NotFound => {
+                debug!("Git discovery error: {:?}", e);
+                return None;
+            }
             Err(e) => {
                 warn!("Git discovery error: {:?}", e);
+                return None;
+            }
+        };
+
+        // libgit2 ignores the configured work tree of a bare repository, such as the ones used
+        // to track the dotfiles of a home directory.
+        let work_tree = match work_tree {
+            Some(work_tree) => Some(work_tree.to_path_buf()),
+            None if repo.is_bare() => repo
+                .config()
+                .and_then(|config| config.get_path("core.worktree"))
+                .ok()
+                .map(|work_tree| repo.path().join(work_tree)),
+            None => None,
+        };
+        if let Some(work_tree) = work_tree {
+            if let Err(e) = repo.set_workdir(&work_tree, false) {
+                warn!("Git work tree error: {:?}", e);
+            }
+        }
+        if let Some(index_file) = index_file {
+            // As for git, a missing index is an empty one.
+            let result = git2::Index::open(index_file).and_then(|mut i| repo.set_index(&mut i));
+            if let Err(e) = result {
+                warn!("Git index file error: {:?}", e);
+            }
+        }
+
+        Some(repo)
+    }
+
+    /// Whether `repo` is declared bare, even if it has been given a work tree. Listing its
+    /// untracked files would flag most of that work tree, as for a home directory.
+    fn is_declared_bare(repo: &git2::Repository) -> bool {
+        repo.config()
+            .and_then(|config| config.get_bool("core.bare"))
+            .unwrap_or(false)
+    }
+
+    /// The statuses of the files of `repo`, with the renames in the index found when
+    /// `detect_renames` is set.
+    fn from_repository(repo: &git2::Repository, path: &Path, detect_renames: bool) -> GitCache {
+        let cachedir = match fs::canonicalize(&path) {
+            Ok(cachedir) => cachedir,
+            // The path has been removed since it was found, there is nothing to cache.
+            Err(e) => {
+                debug!("Git cache path error: {:?}", e);
                 return Self::empty();
             }
         };
+        info!("Trying to retrieve Git statuses for {:?}", cachedir);
 
         if let Some(workdir) = repo.workdir() {
             let mut statuses = Vec::new();
+            let mut renames = HashMap::new();
             info!("Retrieving Git statuses for workdir {:?}", workdir);
-            match repo.statuses(None) {
+            // These are the default options, but for the rename detection and a repository
+            // declared bare.
+            let listed = !Self::is_declared_bare(repo);
+            let mut options = git2::StatusOptions::new();
+            options
+                .include_untracked(listed)
+                .include_ignored(listed)
+                .recurse_untracked_dirs(listed)
+                .renames_head_to_index(detect_renames);
+            match repo.statuses(Some(&mut options)) {
                 Ok(status_list) => {
                     for status_entry in status_list.iter() {
-                        let path = workdir.join(Path::new(status_entry.path().unwrap()));
-                        let elem = (path, status_entry.status());
+                        // The path of an entry is its old one when it has been renamed.
+                        let delta = status_entry.head_to_index();
+                        let new_path = delta.as_ref().and_then(|d| d.new_file().path());
+                        let path = workdir.join(
+                            new_path.unwrap_or_else(|| Path::new(status_entry.path().unwrap())),
+                        );
+                        if status_entry.status().contains(git2::Status::INDEX_RENAMED) {
+                            if let Some(old_path) = delta.as_ref().and_then(|d| d.old_file().path())
+                            {
+                                renames.insert(path.clone(), old_path.to_path_buf());
+                            }
+                        }
+                        let elem = (path, GitFileStatus::new(status_entry.status()));
                         debug!("{:?}", elem);
                         statuses.push(elem);
                     }
@@ -65,6 +222,7 @@ impl GitCache {
 
             GitCache {
                 statuses,
+                renames,
                 _cached_dir: Some(cachedir),
             }
         } else {
@@ -76,31 +234,75 @@ impl GitCache {
     pub fn empty() -> Self {
         GitCache {
             statuses: Vec::new(),
+            renames: HashMap::new(),
             _cached_dir: None,
         }
     }
+}
 
-    pub fn get(&self, filepath: &PathBuf, is_directory: bool) -> GitFileStatus {
-        debug!("Look for [recurse={}] {:?}", is_directory, filepath);
+impl GitStatusProvider for GitCache {
+    fn statuses(&self) -> &[(PathBuf, GitFileStatus)] {
+        &self.statuses
+    }
 
-        if is_directory {
-            self.statuses
-                .iter()
-                .filter(|&x| x.0.starts_with(filepath))
-                .inspect(|&x| debug!("\t{:?}", x.0))
-                .map(|x| GitFileStatus::new(x.1))
-                .fold(GitFileStatus::default(), |acc, x| GitFileStatus {
-                    index: std::cmp::max(acc.index, x.index),
-                    workdir: std::cmp::max(acc.workdir, x.workdir),
-                })
-        } else {
-            self.statuses
-                .iter()
-                .find(|&x| filepath == &x.0)
-                .map(|e| GitFileStatus::new(e.1))
-                .unwrap_or_default()
+    fn renamed_from(&self, filepath: &Path) -> Option<&Path> {
+        self.renames.get(filepath).map(PathBuf::as_path)
+    }
+}
+
+/// The [GitCache]s of the repositories met while listing, keyed by their workdir. The statuses
+/// of a repository are read once, however many of its paths are listed.
+#[derive(Default)]
+pub struct GitCaches {
+    caches: HashMap<PathBuf, Rc<GitCache>>,
+    detect_renames: bool,
+}
+
+impl GitCaches {
+    /// The caches of the repositories, which find the files renamed in the index when
+    /// `detect_renames` is set. The detection compares the content of the files added and
+    /// deleted, so it is left off unless the renames are shown.
+    pub fn new(detect_renames: bool) -> Self {
+        Self {
+            caches: HashMap::new(),
+            detect_renames,
         }
     }
+
+    /// The cache of the repository containing `path`, which is empty outside of a repository.
+    pub fn get(&mut self, path: &Path) -> Rc<GitCache> {
+        let repo = match GitCache::discover(path) {
+            Some(repo) => repo,
+            None => return Rc::new(GitCache::empty()),
+        };
+        let workdir = match repo.workdir() {
+            Some(workdir) => workdir.to_path_buf(),
+            None => {
+                debug!("No workdir");
+                return Rc::new(GitCache::empty());
+            }
+        };
+
+        let detect_renames = self.detect_renames;
+        self.caches
+            .entry(workdir)
+            .or_insert_with(|| Rc::new(GitCache::from_repository(&repo, path, detect_renames)))
+            .clone()
+    }
+}
+
+/// A [GitStatusProvider] serving fixed statuses, to test the code using them without a
+/// repository.
+#[cfg(test)]
+pub struct MockGitStatusProvider {
+    pub statuses: Vec<(PathBuf, GitFileStatus)>,
+}
+
+#[cfg(test)]
+impl GitStatusProvider for MockGitStatusProvider {
+    fn statuses(&self) -> &[(PathBuf, GitFileStatus)] {
+        &self.statuses
+    }
 }
 
 #[cfg(test)]
@@ -120,6 +322,159 @@ mod tests {
         assert!(GitStatus::Unmodified < GitStatus::Conflicted);
     }
 
+    fn status(index: GitStatus, workdir: GitStatus) -> GitFileStatus {
+        GitFileStatus { index, workdir }
+    }
+
+    fn mock() -> MockGitStatusProvider {
+        MockGitStatusProvider {
+            statuses: vec![
+                (
+                    PathBuf::from("/repo/d1/f1"),
+                    status(GitStatus::NewInIndex, GitStatus::Unmodified),
+                ),
+                (
+                    PathBuf::from("/repo/d1/f2"),
+                    status(GitStatus::Unmodified, GitStatus::Modified),
+                ),
+                (
+                    PathBuf::from("/repo/d1/sub/f3"),
+                    status(GitStatus::Unmodified, GitStatus::Conflicted),
+                ),
+                (
+                    PathBuf::from("/repo/d10/f4"),
+                    status(GitStatus::Deleted, GitStatus::Unmodified),
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_provider_file_status() {
+        let provider = mock();
+        assert_eq!(
+            status(GitStatus::Unmodified, GitStatus::Modified),
+            provider.get(Path::new("/repo/d1/f2"), false)
+        );
+        assert_eq!(
+            GitFileStatus::default(),
+            provider.get(Path::new("/repo/d1/unknown"), false)
+        );
+    }
+
+    #[test]
+    fn test_provider_directory_aggregation() {
+        let provider = mock();
+        assert_eq!(
+            status(GitStatus::NewInIndex, GitStatus::Conflicted),
+            provider.get(Path::new("/repo/d1"), true)
+        );
+        assert_eq!(
+            status(GitStatus::Unmodified, GitStatus::Conflicted),
+            provider.get(Path::new("/repo/d1/sub"), true)
+        );
+        // A directory only contains the paths below it, not the ones sharing its name as prefix.
+        assert_eq!(
+            status(GitStatus::Deleted, GitStatus::Unmodified),
+            provider.get(Path::new("/repo/d10"), true)
+        );
+        assert_eq!(
+            GitFileStatus::default(),
+            provider.get(Path::new("/other"), true)
+        );
+    }
+
+    #[test]
+    fn test_provider_untracked_count() {
+        let provider = MockGitStatusProvider {
+            statuses: vec![
+                (
+                    PathBuf::from("/repo/new/f1"),
+                    status(GitStatus::Unmodified, GitStatus::NewInWorkdir),
+                ),
+                (
+                    PathBuf::from("/repo/new/sub/f2"),
+                    status(GitStatus::Unmodified, GitStatus::NewInWorkdir),
+                ),
+                (
+                    PathBuf::from("/repo/new/f3"),
+                    status(GitStatus::NewInIndex, GitStatus::Unmodified),
+                ),
+                (
+                    PathBuf::from("/repo/newer/f4"),
+                    status(GitStatus::Unmodified, GitStatus::NewInWorkdir),
+                ),
+            ],
+        };
+        assert_eq!(2, provider.untracked_count(Path::new("/repo/new")));
+        assert_eq!(1, provider.untracked_count(Path::new("/repo/new/sub")));
+        assert_eq!(0, provider.untracked_count(Path::new("/other")));
+    }
+
+    #[test]
+    fn test_provider_change_counts() {
+        let provider = mock();
+        assert_eq!((1, 2), provider.change_counts(Path::new("/repo/d1")));
+        assert_eq!((0, 1), provider.change_counts(Path::new("/repo/d1/sub")));
+        assert_eq!((1, 0), provider.change_counts(Path::new("/repo/d10")));
+        assert_eq!((0, 0), provider.change_counts(Path::new("/other")));
+    }
+
+    #[test]
+    fn test_provider_statuses_in_listing() {
+        let dir = TempDir::new().unwrap();
+        dir.child("d1/f1").touch().unwrap();
+        dir.child("f2").touch().unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        let provider = MockGitStatusProvider {
+            statuses: vec![(
+                root.join("d1/f1"),
+                status(GitStatus::NewInIndex, GitStatus::Unmodified),
+            )],
+        };
+
+        let meta = crate::meta::Meta::from_path(&root, false).unwrap();
+        let content = meta
+            .recurse_into(
+                1,
+                &crate::flags::Flags::default(),
+                Some(&provider),
+                &crate::meta::MemoryBudget::new(None),
+            )
+            .unwrap()
+            .unwrap();
+
+        for entry in content {
+            let expected = match entry.name.file_name() {
+                "d1" => status(GitStatus::NewInIndex, GitStatus::Unmodified),
+                _ => GitFileStatus::default(),
+            };
+            assert_eq!(
+                Some(expected),
+                entry.git_status,
+                "Invalid status for {:?}",
+                entry.path
+            );
+        }
+    }
+
+    #[test]
+    fn test_caches_shared_per_repository() {
+        let (root, _repo) = repo_init();
+        root.child("d1/f1").touch().unwrap();
+        root.child("d2/f2").touch().unwrap();
+        let outside = TempDir::new().unwrap();
+
+        let mut caches = GitCaches::default();
+        let d1 = caches.get(&root.path().join("d1"));
+        let d2 = caches.get(&root.path().join("d2"));
+
+        assert!(Rc::ptr_eq(&d1, &d2));
+        assert_eq!(2, d1.statuses().len());
+        assert!(caches.get(outside.path()).statuses().is_empty());
+        assert_eq!(1, caches.caches.len());
+    }
+
     macro_rules! t {
         ($e:expr) => {
             match $e {
@@ -147,6 +502,107 @@ mod tests {
         (td, repo)
     }
 
+    #[test]
+    fn test_open_git_dir() {
+        let (root, _repo) = repo_init();
+        let outside = TempDir::new().unwrap();
+
+        let repo =
+            GitCache::open(outside.path(), Some(&root.path().join(".git")), None, None).unwrap();
+        assert_eq!(
+            fs::canonicalize(root.path()).unwrap(),
+            fs::canonicalize(repo.workdir().unwrap()).unwrap()
+        );
+
+        let repo = GitCache::open(root.path(), None, Some(outside.path()), None).unwrap();
+        assert_eq!(
+            fs::canonicalize(outside.path()).unwrap(),
+            fs::canonicalize(repo.workdir().unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_open_index_file() {
+        let (root, repo) = repo_init();
+        root.child("staged").touch().unwrap();
+        let index_file = root.path().join(".git/tmp-index");
+        let staging = GitCache::open(root.path(), None, None, Some(&index_file)).unwrap();
+        let mut index = t!(staging.index());
+        t!(index.read_tree(&t!(t!(repo.head()).peel_to_tree())));
+        t!(index.add_path(Path::new("staged")));
+        t!(index.write());
+
+        let staged = fs::canonicalize(root.path()).unwrap().join("staged");
+        let cache = GitCache::from_repository(&repo, root.path(), false);
+        assert_eq!(GitStatus::NewInWorkdir, cache.get(&staged, false).workdir);
+
+        let repo = GitCache::open(root.path(), None, None, Some(&index_file)).unwrap();
+        let cache = GitCache::from_repository(&repo, root.path(), false);
+        assert_eq!(
+            GitFileStatus {
+                index: GitStatus::NewInIndex,
+                workdir: GitStatus::Unmodified
+            },
+            cache.get(&staged, false)
+        );
+    }
+
+    #[test]
+    fn test_bare_repository_with_work_tree() {
+        let dir = TempDir::new().unwrap();
+        let work_tree = dir.child("home");
+        work_tree.child("tracked").touch().unwrap();
+        work_tree.child("untracked").touch().unwrap();
+        let bare = t!(Repository::init_bare(dir.path().join("dotfiles.git")));
+        t!(t!(bare.config()).set_str("core.worktree", work_tree.path().to_str().unwrap()));
+
+        let repo = GitCache::open(work_tree.path(), Some(bare.path()), None, None).unwrap();
+        let mut index = t!(repo.index());
+        t!(index.add_path(Path::new("tracked")));
+        t!(index.write());
+
+        let cache = GitCache::from_repository(&repo, work_tree.path(), false);
+        let root = fs::canonicalize(work_tree.path()).unwrap();
+        assert_eq!(
+            GitFileStatus {
+                index: GitStatus::NewInIndex,
+                workdir: GitStatus::Unmodified
+            },
+            cache.get(&root.join("tracked"), false)
+        );
+        assert_eq!(
+            GitFileStatus::default(),
+            cache.get(&root.join("untracked"), false)
+        );
+    }
+
+    #[test]
+    fn test_renamed_from() {
+        let (root, repo) = repo_init();
+        root.child("d1/old").write_str("content").unwrap();
+        let mut index = t!(repo.index());
+        t!(index.add_path(Path::new("d1/old")));
+        commit(&repo, &mut index, "Add old");
+
+        t!(fs::rename(
+            root.path().join("d1/old"),
+            root.path().join("new")
+        ));
+        t!(index.remove_path(Path::new("d1/old")));
+        t!(index.add_path(Path::new("new")));
+        t!(index.write());
+
+        let cache = GitCaches::default().get(root.path());
+        let new = fs::canonicalize(root.path()).unwrap().join("new");
+        assert_eq!(None, cache.renamed_from(&new));
+        assert_eq!(GitStatus::NewInIndex, cache.get(&new, false).index);
+
+        let cache = GitCaches::new(true).get(root.path());
+        let new = fs::canonicalize(root.path()).unwrap().join("new");
+        assert_eq!(Some(Path::new("d1/old")), cache.renamed_from(&new));
+        assert_eq!(GitStatus::Renamed, cache.get(&new, false).index);
+    }
+
     fn commit(repo: &Repository, index: &mut Index, msg: &str) -> (Oid, Oid) {
         let tree_id = t!(index.write_tree());
         let tree = t!(repo.find_tree(tree_id));
@@ -158,7 +614,7 @@ mod tests {
     }
 
     fn check_cache(root: &Path, statuses: &HashMap<&PathBuf, GitFileStatus>) {
-        let cache = GitCache::new(root);
+        let cache = GitCaches::default().get(root);
         for (&path, status) in statuses.iter() {
             match fs::canonicalize(&root.join(path)) {
                 Ok(filename) => {
@@ -1,4 +1,5 @@
 use std::path::Path;
+use std::rc::Rc;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum GitStatus {
@@ -8,8 +9,19 @@ pub enum GitStatus {
 
 pub struct GitCache;
 
-impl GitCache {
-    pub fn new(_: &Path) -> Self {
+#[derive(Default)]
+pub struct GitCaches {}
+
+impl GitCaches {
+    pub fn new(_: bool) -> Self {
         Self {}
     }
-}
\ No newline at end of file
+
+    pub fn get(&mut self, _: &Path) -> Rc<GitCache> {
+        Rc::new(GitCache)
+    }
+}
+
+pub trait GitStatusProvider {}
+
+impl GitStatusProvider for GitCache {}
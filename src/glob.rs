@@ -0,0 +1,184 @@
+//! The expansion of the glob patterns given as paths, for the shells which pass the patterns
+//! matching no file as they are, and for the ones which do not expand them at all.
+
+use crate::core::ExitCode;
+use crate::flags::{Display, Flags};
+use crate::print_error;
+
+use globset::{GlobBuilder, GlobMatcher};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Replaces the glob patterns of the `paths` with the paths they match, sorted. The paths which
+/// exist are kept as they are, even if they look like patterns. A pattern matching no file is
+/// reported as an error, unless the no-glob-error flag is set, and an invalid pattern always is.
+pub fn expand(paths: Vec<PathBuf>, flags: &Flags) -> Vec<PathBuf> {
+    let mut expanded = Vec::with_capacity(paths.len());
+    for path in paths {
+        let pattern = match path.to_str() {
+            Some(pattern) if is_pattern(pattern) && path.symlink_metadata().is_err() => pattern,
+            _ => {
+                expanded.push(path);
+                continue;
+            }
+        };
+
+        match matches(pattern, flags) {
+            Ok(matches) if matches.is_empty() => {
+                if !flags.no_glob_error.0 {
+                    print_error!("{}: no match.", pattern);
+                    ExitCode::MajorIssue.report();
+                }
+            }
+            Ok(matches) => expanded.extend(matches),
+            Err(err) => {
+                print_error!("{}: {}.", pattern, err);
+                ExitCode::MajorIssue.report();
+            }
+        }
+    }
+    expanded
+}
+
+/// Whether `text` holds one of the special characters of the glob patterns.
+fn is_pattern(text: &str) -> bool {
+    text.contains(&['*', '?', '[', '{'][..])
+}
+
+/// The paths matching the `pattern`, sorted. Only the directories found under the leading
+/// components without special characters are read, down to as many levels as the pattern has
+/// components left, or all of them when one holds `**`. The hidden files are only matched when
+/// they are displayed, or when a component of the pattern starts with a dot.
+fn matches(pattern: &str, flags: &Flags) -> Result<Vec<PathBuf>, globset::Error> {
+    let matcher = GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()?
+        .compile_matcher();
+
+    let mut base = PathBuf::new();
+    let mut rest = Vec::new();
+    for component in Path::new(pattern).components() {
+        let text = component.as_os_str().to_string_lossy();
+        if rest.is_empty() && !is_pattern(&text) {
+            base.push(component);
+        } else {
+            rest.push(text.to_string());
+        }
+    }
+    let depth = if rest.iter().any(|component| component.contains("**")) {
+        usize::MAX
+    } else {
+        rest.len()
+    };
+    let hidden = flags.display != Display::VisibleOnly
+        || rest.iter().any(|component| component.starts_with('.'));
+
+    let mut matches = Vec::new();
+    walk(&base, depth, hidden, &matcher, &mut matches);
+    matches.sort();
+    Ok(matches)
+}
+
+/// Pushes the entries of `dir` matched by the `matcher`, and the ones of its sub directories up
+/// to `depth` levels down, to `matches`. The symlinks to directories are not followed.
+fn walk(dir: &Path, depth: usize, hidden: bool, matcher: &GlobMatcher, matches: &mut Vec<PathBuf>) {
+    let reading = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let entries = match fs::read_dir(reading) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if !hidden && name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = dir.join(&name);
+        if matcher.is_match(&path) {
+            matches.push(path.clone());
+        }
+        if depth > 1 && entry.file_type().map(|kind| kind.is_dir()).unwrap_or(false) {
+            walk(&path, depth - 1, hidden, matcher, matches);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{expand, is_pattern};
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Flags;
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    fn flags(args: &[&str]) -> Flags {
+        let argv = [&["lsd", "--glob"], args].concat();
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        Flags::configure_from(&matches, &Config::with_none()).unwrap()
+    }
+
+    #[test]
+    fn test_is_pattern() {
+        assert!(is_pattern("*.rs"));
+        assert!(is_pattern("file?.txt"));
+        assert!(is_pattern("[ab].md"));
+        assert!(is_pattern("{a,b}"));
+        assert!(!is_pattern("src/main.rs"));
+    }
+
+    #[test]
+    fn test_expand() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        fs::create_dir_all(tmp_dir.path().join("src/sub")).unwrap();
+        for file in &[
+            "b.rs",
+            "a.rs",
+            ".hidden.rs",
+            "README",
+            "src/lib.rs",
+            "src/sub/mod.rs",
+        ] {
+            fs::write(tmp_dir.path().join(file), "").unwrap();
+        }
+        let dir = tmp_dir.path().to_str().unwrap();
+        let expand = |pattern: &str, args: &[&str]| {
+            expand(
+                vec![PathBuf::from(format!("{}/{}", dir, pattern))],
+                &flags(args),
+            )
+        };
+
+        assert_eq!(
+            vec![tmp_dir.path().join("a.rs"), tmp_dir.path().join("b.rs")],
+            expand("*.rs", &[])
+        );
+        assert_eq!(3, expand("*.rs", &["--all"]).len());
+        assert_eq!(vec![tmp_dir.path().join(".hidden.rs")], expand(".*", &[]));
+        assert_eq!(
+            vec![tmp_dir.path().join("src/lib.rs")],
+            expand("*/*.rs", &[])
+        );
+        assert_eq!(4, expand("**/*.rs", &[]).len());
+        assert_eq!(Vec::<PathBuf>::new(), expand("*.md", &[]));
+        assert_eq!(Vec::<PathBuf>::new(), expand("*.md", &["--no-glob-error"]));
+    }
+
+    #[test]
+    fn test_expand_existing() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let literal = tmp_dir.path().join("[literal]");
+        fs::write(&literal, "").unwrap();
+        let missing = tmp_dir.path().join("missing");
+
+        assert_eq!(
+            vec![literal.clone(), missing.clone()],
+            expand(vec![literal, missing], &flags(&[]))
+        );
+    }
+}
@@ -1,10 +1,11 @@
+use crate::meta::name::find_by_suffix;
 use crate::meta::{FileType, Name};
 use std::collections::HashMap;
 
 pub struct Icons {
     display_icons: bool,
     icons_by_name: HashMap<&'static str, &'static str>,
-    icons_by_extension: HashMap<&'static str, &'static str>,
+    icons_by_extension: HashMap<String, String>,
     default_folder_icon: &'static str,
     default_file_icon: &'static str,
     icon_separator: String,
@@ -34,7 +35,10 @@ impl Icons {
         ) = if theme == Theme::Fancy {
             (
                 Self::get_default_icons_by_name(),
-                Self::get_default_icons_by_extension(),
+                Self::get_default_icons_by_extension()
+                    .into_iter()
+                    .map(|(ext, icon)| (ext.to_string(), icon.to_string()))
+                    .collect(),
                 "\u{f016}", // 
                 "\u{f115}", // 
             )
@@ -59,6 +63,35 @@ impl Icons {
         }
     }
 
+    /// Shows the git statuses with the configured `symbols`.
+    #[cfg(feature = "git")]
+    pub fn with_git_status(self, symbols: &crate::flags::GitStatusIcons) -> Self {
+        Self {
+            git_icons: self.git_icons.with_symbols(symbols.clone()),
+            ..self
+        }
+    }
+
+    #[cfg(not(feature = "git"))]
+    pub fn with_git_status(self, _: &crate::flags::GitStatusIcons) -> Self {
+        self
+    }
+
+    /// Uses the icons configured for the extensions in `extensions`, before the ones of the
+    /// theme.
+    pub fn with_extensions(mut self, extensions: &crate::flags::Extensions) -> Self {
+        for (ext, icon) in extensions.icons() {
+            self.icons_by_extension
+                .insert(ext.to_string(), icon.to_string());
+        }
+        self
+    }
+
+    /// Whether no icon is displayed.
+    pub fn is_plain(&self) -> bool {
+        !self.display_icons
+    }
+
     pub fn get(&self, name: &Name) -> String {
         if !self.display_icons {
             return String::new();
@@ -89,11 +122,8 @@ impl Icons {
         {
             // Use the known names.
             icon
-        } else if let Some(icon) = name.extension().and_then(|extension| {
-            self.icons_by_extension
-                .get(extension.to_lowercase().as_str())
-        }) {
-            // Use the known extensions.
+        } else if let Some(icon) = find_by_suffix(name.file_name(), &self.icons_by_extension) {
+            // Use the known extensions, the longest one first.
             icon
         } else {
             // Use the default icons.
@@ -314,7 +344,9 @@ impl Icons {
         m.insert("svg", "\u{f1c5}"); // ""
         m.insert("swift", "\u{e755}"); // ""
         m.insert("tar", "\u{f410}"); // ""
+        m.insert("tbz2", "\u{f410}"); // ""
         m.insert("tex", "\u{e600}"); // ""
+        m.insert("tgz", "\u{f410}"); // ""
         m.insert("tiff", "\u{f1c5}"); // ""
         m.insert("ts", "\u{e628}"); // ""
         m.insert("tsx", "\u{e7ba}"); // ""
@@ -322,6 +354,7 @@ impl Icons {
         m.insert("ttf", "\u{f031}"); // ""
         m.insert("twig", "\u{e61c}"); // ""
         m.insert("txt", "\u{f15c}"); // ""
+        m.insert("txz", "\u{f410}"); // ""
         m.insert("video", "\u{f03d}"); // ""
         m.insert("vim", "\u{e62b}"); // ""
         m.insert("vlc", "\u{f910}"); // "蘿"
@@ -346,6 +379,7 @@ impl Icons {
         m.insert("zsh", "\u{f489}"); // ""
         m.insert("zsh-theme", "\u{f489}"); // ""
         m.insert("zshrc", "\u{f489}"); // ""
+        m.insert("zst", "\u{f410}"); // ""
 
         m
     }
@@ -0,0 +1,355 @@
+//! The detail panel printed by `--info` for a single file, as a colored `stat` with the icons of
+//! the listing: a row for each of its metadata, of its dates, of its extended attributes, and
+//! for its git status, the chain of its symlinks and its checksum when they apply.
+
+use crate::checksum;
+use crate::color::{Colors, Elem};
+use crate::core::ExitCode;
+use crate::flags::{DateFlag, Flags};
+use crate::icon::Icons;
+use crate::meta::name::DisplayOption;
+use crate::meta::{Date, FileType, Filesystem, Kind, Meta};
+use crate::print_error;
+
+use std::fs::{self, Metadata};
+use std::path::Path;
+use std::time::SystemTime;
+use unicode_width::UnicodeWidthStr;
+
+/// Renders the panel of the file of `meta`, one labelled row per line, the attributes which can
+/// not be read being left out. The checksum is only computed when the checksum flag is set.
+pub fn info(meta: &Meta, flags: &Flags, colors: &Colors, icons: &Icons) -> String {
+    let mut rows: Vec<(&str, String)> = Vec::new();
+
+    let name =
+        meta.name
+            .render_truncated(colors, icons, &DisplayOption::FileName, None, flags.raw.0);
+    rows.push(("Name", format!("{}{}", name, meta.indicator.render(flags))));
+    // The directory of a link is resolved, not the link itself.
+    let path = match (meta.path.parent(), meta.path.file_name()) {
+        (Some(parent), Some(name)) if parent.as_os_str().is_empty() => {
+            fs::canonicalize(".").map(|parent| parent.join(name))
+        }
+        (Some(parent), Some(name)) => fs::canonicalize(parent).map(|parent| parent.join(name)),
+        _ => fs::canonicalize(&meta.path),
+    }
+    .unwrap_or_else(|_| meta.path.clone());
+    rows.push(("Path", path.display().to_string()));
+    if let FileType::SymLink { .. } = meta.file_type {
+        let chain = meta.symlink.render_chain(&meta.path, colors, flags);
+        rows.push(("Links to", chain.to_string()));
+    }
+    rows.push(("Kind", Kind::new(meta).render(colors).to_string()));
+
+    let bytes = |size: u64| colors.colorize(format!("({} bytes)", size), &Elem::Older);
+    match meta.file_type {
+        // The size of a device is its major and minor numbers, as in the listing.
+        FileType::BlockDevice | FileType::CharDevice => {
+            rows.push(("Device", meta.size.render(colors, flags, 0).to_string()));
+        }
+        _ => rows.push((
+            "Size",
+            format!(
+                "{} {}",
+                meta.size.render(colors, flags, 0),
+                bytes(meta.size.get_bytes())
+            ),
+        )),
+    }
+    rows.push((
+        "Allocated",
+        format!(
+            "{} {}",
+            meta.allocated.render(colors, flags, 0),
+            bytes(meta.allocated.get_bytes())
+        ),
+    ));
+    let mut permissions = format!(
+        "{}{} ({:04o})",
+        meta.file_type.render(colors),
+        meta.permissions.render(colors),
+        meta.permissions.mode()
+    );
+    if flags.verbose_permissions.0 {
+        permissions = format!("{} {}", permissions, meta.permissions.summary());
+    }
+    rows.push(("Permissions", permissions));
+    let id = |id: Option<u32>| id.map(|id| format!(" ({})", id)).unwrap_or_default();
+    rows.push((
+        "User",
+        format!(
+            "{}{}",
+            meta.owner.render_user(colors, flags),
+            id(meta.owner.uid())
+        ),
+    ));
+    rows.push((
+        "Group",
+        format!(
+            "{}{}",
+            meta.owner.render_group(colors, flags),
+            id(meta.owner.gid())
+        ),
+    ));
+    rows.push(("Inode", meta.inode.render(colors).to_string()));
+    rows.push(("Links", meta.links.render(colors).to_string()));
+    rows.push((
+        "Filesystem",
+        Filesystem::new(&meta.path).render(colors).to_string(),
+    ));
+
+    // The metadata of the link itself is read for a link, as for the listing.
+    let metadata = match meta.file_type {
+        FileType::SymLink { .. } => meta.path.symlink_metadata(),
+        _ => meta.path.metadata(),
+    }
+    .ok();
+
+    let date_row = |date: Date| {
+        if flags.date == DateFlag::Relative {
+            date.render(colors, flags).to_string()
+        } else {
+            let relative = colors.colorize(format!("({})", date.relative_string()), &Elem::Older);
+            format!("{} {}", date.render(colors, flags), relative)
+        }
+    };
+    rows.push(("Modified", date_row(meta.date.clone())));
+    if let Some(metadata) = &metadata {
+        let dates = [
+            ("Accessed", metadata.accessed().ok()),
+            ("Changed", changed(metadata)),
+            ("Created", metadata.created().ok()),
+        ];
+        for &(label, time) in dates.iter() {
+            if let Some(time) = time {
+                rows.push((label, date_row(Date::from(time))));
+            }
+        }
+    }
+
+    #[cfg(feature = "git")]
+    {
+        if let Some(status) = meta
+            .git_status
+            .filter(|status| *status != Default::default())
+        {
+            let mut row = status
+                .render(colors, icons, flags.git_status_style, None, None)
+                .to_string();
+            if let Some(old_path) = &meta.git_renamed_from {
+                let annotation = format!(" \u{2190} {}", old_path.display());
+                row += &colors
+                    .colorize(annotation, &Elem::GitRenamedFrom)
+                    .to_string();
+            }
+            rows.push(("Git", row));
+        }
+    }
+
+    let follow = !matches!(meta.file_type, FileType::SymLink { .. });
+    for (idx, (name, value)) in xattrs(&meta.path, follow).into_iter().enumerate() {
+        let label = if idx == 0 { "Attributes" } else { "" };
+        rows.push((label, format!("{} = {}", name, xattr_text(&value))));
+    }
+
+    if flags.checksum.0 {
+        if let FileType::File { .. } = meta.file_type {
+            match checksum::sha256(&meta.path) {
+                Ok(digest) => rows.push(("SHA-256", digest)),
+                Err(err) => {
+                    print_error!("{}: {}.", meta.path.display(), err);
+                    ExitCode::MajorIssue.report();
+                }
+            }
+        }
+    }
+
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.width())
+        .max()
+        .unwrap_or(0);
+    let mut output = String::new();
+    for (label, value) in rows {
+        let padding = " ".repeat(width - label.width() + 2);
+        output += &colors
+            .colorize(label.to_string(), &Elem::Header)
+            .to_string();
+        output += &padding;
+        output += &value;
+        output.push('\n');
+    }
+    output
+}
+
+/// The date the metadata of the file last changed, only known on unix.
+#[cfg(unix)]
+fn changed(metadata: &Metadata) -> Option<SystemTime> {
+    use std::os::unix::fs::MetadataExt;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    if metadata.ctime() < 0 {
+        return None;
+    }
+    let since_epoch = Duration::new(metadata.ctime() as u64, metadata.ctime_nsec() as u32);
+    UNIX_EPOCH.checked_add(since_epoch)
+}
+
+#[cfg(windows)]
+fn changed(_: &Metadata) -> Option<SystemTime> {
+    None
+}
+
+/// The extended attributes of the file at `path`, or of the link itself unless `follow` is set,
+/// by name. The attributes are only read on Linux.
+#[cfg(target_os = "linux")]
+fn xattrs(path: &Path, follow: bool) -> Vec<(String, Vec<u8>)> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = match CString::new(path.as_os_str().as_bytes()) {
+        Ok(path) => path,
+        Err(_) => return Vec::new(),
+    };
+    // Both calls return the size needed when given an empty buffer, then fill it.
+    let read = |buffer: &mut Vec<u8>, fill: &dyn Fn(*mut libc::c_void, usize) -> isize| {
+        let size = fill(std::ptr::null_mut(), 0);
+        if size < 0 {
+            return false;
+        }
+        buffer.resize(size as usize, 0);
+        let size = fill(buffer.as_mut_ptr() as *mut libc::c_void, buffer.len());
+        if size < 0 {
+            return false;
+        }
+        buffer.truncate(size as usize);
+        true
+    };
+
+    let mut names = Vec::new();
+    let listed = read(&mut names, &|buffer, size| unsafe {
+        if follow {
+            libc::listxattr(path.as_ptr(), buffer as *mut libc::c_char, size)
+        } else {
+            libc::llistxattr(path.as_ptr(), buffer as *mut libc::c_char, size)
+        }
+    });
+    if !listed {
+        return Vec::new();
+    }
+
+    let mut attributes = Vec::new();
+    for name in names
+        .split(|&byte| byte == 0)
+        .filter(|name| !name.is_empty())
+    {
+        let c_name = match CString::new(name) {
+            Ok(c_name) => c_name,
+            Err(_) => continue,
+        };
+        let mut value = Vec::new();
+        let got = read(&mut value, &|buffer, size| unsafe {
+            if follow {
+                libc::getxattr(path.as_ptr(), c_name.as_ptr(), buffer, size)
+            } else {
+                libc::lgetxattr(path.as_ptr(), c_name.as_ptr(), buffer, size)
+            }
+        });
+        if got {
+            attributes.push((String::from_utf8_lossy(name).to_string(), value));
+        }
+    }
+    attributes
+}
+
+#[cfg(not(target_os = "linux"))]
+fn xattrs(_: &Path, _: bool) -> Vec<(String, Vec<u8>)> {
+    Vec::new()
+}
+
+/// The `value` of an extended attribute as text when it is printable, without its trailing nul
+/// bytes, or else its size.
+fn xattr_text(value: &[u8]) -> String {
+    let end = value
+        .iter()
+        .rposition(|&byte| byte != 0)
+        .map_or(0, |idx| idx + 1);
+    match std::str::from_utf8(&value[..end]) {
+        Ok(text) if !text.chars().any(char::is_control) => format!("\"{}\"", text),
+        _ => format!("({} bytes)", value.len()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{info, xattr_text};
+    use crate::app;
+    use crate::color::{Colors, Theme};
+    use crate::config_file::Config;
+    use crate::flags::Flags;
+    use crate::icon::{self, Icons};
+    use crate::meta::Meta;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn panel(meta: &Meta, args: &[&str]) -> String {
+        let argv = [&["lsd", "--info"], args].concat();
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+        info(
+            meta,
+            &flags,
+            &Colors::new(Theme::NoColor),
+            &Icons::new(icon::Theme::NoIcon, " ".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_info() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("file.txt");
+        fs::write(&path, "abc").unwrap();
+        let meta = Meta::from_path(&path, false).unwrap();
+
+        let panel = panel(&meta, &[]);
+        assert!(panel.starts_with("Name         file.txt\n"), "{}", panel);
+        assert!(
+            panel.contains("\nSize         3 B (3 bytes)\n"),
+            "{}",
+            panel
+        );
+        assert!(panel.contains("\nModified     "), "{}", panel);
+        assert!(!panel.contains("SHA-256"), "{}", panel);
+    }
+
+    #[test]
+    fn test_info_checksum() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("file.txt");
+        fs::write(&path, "abc").unwrap();
+        let meta = Meta::from_path(&path, false).unwrap();
+
+        assert!(panel(&meta, &["--checksum"]).ends_with(
+            "\nSHA-256      ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad\n"
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_info_symlink() {
+        use std::os::unix::fs::symlink;
+
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        fs::write(tmp_dir.path().join("target"), "").unwrap();
+        symlink("target", tmp_dir.path().join("link")).unwrap();
+        let meta = Meta::from_path(&tmp_dir.path().join("link"), false).unwrap();
+
+        assert!(panel(&meta, &[]).contains("\nLinks to     target\n"));
+    }
+
+    #[test]
+    fn test_xattr_text() {
+        assert_eq!("\"text/plain\"", xattr_text(b"text/plain\0"));
+        assert_eq!("(3 bytes)", xattr_text(&[1, 2, 3]));
+    }
+}
@@ -0,0 +1,64 @@
+//! The shell code printed by `--init`, listing each directory entered by the shell. The listings
+//! are limited to the directories having at most [MAX_ENTRIES] entries, by the max-entries flag,
+//! so entering a huge directory stays fast, whatever the shell.
+
+/// The number of entries past which the entered directories are not listed.
+pub const MAX_ENTRIES: usize = 1000;
+
+/// The code to evaluate at the startup of the `shell`, one of bash, zsh and fish, so that the
+/// directory it enters is listed, by `cd` as by any other command.
+pub fn script(shell: &str) -> String {
+    let hook = match shell {
+        "zsh" => ZSH,
+        "fish" => FISH,
+        _ => BASH,
+    };
+    hook.replace("{max_entries}", &MAX_ENTRIES.to_string())
+}
+
+/// The bash code, checking the directory before each prompt, as bash has no hook for its changes.
+const BASH: &str = r#"# Added to ~/.bashrc with: eval "$(lsd --init bash)"
+__lsd_list_pwd() {
+    if [ "${__lsd_pwd-$PWD}" != "$PWD" ]; then
+        command lsd --max-entries {max_entries}
+    fi
+    __lsd_pwd=$PWD
+}
+case ";$PROMPT_COMMAND;" in
+    *";__lsd_list_pwd;"*) ;;
+    *) PROMPT_COMMAND="__lsd_list_pwd${PROMPT_COMMAND:+;$PROMPT_COMMAND}" ;;
+esac
+"#;
+
+/// The zsh code, hooked to the directory changes.
+const ZSH: &str = r#"# Added to ~/.zshrc with: eval "$(lsd --init zsh)"
+__lsd_list_pwd() {
+    command lsd --max-entries {max_entries}
+}
+autoload -Uz add-zsh-hook
+add-zsh-hook chpwd __lsd_list_pwd
+"#;
+
+/// The fish code, run when the PWD variable changes, but in the command substitutions.
+const FISH: &str = r#"# Added to ~/.config/fish/config.fish with: lsd --init fish | source
+function __lsd_list_pwd --on-variable PWD
+    status --is-command-substitution; and return
+    command lsd --max-entries {max_entries}
+end
+"#;
+
+#[cfg(test)]
+mod test {
+    use super::{script, MAX_ENTRIES};
+
+    #[test]
+    fn test_script() {
+        let limit = format!("--max-entries {}", MAX_ENTRIES);
+        for shell in &["bash", "zsh", "fish"] {
+            let script = script(shell);
+            assert!(script.contains(&limit), "{}", shell);
+            assert!(!script.contains("{max_entries}"), "{}", shell);
+        }
+        assert!(script("zsh").contains("add-zsh-hook chpwd"));
+    }
+}
@@ -14,7 +14,7 @@ impl log::Log for SimpleLogger {
 
     fn log(&self, record: &Record) {
         if self.enabled(record.metadata()) {
-            println!(
+            eprintln!(
                 "[{:5}][{}] {}",
                 record.level(),
                 record.target(),
@@ -28,11 +28,16 @@ impl log::Log for SimpleLogger {
 
 static LOGGER: &SimpleLogger = &SimpleLogger;
 
+/// Start the logger on the standard error. The warnings are always shown, setting the
+/// `LSD_LOGGER` environment variable shows the debug messages too.
 pub fn init() {
-    if let Ok(value) = std::env::var("LSD_LOGGER") {
-        log::set_logger(LOGGER)
-            .map(|()| log::set_max_level(LevelFilter::Debug))
-            .unwrap();
-        log::info!("Logger started");
-    }
+    let level = if std::env::var_os("LSD_LOGGER").is_some() {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Warn
+    };
+    log::set_logger(LOGGER)
+        .map(|()| log::set_max_level(level))
+        .unwrap();
+    log::info!("Logger started");
 }
@@ -22,29 +22,37 @@ extern crate wild;
 extern crate xdg;
 extern crate yaml_rust;
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "nss"))]
 extern crate users;
 
 #[cfg(windows)]
 extern crate winapi;
 
 mod app;
+mod checksum;
 mod color;
 mod config_file;
 mod core;
+mod diff;
 mod display;
 mod flags;
 #[cfg(feature = "git")]
 mod git;
 #[cfg(not(feature = "git"))]
 mod git_stub;
+mod glob;
 mod icon;
+mod info;
+mod init;
 mod logger;
 mod meta;
+mod readdir;
 mod sort;
+mod stats;
+mod watch;
 
 use crate::config_file::Config;
-use crate::core::Core;
+use crate::core::{Core, ExitCode};
 use crate::flags::Flags;
 use std::path::PathBuf;
 
@@ -96,25 +104,115 @@ macro_rules! print_output {
 
 fn main() {
     logger::init();
-    let matches = app::build().get_matches_from(wild::args_os());
+    let matches = app::build()
+        .get_matches_from_safe(wild::args_os())
+        .unwrap_or_else(|err| exit_with(err));
+
+    if let Some(shell) = matches.value_of("generate-completions") {
+        let shell = shell
+            .parse::<clap::Shell>()
+            .expect("the shell is one of the possible values");
+        app::build().gen_completions_to("lsd", shell, &mut std::io::stdout());
+        return;
+    }
+
+    if let Some(shell) = matches.value_of("init") {
+        print_output!("{}", init::script(shell));
+        return;
+    }
+
+    if let Some(command) = matches.subcommand_name() {
+        check_no_listing_args(&matches, command);
+    }
 
     // input translate glob FILE without single quote into real names
     // for example:
     // * to all files matched
     // '*' remain as '*'
-    let inputs = matches
+    let mut inputs: Vec<PathBuf> = matches
         .values_of("FILE")
         .expect("failed to retrieve cli value")
         .map(PathBuf::from)
         .collect();
+    if let Some(fds) = matches.values_of("fd") {
+        // The current directory is listed by default, but not along the descriptors.
+        if matches.occurrences_of("FILE") == 0 {
+            inputs.clear();
+        }
+        for fd in fds {
+            match readdir::fd_label(fd.parse().expect("the descriptor is validated")) {
+                Ok(path) => inputs.push(path),
+                Err(err) => {
+                    print_error!("--fd {}: {}.", fd, err);
+                    ExitCode::MajorIssue.report();
+                }
+            }
+        }
+    }
 
     let config = if matches.is_present("ignore-config") {
         Config::with_none()
     } else {
-        Config::default()
+        Config::load(matches.value_of("config-file"))
     };
-    let flags = Flags::configure_from(&matches, &config).unwrap_or_else(|err| err.exit());
+    let flags = Flags::configure_from(&matches, &config).unwrap_or_else(|err| exit_with(err));
     let core = Core::new(flags);
 
-    core.run(inputs);
+    match matches.subcommand_matches("diff") {
+        Some(diff) => {
+            let dir = |name| PathBuf::from(diff.value_of(name).expect("the directory is required"));
+            core.diff(&dir("DIR1"), &dir("DIR2"));
+        }
+        None => match matches.subcommand_matches("stats") {
+            Some(stats) => {
+                let dirs: Vec<PathBuf> = stats
+                    .values_of("DIR")
+                    .expect("the directory has a default value")
+                    .map(PathBuf::from)
+                    .collect();
+                let by = stats
+                    .values_of("by")
+                    .and_then(|mut values| values.next_back());
+                let grouping = stats::Grouping::from_arg_str(by.unwrap_or_default());
+                core.stats(&dirs, grouping);
+            }
+            None => core.run(inputs),
+        },
+    }
+    std::process::exit(ExitCode::reported());
+}
+
+/// Exits with an error if the arguments only meaningful to the listing are given along the
+/// `command`, which reads its own paths.
+fn check_no_listing_args(matches: &clap::ArgMatches, command: &str) {
+    let args = [
+        ("FILE", "<FILE>..."),
+        ("fd", "--fd"),
+        ("info", "--info"),
+        ("watch", "--watch"),
+        ("debug-readdir", "--debug-readdir"),
+    ];
+    for (name, usage) in &args {
+        if matches.occurrences_of(name) > 0 {
+            exit_with(clap::Error::with_description(
+                &format!(
+                    "The argument '{}' cannot be used with the {} command",
+                    usage, command
+                ),
+                clap::ErrorKind::ArgumentConflict,
+            ));
+        }
+    }
+}
+
+/// Prints the error of the arguments and exits with the code of an [ExitCode::MajorIssue], or
+/// prints the help or the version and exits successfully.
+fn exit_with(err: clap::Error) -> ! {
+    if err.use_stderr() {
+        use std::io::Write;
+
+        let _ = writeln!(std::io::stderr(), "{}", err.message);
+        std::process::exit(ExitCode::MajorIssue as i32);
+    }
+    err.exit()
 }
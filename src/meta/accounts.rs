@@ -0,0 +1,139 @@
+//! Resolution of the user and group ids to their names.
+//!
+//! The names are looked up through the system name services (NSS) when the "nss" feature is
+//! enabled, which it is by default. They are also read from `/etc/passwd` and `/etc/group`, so a
+//! static build without NSS, or a container without its libraries, still shows the local names.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// A source of user and group names.
+pub trait AccountProvider {
+    /// The name of the user with the `uid`, if known.
+    fn user_name(&self, uid: u32) -> Option<String>;
+
+    /// The name of the group with the `gid`, if known.
+    fn group_name(&self, gid: u32) -> Option<String>;
+}
+
+/// The names known to the system name services, which also cover LDAP or NIS accounts.
+#[cfg(feature = "nss")]
+pub struct SystemAccounts;
+
+#[cfg(feature = "nss")]
+impl AccountProvider for SystemAccounts {
+    fn user_name(&self, uid: u32) -> Option<String> {
+        users::get_user_by_uid(uid).map(|user| user.name().to_string_lossy().to_string())
+    }
+
+    fn group_name(&self, gid: u32) -> Option<String> {
+        users::get_group_by_gid(gid).map(|group| group.name().to_string_lossy().to_string())
+    }
+}
+
+/// The names listed in files of the `/etc/passwd` and `/etc/group` formats.
+pub struct FileAccounts {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+}
+
+impl FileAccounts {
+    /// Reads the `passwd` and `group` files. A file which can not be read provides no name.
+    pub fn from_files(passwd: &Path, group: &Path) -> Self {
+        let parse = |path: &Path| {
+            fs::read_to_string(path)
+                .map(|content| Self::parse(&content))
+                .unwrap_or_default()
+        };
+
+        Self {
+            users: parse(passwd),
+            groups: parse(group),
+        }
+    }
+
+    /// Maps the ids to the names of the lines of a `passwd` or `group` file, which both have
+    /// the name as first field and the id as third one.
+    fn parse(content: &str) -> HashMap<u32, String> {
+        let mut names = HashMap::new();
+
+        for line in content.lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split(':');
+            let name = fields.next();
+            let id = fields.nth(1).and_then(|id| id.parse::<u32>().ok());
+            if let (Some(name), Some(id)) = (name, id) {
+                // The first entry wins, as for the system lookups.
+                names.entry(id).or_insert_with(|| name.to_string());
+            }
+        }
+
+        names
+    }
+}
+
+impl AccountProvider for FileAccounts {
+    fn user_name(&self, uid: u32) -> Option<String> {
+        self.users.get(&uid).cloned()
+    }
+
+    fn group_name(&self, gid: u32) -> Option<String> {
+        self.groups.get(&gid).cloned()
+    }
+}
+
+/// The providers to query in order, until one knows the name.
+pub fn default_providers() -> Vec<Box<dyn AccountProvider>> {
+    vec![
+        #[cfg(feature = "nss")]
+        Box::new(SystemAccounts),
+        Box::new(FileAccounts::from_files(
+            Path::new("/etc/passwd"),
+            Path::new("/etc/group"),
+        )),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AccountProvider, FileAccounts};
+    use assert_fs::prelude::*;
+
+    #[test]
+    fn test_file_accounts() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let passwd = dir.child("passwd");
+        passwd
+            .write_str(
+                "# comment\n\
+                 root:x:0:0:root:/root:/bin/sh\n\
+                 alice:x:1000:1000:Alice:/home/alice:/bin/sh\n\
+                 duplicate:x:1000:1000::/:/bin/false\n\
+                 broken:x:notanid:0::/:\n",
+            )
+            .unwrap();
+        let group = dir.child("group");
+        group.write_str("root:x:0:\nstaff:x:50:alice\n").unwrap();
+
+        let accounts = FileAccounts::from_files(passwd.path(), group.path());
+
+        assert_eq!(Some("root".to_string()), accounts.user_name(0));
+        assert_eq!(Some("alice".to_string()), accounts.user_name(1000));
+        assert_eq!(None, accounts.user_name(1001));
+        assert_eq!(Some("staff".to_string()), accounts.group_name(50));
+        assert_eq!(None, accounts.group_name(1000));
+    }
+
+    #[test]
+    fn test_file_accounts_missing_files() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let accounts =
+            FileAccounts::from_files(&dir.path().join("passwd"), &dir.path().join("group"));
+
+        assert_eq!(None, accounts.user_name(0));
+        assert_eq!(None, accounts.group_name(0));
+    }
+}
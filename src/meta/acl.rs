@@ -0,0 +1,155 @@
+//! Whether the files have an access control list granting more than their permission bits, shown
+//! as a `+` after the permissions as `ls` does. The lists are read from the `system.posix_acl_*`
+//! extended attributes on Linux, and with the `acl_*` functions of the system on macOS and
+//! FreeBSD.
+
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+/// Whether the file at `path`, followed if it is a symlink, has an extended access control list.
+/// The files whose list can not be read are told to have none.
+pub fn has_extended(path: &Path) -> bool {
+    match CString::new(path.as_os_str().as_bytes()) {
+        Ok(path) => has_extended_c(&path),
+        Err(_) => false,
+    }
+}
+
+/// The access list of the file and the default one of the directory, only set beyond the
+/// permission bits, or else the NFSv4 one.
+#[cfg(target_os = "linux")]
+fn has_extended_c(path: &CString) -> bool {
+    let names: &[&[u8]] = &[
+        b"system.posix_acl_access\0",
+        b"system.posix_acl_default\0",
+        b"system.nfs4_acl\0",
+    ];
+    names.iter().any(|name| {
+        let size = unsafe {
+            libc::getxattr(
+                path.as_ptr(),
+                name.as_ptr() as *const libc::c_char,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        size > 0
+    })
+}
+
+#[cfg(target_os = "macos")]
+extern "C" {
+    fn acl_get_file(path: *const libc::c_char, kind: libc::c_int) -> *mut libc::c_void;
+    fn acl_get_entry(
+        acl: *mut libc::c_void,
+        entry_id: libc::c_int,
+        entry: *mut *mut libc::c_void,
+    ) -> libc::c_int;
+    fn acl_free(object: *mut libc::c_void) -> libc::c_int;
+}
+
+/// The extended list of macOS only holds the entries beyond the permission bits.
+#[cfg(target_os = "macos")]
+fn has_extended_c(path: &CString) -> bool {
+    const ACL_TYPE_EXTENDED: libc::c_int = 0x100;
+    const ACL_FIRST_ENTRY: libc::c_int = 0;
+
+    let acl = unsafe { acl_get_file(path.as_ptr(), ACL_TYPE_EXTENDED) };
+    if acl.is_null() {
+        return false;
+    }
+    let mut entry = std::ptr::null_mut();
+    let found = unsafe { acl_get_entry(acl, ACL_FIRST_ENTRY, &mut entry) } == 0;
+    unsafe { acl_free(acl) };
+    found
+}
+
+#[cfg(target_os = "freebsd")]
+extern "C" {
+    fn acl_get_file(path: *const libc::c_char, kind: libc::c_int) -> *mut libc::c_void;
+    fn acl_is_trivial_np(acl: *const libc::c_void, trivial: *mut libc::c_int) -> libc::c_int;
+    fn acl_free(object: *mut libc::c_void) -> libc::c_int;
+}
+
+/// The POSIX.1e access list, or the NFSv4 one on the file systems using them, is trivial when it
+/// only holds the permission bits.
+#[cfg(target_os = "freebsd")]
+fn has_extended_c(path: &CString) -> bool {
+    const ACL_TYPE_ACCESS: libc::c_int = 2;
+    const ACL_TYPE_NFS4: libc::c_int = 4;
+
+    [ACL_TYPE_ACCESS, ACL_TYPE_NFS4].iter().any(|&kind| {
+        let acl = unsafe { acl_get_file(path.as_ptr(), kind) };
+        if acl.is_null() {
+            return false;
+        }
+        let mut trivial = 1;
+        let read = unsafe { acl_is_trivial_np(acl, &mut trivial) } == 0;
+        unsafe { acl_free(acl) };
+        read && trivial == 0
+    })
+}
+
+/// The access control lists are not read on the other systems.
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "freebsd")))]
+fn has_extended_c(_: &CString) -> bool {
+    false
+}
+
+/// Grants the reading of the file at `path` to the user `nobody` through its access list, as
+/// `setfacl -m u:nobody:r` does, returning whether the file system supports them.
+#[cfg(all(test, target_os = "linux"))]
+pub fn grant_read_to_nobody(path: &Path) -> bool {
+    // The version of the list, then its entries: their tag, permissions and id.
+    let mut acl = 2u32.to_le_bytes().to_vec();
+    for &(tag, perm, id) in &[
+        (0x01u16, 6u16, u32::MAX),
+        (0x02, 4, 65534),
+        (0x04, 4, u32::MAX),
+        (0x10, 4, u32::MAX),
+        (0x20, 4, u32::MAX),
+    ] {
+        acl.extend_from_slice(&tag.to_le_bytes());
+        acl.extend_from_slice(&perm.to_le_bytes());
+        acl.extend_from_slice(&id.to_le_bytes());
+    }
+    let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+    let set = unsafe {
+        libc::setxattr(
+            path.as_ptr(),
+            b"system.posix_acl_access\0".as_ptr() as *const libc::c_char,
+            acl.as_ptr() as *const libc::c_void,
+            acl.len(),
+            0,
+        )
+    };
+    set == 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::has_extended;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_has_extended() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file_path = tmp_dir.path().join("file");
+        File::create(&file_path).expect("failed to create file");
+        assert!(!has_extended(&file_path));
+        assert!(!has_extended(&tmp_dir.path().join("missing")));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_has_extended_posix_acl() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file_path = tmp_dir.path().join("file");
+        File::create(&file_path).expect("failed to create file");
+        if super::grant_read_to_nobody(&file_path) {
+            assert!(has_extended(&file_path));
+        }
+    }
+}
@@ -1,8 +1,15 @@
 use crate::color::{ColoredString, Colors, Elem};
 use crate::flags::{DateFlag, Flags};
-use chrono::{DateTime, Duration, Local};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, TimeZone, Utc};
 use chrono_humanize::HumanTime;
 use std::fs::Metadata;
+use std::time::SystemTime;
+use unicode_width::UnicodeWidthStr;
+
+thread_local! {
+    /// The width of the smart dates is only figured out once, from the names of the months.
+    static SMART_WIDTH: usize = smart_width();
+}
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Date(DateTime<Local>);
@@ -17,15 +24,25 @@ impl<'a> From<&'a Metadata> for Date {
     }
 }
 
+impl From<SystemTime> for Date {
+    fn from(time: SystemTime) -> Self {
+        Date(time.into())
+    }
+}
+
 impl Date {
     pub fn render(&self, colors: &Colors, flags: &Flags) -> ColoredString {
         let now = Local::now();
+        let thresholds = &flags.color.date_thresholds;
+        let within = |seconds: u64| self.0 > now - Duration::seconds(seconds as i64);
 
         let elem;
-        if self.0 > now - Duration::hours(1) {
+        if within(thresholds.recent) {
             elem = &Elem::HourOld;
-        } else if self.0 > now - Duration::days(1) {
+        } else if within(thresholds.old) {
             elem = &Elem::DayOld;
+        } else if thresholds.very_old.map(within) == Some(false) {
+            elem = &Elem::VeryOld;
         } else {
             elem = &Elem::Older;
         }
@@ -33,35 +50,151 @@ impl Date {
         colors.colorize(self.date_string(&flags), elem)
     }
 
+    /// The date in the RFC 3339 format, which does not depend on the date flag.
+    pub fn rfc3339(&self) -> String {
+        self.0.to_rfc3339()
+    }
+
+    /// The seconds since the Unix epoch, with their nanoseconds, which does not depend on the date
+    /// flag either.
+    pub fn epoch(&self) -> String {
+        let (seconds, nanos) = (self.0.timestamp(), self.0.timestamp_subsec_nanos());
+        // The nanoseconds are counted forward from the second before the dates prior to 1970.
+        if seconds < 0 && nanos > 0 {
+            format!("-{}.{:09}", -(seconds + 1), 1_000_000_000 - nanos)
+        } else {
+            format!("{}.{:09}", seconds, nanos)
+        }
+    }
+
+    /// How long ago the date is, as in "3 hours ago", which does not depend on the date flag.
+    pub fn relative_string(&self) -> String {
+        format!("{}", HumanTime::from(self.0 - Local::now()))
+    }
+
+    /// The date in the time zone of the [DateTimezone](crate::flags::DateTimezone) flag.
+    fn in_timezone(&self, flags: &Flags) -> DateTime<FixedOffset> {
+        let offset = flags.date_timezone.offset_of(&self.0);
+        self.0.with_timezone(&offset)
+    }
+
     pub fn date_string(&self, flags: &Flags) -> String {
+        let date = self.in_timezone(flags);
         match &flags.date {
-            DateFlag::Date => self.0.format("%c").to_string(),
-            DateFlag::Relative => format!("{}", HumanTime::from(self.0 - Local::now())),
+            DateFlag::Date => date.format("%c").to_string(),
+            DateFlag::Relative => self.relative_string(),
             DateFlag::ISO => {
                 // 365.2425 * 24 * 60 * 60 = 31556952 seconds per year
                 // 15778476 seconds are 6 months
                 if self.0 > Local::now() - Duration::seconds(15_778_476) {
-                    self.0.format("%m-%d %R").to_string()
+                    date.format("%m-%d %R").to_string()
                 } else {
-                    self.0.format("%F").to_string()
+                    date.format("%F").to_string()
                 }
             }
-            DateFlag::Formatted(format) => self.0.format(&format).to_string(),
+            DateFlag::Formatted(format) => date.format(&format).to_string(),
+            DateFlag::Smart => smart_string(&date),
         }
     }
 }
 
+/// The date as precise as it is recent: the time of the day for today, the month and the day for
+/// this year and the year for the older ones, with the names of the locale. They are all padded
+/// to the width of the widest of them, so that the column keeps the same width
+/// whatever the dates listed. Today and this year are the ones of the time zone of `date`.
+fn smart_string(date: &DateTime<FixedOffset>) -> String {
+    let now = Utc::now().with_timezone(date.offset());
+    let format = if date.date() == now.date() {
+        "%R"
+    } else if date.year() == now.year() {
+        "%b %e"
+    } else {
+        "%Y"
+    };
+    let text = format_localized(date, format);
+    let width = SMART_WIDTH.with(|width| *width);
+    " ".repeat(width.saturating_sub(text.width())) + &text
+}
+
+/// The width of the widest smart date, a month name and a day being wider than a time or a
+/// year in most locales.
+fn smart_width() -> usize {
+    let utc = FixedOffset::east(0);
+    let now = Utc::now().with_timezone(&utc);
+    (1..=12)
+        .map(|month| format_localized(&utc.ymd(now.year(), month, 10).and_hms(0, 0, 0), "%b %e"))
+        .chain(vec![format_localized(&now, "%R")])
+        .map(|text| text.width())
+        .max()
+        .unwrap_or(0)
+        .max(4)
+}
+
+#[cfg(unix)]
+extern "C" {
+    // Not exported by the libc crate.
+    fn strftime(
+        s: *mut libc::c_char,
+        max: libc::size_t,
+        format: *const libc::c_char,
+        tm: *const libc::tm,
+    ) -> libc::size_t;
+}
+
+/// Formats `date` with the names of the `LC_TIME` locale (or `LC_ALL`, `LANG`) as `strftime`
+/// does, the process starting with the "C" locale. The date is shown in its own time zone, whose
+/// name is not known.
+#[cfg(unix)]
+fn format_localized(date: &DateTime<FixedOffset>, format: &str) -> String {
+    use std::ffi::CString;
+    use std::sync::Once;
+
+    let format = match CString::new(format) {
+        Ok(format) => format,
+        Err(_) => return String::new(),
+    };
+    // The fields of the date in its time zone are the ones of the shifted time in UTC.
+    let time = (date.timestamp() + i64::from(date.offset().local_minus_utc())) as libc::time_t;
+    let mut buffer = [0u8; 128];
+    // The locale is read from the environment once, for all the dates of the listing.
+    static SET_LOCALE: Once = Once::new();
+    SET_LOCALE.call_once(|| unsafe {
+        libc::setlocale(libc::LC_TIME, b"\0".as_ptr() as *const libc::c_char);
+    });
+    let written = unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::gmtime_r(&time, &mut tm).is_null() {
+            return date.format(format.to_str().unwrap_or_default()).to_string();
+        }
+        strftime(
+            buffer.as_mut_ptr() as *mut libc::c_char,
+            buffer.len(),
+            format.as_ptr(),
+            &tm,
+        )
+    };
+    String::from_utf8_lossy(&buffer[..written]).to_string()
+}
+
+/// Formats `date` with the English names, the locale being only read on unix.
+#[cfg(windows)]
+fn format_localized(date: &DateTime<FixedOffset>, format: &str) -> String {
+    date.format(format).to_string()
+}
+
 #[cfg(test)]
 mod test {
-    use super::Date;
+    use super::{format_localized, Date};
     use crate::color::{Colors, Theme};
-    use crate::flags::{DateFlag, Flags};
+    use crate::flags::color::{self, DateThresholds};
+    use crate::flags::{DateFlag, DateTimezone, Flags};
     use ansi_term::Colour;
-    use chrono::{DateTime, Duration, Local};
+    use chrono::{DateTime, Datelike, Duration, Local, TimeZone, Utc};
     use std::io;
     use std::path::Path;
     use std::process::{Command, ExitStatus};
     use std::{env, fs};
+    use unicode_width::UnicodeWidthStr;
 
     #[cfg(unix)]
     fn cross_platform_touch(path: &Path, date: &DateTime<Local>) -> io::Result<ExitStatus> {
@@ -169,6 +302,44 @@ mod test {
         fs::remove_file(file_path).unwrap();
     }
 
+    #[test]
+    fn test_a_very_old_file_color() {
+        let mut file_path = env::temp_dir();
+        file_path.push("test_a_very_old_file_color.tmp");
+
+        let creation_date = Local::now() - chrono::Duration::days(10);
+
+        let success = cross_platform_touch(&file_path, &creation_date)
+            .unwrap()
+            .success();
+        assert!(success, "failed to exec touch");
+
+        let colors = Colors::new(Theme::Default);
+        let date = Date::from(&file_path.metadata().unwrap());
+        let thresholds = |very_old| DateThresholds {
+            very_old,
+            ..DateThresholds::default()
+        };
+        let flags = |very_old| Flags {
+            color: color::Color {
+                date_thresholds: thresholds(very_old),
+                ..color::Color::default()
+            },
+            ..Flags::default()
+        };
+
+        assert_eq!(
+            Colour::Fixed(23).paint(creation_date.format("%c").to_string()),
+            date.render(&colors, &flags(Some(7 * 24 * 60 * 60)))
+        );
+        assert_eq!(
+            Colour::Fixed(36).paint(creation_date.format("%c").to_string()),
+            date.render(&colors, &flags(Some(30 * 24 * 60 * 60)))
+        );
+
+        fs::remove_file(file_path).unwrap();
+    }
+
     #[test]
     fn test_with_relative_date() {
         let mut file_path = env::temp_dir();
@@ -266,4 +437,64 @@ mod test {
 
         fs::remove_file(file_path).unwrap();
     }
+
+    #[test]
+    fn test_smart_format() {
+        let flags = Flags {
+            date: DateFlag::Smart,
+            ..Flags::default()
+        };
+        let now = Local::now();
+        let smart = |date: DateTime<Local>| Date(date).date_string(&flags);
+
+        let today = smart(now);
+        assert!(today.ends_with(&now.format("%R").to_string()), "{}", today);
+        let old = smart(now - Duration::days(400));
+        assert!(old.ends_with(&(now - Duration::days(400)).format(" %Y").to_string()));
+        assert_eq!(today.width(), old.width());
+
+        // The day before is still this year unless today is the first of January.
+        let yesterday = now - Duration::days(1);
+        if yesterday.year() == now.year() {
+            let text = smart(yesterday);
+            let yesterday = yesterday.with_timezone(yesterday.offset());
+            assert!(text.ends_with(&format_localized(&yesterday, "%b %e")));
+            assert_eq!(today.width(), text.width());
+        }
+    }
+
+    #[test]
+    fn test_epoch() {
+        let epoch = |seconds, nanos| Date(Local.timestamp(seconds, nanos)).epoch();
+        assert_eq!("1602669620.000000000", epoch(1_602_669_620, 0));
+        assert_eq!("1602669620.000000042", epoch(1_602_669_620, 42));
+        assert_eq!("-1.500000000", epoch(-2, 500_000_000));
+        assert_eq!("-2.000000000", epoch(-2, 0));
+    }
+
+    #[test]
+    fn test_date_timezone() {
+        let instant = Utc.ymd(2020, 1, 31).and_hms(23, 30, 0);
+        let date = Date(instant.with_timezone(&Local));
+        let flags = |date_timezone| Flags {
+            date: DateFlag::Formatted("%F %R %z".into()),
+            date_timezone,
+            ..Flags::default()
+        };
+        assert_eq!(
+            "2020-01-31 23:30 +0000",
+            date.date_string(&flags(DateTimezone::Utc))
+        );
+        assert_eq!(
+            "2020-02-01 05:00 +0530",
+            date.date_string(&flags(DateTimezone::Offset(19800)))
+        );
+
+        let smart = Flags {
+            date: DateFlag::Smart,
+            date_timezone: DateTimezone::Offset(19800),
+            ..Flags::default()
+        };
+        assert!(date.date_string(&smart).ends_with("2020"));
+    }
 }
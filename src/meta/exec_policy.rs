@@ -0,0 +1,143 @@
+use crate::color::{ColoredString, Colors, Elem};
+use crate::meta::{FileType, Meta};
+
+/// Whether the current process may execute a file. A file is [Denied](ExecPolicy::Denied) when
+/// its permission bits allow the execution but the system does not, usually because of the
+/// mandatory access control policy (SELinux, AppArmor...) or a `noexec` mount.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ExecPolicy {
+    NotExecutable,
+    Allowed,
+    Denied,
+}
+
+impl ExecPolicy {
+    /// This is a best-effort check: the kernel is asked with `access(2)`, which consults the
+    /// security modules and the mount options. A policy only enforced when the file is actually
+    /// executed is not detected.
+    #[cfg(unix)]
+    pub fn new(meta: &Meta) -> Self {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        if !matches!(meta.file_type, FileType::File { .. }) || !Self::allowed_by_bits(meta) {
+            return Self::NotExecutable;
+        }
+
+        // `access` needs a path, so the files under `--fd` are told by their bits alone.
+        if crate::readdir::is_anchored(&meta.path) {
+            return Self::Allowed;
+        }
+        let path = match CString::new(meta.path.as_os_str().as_bytes()) {
+            Ok(path) => path,
+            Err(_) => return Self::Allowed,
+        };
+        if unsafe { libc::access(path.as_ptr(), libc::X_OK) } == 0 {
+            Self::Allowed
+        } else {
+            Self::Denied
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn new(meta: &Meta) -> Self {
+        if matches!(meta.file_type, FileType::File { .. }) && meta.permissions.is_executable() {
+            Self::Allowed
+        } else {
+            Self::NotExecutable
+        }
+    }
+
+    /// Whether the execute bit applying to the current process is set, as for its user, one of
+    /// its groups or the others.
+    #[cfg(unix)]
+    fn allowed_by_bits(meta: &Meta) -> bool {
+        let permissions = &meta.permissions;
+        let euid = unsafe { libc::geteuid() };
+
+        if euid == 0 {
+            permissions.is_executable()
+        } else if meta.owner.uid() == Some(euid) {
+            permissions.user_execute
+        } else if matches!(meta.owner.gid(), Some(gid) if Self::in_group(gid)) {
+            permissions.group_execute
+        } else {
+            permissions.other_execute
+        }
+    }
+
+    /// Whether `gid` is the effective or a supplementary group of the current process.
+    #[cfg(unix)]
+    fn in_group(gid: u32) -> bool {
+        if unsafe { libc::getegid() } == gid {
+            return true;
+        }
+
+        let count = unsafe { libc::getgroups(0, std::ptr::null_mut()) };
+        if count <= 0 {
+            return false;
+        }
+        let mut groups = vec![0; count as usize];
+        let count = unsafe { libc::getgroups(count, groups.as_mut_ptr()) };
+        groups.truncate(count.max(0) as usize);
+
+        groups.contains(&gid)
+    }
+
+    pub fn render(self, colors: &Colors) -> ColoredString<'static> {
+        match self {
+            Self::NotExecutable => colors.colorize(String::from("-"), &Elem::NoAccess),
+            Self::Allowed => colors.colorize(String::from("x"), &Elem::Exec),
+            Self::Denied => colors.colorize(String::from("!"), &Elem::ExecDenied),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod test {
+    use super::ExecPolicy;
+    use crate::color::{Colors, Theme};
+    use crate::meta::Meta;
+    use std::fs::{self, File};
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    fn policy_of(mode: u32) -> ExecPolicy {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file_path = tmp_dir.path().join("file");
+        File::create(&file_path).expect("failed to create file");
+        fs::set_permissions(&file_path, fs::Permissions::from_mode(mode)).unwrap();
+        let meta = Meta::from_path(&file_path, false).unwrap();
+
+        ExecPolicy::new(&meta)
+    }
+
+    #[test]
+    fn test_not_executable() {
+        assert_eq!(ExecPolicy::NotExecutable, policy_of(0o644));
+    }
+
+    #[test]
+    fn test_executable() {
+        // The temporary directory could be on a noexec mount, which would deny the execution.
+        assert_ne!(ExecPolicy::NotExecutable, policy_of(0o755));
+    }
+
+    #[test]
+    fn test_directory_is_not_executable() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let meta = Meta::from_path(tmp_dir.path(), false).unwrap();
+
+        assert_eq!(ExecPolicy::NotExecutable, ExecPolicy::new(&meta));
+    }
+
+    #[test]
+    fn test_render() {
+        let colors = Colors::new(Theme::NoColor);
+
+        assert_eq!("-", ExecPolicy::NotExecutable.render(&colors).to_string());
+        assert_eq!("x", ExecPolicy::Allowed.render(&colors).to_string());
+        assert_eq!("!", ExecPolicy::Denied.render(&colors).to_string());
+    }
+}
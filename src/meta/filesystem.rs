@@ -0,0 +1,231 @@
+use crate::color::{ColoredString, Colors, Elem};
+use ansi_term::ANSIStrings;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A mounted file system, as listed by the system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mount {
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+}
+
+thread_local! {
+    /// The mounted file systems are only listed once, when a first file shows its file system.
+    static MOUNTS: Vec<Mount> = mounts();
+}
+
+/// The file system a file is on, and whether the file is its mount point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Filesystem {
+    fs_type: Option<String>,
+    is_mount_point: bool,
+}
+
+impl Filesystem {
+    pub fn new(path: &Path) -> Self {
+        // The files under `--fd` have no path to match with the mount points.
+        if crate::readdir::is_anchored(path) {
+            return Self {
+                fs_type: None,
+                is_mount_point: false,
+            };
+        }
+        MOUNTS.with(|mounts| Self::from_mounts(path, mounts))
+    }
+
+    /// Finds the file system of `path` among the `mounts`, as the one mounted on its longest
+    /// parent directory. A link is on the file system of its own directory, not of its target.
+    fn from_mounts(path: &Path, mounts: &[Mount]) -> Self {
+        let path = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+                fs::canonicalize(parent).map(|parent| parent.join(name))
+            }
+            _ => fs::canonicalize(path),
+        };
+        let path = match path {
+            Ok(path) => path,
+            Err(_) => {
+                return Self {
+                    fs_type: None,
+                    is_mount_point: false,
+                }
+            }
+        };
+
+        // The same mount point may be listed several times, the last mount hiding the others,
+        // which is the one `max_by_key` keeps among equals.
+        let mount = mounts
+            .iter()
+            .filter(|mount| path.starts_with(&mount.mount_point))
+            .max_by_key(|mount| mount.mount_point.components().count());
+
+        Self {
+            fs_type: mount.map(|mount| mount.fs_type.clone()),
+            is_mount_point: mount.map(|mount| &mount.mount_point) == Some(&path),
+        }
+    }
+
+    pub fn render(&self, colors: &Colors) -> ColoredString<'static> {
+        let mark = if self.is_mount_point {
+            colors.colorize(String::from("m"), &Elem::MountPoint)
+        } else {
+            colors.colorize(String::from("-"), &Elem::NoAccess)
+        };
+        let fs_type = match &self.fs_type {
+            Some(fs_type) => colors.colorize(fs_type.clone(), &Elem::Filesystem),
+            None => colors.colorize(String::from("?"), &Elem::NoAccess),
+        };
+
+        let strings = [mark, ColoredString::from(" "), fs_type];
+        ColoredString::from(ANSIStrings(&strings).to_string())
+    }
+}
+
+/// The mounted file systems, read from `/proc/self/mounts`.
+#[cfg(target_os = "linux")]
+fn mounts() -> Vec<Mount> {
+    match fs::read_to_string("/proc/self/mounts") {
+        Ok(content) => parse_mounts(&content),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// The mounted file systems, as returned by `getmntinfo`.
+#[cfg(target_os = "macos")]
+fn mounts() -> Vec<Mount> {
+    use std::ffi::CStr;
+
+    let mut stats: *mut libc::statfs = std::ptr::null_mut();
+    let count = unsafe { libc::getmntinfo(&mut stats, libc::MNT_NOWAIT) };
+    if count <= 0 || stats.is_null() {
+        return Vec::new();
+    }
+
+    // The buffer belongs to libc and is reused by the next calls, so it is copied right away.
+    let stats = unsafe { std::slice::from_raw_parts(stats, count as usize) };
+    stats
+        .iter()
+        .map(|stat| unsafe {
+            Mount {
+                mount_point: PathBuf::from(
+                    CStr::from_ptr(stat.f_mntonname.as_ptr())
+                        .to_string_lossy()
+                        .to_string(),
+                ),
+                fs_type: CStr::from_ptr(stat.f_fstypename.as_ptr())
+                    .to_string_lossy()
+                    .to_string(),
+            }
+        })
+        .collect()
+}
+
+/// The mounted file systems are not known on the other systems.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn mounts() -> Vec<Mount> {
+    Vec::new()
+}
+
+/// Parses the lines of a `fstab` formatted table, of which the second field is the mount point
+/// and the third one the file system type. The spaces and tabs are escaped as in `\040`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_mounts(content: &str) -> Vec<Mount> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = unescape(fields.nth(1)?);
+            let fs_type = unescape(fields.next()?);
+            Some(Mount {
+                mount_point: PathBuf::from(mount_point),
+                fs_type,
+            })
+        })
+        .collect()
+}
+
+/// Replaces the octal escapes, as `\040`, with the characters they stand for.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes.get(i + 1..i + 4).and_then(|digits| {
+            let digits = std::str::from_utf8(digits).ok()?;
+            u8::from_str_radix(digits, 8).ok()
+        });
+        match (bytes[i], escape) {
+            (b'\\', Some(byte)) => {
+                unescaped.push(byte);
+                i += 4;
+            }
+            (byte, _) => {
+                unescaped.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&unescaped).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_mounts, Filesystem, Mount};
+    use crate::color::{Colors, Theme};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_mounts() {
+        let table = "/dev/sda1 / ext4 rw,relatime 0 0\n\
+                     tmpfs /tmp tmpfs rw 0 0\n\
+                     //server/share /mnt/my\\040share cifs rw 0 0\n";
+        assert_eq!(
+            vec![
+                Mount {
+                    mount_point: PathBuf::from("/"),
+                    fs_type: "ext4".to_string(),
+                },
+                Mount {
+                    mount_point: PathBuf::from("/tmp"),
+                    fs_type: "tmpfs".to_string(),
+                },
+                Mount {
+                    mount_point: PathBuf::from("/mnt/my share"),
+                    fs_type: "cifs".to_string(),
+                },
+            ],
+            parse_mounts(table)
+        );
+    }
+
+    #[test]
+    fn test_from_mounts() {
+        let tmp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let dir = tmp_dir.path().canonicalize().unwrap();
+        std::fs::create_dir(dir.join("mnt")).unwrap();
+        std::fs::File::create(dir.join("file")).unwrap();
+        let mounts = vec![
+            Mount {
+                mount_point: PathBuf::from("/"),
+                fs_type: "ext4".to_string(),
+            },
+            Mount {
+                mount_point: dir.join("mnt"),
+                fs_type: "ext2".to_string(),
+            },
+            // Mounted over the previous one, which it hides.
+            Mount {
+                mount_point: dir.join("mnt"),
+                fs_type: "tmpfs".to_string(),
+            },
+        ];
+
+        let colors = Colors::new(Theme::NoColor);
+        let render = |path: PathBuf| Filesystem::from_mounts(&path, &mounts).render(&colors);
+        assert_eq!("- ext4", render(dir.join("file")).to_string());
+        assert_eq!("m tmpfs", render(dir.join("mnt")).to_string());
+        assert_eq!("- ?", render(dir.join("missing").join("file")).to_string());
+    }
+}
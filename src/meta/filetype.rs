@@ -7,9 +7,19 @@ use std::fs::Metadata;
 pub enum FileType {
     BlockDevice,
     CharDevice,
-    Directory { uid: bool },
-    File { uid: bool, exec: bool },
-    SymLink { is_dir: bool },
+    Directory {
+        setgid: bool,
+        sticky: bool,
+        other_writable: bool,
+    },
+    /// The `uid` of a file is whether it has the setuid bit.
+    File {
+        uid: bool,
+        exec: bool,
+    },
+    SymLink {
+        is_dir: bool,
+    },
     Pipe,
     Socket,
     Special,
@@ -33,7 +43,9 @@ impl FileType {
             }
         } else if file_type.is_dir() {
             FileType::Directory {
-                uid: permissions.setuid,
+                setgid: permissions.setgid,
+                sticky: permissions.sticky,
+                other_writable: permissions.other_write,
             }
         } else if file_type.is_fifo() {
             FileType::Pipe
@@ -68,7 +80,9 @@ impl FileType {
             }
         } else if file_type.is_dir() {
             FileType::Directory {
-                uid: permissions.setuid,
+                setgid: permissions.setgid,
+                sticky: permissions.sticky,
+                other_writable: permissions.other_write,
             }
         } else if file_type.is_symlink() {
             FileType::SymLink {
@@ -80,6 +94,20 @@ impl FileType {
         }
     }
 
+    /// The name of the file type in the machine-readable outputs.
+    pub fn kind(self) -> &'static str {
+        match self {
+            FileType::BlockDevice => "block-device",
+            FileType::CharDevice => "char-device",
+            FileType::Directory { .. } => "directory",
+            FileType::File { .. } => "file",
+            FileType::SymLink { .. } => "symlink",
+            FileType::Pipe => "pipe",
+            FileType::Socket => "socket",
+            FileType::Special => "special",
+        }
+    }
+
     pub fn is_dirlike(self) -> bool {
         matches!(
             self,
@@ -95,7 +123,7 @@ impl FileType {
                 colors.colorize(String::from("."), &Elem::File { exec, uid: false })
             }
             FileType::Directory { .. } => {
-                colors.colorize(String::from("d"), &Elem::Dir { uid: false })
+                colors.colorize(String::from("d"), &Elem::Dir { setgid: false })
             }
             FileType::Pipe => colors.colorize(String::from("|"), &Elem::Pipe),
             FileType::SymLink { .. } => colors.colorize(String::from("l"), &Elem::SymLink),
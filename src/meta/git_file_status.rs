@@ -44,15 +44,168 @@ impl GitFileStatus {
         }
     }
 
+    /// The `untracked_count`, if any, is printed after the symbol of the untracked status, and the
+    /// `change_counts` of the staged and unstaged files after the statuses.
     pub fn render(&self,
                   colors: &crate::color::Colors,
-                  icons: &crate::icon::Icons) -> crate::color::ColoredString {
-        let strings = &[
-            colors.colorize(icons.get_status(&self.index), &crate::color::Elem::GitStatus { status: self.index }),
-            crate::color::ColoredString::from(" "),
-            colors.colorize(icons.get_status(&self.workdir), &crate::color::Elem::GitStatus { status: self.workdir })
-        ];
-        let res = ansi_term::ANSIStrings(strings).to_string();
-        crate::color::ColoredString::from(res)
+                  icons: &crate::icon::Icons,
+                  style: crate::flags::GitStatusStyle,
+                  untracked_count: Option<usize>,
+                  change_counts: Option<(usize, usize)>) -> crate::color::ColoredString {
+        let colorize = |status: GitStatus| {
+            let symbol = match untracked_count {
+                Some(count) if status == GitStatus::NewInWorkdir => {
+                    format!("{}({})", icons.get_status(&status), count)
+                }
+                _ => icons.get_status(&status),
+            };
+            colors.colorize(symbol, &crate::color::Elem::GitStatus { status })
+        };
+        let mut strings = match style {
+            crate::flags::GitStatusStyle::TwoChar => vec![
+                colorize(self.index),
+                crate::color::ColoredString::from(" "),
+                colorize(self.workdir),
+            ],
+            // The statuses are ordered by severity.
+            crate::flags::GitStatusStyle::Single => {
+                vec![colorize(std::cmp::max(self.index, self.workdir))]
+            }
+        };
+        if let Some((staged, unstaged)) = change_counts {
+            strings.push(crate::color::ColoredString::from(" "));
+            strings.push(colors.colorize(
+                format!("{}±", staged),
+                &crate::color::Elem::GitStatus {
+                    status: GitStatus::NewInIndex,
+                },
+            ));
+            strings.push(crate::color::ColoredString::from("/"));
+            strings.push(colors.colorize(
+                format!("{}?", unstaged),
+                &crate::color::Elem::GitStatus {
+                    status: GitStatus::Modified,
+                },
+            ));
+        }
+        crate::color::ColoredString::from(ansi_term::ANSIStrings(&strings).to_string())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "git")]
+mod test {
+    use super::GitFileStatus;
+    use crate::color::{Colors, Theme};
+    use crate::flags::GitStatusStyle;
+    use crate::git::GitStatus;
+    use crate::icon::{self, Icons};
+    use ansi_term::Colour;
+
+    #[test]
+    fn test_render_text() {
+        let status = GitFileStatus {
+            index: GitStatus::NewInIndex,
+            workdir: GitStatus::Modified,
+        };
+        let colors = Colors::new(Theme::NoColor);
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+
+        assert_eq!(
+            "N M",
+            status
+                .render(&colors, &icons, GitStatusStyle::TwoChar, None, None)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_render_colors() {
+        let status = GitFileStatus {
+            index: GitStatus::Deleted,
+            workdir: GitStatus::Ignored,
+        };
+        let colors = Colors::new(Theme::NoLscolors);
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+
+        assert_eq!(
+            format!(
+                "{} {}",
+                Colour::Red.paint("D"),
+                Colour::Fixed(245).paint("!")
+            ),
+            status
+                .render(&colors, &icons, GitStatusStyle::TwoChar, None, None)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_render_single() {
+        let colors = Colors::new(Theme::NoColor);
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+
+        for (index, workdir, expected) in &[
+            (GitStatus::NewInIndex, GitStatus::Modified, "M"),
+            (GitStatus::Deleted, GitStatus::Unmodified, "D"),
+            (GitStatus::Default, GitStatus::Default, "-"),
+        ] {
+            let status = GitFileStatus {
+                index: *index,
+                workdir: *workdir,
+            };
+            assert_eq!(
+                *expected,
+                status
+                    .render(&colors, &icons, GitStatusStyle::Single, None, None)
+                    .to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_untracked_count() {
+        let colors = Colors::new(Theme::NoColor);
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+        let status = GitFileStatus {
+            index: GitStatus::Unmodified,
+            workdir: GitStatus::NewInWorkdir,
+        };
+
+        assert_eq!(
+            "- ?(42)",
+            status
+                .render(&colors, &icons, GitStatusStyle::TwoChar, Some(42), None)
+                .to_string()
+        );
+        assert_eq!(
+            "?(42)",
+            status
+                .render(&colors, &icons, GitStatusStyle::Single, Some(42), None)
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_render_change_counts() {
+        let colors = Colors::new(Theme::NoColor);
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+        let status = GitFileStatus {
+            index: GitStatus::NewInIndex,
+            workdir: GitStatus::Modified,
+        };
+
+        assert_eq!(
+            "N M 3±/2?",
+            status
+                .render(&colors, &icons, GitStatusStyle::TwoChar, None, Some((3, 2)))
+                .to_string()
+        );
+        assert_eq!(
+            "M 0±/2?",
+            status
+                .render(&colors, &icons, GitStatusStyle::Single, None, Some((0, 2)))
+                .to_string()
+        );
     }
 }
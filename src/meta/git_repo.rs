@@ -0,0 +1,224 @@
+//! The state of the git repositories listed as directories, shown by the repo block so that the
+//! listing of a directory of projects tells which of them have changes not committed.
+
+use crate::color::{ColoredString, Colors, Elem};
+use ansi_term::ANSIStrings;
+#[cfg(feature = "git")]
+use std::collections::BTreeSet;
+#[cfg(feature = "git")]
+use std::fs;
+#[cfg(feature = "git")]
+use std::path::Path;
+
+/// The branch a repository is on, and whether its work tree or its index holds changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitRepo {
+    /// The name of the branch, the abbreviated commit when detached.
+    pub branch: String,
+    pub dirty: bool,
+}
+
+#[cfg(feature = "git")]
+impl GitRepo {
+    /// The state of the repository whose work tree is `dir`, [None] if it is not one. Only the
+    /// directories holding a `.git` are opened, the repositories they are in are not looked for.
+    pub fn read(dir: &Path) -> Option<Self> {
+        if !dir.join(".git").exists() {
+            return None;
+        }
+        let repo = git2::Repository::open(dir).ok()?;
+        Some(Self {
+            branch: branch(&repo),
+            dirty: is_dirty(&repo),
+        })
+    }
+}
+
+impl GitRepo {
+    /// Renders the branch, then a filled dot for a dirty repository or a hollow one for a clean
+    /// repository, so they are told apart without colors too.
+    pub fn render(&self, colors: &Colors) -> ColoredString<'static> {
+        let (dot, elem) = if self.dirty {
+            ("\u{25cf}", Elem::GitRepoDirty)
+        } else {
+            ("\u{25cb}", Elem::GitRepoClean)
+        };
+        let strings = &[
+            colors.colorize(self.branch.clone(), &Elem::GitRepoBranch),
+            ColoredString::from(" "),
+            colors.colorize(dot.to_string(), &elem),
+        ];
+        ColoredString::from(ANSIStrings(strings).to_string())
+    }
+}
+
+/// The branch HEAD is on, even without commit yet, or else the abbreviated commit it is at.
+#[cfg(feature = "git")]
+fn branch(repo: &git2::Repository) -> String {
+    match repo.head() {
+        Ok(head) if head.is_branch() => head.shorthand().unwrap_or_default().to_string(),
+        Ok(head) => head
+            .target()
+            .map(|id| id.to_string().chars().take(7).collect())
+            .unwrap_or_default(),
+        // The branch has no commit yet, so HEAD can only be read as the name it refers to.
+        Err(_) => repo
+            .find_reference("HEAD")
+            .ok()
+            .and_then(|head| {
+                head.symbolic_target()
+                    .map(|target| target.trim_start_matches("refs/heads/").to_string())
+            })
+            .unwrap_or_default(),
+    }
+}
+
+/// Whether the index differs from HEAD, or else the work tree from the index. The index, which
+/// is cheap to compare, is looked at first. The work tree is then compared one top level entry
+/// at a time, to stop at the first one holding a change rather than walk the whole tree: a dirty
+/// repository is told apart as soon as a change is found, while a clean one pays a diff per top
+/// level entry instead of a single walk. The untracked directories and the submodules are not
+/// entered.
+#[cfg(feature = "git")]
+fn is_dirty(repo: &git2::Repository) -> bool {
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    if let Ok(diff) = repo.diff_tree_to_index(head_tree.as_ref(), None, None) {
+        if diff.deltas().next().is_some() {
+            return true;
+        }
+    }
+
+    let workdir = match repo.workdir() {
+        Some(workdir) => workdir,
+        None => return false,
+    };
+    // The entries deleted from the work tree are only known to the index.
+    let mut roots = BTreeSet::new();
+    if let Ok(index) = repo.index() {
+        for entry in index.iter() {
+            let root = entry
+                .path
+                .split(|&byte| byte == b'/')
+                .next()
+                .unwrap_or_default();
+            roots.insert(root.to_vec());
+        }
+    }
+    if let Ok(entries) = fs::read_dir(workdir) {
+        for entry in entries.filter_map(Result::ok) {
+            match entry.file_name().to_str() {
+                Some(".git") => {}
+                Some(name) => {
+                    roots.insert(name.as_bytes().to_vec());
+                }
+                // A name git can not be given as a pathspec, the work tree is compared at once.
+                None => return workdir_differs(repo, None),
+            }
+        }
+    }
+    roots
+        .into_iter()
+        .any(|root| workdir_differs(repo, Some(root)))
+}
+
+/// Whether the work tree differs from the index under the top level entry `root`, or anywhere
+/// when [None].
+#[cfg(feature = "git")]
+fn workdir_differs(repo: &git2::Repository, root: Option<Vec<u8>>) -> bool {
+    let mut options = git2::DiffOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .enable_fast_untracked_dirs(true)
+        .ignore_submodules(true)
+        .skip_binary_check(true)
+        .disable_pathspec_match(true);
+    if let Some(root) = root {
+        options.pathspec(root);
+    }
+    repo.diff_index_to_workdir(None, Some(&mut options))
+        .map(|diff| diff.deltas().next().is_some())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod test {
+    use super::GitRepo;
+    use crate::color::{Colors, Theme};
+
+    #[test]
+    fn test_render() {
+        let colors = Colors::new(Theme::NoColor);
+        let repo = |dirty| GitRepo {
+            branch: "main".to_string(),
+            dirty,
+        };
+        assert_eq!("main \u{25cf}", repo(true).render(&colors).to_string());
+        assert_eq!("main \u{25cb}", repo(false).render(&colors).to_string());
+    }
+
+    #[cfg(feature = "git")]
+    #[test]
+    fn test_read() {
+        use std::fs;
+        use tempfile::tempdir;
+
+        let dir = tempdir().expect("failed to create temp dir");
+        assert_eq!(None, GitRepo::read(dir.path()));
+
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/trunk").unwrap();
+        let state = GitRepo::read(dir.path()).unwrap();
+        assert_eq!("trunk", state.branch);
+        assert!(!state.dirty);
+
+        fs::write(dir.path().join("file"), "").unwrap();
+        assert!(GitRepo::read(dir.path()).unwrap().dirty);
+
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("file")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("lsd", "lsd@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+        assert_eq!(
+            Some(GitRepo {
+                branch: "trunk".to_string(),
+                dirty: false
+            }),
+            GitRepo::read(dir.path())
+        );
+
+        // The changes are found under the top level directories, and in the deleted files.
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src").join("main.rs"), "").unwrap();
+        assert!(GitRepo::read(dir.path()).unwrap().dirty);
+        index.add_path(std::path::Path::new("src/main.rs")).unwrap();
+        index.write().unwrap();
+        assert!(GitRepo::read(dir.path()).unwrap().dirty);
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "src",
+            &tree,
+            &[&parent],
+        )
+        .unwrap();
+        assert!(!GitRepo::read(dir.path()).unwrap().dirty);
+        fs::write(dir.path().join("src").join("main.rs"), "fn main() {}").unwrap();
+        assert!(GitRepo::read(dir.path()).unwrap().dirty);
+        fs::remove_dir_all(dir.path().join("src")).unwrap();
+        assert!(GitRepo::read(dir.path()).unwrap().dirty);
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src").join("main.rs"), "").unwrap();
+        assert!(!GitRepo::read(dir.path()).unwrap().dirty);
+
+        // A sub directory is not a repository of its own.
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        assert_eq!(None, GitRepo::read(&dir.path().join("sub")));
+    }
+}
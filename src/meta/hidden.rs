@@ -0,0 +1,173 @@
+//! Which files of a directory are hidden, unless `--all` or `--almost-all` is passed.
+//!
+//! The files whose name starts with a dot are hidden everywhere. Some file systems and desktops
+//! hide other files as well: these semantics are each implemented by a [HiddenPolicy], and
+//! [Hidden::for_directory] picks the ones applying to a directory.
+
+use std::collections::HashSet;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::Path;
+
+/// A way for a file system or a desktop to hide some files.
+pub trait HiddenPolicy {
+    /// Whether the file at `path`, named `name`, is hidden.
+    fn is_hidden(&self, path: &Path, name: &OsStr) -> bool;
+}
+
+/// The files whose name starts with a dot, as on every platform.
+pub struct DotFiles;
+
+impl HiddenPolicy for DotFiles {
+    fn is_hidden(&self, _: &Path, name: &OsStr) -> bool {
+        name.to_string_lossy().starts_with('.')
+    }
+}
+
+/// The files listed in the `.hidden` file of their directory, one name per line, as hidden by
+/// the GNOME (gvfs) and KDE file managers.
+pub struct HiddenList {
+    names: HashSet<OsString>,
+}
+
+impl HiddenList {
+    /// Reads the `.hidden` file of `dir`, [None] if there is none.
+    pub fn read(dir: &Path) -> Option<Self> {
+        // The `.hidden` files are not read under `--fd`.
+        if crate::readdir::is_anchored(dir) {
+            return None;
+        }
+        let content = fs::read_to_string(dir.join(".hidden")).ok()?;
+        let names = content
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(OsString::from)
+            .collect();
+        Some(Self { names })
+    }
+}
+
+impl HiddenPolicy for HiddenList {
+    fn is_hidden(&self, _: &Path, name: &OsStr) -> bool {
+        self.names.contains(name)
+    }
+}
+
+/// The metadata files the sync clients of cloud drives leave in the directories they mount
+/// through FUSE, such as `desktop.ini` or the `~$` lock files of office documents.
+pub struct SyncMetadata;
+
+impl SyncMetadata {
+    const NAMES: &'static [&'static str] = &["desktop.ini", "thumbs.db", "icon\r"];
+    const PREFIXES: &'static [&'static str] = &["~$"];
+
+    /// Whether `dir` is on a FUSE file system, which the cloud drives are mounted with.
+    #[cfg(target_os = "linux")]
+    pub fn applies_to(dir: &Path) -> bool {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        const FUSE_SUPER_MAGIC: u64 = 0x6573_5546;
+
+        let path = match CString::new(dir.as_os_str().as_bytes()) {
+            Ok(path) => path,
+            Err(_) => return false,
+        };
+        let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statfs(path.as_ptr(), &mut stat) } != 0 {
+            return false;
+        }
+        stat.f_type as u64 == FUSE_SUPER_MAGIC
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn applies_to(_: &Path) -> bool {
+        false
+    }
+}
+
+impl HiddenPolicy for SyncMetadata {
+    fn is_hidden(&self, _: &Path, name: &OsStr) -> bool {
+        let name = name.to_string_lossy().to_lowercase();
+        Self::NAMES.contains(&name.as_str())
+            || Self::PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+    }
+}
+
+/// The files with the hidden attribute of Windows.
+#[cfg(windows)]
+pub struct HiddenAttribute;
+
+#[cfg(windows)]
+impl HiddenPolicy for HiddenAttribute {
+    fn is_hidden(&self, path: &Path, _: &OsStr) -> bool {
+        use std::os::windows::fs::MetadataExt;
+        use winapi::um::winnt::FILE_ATTRIBUTE_HIDDEN;
+
+        match path.symlink_metadata() {
+            Ok(metadata) => metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0,
+            Err(_) => false,
+        }
+    }
+}
+
+/// The policies hiding the files of a directory.
+pub struct Hidden {
+    policies: Vec<Box<dyn HiddenPolicy>>,
+}
+
+impl Hidden {
+    /// The policies applying to the files of `dir`.
+    pub fn for_directory(dir: &Path) -> Self {
+        let mut policies: Vec<Box<dyn HiddenPolicy>> = vec![Box::new(DotFiles)];
+        #[cfg(windows)]
+        policies.push(Box::new(HiddenAttribute));
+        if let Some(list) = HiddenList::read(dir) {
+            policies.push(Box::new(list));
+        }
+        if SyncMetadata::applies_to(dir) {
+            policies.push(Box::new(SyncMetadata));
+        }
+
+        Self { policies }
+    }
+
+    /// Whether the file at `path`, named `name`, is hidden by any of the policies.
+    pub fn is_hidden(&self, path: &Path, name: &OsStr) -> bool {
+        self.policies
+            .iter()
+            .any(|policy| policy.is_hidden(path, name))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Hidden, HiddenPolicy, SyncMetadata};
+    use assert_fs::prelude::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn test_hidden_list() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child(".hidden")
+            .write_str("build\nnotes.txt\n")
+            .unwrap();
+
+        let hidden = Hidden::for_directory(dir.path());
+        let is_hidden = |name: &str| hidden.is_hidden(&dir.path().join(name), OsStr::new(name));
+        assert!(is_hidden(".git"));
+        assert!(is_hidden("build"));
+        assert!(is_hidden("notes.txt"));
+        assert!(!is_hidden("src"));
+    }
+
+    #[test]
+    fn test_sync_metadata() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let is_hidden = |name: &str| SyncMetadata.is_hidden(dir.path(), OsStr::new(name));
+
+        assert!(is_hidden("Desktop.ini"));
+        assert!(is_hidden("~$report.docx"));
+        assert!(!is_hidden("report.docx"));
+    }
+}
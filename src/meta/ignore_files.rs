@@ -0,0 +1,108 @@
+//! The `.ignore` and `.lsdignore` files, holding gitignore style patterns of the files not to
+//! list, respected when recursing when the [IgnoreFiles](crate::flags::IgnoreFiles) flag is set,
+//! so that the generated directories of a project can be left out without git.
+//!
+//! The patterns of a directory apply to its whole content, and take precedence over the ones of
+//! the directories it is in: the files it ignores are listed again by a negated pattern
+//! (`!pattern`) of a sub directory. Only the files of the listed directories and of their sub
+//! directories are read, not the ones of the directories above them.
+
+use crate::core::ExitCode;
+use crate::print_error;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::path::Path;
+
+/// The names of the ignore files, the patterns of the latter being read after the ones of the
+/// former.
+const NAMES: &[&str] = &[".ignore", ".lsdignore"];
+
+/// Reads the patterns of the ignore files of `dir`, [None] if it has none. The invalid patterns
+/// are reported and skipped.
+pub fn read(dir: &Path) -> Option<Gitignore> {
+    // No ignore file is read under `--fd`, the directories having no path there.
+    if crate::readdir::is_anchored(dir) {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(dir);
+    let mut found = false;
+    for name in NAMES {
+        let file = dir.join(name);
+        if !file.is_file() {
+            continue;
+        }
+        found = true;
+        if let Some(err) = builder.add(&file) {
+            print_error!("{}: {}.", file.display(), err);
+            ExitCode::MinorIssue.report();
+        }
+    }
+    if !found {
+        return None;
+    }
+    builder
+        .build()
+        .ok()
+        .filter(|gitignore| !gitignore.is_empty())
+}
+
+/// Whether the file at `path` is ignored by the patterns of the directories it is in, from the
+/// listed one down to its parent, the nearest one matching it deciding.
+pub fn is_ignored<'a>(
+    layers: impl DoubleEndedIterator<Item = &'a Gitignore>,
+    path: &Path,
+    is_dir: bool,
+) -> bool {
+    for layer in layers.rev() {
+        match layer.matched(path, is_dir) {
+            Match::None => continue,
+            Match::Ignore(_) => return true,
+            Match::Whitelist(_) => return false,
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::{is_ignored, read};
+    use assert_fs::prelude::*;
+
+    #[test]
+    fn test_read_none() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        assert!(read(dir.path()).is_none());
+        dir.child(".ignore").write_str("# nothing\n").unwrap();
+        assert!(read(dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_is_ignored_layers() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child(".ignore").write_str("target/\n*.log\n").unwrap();
+        dir.child(".lsdignore").write_str("build\n").unwrap();
+        dir.child("sub/.ignore").write_str("!kept.log\n").unwrap();
+        let top = read(dir.path()).unwrap();
+        let sub = read(&dir.path().join("sub")).unwrap();
+
+        let path = |name: &str| dir.path().join(name);
+        assert!(is_ignored([&top].iter().copied(), &path("target"), true));
+        assert!(!is_ignored([&top].iter().copied(), &path("target"), false));
+        assert!(is_ignored([&top].iter().copied(), &path("build"), false));
+        assert!(is_ignored([&top].iter().copied(), &path("a.log"), false));
+        assert!(!is_ignored([&top].iter().copied(), &path("a.txt"), false));
+
+        let layers = [&top, &sub];
+        assert!(is_ignored(
+            layers.iter().copied(),
+            &path("sub/a.log"),
+            false
+        ));
+        assert!(!is_ignored(
+            layers.iter().copied(),
+            &path("sub/kept.log"),
+            false
+        ));
+    }
+}
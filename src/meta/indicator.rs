@@ -23,10 +23,15 @@ impl From<FileType> for Indicator {
 
 impl Indicator {
     pub fn render(&self, flags: &Flags) -> ColoredString {
+        ANSIString::from(self.plain(flags))
+    }
+
+    /// The indicator as displayed, which is empty unless the indicators are enabled.
+    pub fn plain(&self, flags: &Flags) -> &'static str {
         if flags.display_indicators.0 {
-            ANSIString::from(self.0)
+            self.0
         } else {
-            ANSIString::from("")
+            ""
         }
     }
 }
@@ -42,7 +47,11 @@ mod test {
         let mut flags = Flags::default();
         flags.display_indicators = Indicators(true);
 
-        let file_type = Indicator::from(FileType::Directory { uid: false });
+        let file_type = Indicator::from(FileType::Directory {
+            setgid: false,
+            sticky: false,
+            other_writable: false,
+        });
 
         assert_eq!("/", file_type.render(&flags).to_string().as_str());
     }
@@ -0,0 +1,221 @@
+use crate::color::{ColoredString, Colors, Elem};
+use crate::meta::name::find_by_suffix;
+use crate::meta::{FileType, Meta};
+use std::collections::HashMap;
+
+/// The kinds of the files by their extension, without its leading dot.
+const KINDS_BY_EXTENSION: &[(&str, &str)] = &[
+    // Sources
+    ("c", "C source"),
+    ("h", "C header"),
+    ("cc", "C++ source"),
+    ("cpp", "C++ source"),
+    ("cxx", "C++ source"),
+    ("hpp", "C++ header"),
+    ("cs", "C# source"),
+    ("go", "Go source"),
+    ("hs", "Haskell source"),
+    ("java", "Java source"),
+    ("js", "JavaScript source"),
+    ("kt", "Kotlin source"),
+    ("lua", "Lua script"),
+    ("php", "PHP script"),
+    ("pl", "Perl script"),
+    ("py", "Python script"),
+    ("rb", "Ruby script"),
+    ("rs", "Rust source"),
+    ("scala", "Scala source"),
+    ("sh", "shell script"),
+    ("bash", "shell script"),
+    ("zsh", "shell script"),
+    ("fish", "shell script"),
+    ("ps1", "PowerShell script"),
+    ("sql", "SQL script"),
+    ("swift", "Swift source"),
+    ("ts", "TypeScript source"),
+    ("vim", "Vim script"),
+    // Documents and data
+    ("css", "CSS stylesheet"),
+    ("csv", "CSV table"),
+    ("doc", "Word document"),
+    ("docx", "Word document"),
+    ("epub", "EPUB book"),
+    ("htm", "HTML document"),
+    ("html", "HTML document"),
+    ("ini", "configuration"),
+    ("json", "JSON data"),
+    ("log", "log"),
+    ("md", "Markdown document"),
+    ("markdown", "Markdown document"),
+    ("odp", "OpenDocument presentation"),
+    ("ods", "OpenDocument spreadsheet"),
+    ("odt", "OpenDocument text"),
+    ("pdf", "PDF document"),
+    ("ppt", "PowerPoint presentation"),
+    ("pptx", "PowerPoint presentation"),
+    ("rst", "reStructuredText document"),
+    ("tex", "TeX document"),
+    ("toml", "TOML data"),
+    ("txt", "text"),
+    ("xls", "Excel spreadsheet"),
+    ("xlsx", "Excel spreadsheet"),
+    ("xml", "XML document"),
+    ("yaml", "YAML data"),
+    ("yml", "YAML data"),
+    // Images
+    ("bmp", "BMP image"),
+    ("gif", "GIF image"),
+    ("ico", "icon"),
+    ("jpeg", "JPEG image"),
+    ("jpg", "JPEG image"),
+    ("png", "PNG image"),
+    ("psd", "Photoshop image"),
+    ("svg", "SVG image"),
+    ("tif", "TIFF image"),
+    ("tiff", "TIFF image"),
+    ("webp", "WebP image"),
+    // Audio and video
+    ("aac", "AAC audio"),
+    ("flac", "FLAC audio"),
+    ("m4a", "MPEG-4 audio"),
+    ("mp3", "MP3 audio"),
+    ("ogg", "Ogg audio"),
+    ("opus", "Opus audio"),
+    ("wav", "WAV audio"),
+    ("avi", "AVI video"),
+    ("mkv", "Matroska video"),
+    ("mov", "QuickTime video"),
+    ("mp4", "MPEG-4 video"),
+    ("webm", "WebM video"),
+    // Archives and packages
+    ("7z", "7-Zip archive"),
+    ("bz2", "bzip2 archive"),
+    ("gz", "gzip archive"),
+    ("rar", "RAR archive"),
+    ("tar", "tar archive"),
+    ("tar.bz2", "tar archive"),
+    ("tar.gz", "tar archive"),
+    ("tar.xz", "tar archive"),
+    ("tar.zst", "tar archive"),
+    ("tbz2", "tar archive"),
+    ("tgz", "tar archive"),
+    ("txz", "tar archive"),
+    ("xz", "xz archive"),
+    ("zip", "Zip archive"),
+    ("zst", "Zstandard archive"),
+    ("apk", "Android package"),
+    ("deb", "Debian package"),
+    ("jar", "Java archive"),
+    ("rpm", "RPM package"),
+    ("dmg", "disk image"),
+    ("img", "disk image"),
+    ("iso", "disk image"),
+    // Binaries
+    ("a", "static library"),
+    ("dll", "shared library"),
+    ("dylib", "shared library"),
+    ("exe", "Windows executable"),
+    ("o", "object file"),
+    ("so", "shared library"),
+    ("wasm", "WebAssembly module"),
+    // Miscellaneous
+    ("bak", "backup"),
+    ("lock", "lock file"),
+    ("patch", "patch"),
+    ("diff", "patch"),
+    ("ttf", "font"),
+    ("otf", "font"),
+    ("woff", "font"),
+    ("woff2", "font"),
+];
+
+/// The kinds of the files known by their whole name, in lower case.
+const KINDS_BY_NAME: &[(&str, &str)] = &[
+    ("dockerfile", "Dockerfile"),
+    ("makefile", "Makefile"),
+    ("license", "license"),
+    ("readme", "text"),
+];
+
+thread_local! {
+    static EXTENSIONS: HashMap<String, &'static str> = KINDS_BY_EXTENSION
+        .iter()
+        .map(|&(extension, kind)| (extension.to_string(), kind))
+        .collect();
+}
+
+/// Whether `extension`, in lower case and without its leading dot, is one of the known ones,
+/// which include the compound `tar.gz`.
+pub fn is_known_extension(extension: &str) -> bool {
+    EXTENSIONS.with(|extensions| extensions.contains_key(extension))
+}
+
+/// A human word for what a file is, as "directory" or "JPEG image", taken from its type and, for
+/// the regular files, from its name and extension.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Kind(&'static str);
+
+impl Kind {
+    pub fn new(meta: &Meta) -> Self {
+        let kind = match meta.file_type {
+            FileType::Directory { .. } => "directory",
+            FileType::SymLink { is_dir: true } => "directory symlink",
+            FileType::SymLink { is_dir: false } => "symlink",
+            FileType::Pipe => "named pipe",
+            FileType::Socket => "socket",
+            FileType::BlockDevice => "block device",
+            FileType::CharDevice => "character device",
+            FileType::Special => "special file",
+            FileType::File { exec, .. } => match Self::from_name(&meta.name.name) {
+                Some(kind) => kind,
+                None if exec => "executable",
+                None => "file",
+            },
+        };
+
+        Self(kind)
+    }
+
+    fn from_name(name: &str) -> Option<&'static str> {
+        let lower = name.to_lowercase();
+        KINDS_BY_NAME
+            .iter()
+            .find(|(known, _)| *known == lower)
+            .map(|&(_, kind)| kind)
+            .or_else(|| EXTENSIONS.with(|extensions| find_by_suffix(name, extensions).copied()))
+    }
+
+    pub fn render(&self, colors: &Colors) -> ColoredString<'static> {
+        colors.colorize(self.0.to_string(), &Elem::Kind)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Kind;
+    use crate::meta::Meta;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_kind() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let kind_of = |name: &str| {
+            let path = tmp_dir.path().join(name);
+            if !path.exists() {
+                File::create(&path).expect("failed to create file");
+            }
+            Kind::new(&Meta::from_path(&path, false).unwrap()).0
+        };
+
+        assert_eq!("Rust source", kind_of("main.rs"));
+        assert_eq!("JPEG image", kind_of("Photo.JPG"));
+        assert_eq!("tar archive", kind_of("backup.tar.gz"));
+        assert_eq!("gzip archive", kind_of("notes.gz"));
+        assert_eq!("Makefile", kind_of("Makefile"));
+        assert_eq!("file", kind_of("unknown.xyz"));
+
+        fs::create_dir(tmp_dir.path().join("src.rs")).unwrap();
+        assert_eq!("directory", kind_of("src.rs"));
+    }
+}
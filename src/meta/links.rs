@@ -23,6 +23,10 @@ impl<'a> From<&'a Metadata> for Links {
 }
 
 impl Links {
+    pub fn count(&self) -> Option<u64> {
+        self.nlink
+    }
+
     pub fn render(&self, colors: &Colors) -> ColoredString {
         match self.nlink {
             Some(i) => colors.colorize(i.to_string(), &Elem::Links { valid: true }),
@@ -1,41 +1,70 @@
+#[cfg(unix)]
+mod accounts;
+#[cfg(unix)]
+mod acl;
 mod date;
+mod exec_policy;
+mod filesystem;
 mod filetype;
 pub mod git_file_status;
+mod git_repo;
+mod hidden;
+pub mod ignore_files;
 mod indicator;
 mod inode;
+mod kind;
 mod links;
 pub mod name;
 mod owner;
 mod permissions;
+#[cfg(feature = "lnk")]
+mod shortcut;
 mod size;
 mod symlink;
+mod walk;
 
 #[cfg(windows)]
 mod windows_utils;
 
 pub use self::date::Date;
+pub use self::exec_policy::ExecPolicy;
+pub use self::filesystem::Filesystem;
 pub use self::filetype::FileType;
+pub use self::hidden::Hidden;
 pub use self::indicator::Indicator;
 pub use self::inode::INode;
+pub use self::kind::Kind;
 pub use self::links::Links;
 pub use self::name::Name;
 pub use self::owner::Owner;
 pub use self::permissions::Permissions;
 pub use self::size::Size;
 pub use self::git_file_status::GitFileStatus;
-pub use self::symlink::SymLink;
+pub use self::git_repo::GitRepo;
+pub use self::symlink::{BrokenReason, SymLink};
+pub use self::walk::walk;
 pub use crate::icon::Icons;
 
-use crate::flags::{Display, Flags, Layout};
+use crate::core::ExitCode;
+#[cfg(feature = "git")]
+use crate::flags::SortColumn;
+use crate::flags::{Block, Display, Flags, Layout, TotalSizeSymlinks};
 use crate::print_error;
+use crate::readdir::{self, DirEntry};
+use crate::watch::Change;
 
 #[cfg(feature = "git")]
-use crate::git::GitCache;
+use crate::git::GitStatusProvider;
 #[cfg(not(feature = "git"))]
-use crate::git_stub::GitCache;
-use std::fs::read_link;
+use crate::git_stub::GitStatusProvider;
+use ignore::gitignore::Gitignore;
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs::Metadata;
 use std::io::{Error, ErrorKind};
 use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
 
 #[derive(Clone, Debug)]
 pub struct Meta {
@@ -43,23 +72,132 @@ pub struct Meta {
     pub path: PathBuf,
     pub permissions: Permissions,
     pub date: Date,
+    /// The birth time of the file, where the system and the filesystem record it.
+    pub created: Option<Date>,
     pub owner: Owner,
     pub file_type: FileType,
     pub size: Size,
+    /// The space allocated on the disk to the file.
+    pub allocated: Size,
     pub symlink: SymLink,
     pub indicator: Indicator,
     pub inode: INode,
     pub links: Links,
     pub content: Option<Vec<Meta>>,
     pub git_status: Option<GitFileStatus>,
+    /// The path the file had before being renamed in the git index, relative to the workdir.
+    pub git_renamed_from: Option<PathBuf>,
+    /// The number of untracked files in the directory, when they are counted.
+    #[cfg_attr(not(feature = "git"), allow(dead_code))]
+    pub git_untracked_count: Option<usize>,
+    /// The numbers of files in the directory with staged and with unstaged changes, when they are
+    /// counted.
+    #[cfg_attr(not(feature = "git"), allow(dead_code))]
+    pub git_change_counts: Option<(usize, usize)>,
+    /// Why the content of the directory could not be read while recursing, if it could not.
+    pub content_error: Option<BrokenReason>,
+    /// The state of the git repository whose work tree the directory is, when it is shown.
+    pub git_repo: Option<GitRepo>,
+    /// How the file changed since the previous listing, when it is highlighted in the watch mode.
+    pub change: Option<Change>,
 }
 
+/// The device and inode numbers identifying a directory.
+type DirectoryId = (u64, u64);
+
+/// The device and inode numbers identifying a file counted in a total size.
+type FileId = (u64, u64);
+
+/// A directory entries are listed from, one of their parent directories up to the listed one.
+#[derive(Clone, Debug)]
+struct Ancestor {
+    id: Option<DirectoryId>,
+    /// The patterns of its ignore files, when they are respected.
+    ignore: Option<Rc<Gitignore>>,
+}
+
+impl Ancestor {
+    fn new(path: &Path, flags: &Flags) -> Self {
+        Self {
+            id: directory_id(path),
+            ignore: if flags.ignore_files.0 {
+                ignore_files::read(path).map(Rc::new)
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// The directories a level of a streamed tree is listed from, see [Meta::walk_level].
+#[derive(Clone, Debug, Default)]
+pub struct Ancestors(Vec<Ancestor>);
+
 impl Meta {
     pub fn recurse_into(
         &self,
         depth: usize,
         flags: &Flags,
-        cache: Option<&GitCache>,
+        cache: Option<&dyn GitStatusProvider>,
+        budget: &MemoryBudget,
+    ) -> Result<Option<Vec<Meta>>, std::io::Error> {
+        self.walk(depth, flags, cache, budget, &[])
+    }
+
+    /// Lists the entries of `self` without their content, as a level of a tree printed as it is
+    /// read, from the directories of `ancestors`. The ones to list the entries from are returned
+    /// along them. When the entries are `entered` too, the directories among them whose content
+    /// can not be read are told by their `content_error`, as they are when the tree is collected.
+    pub fn walk_level(
+        &self,
+        flags: &Flags,
+        cache: Option<&dyn GitStatusProvider>,
+        ancestors: &Ancestors,
+        entered: bool,
+    ) -> Result<Option<(Vec<Meta>, Ancestors)>, std::io::Error> {
+        let budget = MemoryBudget::new(None);
+        let mut content = match self.walk(1, flags, cache, &budget, &ancestors.0)? {
+            Some(content) => content,
+            None => return Ok(None),
+        };
+
+        if entered {
+            for entry in content.iter_mut().filter(|entry| entry.is_entered(flags)) {
+                match readdir::read_dir(&entry.path) {
+                    Err(err) if !is_vanished(&err) => {
+                        entry.content_error = Some(BrokenReason::from_error(&err));
+                        ExitCode::MinorIssue.report();
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        let mut ancestors = ancestors.clone();
+        ancestors.0.push(Ancestor::new(&self.path, flags));
+        Ok(Some((content, ancestors)))
+    }
+
+    /// Whether the content of `self`, found when recursing, is listed: the directories and the
+    /// links to directories when followed, but the snapshot directories unless asked for.
+    pub fn is_entered(&self, flags: &Flags) -> bool {
+        let is_dir = match self.file_type {
+            FileType::Directory { .. } => true,
+            FileType::SymLink { is_dir: true } => flags.follow_symlinks.0,
+            _ => false,
+        };
+        is_dir && (flags.include_snapshots.0 || !is_snapshot_dir(&self.path))
+    }
+
+    /// Lists the content of `self`, `ancestors` holding the directories it is listed from. Their
+    /// identifiers are unknown on Windows, where the cycles are not detected.
+    fn walk(
+        &self,
+        depth: usize,
+        flags: &Flags,
+        cache: Option<&dyn GitStatusProvider>,
+        budget: &MemoryBudget,
+        ancestors: &[Ancestor],
     ) -> Result<Option<Vec<Meta>>, std::io::Error> {
         if depth == 0 {
             return Ok(None);
@@ -72,37 +210,95 @@ impl Meta {
         match self.file_type {
             FileType::Directory { .. } => (),
             FileType::SymLink { is_dir: true } => {
-                if flags.layout == Layout::OneLine {
+                // A link given by the user is listed as its target unless in the long layout,
+                // while the links found when recursing are only entered if asked for.
+                let follow = if ancestors.is_empty() {
+                    flags.layout != Layout::OneLine
+                } else {
+                    flags.follow_symlinks.0
+                };
+                if !follow {
                     return Ok(None);
                 }
             }
             _ => return Ok(None),
         }
 
-        let entries = match self.path.read_dir() {
+        let ancestor = Ancestor::new(&self.path, flags);
+        let id = ancestor.id;
+        if id.is_some() && ancestors.iter().any(|ancestor| ancestor.id == id) {
+            print_error!(
+                "{}: not listing a directory which is already listed.",
+                self.path.display()
+            );
+            ExitCode::MajorIssue.report();
+            return Ok(None);
+        }
+        let mut ancestors = ancestors.to_vec();
+        ancestors.push(ancestor);
+
+        let entries = match readdir::read_dir(&self.path) {
             Ok(entries) => entries,
+            // The directory has been removed since it was found in its parent.
+            Err(err) if is_vanished(&err) && ancestors.len() > 1 => {
+                log::debug!("{}: vanished before being listed", self.path.display());
+                return Ok(None);
+            }
+            // The sub directories are listed with the reason, see [Meta::list_entries].
+            Err(err) if ancestors.len() > 1 => return Err(err),
             Err(err) => {
                 print_error!("{}: {}.", self.path.display(), err);
+                ExitCode::MajorIssue.report();
                 return Ok(None);
             }
         };
 
         let mut content: Vec<Meta> = Vec::new();
-
         if Display::All == flags.display && flags.layout != Layout::Tree {
             let mut current_meta;
 
             current_meta = self.clone();
             current_meta.name.name = ".".to_owned();
 
-            let mut parent_meta =
-                Self::from_path(&self.path.join(Component::ParentDir), flags.dereference.0)?;
+            let parent_path = self.path.join(Component::ParentDir);
+            let mut parent_meta = Self::from_listed_path(
+                &parent_path,
+                flags.dereference.0
+                    && SymLink::resolves_within(&parent_path, flags.symlink_depth.0),
+                flags,
+            )?;
             parent_meta.name.name = "..".to_owned();
 
             content.push(current_meta);
             content.push(parent_meta);
         }
 
+        content.extend(self.list_entries(entries, depth, flags, cache, budget, &ancestors)?);
+        Ok(Some(content))
+    }
+
+    /// Collects the metadata of the directory `entries` of `self`, read from `ancestors`, and
+    /// of their content up to `depth` levels down.
+    ///
+    /// The entries removed between the reading of the directory and of their metadata are
+    /// skipped, as if they had been removed before, with a debug log only. The directories whose
+    /// content can not be read are listed with the reason in their `content_error`, instead of
+    /// an error being printed.
+    fn list_entries(
+        &self,
+        entries: impl Iterator<Item = Result<DirEntry, Error>>,
+        depth: usize,
+        flags: &Flags,
+        cache: Option<&dyn GitStatusProvider>,
+        budget: &MemoryBudget,
+        ancestors: &[Ancestor],
+    ) -> Result<Vec<Meta>, Error> {
+        let mut content: Vec<Meta> = Vec::new();
+        let hidden = match flags.display {
+            Display::VisibleOnly => Some(Hidden::for_directory(&self.path)),
+            _ => None,
+        };
+
         for entry in entries {
             let entry = entry?;
             let path = entry.path();
@@ -115,23 +311,47 @@ impl Meta {
                 continue;
             }
 
-            if let Display::VisibleOnly = flags.display {
-                if name.to_string_lossy().starts_with('.') {
+            let is_directory = match entry.is_dir() {
+                Ok(is_dir) => is_dir,
+                Err(err) if is_vanished(&err) => {
+                    log::debug!("{}: vanished while listed", path.display());
                     continue;
                 }
+                Err(err) => return Err(err),
+            };
+
+            if flags.exclude_from.is_excluded(&path, is_directory) {
+                continue;
             }
 
-            let mut entry_meta = match Self::from_path(&path, flags.dereference.0) {
+            let layers = ancestors
+                .iter()
+                .filter_map(|ancestor| ancestor.ignore.as_deref());
+            if ignore_files::is_ignored(layers, &path, is_directory) {
+                continue;
+            }
+
+            if let Some(hidden) = &hidden {
+                if hidden.is_hidden(&path, name) {
+                    continue;
+                }
+            }
+
+            let dereference =
+                flags.dereference.0 && SymLink::resolves_within(&path, flags.symlink_depth.0);
+            let mut entry_meta = match Self::from_listed_path(&path, dereference, flags) {
                 Ok(res) => res,
+                Err(err) if is_vanished(&err) => {
+                    log::debug!("{}: vanished while listed", path.display());
+                    continue;
+                }
                 Err(err) => {
                     print_error!("{}: {}.", path.display(), err);
+                    ExitCode::MinorIssue.report();
                     continue;
                 }
             };
 
-            let is_directory =entry.file_type()?.is_dir(); 
-            
-            
             // skip files for --tree -d
             if flags.layout == Layout::Tree {
                 if let Display::DirectoryOnly = flags.display {
@@ -141,72 +361,225 @@ impl Meta {
                 }
             }
 
-            match entry_meta.recurse_into(depth - 1, &flags, cache) {
-                Ok(content) => entry_meta.content = content,
-                Err(err) => {
-                    print_error!("{}: {}.", path.display(), err);
-                    continue;
-                }
-            };
+            // Snapshot directories mirror the whole file system, so they are not entered unless
+            // asked for.
+            if flags.include_snapshots.0 || !is_snapshot_dir(&path) {
+                match entry_meta.walk(depth - 1, &flags, cache, budget, ancestors) {
+                    Ok(content) => entry_meta.content = content,
+                    Err(err) if budget.is_exceeded() => return Err(err),
+                    Err(err) => {
+                        entry_meta.content_error = Some(BrokenReason::from_error(&err));
+                        ExitCode::MinorIssue.report();
+                    }
+                };
+            }
 
             #[cfg(feature = "git")]
             if let Some(cache) = cache {
-                entry_meta.git_status = match std::fs::canonicalize(&entry_meta.path) {
-                    Ok(filename) => Some(cache.get(&filename, is_directory)),
-                    Err(err) => {
-                        log::debug!("error {}", err);
-                        None
-                    }
-                }
+                entry_meta.set_git_status(cache, is_directory, flags);
             };
-            
+            #[cfg(feature = "git")]
+            entry_meta.set_git_repo(flags);
+
+            budget.charge(&entry_meta)?;
             content.push(entry_meta);
         }
 
-        Ok(Some(content))
+        Ok(content)
     }
 
-    pub fn calculate_total_size(&mut self) {
-        if let FileType::Directory { .. } = self.file_type {
-            if let Some(metas) = &mut self.content {
-                let mut size_accumulated = self.size.get_bytes();
-                for x in &mut metas.iter_mut() {
-                    x.calculate_total_size();
-                    size_accumulated += x.size.get_bytes();
+    /// Sets the git status of `self` from the `cache`, along the details asked for by the `flags`.
+    #[cfg(feature = "git")]
+    pub fn set_git_status(
+        &mut self,
+        cache: &dyn GitStatusProvider,
+        is_directory: bool,
+        flags: &Flags,
+    ) {
+        // The files under `--fd` have no path to find their repository from.
+        if readdir::is_anchored(&self.path) {
+            return;
+        }
+        self.git_status = match std::fs::canonicalize(&self.path) {
+            Ok(filename) => {
+                self.git_renamed_from = cache.renamed_from(&filename).map(Path::to_path_buf);
+                if let FileType::Directory { .. } = self.file_type {
+                    if flags.git_untracked_count.0 {
+                        self.git_untracked_count = Some(cache.untracked_count(&filename));
+                    }
+                    if flags.git_change_counts.0 {
+                        self.git_change_counts = Some(cache.change_counts(&filename));
+                    }
                 }
-                self.size = Size::new(size_accumulated);
-            } else {
-                // possibility that 'depth' limited the recursion in 'recurse_into'
-                self.size = Size::new(Meta::calculate_total_file_size(&self.path));
+                Some(cache.get(&filename, is_directory))
+            }
+            Err(err) => {
+                log::debug!("error {}", err);
+                None
+            }
+        }
+    }
+
+    /// Sets the state of the git repository of `self` when it is a directory holding one, only if
+    /// the repo block is shown or the files are sorted by it.
+    #[cfg(feature = "git")]
+    pub fn set_git_repo(&mut self, flags: &Flags) {
+        let wanted =
+            flags.blocks.0.contains(&Block::GitRepo) || flags.sorting.column == SortColumn::GitRepo;
+        if wanted && self.file_type.is_dirlike() {
+            self.git_repo = GitRepo::read(&self.path);
+        }
+    }
+
+    /// Keeps in the content of the directory, down its tree, only the entries whose name is
+    /// matched by the [Find](crate::flags::Find) flag and the directories holding such entries,
+    /// so that the entries found are shown in context.
+    pub fn retain_found(&mut self, flags: &Flags) {
+        if let Some(content) = self.content.take() {
+            let found: Vec<Meta> = content
+                .into_iter()
+                .filter_map(|mut meta| {
+                    meta.retain_found(flags);
+                    let holds_found = matches!(&meta.content, Some(content) if !content.is_empty());
+                    if holds_found || flags.find.is_match(&meta.name.name) {
+                        Some(meta)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            self.content = Some(found);
+        }
+    }
+
+    /// Sets the size of the directories to the total size of their content, counted as `du`
+    /// does: the symlinks as set by the [TotalSizeSymlinks] flag, and the hard linked files only
+    /// once, unless the [CountLinks](crate::flags::CountLinks) flag is set. The totals are summed
+    /// up from the content already walked, bottom up, only the directories the walk did not
+    /// enter being read.
+    pub fn calculate_total_size(&mut self, flags: &Flags) {
+        self.accumulate_total_size(flags, &mut HashSet::new());
+    }
+
+    /// Sets the total size of the directories, returning the size this file adds to the total of
+    /// its parent. The hard linked files, and the directories when the symlinks are followed, are
+    /// added to `counted` once they are, so they are not counted again.
+    fn accumulate_total_size(&mut self, flags: &Flags, counted: &mut HashSet<FileId>) -> u64 {
+        match self.file_type {
+            FileType::Directory { .. } => {
+                let follow = flags.total_size_symlinks == TotalSizeSymlinks::Follow;
+                if follow && self.content.is_some() {
+                    // The directory already counted through a symlink still shows its total.
+                    if let Some(id) = directory_id(&self.path) {
+                        if !counted.insert(id) {
+                            self.accumulate_total_size(flags, &mut HashSet::new());
+                            return 0;
+                        }
+                    }
+                }
+                let total = if let Some(metas) = &mut self.content {
+                    let mut total = self.size.get_bytes();
+                    for meta in metas.iter_mut() {
+                        // The . and .. entries are not part of the content, their total is theirs.
+                        if meta.name.name == "." || meta.name.name == ".." {
+                            meta.accumulate_total_size(flags, &mut HashSet::new());
+                        } else {
+                            total += meta.accumulate_total_size(flags, counted);
+                        }
+                    }
+                    total
+                } else if self.content_error.is_some() {
+                    // The walk already failed to read it, it is not read again.
+                    self.size.get_bytes()
+                } else if flags.include_snapshots.0 || !is_snapshot_dir(&self.path) {
+                    // possibility that 'depth' limited the recursion in 'recurse_into'
+                    Meta::calculate_total_file_size(&self.path, flags, counted)
+                } else {
+                    self.size.get_bytes()
+                };
+                self.size = Size::new(total);
+                total
             }
+            FileType::SymLink { .. } => match flags.total_size_symlinks {
+                TotalSizeSymlinks::Link => self.size.get_bytes(),
+                TotalSizeSymlinks::Skip => 0,
+                TotalSizeSymlinks::Follow => {
+                    Meta::calculate_total_file_size(&self.path, flags, counted)
+                }
+            },
+            _ => match self.links.count() {
+                Some(links) if links > 1 && !flags.count_links.0 => {
+                    match readdir::metadata(&self.path)
+                        .ok()
+                        .and_then(|m| linked_file_id(&m))
+                    {
+                        Some(id) if !counted.insert(id) => 0,
+                        _ => self.size.get_bytes(),
+                    }
+                }
+                _ => self.size.get_bytes(),
+            },
         }
     }
 
-    fn calculate_total_file_size(path: &PathBuf) -> u64 {
-        let metadata = if read_link(&path).is_ok() {
+    /// The total size of the file at `path`, counted as by
+    /// [accumulate_total_size](Meta::accumulate_total_size).
+    fn calculate_total_file_size(path: &Path, flags: &Flags, counted: &mut HashSet<FileId>) -> u64 {
+        let follow = flags.total_size_symlinks == TotalSizeSymlinks::Follow;
+        let metadata = if readdir::read_link(path).is_ok() && !follow {
             // If the file is a link, retrieve the metadata without following
             // the link.
-            path.symlink_metadata()
+            readdir::symlink_metadata(path)
         } else {
-            path.metadata()
+            // The links which can not be followed are counted as links.
+            readdir::metadata(path).or_else(|_| readdir::symlink_metadata(path))
         };
         let metadata = match metadata {
             Ok(meta) => meta,
+            Err(err) if is_vanished(&err) => {
+                log::debug!("{}: vanished while measured", path.display());
+                return 0;
+            }
             Err(err) => {
                 print_error!("{}: {}.", path.display(), err);
+                ExitCode::MinorIssue.report();
                 return 0;
             }
         };
         let file_type = metadata.file_type();
-        if file_type.is_file() {
-            metadata.len()
+        if file_type.is_symlink() {
+            match flags.total_size_symlinks {
+                TotalSizeSymlinks::Skip => 0,
+                _ => metadata.len(),
+            }
+        } else if file_type.is_file() {
+            match linked_file_id(&metadata) {
+                Some(id) if !flags.count_links.0 && !counted.insert(id) => 0,
+                _ => metadata.len(),
+            }
         } else if file_type.is_dir() {
             let mut size = metadata.len();
+            if !flags.include_snapshots.0 && is_snapshot_dir(path) {
+                return size;
+            }
+            // A directory reached through several links, or through a loop, is counted once.
+            if follow {
+                if let Some(id) = directory_id(path) {
+                    if !counted.insert(id) {
+                        return 0;
+                    }
+                }
+            }
 
-            let entries = match path.read_dir() {
+            let entries = match readdir::read_dir(path) {
                 Ok(entries) => entries,
+                Err(err) if is_vanished(&err) => {
+                    log::debug!("{}: vanished while measured", path.display());
+                    return size;
+                }
                 Err(err) => {
                     print_error!("{}: {}.", path.display(), err);
+                    ExitCode::MinorIssue.report();
                     return size;
                 }
             };
@@ -215,10 +588,11 @@ impl Meta {
                     Ok(entry) => entry.path(),
                     Err(err) => {
                         print_error!("{}: {}.", path.display(), err);
+                        ExitCode::MinorIssue.report();
                         continue;
                     }
                 };
-                size += Meta::calculate_total_file_size(&path);
+                size += Meta::calculate_total_file_size(&path, flags, counted);
             }
             size
         } else {
@@ -227,17 +601,54 @@ impl Meta {
     }
 
     pub fn from_path(path: &Path, dereference: bool) -> Result<Self, std::io::Error> {
+        Self::read(path, dereference, true)
+    }
+
+    /// The metadata of the file at `path` as listed with `flags`. Its capabilities and its
+    /// access control list, which take a system call each, are only read when the permission
+    /// block shows them.
+    pub fn from_listed_path(
+        path: &Path,
+        dereference: bool,
+        flags: &Flags,
+    ) -> Result<Self, std::io::Error> {
+        let permission_extras = flags.blocks.0.contains(&Block::Permission);
+        Self::read(path, dereference, permission_extras)
+    }
+
+    fn read(
+        path: &Path,
+        dereference: bool,
+        permission_extras: bool,
+    ) -> Result<Self, std::io::Error> {
         // If the file is a link then retrieve link metadata instead with target metadata (if present).
-        let (metadata, symlink_meta) = if read_link(path).is_ok() && !dereference {
-            (path.symlink_metadata()?, path.metadata().ok())
+        let (metadata, symlink_meta) = if readdir::read_link(path).is_ok() && !dereference {
+            (
+                readdir::symlink_metadata(path)?,
+                readdir::metadata(path).ok(),
+            )
         } else {
-            (path.metadata()?, None)
+            match readdir::metadata(path) {
+                Ok(metadata) => (metadata, None),
+                // The link can not be resolved, show the link itself instead of failing.
+                Err(err) if is_symlink_loop(&err) || readdir::read_link(path).is_ok() => {
+                    (readdir::symlink_metadata(path)?, None)
+                }
+                Err(err) => return Err(err),
+            }
         };
 
         #[cfg(unix)]
         let owner = Owner::from(&metadata);
+        // The capabilities and the access lists are read by path, unknown under `--fd`.
         #[cfg(unix)]
-        let permissions = Permissions::from(&metadata);
+        let permissions = if !permission_extras || readdir::is_anchored(path) {
+            Permissions::from(&metadata)
+        } else {
+            Permissions::from(&metadata)
+                .with_capabilities(path, &metadata)
+                .with_acl(path, &metadata)
+        };
 
         #[cfg(windows)]
         let (owner, permissions) = windows_utils::get_file_data(&path)?;
@@ -253,7 +664,9 @@ impl Meta {
             path: path.to_path_buf(),
             symlink: SymLink::from(path),
             size: Size::from(&metadata),
+            allocated: Size::allocated(&metadata),
             date: Date::from(&metadata),
+            created: metadata.created().ok().map(Date::from),
             indicator: Indicator::from(file_type),
             owner,
             permissions,
@@ -261,6 +674,317 @@ impl Meta {
             file_type,
             content: None,
             git_status: None,
+            git_renamed_from: None,
+            git_untracked_count: None,
+            git_change_counts: None,
+            content_error: None,
+            git_repo: None,
+            change: None,
         })
     }
 }
+
+/// The identifier of the directory at `path`, the links being followed.
+#[cfg(unix)]
+fn directory_id(path: &Path) -> Option<DirectoryId> {
+    use std::os::unix::fs::MetadataExt;
+
+    readdir::metadata(path).ok().map(|m| (m.dev(), m.ino()))
+}
+
+#[cfg(windows)]
+fn directory_id(_: &Path) -> Option<DirectoryId> {
+    None
+}
+
+/// The identifier of the file of `metadata`, when it has several hard links.
+#[cfg(unix)]
+fn linked_file_id(metadata: &Metadata) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+
+    if metadata.nlink() > 1 {
+        Some((metadata.dev(), metadata.ino()))
+    } else {
+        None
+    }
+}
+
+#[cfg(windows)]
+fn linked_file_id(_: &Metadata) -> Option<FileId> {
+    None
+}
+
+/// The memory which may be used to collect the metadata of the files, shared by the whole
+/// recursion.
+pub struct MemoryBudget {
+    limit: Option<usize>,
+    used: Cell<usize>,
+}
+
+impl MemoryBudget {
+    /// A budget of `limit` bytes, unlimited when it is [None].
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            used: Cell::new(0),
+        }
+    }
+
+    /// Accounts for the memory held by `meta`, the size of its struct and of its path and name.
+    ///
+    /// # Errors
+    ///
+    /// If this exceeds the limit. The budget then stays exceeded.
+    pub fn charge(&self, meta: &Meta) -> Result<(), Error> {
+        let size =
+            std::mem::size_of::<Meta>() + meta.path.as_os_str().len() + meta.name.file_name().len();
+        self.used.set(self.used.get().saturating_add(size));
+
+        if self.is_exceeded() {
+            Err(Error::from(ErrorKind::Other))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn is_exceeded(&self) -> bool {
+        matches!(self.limit, Some(limit) if self.used.get() > limit)
+    }
+}
+
+/// Checks whether `path` is a snapshot directory, either a NetApp `.snapshot` or a ZFS
+/// `.zfs/snapshot` one.
+fn is_snapshot_dir(path: &Path) -> bool {
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some(".snapshot") => true,
+        Some("snapshot") => {
+            path.parent().and_then(|parent| parent.file_name()) == Some(OsStr::new(".zfs"))
+        }
+        _ => false,
+    }
+}
+
+/// Checks whether `err` has been caused by the removal of the file, or of one of its parents, since
+/// it has been found in its directory.
+fn is_vanished(err: &Error) -> bool {
+    err.kind() == ErrorKind::NotFound
+}
+
+/// Checks whether `err` has been caused by a chain of symbolic links that is too long to resolve.
+#[cfg(unix)]
+fn is_symlink_loop(err: &Error) -> bool {
+    err.raw_os_error() == Some(libc::ELOOP)
+}
+
+#[cfg(windows)]
+fn is_symlink_loop(_: &Error) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Ancestor, BrokenReason, MemoryBudget, Meta};
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Flags;
+    use assert_fs::prelude::*;
+    use std::fs;
+
+    fn flags() -> Flags {
+        let matches = app::build().get_matches_from_safe(vec!["lsd"]).unwrap();
+        Flags::configure_from(&matches, &Config::with_none()).unwrap()
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_from_listed_path_permission_extras() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("shared");
+        fs::write(&path, "").unwrap();
+        if !super::acl::grant_read_to_nobody(&path) {
+            return;
+        }
+
+        assert!(Meta::from_path(&path, false).unwrap().permissions.acl);
+        let meta = Meta::from_listed_path(&path, false, &flags()).unwrap();
+        assert!(!meta.permissions.acl);
+        let argv = vec!["lsd", "-l"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let long = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+        let meta = Meta::from_listed_path(&path, false, &long).unwrap();
+        assert!(meta.permissions.acl);
+    }
+
+    #[test]
+    fn test_list_entries_vanished() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("kept").touch().unwrap();
+        dir.child("file").touch().unwrap();
+        dir.child("sub/file").touch().unwrap();
+
+        // The directory is read before its entries are removed, as by a concurrent process.
+        let entries: Vec<_> = crate::readdir::read_dir(dir.path()).unwrap().collect();
+        fs::remove_file(dir.path().join("file")).unwrap();
+        fs::remove_dir_all(dir.path().join("sub")).unwrap();
+
+        let meta = Meta::from_path(dir.path(), false).unwrap();
+        let content = meta
+            .list_entries(
+                entries.into_iter(),
+                2,
+                &flags(),
+                None,
+                &MemoryBudget::new(None),
+                &[Ancestor::new(dir.path(), &flags())],
+            )
+            .unwrap();
+        let names: Vec<_> = content.iter().map(|meta| meta.name.name.as_str()).collect();
+        assert_eq!(vec!["kept"], names);
+    }
+
+    #[test]
+    fn test_walk_vanished_directory() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("sub/file").touch().unwrap();
+
+        let meta = Meta::from_path(&dir.path().join("sub"), false).unwrap();
+        fs::remove_dir_all(dir.path().join("sub")).unwrap();
+
+        let budget = MemoryBudget::new(None);
+        let ancestors = [Ancestor::new(dir.path(), &flags())];
+        assert!(meta
+            .walk(1, &flags(), None, &budget, &ancestors)
+            .unwrap()
+            .is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_unreadable_directory() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("sub/file").touch().unwrap();
+        let sub = dir.path().join("sub");
+        fs::set_permissions(&sub, fs::Permissions::from_mode(0o000)).unwrap();
+        // The permissions do not apply to root, which reads any directory.
+        let readable = sub.read_dir().is_ok();
+
+        let meta = Meta::from_path(dir.path(), false).unwrap();
+        let content = meta
+            .recurse_into(2, &flags(), None, &MemoryBudget::new(None))
+            .unwrap()
+            .unwrap();
+        fs::set_permissions(&sub, fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(1, content.len());
+        if readable {
+            assert_eq!(None, content[0].content_error);
+        } else {
+            assert!(content[0].content.is_none());
+            assert_eq!(
+                Some(BrokenReason::PermissionDenied),
+                content[0].content_error
+            );
+        }
+    }
+
+    #[test]
+    fn test_calculate_total_size_walked() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("dir/sub/file").write_str("12345").unwrap();
+        dir.child("dir/other").write_str("123").unwrap();
+
+        let flags = flags();
+        let mut meta = Meta::from_path(&dir.path().join("dir"), false).unwrap();
+        let dirs = meta.size.get_bytes();
+        let mut content = meta
+            .recurse_into(1, &flags, None, &MemoryBudget::new(None))
+            .unwrap()
+            .unwrap();
+        let sub = content
+            .iter()
+            .position(|meta| meta.name.name == "sub")
+            .unwrap();
+        let sub_size = content[sub].size.get_bytes();
+        // A file added once walked is not counted, as the walked content is summed up.
+        dir.child("dir/new").write_str("1234567").unwrap();
+        // Neither is the content of a directory the walk failed to read.
+        content[sub].content_error = Some(BrokenReason::PermissionDenied);
+        meta.content = Some(content);
+
+        meta.calculate_total_size(&flags);
+        assert_eq!(dirs + sub_size + 3, meta.size.get_bytes());
+    }
+
+    #[test]
+    fn test_retain_found() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("dir/src/main.rs").touch().unwrap();
+        dir.child("dir/src/lib.txt").touch().unwrap();
+        dir.child("dir/doc/guide.md").touch().unwrap();
+        dir.child("dir/build.rs").touch().unwrap();
+
+        let argv = vec!["lsd", "--find", r"\.rs$"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+        let mut meta = Meta::from_path(&dir.path().join("dir"), false).unwrap();
+        meta.content = meta
+            .recurse_into(3, &flags, None, &MemoryBudget::new(None))
+            .unwrap();
+
+        meta.retain_found(&flags);
+        let names = |meta: &Meta| {
+            let mut names: Vec<String> = meta
+                .content
+                .iter()
+                .flatten()
+                .map(|meta| meta.name.name.clone())
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(vec!["build.rs", "src"], names(&meta));
+        let src = meta.content.iter().flatten().find(|m| m.name.name == "src");
+        assert_eq!(vec!["main.rs"], names(src.unwrap()));
+    }
+
+    #[test]
+    fn test_calculate_total_file_size_vanished() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("sub/file").write_str("content").unwrap();
+
+        let mut meta = Meta::from_path(&dir.path().join("sub"), false).unwrap();
+        fs::remove_dir_all(dir.path().join("sub")).unwrap();
+
+        meta.calculate_total_size(&flags());
+        assert_eq!(0, meta.size.get_bytes());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_calculate_total_size_links() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("dir/file").write_str("1234567890").unwrap();
+        dir.child("dir/sub/other").write_str("12345").unwrap();
+        fs::hard_link(dir.path().join("dir/file"), dir.path().join("dir/hard")).unwrap();
+        std::os::unix::fs::symlink("sub", dir.path().join("dir/link")).unwrap();
+
+        let total = |args: &[&str]| {
+            let argv = [&["lsd"], args].concat();
+            let matches = app::build().get_matches_from_safe(argv).unwrap();
+            let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+            let mut meta = Meta::from_path(&dir.path().join("dir"), false).unwrap();
+            let sub = Meta::from_path(&dir.path().join("dir/sub"), false).unwrap();
+            let dirs = meta.size.get_bytes() + sub.size.get_bytes();
+            meta.calculate_total_size(&flags);
+            meta.size.get_bytes() - dirs
+        };
+
+        // The link to "sub" is 3 bytes long.
+        assert_eq!(10 + 5 + 3, total(&[]));
+        assert_eq!(10 + 10 + 5 + 3, total(&["--count-links"]));
+        assert_eq!(10 + 5, total(&["--total-size-symlinks", "skip"]));
+        assert_eq!(10 + 5, total(&["--total-size-symlinks", "follow"]));
+    }
+}
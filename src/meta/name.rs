@@ -1,9 +1,100 @@
 use crate::color::{ColoredString, Colors, Elem};
 use crate::icon::Icons;
 use crate::meta::filetype::FileType;
+use crate::meta::kind;
+use std::borrow::Cow;
 use std::cmp::{Ordering, PartialOrd};
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::path::{Component, Path, PathBuf};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Looks up the value of the longest extension of `file_name` in `table`, whose keys are lower
+/// case extensions without their leading dot. A compound extension such as `tar.gz` is preferred
+/// to the last one, `gz`. The leading dots of a hidden file do not start an extension.
+pub fn find_by_suffix<'t, V>(file_name: &str, table: &'t HashMap<String, V>) -> Option<&'t V> {
+    suffixes(&file_name.to_lowercase()).find_map(|suffix| table.get(suffix))
+}
+
+/// The extension of `file_name` without its leading dot, a known compound extension such as
+/// `tar.gz` being preferred to the last one, `gz`, as by [find_by_suffix].
+pub fn compound_extension(file_name: &str) -> Option<&str> {
+    suffixes(file_name)
+        .find(|suffix| kind::is_known_extension(&suffix.to_lowercase()))
+        .or_else(|| suffixes(file_name).last())
+}
+
+/// The extensions `file_name` may have, the longest first, without their leading dot.
+fn suffixes(file_name: &str) -> impl Iterator<Item = &str> {
+    let stem = file_name.trim_start_matches('.');
+
+    stem.match_indices('.')
+        .map(move |(index, _)| &stem[index + 1..])
+        .filter(|suffix| !suffix.is_empty())
+}
+
+/// The first of the 256 code points standing for the bytes of the names which are not valid UTF-8
+/// in the raw mode, taken at the end of the last private use plane. They are as wide as the
+/// character the terminal shows for such a byte, and turned back into it by [raw_bytes].
+const RAW_BYTES: u32 = 0x10_FF00;
+
+/// The text of a name or a path, its bytes which are not valid UTF-8 being written as `\xNN`
+/// escapes, or kept as the code points of [RAW_BYTES] when `raw` is set.
+#[cfg(unix)]
+pub fn os_str_text(text: &OsStr, raw: bool) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut bytes = text.as_bytes();
+    let mut result = String::with_capacity(bytes.len());
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(valid) => {
+                result.push_str(valid);
+                return result;
+            }
+            Err(err) => {
+                let (valid, rest) = bytes.split_at(err.valid_up_to());
+                // The bytes which were checked are valid UTF-8.
+                result.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                let invalid = err.error_len().unwrap_or(rest.len());
+                for byte in &rest[..invalid] {
+                    match std::char::from_u32(RAW_BYTES + u32::from(*byte)) {
+                        Some(c) if raw => result.push(c),
+                        _ => result.push_str(&format!("\\x{:02x}", byte)),
+                    }
+                }
+                bytes = &rest[invalid..];
+            }
+        }
+    }
+}
+
+/// The names are made of UTF-16 code units on Windows, the few which can not be decoded are
+/// replaced.
+#[cfg(windows)]
+pub fn os_str_text(text: &OsStr, _raw: bool) -> String {
+    text.to_string_lossy().to_string()
+}
+
+/// The bytes of `text`, the code points of [RAW_BYTES] being turned back into the bytes they
+/// stand for.
+pub fn raw_bytes(text: &str) -> Cow<'_, [u8]> {
+    let is_raw = |c: char| (RAW_BYTES..=RAW_BYTES + 0xff).contains(&(c as u32));
+    if !text.chars().any(is_raw) {
+        return Cow::Borrowed(text.as_bytes());
+    }
+
+    let mut bytes = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        if is_raw(c) {
+            bytes.push((c as u32 - RAW_BYTES) as u8);
+        } else {
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+        }
+    }
+    Cow::Owned(bytes)
+}
 
 #[derive(Debug)]
 pub enum DisplayOption<'a> {
@@ -22,10 +113,7 @@ pub struct Name {
 
 impl Name {
     pub fn new(path: &Path, file_type: FileType) -> Self {
-        let name = match path.file_name() {
-            Some(name) => name.to_string_lossy().to_string(),
-            None => path.to_string_lossy().to_string(),
-        };
+        let name = os_str_text(path.file_name().unwrap_or(path.as_os_str()), false);
 
         let extension = path
             .extension()
@@ -96,31 +184,69 @@ impl Name {
         }
     }
 
+    /// The name as displayed, without icon nor color.
+    pub fn plain(&self, display_option: &DisplayOption, raw: bool) -> String {
+        self.text(display_option, raw)
+    }
+
+    /// The name as displayed, its control characters and the bytes which are not valid UTF-8
+    /// being escaped, or as they are in the `raw` mode, see [os_str_text].
+    fn text(&self, display_option: &DisplayOption, raw: bool) -> String {
+        let text = match display_option {
+            DisplayOption::FileName => match self.path.file_name() {
+                Some(name) => os_str_text(name, raw),
+                None => self.name.clone(),
+            },
+            DisplayOption::Relative { base_path } => {
+                os_str_text(self.relative_path(base_path).as_os_str(), raw)
+            }
+            DisplayOption::None => os_str_text(self.path.as_os_str(), raw),
+        };
+        if raw {
+            text
+        } else {
+            self.escape(&text)
+        }
+    }
+
+    #[cfg(test)]
     pub fn render(
         &self,
         colors: &Colors,
         icons: &Icons,
         display_option: &DisplayOption,
     ) -> ColoredString {
-        let content = match display_option {
-            DisplayOption::FileName => {
-                format!("{}{}", icons.get(self), self.escape(self.file_name()))
-            }
-            DisplayOption::Relative { base_path } => format!(
-                "{}{}",
-                icons.get(self),
-                self.escape(&self.relative_path(base_path).to_string_lossy())
-            ),
-            DisplayOption::None => format!(
-                "{}{}",
-                icons.get(self),
-                self.escape(&self.path.to_string_lossy())
-            ),
-        };
+        self.render_truncated(colors, icons, display_option, None, false)
+    }
+
+    /// Renders the name with its icon and color, shortening it by the `truncation` if given,
+    /// and without escaping it in the `raw` mode. The icon is not counted in the width.
+    pub fn render_truncated(
+        &self,
+        colors: &Colors,
+        icons: &Icons,
+        display_option: &DisplayOption,
+        truncation: Option<Truncation>,
+        raw: bool,
+    ) -> ColoredString<'_> {
+        let mut name = self.text(display_option, raw);
+        if let Some(truncation) = truncation {
+            name = truncation.apply(name);
+        }
+        let content = format!("{}{}", icons.get(self), name);
 
         let elem = match self.file_type {
             FileType::CharDevice => Elem::CharDevice,
-            FileType::Directory { uid } => Elem::Dir { uid },
+            FileType::Directory {
+                setgid,
+                sticky,
+                other_writable,
+            } => match (sticky, other_writable) {
+                (true, true) => Elem::StickyOtherWritableDir,
+                (false, true) => Elem::OtherWritableDir,
+                (true, false) => Elem::StickyDir,
+                (false, false) => Elem::Dir { setgid },
+            },
             FileType::SymLink { .. } => Elem::SymLink,
             FileType::File { uid, exec } => Elem::File { uid, exec },
             _ => Elem::File {
@@ -147,6 +273,88 @@ impl Ord for Name {
     }
 }
 
+/// How the names longer than a width are shortened.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Truncation {
+    /// At their end, see [truncate].
+    End(usize),
+    /// In their middle, see [truncate_middle].
+    Middle(usize),
+}
+
+impl Truncation {
+    pub fn apply(self, name: String) -> String {
+        match self {
+            Self::End(width) => truncate(name, width),
+            Self::Middle(width) => truncate_middle(name, width),
+        }
+    }
+}
+
+/// Shortens `name` to `width` columns, its end being replaced by an ellipsis.
+pub fn truncate(name: String, width: usize) -> String {
+    if UnicodeWidthStr::width(&*name) <= width {
+        return name;
+    }
+
+    let mut truncated = String::new();
+    let mut truncated_width = 0;
+    for c in name.chars() {
+        let char_width = UnicodeWidthChar::width(c).unwrap_or(0);
+        if truncated_width + char_width + 1 > width {
+            break;
+        }
+        truncated.push(c);
+        truncated_width += char_width;
+    }
+    truncated.push('\u{2026}'); // …
+
+    truncated
+}
+
+/// Shortens `name` to `width` columns, its middle being replaced by an ellipsis. Its end is kept
+/// along its start, at least as wide as its [compound_extension] when it fits, so that the names sharing a
+/// long prefix can still be told apart.
+pub fn truncate_middle(name: String, width: usize) -> String {
+    if UnicodeWidthStr::width(&*name) <= width {
+        return name;
+    }
+
+    let available = width.saturating_sub(1);
+    let extension =
+        compound_extension(&name).map_or(0, |extension| UnicodeWidthStr::width(extension) + 1);
+    let end_width = if extension < available {
+        extension.max(available / 2)
+    } else {
+        available / 2
+    };
+
+    let mut end = Vec::new();
+    let mut kept_width = 0;
+    for c in name.chars().rev() {
+        let char_width = UnicodeWidthChar::width(c).unwrap_or(0);
+        if kept_width + char_width > end_width {
+            break;
+        }
+        end.push(c);
+        kept_width += char_width;
+    }
+
+    let mut truncated = String::new();
+    for c in name.chars() {
+        let char_width = UnicodeWidthChar::width(c).unwrap_or(0);
+        if kept_width + char_width > available {
+            break;
+        }
+        truncated.push(c);
+        kept_width += char_width;
+    }
+    truncated.push('\u{2026}'); // …
+    truncated.extend(end.into_iter().rev());
+
+    truncated
+}
+
 impl PartialOrd for Name {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         self.name
@@ -164,15 +372,21 @@ impl PartialEq for Name {
 #[cfg(test)]
 mod test {
     use super::DisplayOption;
-    use super::Name;
+    use super::{compound_extension, find_by_suffix, Name, Truncation};
+    #[cfg(unix)]
+    use super::{os_str_text, raw_bytes};
     use crate::color::{self, Colors};
+    use crate::flags::color::{Background, ColorValue, SetidBackgrounds};
+    use crate::flags::extensions::{ExtensionStyle, Extensions};
     use crate::icon::{self, Icons};
     use crate::meta::FileType;
     use crate::meta::Meta;
     #[cfg(unix)]
     use crate::meta::Permissions;
     use ansi_term::Colour;
+    use lscolors::LsColors;
     use std::cmp::Ordering;
+    use std::collections::HashMap;
     use std::fs::{self, File};
     #[cfg(unix)]
     use std::os::unix::fs::symlink;
@@ -181,6 +395,61 @@ mod test {
     use std::process::Command;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_truncate() {
+        for (name, width, expected) in &[
+            ("short", 8, "short"),
+            ("exactly8", 8, "exactly8"),
+            ("longer_name", 8, "longer_\u{2026}"),
+            ("日本語のファイル", 8, "日本語\u{2026}"),
+            ("name", 0, "\u{2026}"),
+        ] {
+            assert_eq!(*expected, super::truncate(name.to_string(), *width));
+        }
+    }
+
+    #[test]
+    fn test_truncate_middle() {
+        for (name, width, expected) in &[
+            ("short.txt", 9, "short.txt"),
+            (
+                "long_prefix_and_suffix.tar.gz",
+                17,
+                "long_pre\u{2026}x.tar.gz",
+            ),
+            ("a_long_description.xlsx", 12, "a_long\u{2026}.xlsx"),
+            ("file_with_a_long_name.tar.gz", 12, "file\u{2026}.tar.gz"),
+            ("kept_extension.extension", 14, "kep\u{2026}.extension"),
+            ("no_extension_at_all", 8, "no_e\u{2026}all"),
+            ("日本語のファイル.txt", 11, "日本語\u{2026}.txt"),
+            ("name", 0, "\u{2026}"),
+        ] {
+            assert_eq!(*expected, super::truncate_middle(name.to_string(), *width));
+        }
+    }
+
+    #[test]
+    fn test_render_truncated_keeps_icon_and_color() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file_path = tmp_dir.path().join("a_long_file_name.txt");
+        File::create(&file_path).expect("failed to create file");
+        let meta = Meta::from_path(&file_path, false).unwrap();
+
+        let colors = Colors::new(color::Theme::NoLscolors);
+        let icons = Icons::new(icon::Theme::Fancy, " ".to_string());
+
+        assert_eq!(
+            Colour::Fixed(184).paint("\u{f15c} a_lon\u{2026}"),
+            meta.name.render_truncated(
+                &colors,
+                &icons,
+                &DisplayOption::FileName,
+                Some(Truncation::End(6)),
+                false
+            )
+        );
+    }
+
     #[test]
     #[cfg(unix)] // Windows uses different default permissions
     fn test_print_file_name() {
@@ -202,6 +471,233 @@ mod test {
         );
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_print_file_name_with_extension_style() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let mut styles = HashMap::new();
+        styles.insert(
+            "tar.gz".to_string(),
+            ExtensionStyle {
+                icon: Some("T".to_string()),
+                color: Some(ColorValue::Fixed(172)),
+            },
+        );
+        styles.insert(
+            "gz".to_string(),
+            ExtensionStyle {
+                icon: Some("G".to_string()),
+                color: Some(ColorValue::Fixed(1)),
+            },
+        );
+        let extensions = Extensions(styles);
+        let icons = Icons::new(icon::Theme::Fancy, " ".to_string()).with_extensions(&extensions);
+        let colors = Colors::new(color::Theme::NoLscolors).with_extensions(&extensions);
+
+        let file_path = tmp_dir.path().join("backup.tar.gz");
+        File::create(&file_path).expect("failed to create file");
+        let meta = file_path.metadata().expect("failed to get metas");
+        let file_type = FileType::new(&meta, None, &Permissions::from(&meta));
+        let name = Name::new(&file_path, file_type);
+
+        assert_eq!(
+            Colour::Fixed(172).paint("T backup.tar.gz"),
+            name.render(&colors, &icons, &DisplayOption::FileName)
+        );
+    }
+
+    #[test]
+    fn test_print_file_name_with_builtin_extension_color() {
+        // The built-in colors are only used when LS_COLORS is not set.
+        if std::env::var_os("LS_COLORS").is_some() {
+            return;
+        }
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+        let colors = Colors::new(color::Theme::Default);
+
+        let file_path = tmp_dir.path().join("photo.webp");
+        File::create(&file_path).expect("failed to create file");
+        let meta = file_path.metadata().expect("failed to get metas");
+        let file_type = FileType::new(&meta, None, &Permissions::from(&meta));
+        let name = Name::new(&file_path, file_type);
+
+        assert_eq!(
+            Colour::Purple.bold().paint("photo.webp"),
+            name.render(&colors, &icons, &DisplayOption::FileName)
+        );
+    }
+
+    #[test]
+    fn test_print_file_name_with_extension_color_over_lscolors() {
+        let mut styles = HashMap::new();
+        styles.insert(
+            "tar.gz".to_string(),
+            ExtensionStyle {
+                icon: None,
+                color: Some(ColorValue::Fixed(172)),
+            },
+        );
+        let extensions = Extensions(styles);
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+        // The default database of `lscolors`, which styles the archives, is the one used when
+        // LS_COLORS is not set.
+        let colors = Colors::new(color::Theme::Default)
+            .with_lscolors(lscolors::LsColors::default())
+            .with_extensions(&extensions);
+        let file_type = FileType::File {
+            uid: false,
+            exec: false,
+        };
+        let name = Name::new(Path::new("backup.tar.gz"), file_type);
+
+        assert_eq!(
+            Colour::Fixed(172).paint("backup.tar.gz"),
+            name.render(&colors, &icons, &DisplayOption::FileName)
+        );
+    }
+
+    #[test]
+    fn test_print_setuid_file_name() {
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+        let file_type = FileType::File {
+            uid: true,
+            exec: true,
+        };
+        // The name is only rendered, so the file does not need to exist.
+        let name = Name::new(Path::new("passwd"), file_type);
+
+        let colors = Colors::new(color::Theme::NoLscolors);
+        assert_eq!(
+            Colour::Fixed(40).on(Colour::Fixed(124)).paint("passwd"),
+            name.render(&colors, &icons, &DisplayOption::FileName)
+        );
+
+        let backgrounds = SetidBackgrounds {
+            file_setuid: Some(Background::None),
+            dir_setgid: Some(Background::Color(ColorValue::Fixed(22))),
+        };
+        let colors = Colors::new(color::Theme::NoLscolors).with_setid_backgrounds(&backgrounds);
+        assert_eq!(
+            Colour::Fixed(40).paint("passwd"),
+            name.render(&colors, &icons, &DisplayOption::FileName)
+        );
+    }
+
+    #[test]
+    fn test_print_setgid_dir_name() {
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+        let file_type = FileType::Directory {
+            setgid: true,
+            sticky: false,
+            other_writable: false,
+        };
+        let name = Name::new(Path::new("shared"), file_type);
+
+        // The setgid directories are only highlighted when a background is configured for them.
+        let colors = Colors::new(color::Theme::NoLscolors);
+        assert_eq!(
+            Colour::Fixed(33).paint("shared"),
+            name.render(&colors, &icons, &DisplayOption::FileName)
+        );
+
+        let backgrounds = SetidBackgrounds {
+            file_setuid: None,
+            dir_setgid: Some(Background::Color(ColorValue::Fixed(22))),
+        };
+        let colors = Colors::new(color::Theme::NoLscolors).with_setid_backgrounds(&backgrounds);
+        assert_eq!(
+            Colour::Fixed(33).on(Colour::Fixed(22)).paint("shared"),
+            name.render(&colors, &icons, &DisplayOption::FileName)
+        );
+    }
+
+    #[test]
+    fn test_print_dir_name_by_permissions() {
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+        let colors = Colors::new(color::Theme::NoLscolors);
+        let render = |sticky, other_writable| {
+            let file_type = FileType::Directory {
+                setgid: false,
+                sticky,
+                other_writable,
+            };
+            // The name is only rendered, so the directory does not need to exist.
+            let name = Name::new(Path::new("tmp"), file_type);
+            name.render(&colors, &icons, &DisplayOption::FileName)
+                .to_string()
+        };
+
+        assert_eq!(
+            Colour::Fixed(33).paint("tmp").to_string(),
+            render(false, false)
+        );
+        assert_eq!(
+            Colour::Fixed(75).paint("tmp").to_string(),
+            render(true, false)
+        );
+        assert_eq!(
+            Colour::Fixed(34).paint("tmp").to_string(),
+            render(false, true)
+        );
+        assert_eq!(
+            Colour::Fixed(35).paint("tmp").to_string(),
+            render(true, true)
+        );
+    }
+
+    #[test]
+    fn test_print_file_name_with_extension_true_color() {
+        let mut styles = HashMap::new();
+        styles.insert(
+            "rs".to_string(),
+            ExtensionStyle {
+                icon: Some("\u{e7a8}".to_string()),
+                color: Some(ColorValue::Rgb(0xde, 0xa5, 0x84)),
+            },
+        );
+        let extensions = Extensions(styles);
+        let icons = Icons::new(icon::Theme::Fancy, " ".to_string()).with_extensions(&extensions);
+        let colors = Colors::new(color::Theme::NoLscolors).with_extensions(&extensions);
+        let file_type = FileType::File {
+            uid: false,
+            exec: false,
+        };
+        let name = Name::new(Path::new("main.rs"), file_type);
+
+        // The true color is only kept by the terminals showing them.
+        let colour = match std::env::var("COLORTERM").as_deref() {
+            Ok("truecolor") | Ok("24bit") => Colour::RGB(0xde, 0xa5, 0x84),
+            _ => Colour::Fixed(180),
+        };
+        assert_eq!(
+            colour.paint("\u{e7a8} main.rs"),
+            name.render(&colors, &icons, &DisplayOption::FileName)
+        );
+    }
+
+    #[test]
+    fn test_print_file_name_with_lscolors() {
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+        let colors = Colors::new(color::Theme::NoLscolors)
+            .with_lscolors(LsColors::from_string("*.rs=31:*.md=01;34"));
+        let render = |name: &str| {
+            let file_type = FileType::File {
+                uid: false,
+                exec: false,
+            };
+            Name::new(Path::new(name), file_type)
+                .render(&colors, &icons, &DisplayOption::FileName)
+                .to_string()
+        };
+
+        assert_eq!(Colour::Red.paint("main.rs").to_string(), render("main.rs"));
+        assert_eq!(
+            Colour::Blue.bold().paint("README.md").to_string(),
+            render("README.md")
+        );
+    }
+
     #[test]
     fn test_print_dir_name() {
         let tmp_dir = tempdir().expect("failed to create temp dir");
@@ -549,4 +1045,64 @@ mod test {
             name.render(&colors, &icons, &DisplayOption::FileName)
         );
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_invalid_utf8_in_filename() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let text = OsStr::from_bytes(b"caf\xe9");
+        assert_eq!("caf\\xe9", os_str_text(text, false));
+        assert_eq!(&b"caf\xe9"[..], &*raw_bytes(&os_str_text(text, true)));
+        assert_eq!(&b"caf\\xe9"[..], &*raw_bytes(&os_str_text(text, false)));
+
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let icons = Icons::new(icon::Theme::NoIcon, " ".to_string());
+        let colors = Colors::new(color::Theme::NoColor);
+        let names: Vec<Name> = [&b"caf\xe9"[..], &b"caf\xe8"[..]]
+            .iter()
+            .map(|bytes| {
+                let file_path = tmp_dir.path().join(OsStr::from_bytes(bytes));
+                File::create(&file_path).expect("failed to create file");
+                let meta = file_path.metadata().expect("failed to get metas");
+                let file_type = FileType::new(&meta, None, &Permissions::from(&meta));
+                Name::new(&file_path, file_type)
+            })
+            .collect();
+
+        assert_eq!(
+            "caf\\xe9",
+            names[0]
+                .render(&colors, &icons, &DisplayOption::FileName)
+                .to_string()
+        );
+        assert_eq!(Ordering::Greater, names[0].cmp(&names[1]));
+    }
+
+    #[test]
+    fn test_find_by_suffix() {
+        let mut table = HashMap::new();
+        table.insert("gz".to_string(), 1);
+        table.insert("tar.gz".to_string(), 2);
+        table.insert("bashrc".to_string(), 3);
+
+        assert_eq!(Some(&2), find_by_suffix("archive.tar.gz", &table));
+        assert_eq!(Some(&2), find_by_suffix("Archive.TAR.GZ", &table));
+        assert_eq!(Some(&1), find_by_suffix("archive.gz", &table));
+        assert_eq!(Some(&2), find_by_suffix("v1.2.tar.gz", &table));
+        assert_eq!(None, find_by_suffix("archive.tar.", &table));
+        assert_eq!(None, find_by_suffix("tar", &table));
+        assert_eq!(None, find_by_suffix(".bashrc", &table));
+    }
+
+    #[test]
+    fn test_compound_extension() {
+        assert_eq!(Some("tar.gz"), compound_extension("v1.2.tar.gz"));
+        assert_eq!(Some("TAR.GZ"), compound_extension("Archive.TAR.GZ"));
+        assert_eq!(Some("gz"), compound_extension("notes.txt.gz"));
+        assert_eq!(Some("rs"), compound_extension("main.rs"));
+        assert_eq!(None, compound_extension("archive."));
+        assert_eq!(None, compound_extension(".bashrc"));
+    }
 }
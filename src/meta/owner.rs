@@ -1,15 +1,37 @@
 use crate::color::{ColoredString, Colors, Elem};
+use crate::flags::Flags;
+#[cfg(unix)]
+use crate::meta::accounts::{self, AccountProvider};
+#[cfg(unix)]
+use std::cell::RefCell;
+#[cfg(unix)]
+use std::collections::HashMap;
 #[cfg(unix)]
 use std::fs::Metadata;
 
+// Resolving an id can be slow (LDAP, NIS...), so each one is only resolved once. The lookups all
+// happen on the main thread, which makes these caches process-wide.
+#[cfg(unix)]
+thread_local! {
+    static PROVIDERS: Vec<Box<dyn AccountProvider>> = accounts::default_providers();
+    static USER_NAMES: RefCell<HashMap<u32, Option<String>>> = RefCell::new(HashMap::new());
+    static GROUP_NAMES: RefCell<HashMap<u32, Option<String>>> = RefCell::new(HashMap::new());
+}
+
 #[derive(Clone, Debug)]
 pub struct Owner {
+    #[cfg(unix)]
+    uid: u32,
+    #[cfg(unix)]
+    gid: u32,
+    #[cfg(windows)]
     user: String,
+    #[cfg(windows)]
     group: String,
 }
 
 impl Owner {
-    #[cfg_attr(unix, allow(dead_code))]
+    #[cfg(windows)]
     pub fn new(user: String, group: String) -> Self {
         Self { user, group }
     }
@@ -19,28 +41,162 @@ impl Owner {
 impl<'a> From<&'a Metadata> for Owner {
     fn from(meta: &Metadata) -> Self {
         use std::os::unix::fs::MetadataExt;
-        use users::{get_group_by_gid, get_user_by_uid};
 
-        let user = match get_user_by_uid(meta.uid()) {
-            Some(res) => res.name().to_string_lossy().to_string(),
-            None => meta.uid().to_string(),
-        };
+        Self {
+            uid: meta.uid(),
+            gid: meta.gid(),
+        }
+    }
+}
 
-        let group = match get_group_by_gid(meta.gid()) {
-            Some(res) => res.name().to_string_lossy().to_string(),
-            None => meta.gid().to_string(),
-        };
+#[cfg(unix)]
+impl Owner {
+    /// The user name, or the numeric uid if it can not be resolved.
+    pub fn user(&self) -> String {
+        let name = USER_NAMES.with(|names| {
+            names
+                .borrow_mut()
+                .entry(self.uid)
+                .or_insert_with(|| {
+                    PROVIDERS.with(|providers| providers.iter().find_map(|p| p.user_name(self.uid)))
+                })
+                .clone()
+        });
+        name.unwrap_or_else(|| self.uid.to_string())
+    }
 
-        Self { user, group }
+    /// The group name, or the numeric gid if it can not be resolved.
+    pub fn group(&self) -> String {
+        let name = GROUP_NAMES.with(|names| {
+            names
+                .borrow_mut()
+                .entry(self.gid)
+                .or_insert_with(|| {
+                    PROVIDERS
+                        .with(|providers| providers.iter().find_map(|p| p.group_name(self.gid)))
+                })
+                .clone()
+        });
+        name.unwrap_or_else(|| self.gid.to_string())
+    }
+
+    pub fn uid(&self) -> Option<u32> {
+        Some(self.uid)
+    }
+
+    pub fn gid(&self) -> Option<u32> {
+        Some(self.gid)
+    }
+
+    fn numeric_user(&self) -> String {
+        self.uid.to_string()
+    }
+
+    fn numeric_group(&self) -> String {
+        self.gid.to_string()
+    }
+}
+
+#[cfg(windows)]
+impl Owner {
+    pub fn user(&self) -> String {
+        self.user.clone()
+    }
+
+    pub fn group(&self) -> String {
+        self.group.clone()
+    }
+
+    pub fn uid(&self) -> Option<u32> {
+        None
+    }
+
+    pub fn gid(&self) -> Option<u32> {
+        None
+    }
+
+    // Windows has no numeric ids to show, the names are used instead.
+    fn numeric_user(&self) -> String {
+        self.user()
+    }
+
+    fn numeric_group(&self) -> String {
+        self.group()
     }
 }
 
 impl Owner {
-    pub fn render_user(&self, colors: &Colors) -> ColoredString {
-        colors.colorize(self.user.clone(), &Elem::User)
+    pub fn render_user(&self, colors: &Colors, flags: &Flags) -> ColoredString {
+        let user = if flags.numeric_uid_gid.0 {
+            self.numeric_user()
+        } else {
+            self.user()
+        };
+        colors.colorize(user, &Elem::User)
     }
 
-    pub fn render_group(&self, colors: &Colors) -> ColoredString {
-        colors.colorize(self.group.clone(), &Elem::Group)
+    pub fn render_group(&self, colors: &Colors, flags: &Flags) -> ColoredString {
+        let group = if flags.numeric_uid_gid.0 {
+            self.numeric_group()
+        } else {
+            self.group()
+        };
+        colors.colorize(group, &Elem::Group)
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod test {
+    use super::{Owner, GROUP_NAMES, USER_NAMES};
+    use crate::color::{Colors, Theme};
+    use crate::flags::{Flags, NumericUidGid};
+
+    #[test]
+    fn test_render_numeric_uid_gid() {
+        let owner = Owner {
+            uid: 4242,
+            gid: 4343,
+        };
+        let flags = Flags {
+            numeric_uid_gid: NumericUidGid(true),
+            ..Flags::default()
+        };
+        let colors = Colors::new(Theme::NoColor);
+
+        assert_eq!("4242", owner.render_user(&colors, &flags).to_string());
+        assert_eq!("4343", owner.render_group(&colors, &flags).to_string());
+    }
+
+    #[test]
+    fn test_render_root_names() {
+        let owner = Owner { uid: 0, gid: 0 };
+        let flags = Flags::default();
+        let colors = Colors::new(Theme::NoColor);
+
+        assert_eq!("root", owner.render_user(&colors, &flags).to_string());
+        assert_eq!("0", owner.numeric_user());
+    }
+
+    #[test]
+    fn test_names_are_cached() {
+        // An id which is very unlikely to exist.
+        let owner = Owner {
+            uid: 4_000_000_001,
+            gid: 4_000_000_002,
+        };
+
+        assert_eq!("4000000001", owner.user());
+        assert_eq!("4000000002", owner.group());
+        USER_NAMES.with(|names| {
+            assert_eq!(Some(&None), names.borrow().get(&4_000_000_001));
+            names
+                .borrow_mut()
+                .insert(4_000_000_001, Some("cached".to_string()));
+        });
+        GROUP_NAMES.with(|names| assert_eq!(Some(&None), names.borrow().get(&4_000_000_002)));
+
+        // The second lookup is served by the cache.
+        assert_eq!("cached", owner.user());
     }
 }
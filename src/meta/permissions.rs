@@ -1,6 +1,7 @@
 use crate::color::{ColoredString, Colors, Elem};
 use ansi_term::ANSIStrings;
 use std::fs::Metadata;
+use std::path::Path;
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub struct Permissions {
@@ -19,6 +20,11 @@ pub struct Permissions {
     pub sticky: bool,
     pub setgid: bool,
     pub setuid: bool,
+
+    /// Whether the file carries capabilities, granted to the processes executing it.
+    pub capabilities: bool,
+    /// Whether the file has an access control list granting more than its permission bits.
+    pub acl: bool,
 }
 
 impl<'a> From<&'a Metadata> for Permissions {
@@ -45,6 +51,9 @@ impl<'a> From<&'a Metadata> for Permissions {
             sticky: has_bit(modes::STICKY),
             setgid: has_bit(modes::SETGID),
             setuid: has_bit(modes::SETUID),
+
+            capabilities: false,
+            acl: false,
         }
     }
 
@@ -55,6 +64,57 @@ impl<'a> From<&'a Metadata> for Permissions {
 }
 
 impl Permissions {
+    /// Reads whether the regular file at `path` carries capabilities, from its
+    /// `security.capability` extended attribute.
+    #[cfg(target_os = "linux")]
+    pub fn with_capabilities(mut self, path: &Path, metadata: &Metadata) -> Self {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        if !metadata.is_file() {
+            return self;
+        }
+        let path = match CString::new(path.as_os_str().as_bytes()) {
+            Ok(path) => path,
+            Err(_) => return self,
+        };
+        let name = b"security.capability\0";
+        let size = unsafe {
+            libc::lgetxattr(
+                path.as_ptr(),
+                name.as_ptr() as *const libc::c_char,
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        self.capabilities = size > 0;
+        self
+    }
+
+    /// The capabilities are only known on Linux.
+    #[cfg(not(target_os = "linux"))]
+    pub fn with_capabilities(self, _: &Path, _: &Metadata) -> Self {
+        self
+    }
+
+    /// Reads whether the file at `path` has an extended access control list, which the symlinks
+    /// shown as such do not have.
+    #[cfg(unix)]
+    pub fn with_acl(mut self, path: &Path, metadata: &Metadata) -> Self {
+        if !metadata.file_type().is_symlink() {
+            self.acl = super::acl::has_extended(path);
+        }
+        self
+    }
+
+    /// The access control lists are only read on unix.
+    #[cfg(windows)]
+    pub fn with_acl(self, _: &Path, _: &Metadata) -> Self {
+        self
+    }
+
+    /// The permissions in the symbolic notation, followed by a `+` when the file has an extended
+    /// access control list.
     pub fn render(&self, colors: &Colors) -> ColoredString {
         let bit = |bit, chr: &'static str, elem: &Elem| {
             if bit {
@@ -70,6 +130,9 @@ impl Permissions {
             bit(self.user_write, "w", &Elem::Write),
             match (self.user_execute, self.setuid) {
                 (false, false) => colors.colorize(String::from("-"), &Elem::NoAccess),
+                (true, false) if self.capabilities => {
+                    colors.colorize(String::from("x"), &Elem::Capability)
+                }
                 (true, false) => colors.colorize(String::from("x"), &Elem::Exec),
                 (false, true) => colors.colorize(String::from("S"), &Elem::ExecSticky),
                 (true, true) => colors.colorize(String::from("s"), &Elem::ExecSticky),
@@ -94,10 +157,81 @@ impl Permissions {
             },
         ];
 
-        let res = ANSIStrings(strings).to_string();
+        let mut res = ANSIStrings(strings).to_string();
+        if self.acl {
+            res += &colors.colorize(String::from("+"), &Elem::Acl).to_string();
+        }
         ColoredString::from(res)
     }
 
+    /// The permissions of each class spelled out, as in `(owner: read/write, group: read,
+    /// others: none)`, the setuid, setgid and sticky bits following the permissions of the
+    /// class they are shown in by the symbolic notation.
+    pub fn summary(&self) -> String {
+        let class = |name: &str, read: bool, write: bool, execute: bool, special: (bool, &str)| {
+            let granted: Vec<&str> = [
+                (read, "read"),
+                (write, "write"),
+                (execute, "execute"),
+                special,
+            ]
+            .iter()
+            .filter(|(bit, _)| *bit)
+            .map(|(_, word)| *word)
+            .collect();
+            if granted.is_empty() {
+                format!("{}: none", name)
+            } else {
+                format!("{}: {}", name, granted.join("/"))
+            }
+        };
+
+        format!(
+            "({}, {}, {})",
+            class(
+                "owner",
+                self.user_read,
+                self.user_write,
+                self.user_execute,
+                (self.setuid, "setuid")
+            ),
+            class(
+                "group",
+                self.group_read,
+                self.group_write,
+                self.group_execute,
+                (self.setgid, "setgid")
+            ),
+            class(
+                "others",
+                self.other_read,
+                self.other_write,
+                self.other_execute,
+                (self.sticky, "sticky")
+            ),
+        )
+    }
+
+    /// The permission bits, as in the octal notation of `chmod`.
+    pub fn mode(&self) -> u32 {
+        [
+            self.setuid,
+            self.setgid,
+            self.sticky,
+            self.user_read,
+            self.user_write,
+            self.user_execute,
+            self.group_read,
+            self.group_write,
+            self.group_execute,
+            self.other_read,
+            self.other_write,
+            self.other_execute,
+        ]
+        .iter()
+        .fold(0, |mode, &bit| mode << 1 | bit as u32)
+    }
+
     pub fn is_executable(&self) -> bool {
         self.user_execute || self.group_execute || self.other_execute
     }
@@ -127,3 +261,74 @@ mod modes {
     pub const SETGID: Mode = libc::S_ISGID as Mode;
     pub const SETUID: Mode = libc::S_ISUID as Mode;
 }
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::Permissions;
+    use crate::color::{Colors, Theme};
+    use ansi_term::Colour;
+    use std::fs::{self, File};
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_render_capabilities() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file_path = tmp_dir.path().join("ping");
+        File::create(&file_path).expect("failed to create file");
+        fs::set_permissions(&file_path, fs::Permissions::from_mode(0o700)).unwrap();
+        let metadata = file_path.metadata().unwrap();
+
+        let permissions = Permissions::from(&metadata).with_capabilities(&file_path, &metadata);
+        assert!(!permissions.capabilities);
+
+        let colors = Colors::new(Theme::NoLscolors);
+        let permissions = Permissions {
+            capabilities: true,
+            ..permissions
+        };
+        assert!(permissions
+            .render(&colors)
+            .to_string()
+            .contains(&format!("{}x", Colour::Fixed(208).prefix())));
+    }
+
+    #[test]
+    fn test_acl() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file_path = tmp_dir.path().join("file");
+        File::create(&file_path).expect("failed to create file");
+        fs::set_permissions(&file_path, fs::Permissions::from_mode(0o640)).unwrap();
+        let metadata = file_path.metadata().unwrap();
+
+        let permissions = Permissions::from(&metadata).with_acl(&file_path, &metadata);
+        assert!(!permissions.acl);
+
+        let colors = Colors::new(Theme::NoColor);
+        let permissions = Permissions {
+            acl: true,
+            ..permissions
+        };
+        assert_eq!("rw-r-----+", permissions.render(&colors).to_string());
+    }
+
+    #[test]
+    fn test_summary() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file_path = tmp_dir.path().join("file");
+        File::create(&file_path).expect("failed to create file");
+
+        let summary = |mode| {
+            fs::set_permissions(&file_path, fs::Permissions::from_mode(mode)).unwrap();
+            Permissions::from(&file_path.metadata().unwrap()).summary()
+        };
+        assert_eq!(
+            "(owner: read/write, group: read, others: none)",
+            summary(0o640)
+        );
+        assert_eq!(
+            "(owner: read/write/execute/setuid, group: execute, others: execute)",
+            summary(0o4711)
+        );
+    }
+}
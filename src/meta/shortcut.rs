@@ -0,0 +1,242 @@
+//! The targets of the Windows shortcuts, the `.lnk` shell link files, shown as the ones of the
+//! symbolic links when the `lnk` feature is enabled. Only the target path is read from the
+//! [MS-SHLLINK] format: the local or network path of the link info, or else the relative path of
+//! the string data.
+//!
+//! [MS-SHLLINK]: https://docs.microsoft.com/en-us/openspecs/windows_protocols/ms-shllink
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+const HEADER_SIZE: usize = 0x4c;
+const LINK_CLSID: [u8; 16] = [
+    0x01, 0x14, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46,
+];
+/// The largest shortcut read, the ones holding a target path being much smaller.
+const MAX_SIZE: u64 = 1 << 20;
+
+const HAS_LINK_TARGET_ID_LIST: u32 = 0x1;
+const HAS_LINK_INFO: u32 = 0x2;
+const HAS_NAME: u32 = 0x4;
+const HAS_RELATIVE_PATH: u32 = 0x8;
+const IS_UNICODE: u32 = 0x80;
+
+const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 0x1;
+const COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX: u32 = 0x2;
+
+/// The target path of the shortcut at `path`, [None] if it is not a shortcut or holds no path,
+/// as the ones to the special folders.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn target(path: &Path) -> Option<String> {
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    if !matches!(extension, Some(extension) if extension.eq_ignore_ascii_case("lnk")) {
+        return None;
+    }
+
+    let mut data = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(MAX_SIZE)
+        .read_to_end(&mut data)
+        .ok()?;
+    parse(&data)
+}
+
+/// Parses the target path of the shell link `data`.
+fn parse(data: &[u8]) -> Option<String> {
+    if u32_at(data, 0)? as usize != HEADER_SIZE || data.get(4..20)? != LINK_CLSID {
+        return None;
+    }
+    let flags = u32_at(data, 0x14)?;
+
+    let mut offset = HEADER_SIZE;
+    if flags & HAS_LINK_TARGET_ID_LIST != 0 {
+        offset += 2 + u16_at(data, offset)? as usize;
+    }
+    if flags & HAS_LINK_INFO != 0 {
+        let info = data.get(offset..)?;
+        if let Some(target) = link_info_target(info) {
+            return Some(target);
+        }
+        offset += u32_at(info, 0)? as usize;
+    }
+
+    // The relative path follows the name in the string data.
+    if flags & HAS_RELATIVE_PATH == 0 {
+        return None;
+    }
+    let unicode = flags & IS_UNICODE != 0;
+    if flags & HAS_NAME != 0 {
+        offset = string_data(data, offset, unicode)?.1;
+    }
+    string_data(data, offset, unicode).map(|(relative_path, _)| relative_path)
+}
+
+/// The path of the link info `info`: its local base path or its network share name, followed by
+/// its common path suffix.
+fn link_info_target(info: &[u8]) -> Option<String> {
+    let header_size = u32_at(info, 4)?;
+    let flags = u32_at(info, 8)?;
+    // The offsets of the unicode strings are only there in the larger headers.
+    let unicode_offset = |at| match u32_at(info, at) {
+        Some(offset) if header_size >= 0x24 && offset != 0 => Some(offset),
+        _ => None,
+    };
+
+    let suffix = match unicode_offset(0x20) {
+        Some(offset) => unicode_at(info, offset),
+        None => ansi_at(info, u32_at(info, 0x18)?),
+    }
+    .unwrap_or_default();
+
+    if flags & VOLUME_ID_AND_LOCAL_BASE_PATH != 0 {
+        let base = match unicode_offset(0x1c) {
+            Some(offset) => unicode_at(info, offset),
+            None => ansi_at(info, u32_at(info, 0x10)?),
+        }?;
+        return Some(base + &suffix);
+    }
+    if flags & COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX != 0 {
+        let link = info.get(u32_at(info, 0x14)? as usize..)?;
+        let net_name_offset = u32_at(link, 8)?;
+        let net_name = if net_name_offset > 0x14 {
+            unicode_at(link, u32_at(link, 0x14)?)
+        } else {
+            ansi_at(link, net_name_offset)
+        }?;
+        return Some(if suffix.is_empty() {
+            net_name
+        } else {
+            net_name + "\\" + &suffix
+        });
+    }
+    None
+}
+
+/// The counted string of the string data at `offset`, and the offset following it.
+fn string_data(data: &[u8], offset: usize, unicode: bool) -> Option<(String, usize)> {
+    let count = u16_at(data, offset)? as usize;
+    let start = offset + 2;
+    if unicode {
+        let bytes = data.get(start..start + 2 * count)?;
+        Some((utf16(bytes), start + 2 * count))
+    } else {
+        let bytes = data.get(start..start + count)?;
+        Some((String::from_utf8_lossy(bytes).into_owned(), start + count))
+    }
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// The nul terminated string at `offset`, in the code page of the system which is read as UTF-8
+/// as long as it is ASCII.
+fn ansi_at(data: &[u8], offset: u32) -> Option<String> {
+    let bytes = data.get(offset as usize..)?;
+    let end = bytes.iter().position(|&byte| byte == 0)?;
+    Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+/// The nul terminated UTF-16 string at `offset`.
+fn unicode_at(data: &[u8], offset: u32) -> Option<String> {
+    let bytes = data.get(offset as usize..)?;
+    let end = bytes.chunks(2).position(|unit| unit == [0, 0])?;
+    Some(utf16(&bytes[..2 * end]))
+}
+
+fn utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks(2)
+        .map(|unit| u16::from_le_bytes([unit[0], *unit.get(1).unwrap_or(&0)]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, target, HAS_LINK_INFO, HAS_NAME, HAS_RELATIVE_PATH, IS_UNICODE};
+    use super::{HEADER_SIZE, LINK_CLSID};
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn header(flags: u32) -> Vec<u8> {
+        let mut data = vec![0; HEADER_SIZE];
+        data[0] = HEADER_SIZE as u8;
+        data[4..20].copy_from_slice(&LINK_CLSID);
+        data[0x14..0x18].copy_from_slice(&flags.to_le_bytes());
+        data
+    }
+
+    fn push_u32(data: &mut Vec<u8>, value: u32) {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn push_unicode(data: &mut Vec<u8>, string: &str) {
+        let units: Vec<u16> = string.encode_utf16().collect();
+        data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+        for unit in units {
+            data.extend_from_slice(&unit.to_le_bytes());
+        }
+    }
+
+    /// A shortcut to the local path `base` + `suffix`, in the ANSI strings of its link info.
+    fn local_shortcut(base: &str, suffix: &str) -> Vec<u8> {
+        let mut data = header(HAS_LINK_INFO);
+        let header_size = 0x1c;
+        let base_offset = header_size;
+        let suffix_offset = base_offset + base.len() as u32 + 1;
+        let size = suffix_offset + suffix.len() as u32 + 1;
+        for value in &[size, header_size, 1, 0, base_offset, 0, suffix_offset] {
+            push_u32(&mut data, *value);
+        }
+        data.extend_from_slice(base.as_bytes());
+        data.push(0);
+        data.extend_from_slice(suffix.as_bytes());
+        data.push(0);
+        data
+    }
+
+    #[test]
+    fn test_parse_local_path() {
+        let data = local_shortcut("C:\\Users\\me\\", "notes.txt");
+        assert_eq!(Some("C:\\Users\\me\\notes.txt"), parse(&data).as_deref());
+    }
+
+    #[test]
+    fn test_parse_relative_path() {
+        let mut data = header(HAS_NAME | HAS_RELATIVE_PATH | IS_UNICODE);
+        push_unicode(&mut data, "My notes");
+        push_unicode(&mut data, "..\\notes.txt");
+        assert_eq!(Some("..\\notes.txt"), parse(&data).as_deref());
+    }
+
+    #[test]
+    fn test_parse_not_a_shortcut() {
+        assert_eq!(None, parse(b"not a shortcut"));
+        let mut data = local_shortcut("C:\\", "");
+        data[4] = 0;
+        assert_eq!(None, parse(&data));
+        // The shortcuts to the special folders only hold their item id list.
+        assert_eq!(None, parse(&header(0)));
+    }
+
+    #[test]
+    fn test_target_extension() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let data = local_shortcut("D:\\", "data");
+        fs::write(tmp_dir.path().join("Data.LNK"), &data).unwrap();
+        fs::write(tmp_dir.path().join("data.bin"), &data).unwrap();
+
+        let shortcut = target(&tmp_dir.path().join("Data.LNK"));
+        assert_eq!(Some("D:\\data"), shortcut.as_deref());
+        assert_eq!(None, target(&tmp_dir.path().join("data.bin")));
+    }
+}
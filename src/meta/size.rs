@@ -2,6 +2,7 @@ use crate::color::{ColoredString, Colors, Elem};
 use crate::flags::{Flags, SizeFlag};
 use ansi_term::ANSIStrings;
 use std::fs::Metadata;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Unit {
@@ -13,21 +14,124 @@ pub enum Unit {
     Tera,
 }
 
+/// The device a block or character special file stands for, shown instead of its size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Device {
+    major: u32,
+    minor: u32,
+    is_block: bool,
+}
+
+impl Device {
+    /// The device number (`st_rdev`) of the special file of `meta`, [None] for the other files.
+    #[cfg(unix)]
+    fn from_metadata(meta: &Metadata) -> Option<Self> {
+        use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+        let file_type = meta.file_type();
+        if !file_type.is_block_device() && !file_type.is_char_device() {
+            return None;
+        }
+        let (major, minor) = Self::split(meta.rdev())?;
+        Some(Self {
+            major,
+            minor,
+            is_block: file_type.is_block_device(),
+        })
+    }
+
+    #[cfg(windows)]
+    fn from_metadata(_: &Metadata) -> Option<Self> {
+        None
+    }
+
+    #[cfg(target_os = "linux")]
+    fn split(rdev: u64) -> Option<(u32, u32)> {
+        // Decoded as by the `major` and `minor` macros of glibc.
+        let major = ((rdev >> 8) & 0xfff) | ((rdev >> 32) & !0xfff);
+        let minor = (rdev & 0xff) | ((rdev >> 12) & !0xff);
+        Some((major as u32, minor as u32))
+    }
+
+    #[cfg(target_os = "macos")]
+    fn split(rdev: u64) -> Option<(u32, u32)> {
+        Some((((rdev >> 24) & 0xff) as u32, (rdev & 0xff_ffff) as u32))
+    }
+
+    /// The layout of the device numbers is not known for the other systems.
+    #[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
+    fn split(_: u64) -> Option<(u32, u32)> {
+        None
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Size {
     bytes: u64,
+    device: Option<Device>,
+    /// Whether this is the space allocated on the disk, colored apart from the sizes.
+    allocated: bool,
+    /// Whether this is the size of a sparse file, which takes less space on the disk.
+    sparse: bool,
 }
 
 impl<'a> From<&'a Metadata> for Size {
     fn from(meta: &Metadata) -> Self {
         let len = meta.len();
-        Self { bytes: len }
+        Self {
+            bytes: len,
+            device: Device::from_metadata(meta),
+            allocated: false,
+            sparse: Self::is_sparse(meta),
+        }
     }
 }
 
 impl Size {
     pub fn new(bytes: u64) -> Self {
-        Self { bytes }
+        Self {
+            bytes,
+            device: None,
+            allocated: false,
+            sparse: false,
+        }
+    }
+
+    /// The space allocated on the disk to the file of `meta`, from its number of 512-byte blocks
+    /// (`st_blocks`).
+    #[cfg(unix)]
+    pub fn allocated(meta: &Metadata) -> Self {
+        use std::os::unix::fs::MetadataExt;
+
+        Self {
+            bytes: meta.blocks().saturating_mul(512),
+            device: None,
+            allocated: true,
+            sparse: false,
+        }
+    }
+
+    /// The allocated blocks are not known on Windows, the length of the file is used instead.
+    #[cfg(windows)]
+    pub fn allocated(meta: &Metadata) -> Self {
+        Self {
+            bytes: meta.len(),
+            device: None,
+            allocated: true,
+            sparse: false,
+        }
+    }
+
+    #[cfg(unix)]
+    fn is_sparse(meta: &Metadata) -> bool {
+        use std::os::unix::fs::MetadataExt;
+
+        meta.is_file() && has_holes(meta.len(), Self::allocated(meta).bytes, meta.blksize())
+    }
+
+    #[cfg(windows)]
+    fn is_sparse(_: &Metadata) -> bool {
+        false
     }
 
     pub fn get_bytes(&self) -> u64 {
@@ -56,12 +160,16 @@ impl Size {
         let val_content = self.render_value(colors, flags);
         let unit_content = self.render_unit(colors, flags);
 
-        let mut left_pad = String::with_capacity(val_alignment - val_content.len());
-        for _ in 0..left_pad.capacity() {
-            left_pad.push(' ');
-        }
+        // The value is right-aligned using its display width, and padded with figure spaces which
+        // are as wide as a digit.
+        let val_width = UnicodeWidthStr::width(&*self.value_string(flags));
+        let left_pad = "\u{2007}".repeat(val_alignment.saturating_sub(val_width));
 
         let mut strings: Vec<ColoredString> = vec![ColoredString::from(left_pad), val_content];
+        if self.device.is_some() {
+            // The device numbers have no unit, as for `ls`.
+            return ColoredString::from(ANSIStrings(&strings).to_string());
+        }
         if flags.size != SizeFlag::Short {
             strings.push(ColoredString::from(" "));
         }
@@ -71,17 +179,51 @@ impl Size {
         ColoredString::from(res)
     }
 
-    fn paint(&self, colors: &Colors, flags: &Flags, content: String) -> ColoredString {
+    /// Colors `content` as the size is, by its value.
+    pub fn paint(&self, colors: &Colors, flags: &Flags, content: String) -> ColoredString {
+        if let Some(device) = self.device {
+            let elem = if device.is_block {
+                Elem::BlockDevice
+            } else {
+                Elem::CharDevice
+            };
+            return colors.colorize(content, &elem);
+        }
+
         let unit = self.get_unit(flags);
+        let thresholds = &flags.color.size_thresholds;
+        let [small, medium, large, huge] = if self.allocated {
+            [
+                Elem::AllocatedSmall,
+                Elem::AllocatedMedium,
+                Elem::AllocatedLarge,
+                Elem::AllocatedHuge,
+            ]
+        } else {
+            [
+                Elem::FileSmall,
+                Elem::FileMedium,
+                Elem::FileLarge,
+                Elem::FileHuge,
+            ]
+        };
+
+        let elem = if unit == Unit::None {
+            Elem::NonFile
+        } else if self.bytes < thresholds.medium {
+            small
+        } else if self.bytes < thresholds.large {
+            medium
+        } else if self.bytes < thresholds.huge {
+            large
+        } else {
+            huge
+        };
 
-        if unit == Unit::None {
-            colors.colorize(content, &Elem::NonFile)
-        } else if unit == Unit::Byte || unit == Unit::Kilo {
-            colors.colorize(content, &Elem::FileSmall)
-        } else if unit == Unit::Mega {
-            colors.colorize(content, &Elem::FileMedium)
+        if self.sparse {
+            colors.colorize_sparse(content, &elem)
         } else {
-            colors.colorize(content, &Elem::FileLarge)
+            colors.colorize(content, &elem)
         }
     }
 
@@ -92,6 +234,10 @@ impl Size {
     }
 
     pub fn value_string(&self, flags: &Flags) -> String {
+        if let Some(device) = self.device {
+            return format!("{}, {}", device.major, device.minor);
+        }
+
         let unit = self.get_unit(flags);
 
         match unit {
@@ -117,6 +263,10 @@ impl Size {
     }
 
     pub fn unit_string(&self, flags: &Flags) -> String {
+        if self.device.is_some() {
+            return String::new();
+        }
+
         let unit = self.get_unit(flags);
 
         match flags.size {
@@ -141,11 +291,23 @@ impl Size {
     }
 }
 
+/// Whether a file of `len` bytes with `allocated` bytes on the disk has holes, at least a whole
+/// `block_size` of its size rounded up to the blocks having no space on the disk. The files
+/// allocated by smaller units than the blocks are not taken for sparse ones.
+#[cfg(unix)]
+fn has_holes(len: u64, allocated: u64, block_size: u64) -> bool {
+    let block_size = block_size.max(1);
+    let rounded = len.saturating_add(block_size - 1) / block_size * block_size;
+    allocated.saturating_add(block_size) <= rounded
+}
+
 #[cfg(test)]
 mod test {
     use super::Size;
     use crate::color::{Colors, Theme};
-    use crate::flags::{Flags, SizeFlag};
+    use crate::flags::color::SizeThresholds;
+    use crate::flags::{Color, Flags, SizeFlag};
+    use ansi_term::Colour;
 
     #[test]
     fn render_byte() {
@@ -319,6 +481,141 @@ mod test {
         let colors = Colors::new(Theme::NoColor);
 
         assert_eq!(size.render(&colors, &flags, 2).to_string(), "42K");
-        assert_eq!(size.render(&colors, &flags, 3).to_string(), " 42K");
+        assert_eq!(size.render(&colors, &flags, 3).to_string(), "\u{2007}42K");
+    }
+
+    #[test]
+    fn render_default_thresholds_colors() {
+        let flags = Flags::default();
+        let colors = Colors::new(Theme::NoLscolors);
+
+        for (bytes, colour) in &[
+            (42, Colour::Fixed(229)),
+            (3 * 1024 * 1024, Colour::Fixed(216)),
+            (3 * 1024 * 1024 * 1024, Colour::Fixed(172)),
+            (3 * 1024 * 1024 * 1024 * 1024, Colour::Fixed(196)),
+        ] {
+            let size = Size::new(*bytes);
+            assert_eq!(
+                colour.paint(size.value_string(&flags)),
+                size.render_value(&colors, &flags)
+            );
+        }
+    }
+
+    #[test]
+    fn render_custom_thresholds_colors() {
+        let flags = Flags {
+            color: Color {
+                size_thresholds: SizeThresholds {
+                    medium: 10,
+                    large: 100,
+                    huge: 1000,
+                },
+                ..Color::default()
+            },
+            ..Flags::default()
+        };
+        let colors = Colors::new(Theme::NoLscolors);
+
+        for (bytes, colour) in &[
+            (9, Colour::Fixed(229)),
+            (10, Colour::Fixed(216)),
+            (100, Colour::Fixed(172)),
+            (1000, Colour::Fixed(196)),
+        ] {
+            let size = Size::new(*bytes);
+            assert_eq!(
+                colour.paint(size.value_string(&flags)),
+                size.render_value(&colors, &flags)
+            );
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn render_device_numbers() {
+        use std::path::Path;
+
+        let meta = std::fs::metadata(Path::new("/dev/null")).unwrap();
+        let size = Size::from(&meta);
+        let flags = Flags::default();
+
+        assert_eq!("1, 3", size.value_string(&flags));
+        assert_eq!("", size.unit_string(&flags));
+        assert_eq!(
+            "\u{2007}1, 3",
+            size.render(&Colors::new(Theme::NoColor), &flags, 5)
+                .to_string()
+        );
+        assert_eq!(
+            Colour::Fixed(172).paint("1, 3"),
+            size.render_value(&Colors::new(Theme::NoLscolors), &flags)
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn split_large_device_numbers() {
+        use super::Device;
+
+        // The major number 259 and the minor number 65536, which take more than 8 bits.
+        assert_eq!(Some((259, 65536)), Device::split(0x1001_0300));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn render_allocated() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("sparse");
+        let file = std::fs::File::create(&path).unwrap();
+        file.set_len(64 * 1024 * 1024).unwrap();
+
+        // No block is allocated to the hole of a sparse file.
+        let meta = std::fs::metadata(&path).unwrap();
+        let allocated = Size::allocated(&meta);
+        assert!(allocated.get_bytes() < Size::from(&meta).get_bytes());
+
+        let flags = Flags::default();
+        assert_eq!(
+            Colour::Fixed(189).paint(allocated.value_string(&flags)),
+            allocated.render_value(&Colors::new(Theme::NoLscolors), &flags)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn detect_holes() {
+        use super::has_holes;
+
+        assert!(has_holes(64 * 1024 * 1024, 0, 4096));
+        assert!(has_holes(10_000, 8192, 4096));
+        assert!(!has_holes(10_000, 10_240, 4096));
+        assert!(!has_holes(10_000, 12_288, 4096));
+        assert!(!has_holes(0, 0, 4096));
+    }
+
+    #[test]
+    fn render_sparse() {
+        use crate::flags::color::{Background, SparseBackground};
+
+        let size = Size {
+            sparse: true,
+            ..Size::new(64 * 1024 * 1024)
+        };
+        let flags = Flags::default();
+        assert_eq!(
+            Colour::Fixed(216)
+                .on(Colour::Fixed(24))
+                .paint(size.value_string(&flags)),
+            size.render_value(&Colors::new(Theme::NoLscolors), &flags)
+        );
+
+        let colors = Colors::new(Theme::NoLscolors)
+            .with_sparse_background(&SparseBackground(Some(Background::None)));
+        assert_eq!(
+            Colour::Fixed(216).paint(size.value_string(&flags)),
+            size.render_value(&colors, &flags)
+        );
     }
 }
@@ -1,29 +1,78 @@
 use crate::color::{ColoredString, Colors, Elem};
 use crate::flags::Flags;
+use crate::readdir::{self, read_link};
 use ansi_term::{ANSIString, ANSIStrings};
-use std::fs::read_link;
+use std::io::{Error, ErrorKind};
 use std::path::Path;
 
+/// The longest chain of symbolic links that is walked, whatever the configured depth is.
+const MAX_CHAIN_LENGTH: usize = 255;
+
+/// Why the target of a symbolic link can not be resolved, or why the content of a directory can
+/// not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BrokenReason {
+    /// The target does not exist (`ENOENT`).
+    NotFound,
+    /// A component of the target path is not a directory (`ENOTDIR`).
+    NotADirectory,
+    /// The target is a chain of links which loops or is too long (`ELOOP`).
+    Loop,
+    /// A directory of the target path can not be searched (`EACCES`).
+    PermissionDenied,
+    /// Any other error, with its description.
+    Other(String),
+}
+
+impl BrokenReason {
+    pub fn from_error(err: &Error) -> Self {
+        #[cfg(unix)]
+        match err.raw_os_error() {
+            Some(libc::ENOENT) => return Self::NotFound,
+            Some(libc::ENOTDIR) => return Self::NotADirectory,
+            Some(libc::ELOOP) => return Self::Loop,
+            Some(libc::EACCES) => return Self::PermissionDenied,
+            _ => {}
+        }
+
+        match err.kind() {
+            ErrorKind::NotFound => Self::NotFound,
+            ErrorKind::PermissionDenied => Self::PermissionDenied,
+            _ => Self::Other(err.to_string()),
+        }
+    }
+
+    pub fn describe(&self) -> &str {
+        match self {
+            Self::NotFound => "not found",
+            Self::NotADirectory => "not a directory",
+            Self::Loop => "too many levels",
+            Self::PermissionDenied => "permission denied",
+            Self::Other(description) => description,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SymLink {
     target: Option<String>,
     valid: bool,
+    /// The number of links to follow before reaching a non-link, [None] if the chain is longer
+    /// than [MAX_CHAIN_LENGTH] or loops.
+    chain_length: Option<usize>,
+    /// Why the target can not be resolved, [None] if it can or the file is not a link.
+    broken_reason: Option<BrokenReason>,
 }
 
 impl<'a> From<&'a Path> for SymLink {
     fn from(path: &'a Path) -> Self {
         if let Ok(target) = read_link(path) {
-            if target.is_absolute() || path.parent() == None {
-                return Self {
-                    valid: target.exists(),
-                    target: Some(
-                        target
-                            .to_str()
-                            .expect("failed to convert symlink to str")
-                            .to_string(),
-                    ),
-                };
-            }
+            // The target is resolved from the link, as the kernel does, so the reason it is
+            // broken is the one of the error.
+            let broken_reason = match readdir::metadata(path) {
+                Ok(_) => None,
+                Err(err) => Some(BrokenReason::from_error(&err)),
+            };
 
             return Self {
                 target: Some(
@@ -32,18 +81,89 @@ impl<'a> From<&'a Path> for SymLink {
                         .expect("failed to convert symlink to str")
                         .to_string(),
                 ),
-                valid: path.parent().unwrap().join(target).exists(),
+                valid: broken_reason.is_none(),
+                chain_length: Self::chain_length(path),
+                broken_reason,
             };
         }
 
+        #[cfg(all(windows, feature = "lnk"))]
+        {
+            if let Some(target) = super::shortcut::target(path) {
+                return Self::from_shortcut(path, target);
+            }
+        }
+
         Self {
             target: None,
             valid: false,
+            chain_length: Some(0),
+            broken_reason: None,
         }
     }
 }
 
 impl SymLink {
+    /// The shortcut at `path` to `target`, shown as a link to it. A relative target is resolved
+    /// from the directory of the shortcut.
+    #[cfg(all(windows, feature = "lnk"))]
+    fn from_shortcut(path: &Path, target: String) -> Self {
+        let resolved = match path.parent() {
+            Some(parent) => parent.join(&target),
+            None => Path::new(&target).to_path_buf(),
+        };
+        let broken_reason = match resolved.metadata() {
+            Ok(_) => None,
+            Err(err) => Some(BrokenReason::from_error(&err)),
+        };
+
+        Self {
+            target: Some(target),
+            valid: broken_reason.is_none(),
+            chain_length: Some(1),
+            broken_reason,
+        }
+    }
+
+    /// Counts the links to follow from `path` until a non-link is reached.
+    fn chain_length(path: &Path) -> Option<usize> {
+        let mut current = path.to_path_buf();
+        for length in 0..=MAX_CHAIN_LENGTH {
+            match read_link(&current) {
+                Ok(target) => {
+                    current = match current.parent() {
+                        Some(parent) => parent.join(target),
+                        None => target,
+                    }
+                }
+                Err(_) => return Some(length),
+            }
+        }
+        None
+    }
+
+    /// Checks whether `path` is a chain of symbolic links which can be resolved by following at
+    /// most `max_depth` links.
+    pub fn resolves_within(path: &Path, max_depth: usize) -> bool {
+        match Self::chain_length(path) {
+            Some(length) => length <= max_depth,
+            None => false,
+        }
+    }
+
+    /// Checks whether resolving this link requires following more than `max_depth` links.
+    pub fn exceeds_depth(&self, max_depth: usize) -> bool {
+        match self.chain_length {
+            Some(length) => length > max_depth,
+            None => true,
+        }
+    }
+
+    /// Why the target of this link can not be resolved, [None] if it can or this is not a link.
+    pub fn broken_reason(&self) -> Option<&BrokenReason> {
+        self.broken_reason.as_ref()
+    }
+
     pub fn symlink_string(&self) -> Option<String> {
         if let Some(ref target) = self.target {
             Some(target.to_string())
@@ -54,28 +174,75 @@ impl SymLink {
 
     pub fn render(&self, colors: &Colors, flag: &Flags) -> ColoredString {
         if let Some(target_string) = self.symlink_string() {
-            let elem = if self.valid {
+            let too_many_levels = self.exceeds_depth(flag.symlink_depth.0);
+            let elem = if self.valid && !too_many_levels {
                 &Elem::SymLink
             } else {
                 &Elem::BrokenSymLink
             };
 
-            let strings: &[ColoredString] = &[
+            let mut strings: Vec<ColoredString> = vec![
                 ColoredString::from(format!(" {} ", flag.symlink_arrow)), // ⇒ \u{21d2}
                 colors.colorize(target_string, elem),
             ];
+            if too_many_levels {
+                strings.push(colors.colorize(String::from(" [too many levels]"), elem));
+            } else if let Some(reason) = &self.broken_reason {
+                strings.push(colors.colorize(format!(" [{}]", reason.describe()), elem));
+            }
 
-            let res = ANSIStrings(strings).to_string();
+            let res = ANSIStrings(&strings).to_string();
             ColoredString::from(res)
         } else {
             ANSIString::from("")
         }
     }
+
+    /// Renders the targets of the links followed from `path`, the link of this `SymLink`, one
+    /// after the other until a non-link is reached, then the reason the chain is broken, if it
+    /// is. A chain looping or longer than the symlink depth is cut after the first link past it.
+    pub fn render_chain(
+        &self,
+        path: &Path,
+        colors: &Colors,
+        flag: &Flags,
+    ) -> ColoredString<'static> {
+        let too_many_levels = self.exceeds_depth(flag.symlink_depth.0);
+        let mut targets = Vec::new();
+        let mut current = path.to_path_buf();
+        while targets.len() <= flag.symlink_depth.0.min(MAX_CHAIN_LENGTH) {
+            let target = match read_link(&current) {
+                Ok(target) => target,
+                Err(_) => break,
+            };
+            targets.push(target.to_string_lossy().to_string());
+            current = match current.parent() {
+                Some(parent) => parent.join(target),
+                None => target,
+            };
+        }
+
+        let mut strings: Vec<ColoredString> = Vec::new();
+        for (idx, target) in targets.into_iter().enumerate() {
+            if idx > 0 {
+                strings.push(ColoredString::from(format!(" {} ", flag.symlink_arrow)));
+            }
+            strings.push(colors.colorize(target, &Elem::SymLink));
+        }
+        if too_many_levels {
+            strings.push(colors.colorize(String::from(" [too many levels]"), &Elem::BrokenSymLink));
+        } else if let Some(reason) = &self.broken_reason {
+            let reason = format!(" [{}]", reason.describe());
+            strings.push(colors.colorize(reason, &Elem::BrokenSymLink));
+        }
+
+        ColoredString::from(ANSIStrings(&strings).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::SymLink;
+    use super::{BrokenReason, SymLink};
     use crate::app;
     use crate::color::{Colors, Theme};
     use crate::config_file::Config;
@@ -86,6 +253,8 @@ mod tests {
         let link = SymLink {
             target: Some("/target".to_string()),
             valid: true,
+            chain_length: Some(1),
+            broken_reason: None,
         };
         let argv = vec!["lsd"];
         let matches = app::build().get_matches_from_safe(argv).unwrap();
@@ -104,6 +273,8 @@ mod tests {
         let link = SymLink {
             target: Some("/target".to_string()),
             valid: false,
+            chain_length: Some(1),
+            broken_reason: None,
         };
         let argv = vec!["lsd"];
         let matches = app::build().get_matches_from_safe(argv).unwrap();
@@ -116,4 +287,128 @@ mod tests {
             .to_string()
         );
     }
+
+    #[test]
+    fn test_symlink_render_broken_reason_nocolor() {
+        let link = SymLink {
+            target: Some("/target".to_string()),
+            valid: false,
+            chain_length: Some(1),
+            broken_reason: Some(BrokenReason::NotFound),
+        };
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            " ⇒ /target [not found]",
+            link.render(
+                &Colors::new(Theme::NoColor),
+                &Flags::configure_from(&matches, &Config::with_none()).unwrap()
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_symlink_render_too_many_levels_nocolor() {
+        let link = SymLink {
+            target: Some("/target".to_string()),
+            valid: true,
+            chain_length: Some(3),
+            broken_reason: None,
+        };
+        let argv = vec!["lsd", "--max-symlink-depth", "2"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            " ⇒ /target [too many levels]",
+            link.render(
+                &Colors::new(Theme::NoColor),
+                &Flags::configure_from(&matches, &Config::with_none()).unwrap()
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_symlink_render_loop_nocolor() {
+        let link = SymLink {
+            target: Some("loop".to_string()),
+            valid: false,
+            chain_length: None,
+            broken_reason: None,
+        };
+        let argv = vec!["lsd"];
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        assert_eq!(
+            " ⇒ loop [too many levels]",
+            link.render(
+                &Colors::new(Theme::NoColor),
+                &Flags::configure_from(&matches, &Config::with_none()).unwrap()
+            )
+            .to_string()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_chain_length() {
+        use std::os::unix::fs::symlink;
+
+        let tmp = tempfile::tempdir().expect("failed to create temp dir");
+        std::fs::File::create(tmp.path().join("target")).expect("failed to create file");
+        symlink("target", tmp.path().join("one")).expect("failed to create symlink");
+        symlink("one", tmp.path().join("two")).expect("failed to create symlink");
+        symlink("loop", tmp.path().join("loop")).expect("failed to create symlink");
+
+        assert_eq!(Some(0), SymLink::chain_length(&tmp.path().join("target")));
+        assert_eq!(Some(2), SymLink::chain_length(&tmp.path().join("two")));
+        assert_eq!(None, SymLink::chain_length(&tmp.path().join("loop")));
+
+        assert!(SymLink::resolves_within(&tmp.path().join("two"), 2));
+        assert!(!SymLink::resolves_within(&tmp.path().join("two"), 1));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_broken_reason() {
+        use std::os::unix::fs::symlink;
+
+        let tmp = tempfile::tempdir().expect("failed to create temp dir");
+        std::fs::File::create(tmp.path().join("file")).expect("failed to create file");
+        symlink("file", tmp.path().join("valid")).expect("failed to create symlink");
+        symlink("missing", tmp.path().join("dangling")).expect("failed to create symlink");
+        symlink("file/child", tmp.path().join("through")).expect("failed to create symlink");
+        symlink("loop", tmp.path().join("loop")).expect("failed to create symlink");
+
+        let reason = |name: &str| SymLink::from(tmp.path().join(name).as_path()).broken_reason;
+        assert_eq!(None, reason("file"));
+        assert_eq!(None, reason("valid"));
+        assert_eq!(Some(BrokenReason::NotFound), reason("dangling"));
+        assert_eq!(Some(BrokenReason::NotADirectory), reason("through"));
+        assert_eq!(Some(BrokenReason::Loop), reason("loop"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_render_chain() {
+        use std::os::unix::fs::symlink;
+
+        let tmp = tempfile::tempdir().expect("failed to create temp dir");
+        std::fs::File::create(tmp.path().join("target")).expect("failed to create file");
+        symlink("target", tmp.path().join("one")).expect("failed to create symlink");
+        symlink("one", tmp.path().join("two")).expect("failed to create symlink");
+        symlink("missing", tmp.path().join("dangling")).expect("failed to create symlink");
+        symlink("loop", tmp.path().join("loop")).expect("failed to create symlink");
+
+        let matches = app::build().get_matches_from_safe(vec!["lsd"]).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+        let chain = |name: &str| {
+            let path = tmp.path().join(name);
+            SymLink::from(path.as_path())
+                .render_chain(&path, &Colors::new(Theme::NoColor), &flags)
+                .to_string()
+        };
+        assert_eq!("one \u{21d2} target", chain("two"));
+        assert_eq!("missing [not found]", chain("dangling"));
+        assert!(chain("loop").ends_with("loop [too many levels]"));
+    }
 }
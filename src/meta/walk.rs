@@ -0,0 +1,188 @@
+//! A lazy traversal of the files to list, yielding them one at a time.
+//!
+//! Unlike [Meta::recurse_into], which collects a whole tree before it is sorted and displayed, a
+//! [Walk] only reads a directory when the entries before it have been consumed. Each directory is
+//! read and sorted on its own, so no more than one directory per depth level is held in memory,
+//! and dropping the walk stops the traversal.
+
+use super::{is_snapshot_dir, Ancestor, MemoryBudget, Meta, SymLink};
+use crate::flags::{Flags, SortOrder};
+use crate::sort::{self, SortFn};
+
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::vec::IntoIter;
+
+/// The sorted entries of a directory which are yet to be yielded.
+struct Frame {
+    entries: IntoIter<Meta>,
+    /// The number of levels which may still be entered below these entries.
+    depth: usize,
+    /// The directories the entries are listed from, to detect the cycles.
+    ancestors: Vec<Ancestor>,
+}
+
+/// An [Iterator] over the files of a path, in the order they are displayed: each directory is
+/// followed by its content, and the entries of a directory are sorted as set by the [Flags].
+pub struct Walk<'a> {
+    flags: &'a Flags,
+    sorters: Vec<(SortOrder, SortFn)>,
+    budget: MemoryBudget,
+    frames: Vec<Frame>,
+    /// The next item to yield, before the remaining entries: the file given to walk when it is
+    /// not a directory to list, or the error met while reading it.
+    pending: Option<Result<Meta, (PathBuf, Error)>>,
+}
+
+/// Walks the files of `path` up to `depth` levels down, yielding the entries of `path` if it is a
+/// directory, or `path` itself otherwise. The [Flags] filtering the listed files apply, and the
+/// errors met in the sub directories are yielded in their place along their path, the walk going
+/// on after them.
+pub fn walk<'a>(path: &Path, depth: usize, flags: &'a Flags) -> Walk<'a> {
+    let mut walk = Walk {
+        flags,
+        sorters: sort::assemble_sorters(flags),
+        budget: MemoryBudget::new(None),
+        frames: Vec::new(),
+        pending: None,
+    };
+
+    let dereference = flags.dereference.0 && SymLink::resolves_within(path, flags.symlink_depth.0);
+    let meta = match Meta::from_listed_path(path, dereference, flags) {
+        Ok(meta) => meta,
+        Err(err) => {
+            walk.pending = Some(Err((path.to_path_buf(), err)));
+            return walk;
+        }
+    };
+    match meta.walk(depth.min(1), flags, None, &walk.budget, &[]) {
+        Ok(Some(content)) => walk.push(content, depth - 1, vec![Ancestor::new(path, flags)]),
+        Ok(None) => walk.pending = Some(Ok(meta)),
+        Err(err) => walk.pending = Some(Err((path.to_path_buf(), err))),
+    }
+
+    walk
+}
+
+impl<'a> Walk<'a> {
+    fn push(&mut self, mut content: Vec<Meta>, depth: usize, ancestors: Vec<Ancestor>) {
+        let sorters = &self.sorters;
+        content.sort_unstable_by(|a, b| sort::by_meta(sorters, a, b));
+        self.frames.push(Frame {
+            entries: content.into_iter(),
+            depth,
+            ancestors,
+        });
+    }
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = Result<Meta, (PathBuf, Error)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.pending.take() {
+            return Some(item);
+        }
+
+        loop {
+            let frame = self.frames.last_mut()?;
+            let meta = match frame.entries.next() {
+                Some(meta) => meta,
+                None => {
+                    self.frames.pop();
+                    continue;
+                }
+            };
+
+            let enter = frame.depth > 0
+                && meta.name.name != "."
+                && meta.name.name != ".."
+                && (self.flags.include_snapshots.0 || !is_snapshot_dir(&meta.path));
+            if enter {
+                let depth = frame.depth - 1;
+                let mut ancestors = frame.ancestors.clone();
+                match meta.walk(1, self.flags, None, &self.budget, &frame.ancestors) {
+                    Ok(Some(content)) => {
+                        ancestors.push(Ancestor::new(&meta.path, self.flags));
+                        self.push(content, depth, ancestors);
+                    }
+                    Ok(None) => {}
+                    Err(err) => self.pending = Some(Err((meta.path.clone(), err))),
+                }
+            }
+
+            return Some(Ok(meta));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::walk;
+    use crate::app;
+    use crate::config_file::Config;
+    use crate::flags::Flags;
+    use assert_fs::prelude::*;
+
+    fn names(argv: Vec<&str>, dir: &assert_fs::TempDir, depth: usize) -> Vec<String> {
+        let matches = app::build().get_matches_from_safe(argv).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+        walk(dir.path(), depth, &flags)
+            .map(|meta| {
+                let meta = meta.unwrap();
+                let path = meta.path.strip_prefix(dir.path()).unwrap();
+                path.to_string_lossy().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_walk_sorted_depth_first() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("b/d/e").touch().unwrap();
+        dir.child("b/c").touch().unwrap();
+        dir.child("a").touch().unwrap();
+        dir.child(".hidden").touch().unwrap();
+
+        assert_eq!(
+            vec!["a", "b", "b/c", "b/d", "b/d/e"],
+            names(vec!["lsd"], &dir, usize::MAX)
+        );
+        assert_eq!(vec!["b", "a"], names(vec!["lsd", "-r"], &dir, 1));
+    }
+
+    #[test]
+    fn test_walk_file() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("a").touch().unwrap();
+
+        let matches = app::build().get_matches_from_safe(vec!["lsd"]).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+        let metas: Vec<_> = walk(&dir.path().join("a"), 1, &flags).collect();
+        assert_eq!(1, metas.len());
+        assert_eq!("a", metas[0].as_ref().unwrap().name.name);
+
+        let missing = dir.path().join("b");
+        let (path, _) = walk(&missing, 1, &flags).next().unwrap().unwrap_err();
+        assert_eq!(missing, path);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_walk_error_path() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        dir.child("a").touch().unwrap();
+        dir.child("b/c").touch().unwrap();
+
+        let matches = app::build().get_matches_from_safe(vec!["lsd"]).unwrap();
+        let flags = Flags::configure_from(&matches, &Config::with_none()).unwrap();
+        let mut walk = walk(dir.path(), usize::MAX, &flags);
+        assert_eq!("a", walk.next().unwrap().unwrap().name.name);
+        // The directory is replaced by a link to itself once listed, before it is read.
+        std::fs::remove_dir_all(dir.path().join("b")).unwrap();
+        std::os::unix::fs::symlink("b", dir.path().join("b")).unwrap();
+        assert_eq!("b", walk.next().unwrap().unwrap().name.name);
+        let (path, _) = walk.next().unwrap().unwrap_err();
+        assert_eq!(dir.path().join("b"), path);
+    }
+}
@@ -156,6 +156,9 @@ pub fn get_file_data(path: &Path) -> Result<(Owner, Permissions), io::Error> {
         sticky: false,
         setuid: false,
         setgid: false,
+
+        capabilities: false,
+        acl: false,
     };
 
     // Assumptions:
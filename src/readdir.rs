@@ -0,0 +1,483 @@
+//! The diagnostic listing of `--debug-readdir`: the entries of a directory in the order the system
+//! returns them, with the type it reports for each of them, compared with the one the metadata
+//! of the entry gives, to find the file systems misreporting the types of their files.
+//!
+//! The directories are read with [read_dir] too, which reads the directories given by `--fd`
+//! from their file descriptor rather than opening them again, and their files are looked up from
+//! it with `openat` by the functions of this module, rather than by path.
+
+use crate::meta::name::os_str_text;
+
+use std::cell::RefCell;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// An entry of a directory, as returned by the system.
+struct RawEntry {
+    name: OsString,
+    /// The type reported along the entry, as the name of its `DT_` constant.
+    kind: &'static str,
+    inode: u64,
+}
+
+/// Renders the entries of the directory `dir` in the order they are read, one per line: the type
+/// reported along it, its inode, whether its metadata could be read and the type it gives when
+/// it differs, then its name. The `.` and `..` entries are kept, as they are returned.
+pub fn debug_readdir(dir: &Path, raw: bool) -> io::Result<String> {
+    let entries = read(dir)?;
+
+    let rows: Vec<(&str, String, String, String)> = entries
+        .iter()
+        .map(|entry| {
+            let stat = match symlink_metadata(&dir.join(&entry.name)) {
+                Ok(metadata) => {
+                    let kind = kind_of(&metadata.file_type());
+                    if kind == entry.kind {
+                        "ok".to_string()
+                    } else {
+                        format!("ok, is {}", kind)
+                    }
+                }
+                Err(err) => format!("failed: {}", err),
+            };
+            (
+                entry.kind,
+                entry.inode.to_string(),
+                stat,
+                os_str_text(&entry.name, raw),
+            )
+        })
+        .collect();
+
+    let inode_width = rows.iter().map(|row| row.1.len()).max().unwrap_or(0);
+    let stat_width = rows.iter().map(|row| row.2.len()).max().unwrap_or(0);
+    let mut output = String::new();
+    for (kind, inode, stat, name) in rows {
+        output += &format!(
+            "{:<7} {:>inode_width$} {:<stat_width$} {}\n",
+            kind,
+            inode,
+            stat,
+            name,
+            inode_width = inode_width,
+            stat_width = stat_width
+        );
+    }
+    Ok(output)
+}
+
+thread_local! {
+    /// The directories given by `--fd`: the label they are shown with, and their descriptor.
+    static ANCHORS: RefCell<Vec<(PathBuf, i32)>> = RefCell::default();
+}
+
+/// The label the directory open as the file descriptor `fd`, inherited from the parent process,
+/// is listed as: `fd:<fd>`. The paths under the label are looked up from the descriptor with
+/// `openat`, by [symlink_metadata], [metadata], [read_link] and [read_dir], so neither the path
+/// the directory was opened from nor the permission to open it again are needed, only to search
+/// it. The directory itself is read from the descriptor.
+#[cfg(target_os = "linux")]
+pub fn fd_label(fd: i32) -> io::Result<PathBuf> {
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    if unsafe { libc::fstat(fd, &mut stat) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    if stat.st_mode & libc::S_IFMT != libc::S_IFDIR {
+        return Err(io::Error::from_raw_os_error(libc::ENOTDIR));
+    }
+    let label = PathBuf::from(format!("fd:{}", fd));
+    ANCHORS.with(|anchors| anchors.borrow_mut().push((label.clone(), fd)));
+    Ok(label)
+}
+
+/// The directories can only be looked up from their descriptor on Linux.
+#[cfg(not(target_os = "linux"))]
+pub fn fd_label(_: i32) -> io::Result<PathBuf> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        "the directories can only be listed from their file descriptor on Linux",
+    ))
+}
+
+/// The descriptor of the `--fd` directory `path` is under, and the path relative to it.
+fn anchored(path: &Path) -> Option<(i32, PathBuf)> {
+    ANCHORS.with(|anchors| {
+        anchors.borrow().iter().find_map(|(label, fd)| {
+            let relative = path.strip_prefix(label).ok()?;
+            Some((*fd, relative.to_path_buf()))
+        })
+    })
+}
+
+/// Whether `path` is under a directory given by `--fd`. Its files can then only be looked up
+/// through the functions of this module, and the other lookups by path are skipped.
+pub fn is_anchored(path: &Path) -> bool {
+    anchored(path).is_some()
+}
+
+/// The metadata of the file at `path`, not following a link, as [fs::symlink_metadata].
+pub fn symlink_metadata(path: &Path) -> io::Result<fs::Metadata> {
+    #[cfg(target_os = "linux")]
+    {
+        if let Some((fd, relative)) = anchored(path) {
+            return open_at(fd, &relative, libc::O_PATH | libc::O_NOFOLLOW)?.metadata();
+        }
+    }
+    fs::symlink_metadata(path)
+}
+
+/// The metadata of the file at `path`, following the links, as [fs::metadata].
+pub fn metadata(path: &Path) -> io::Result<fs::Metadata> {
+    #[cfg(target_os = "linux")]
+    {
+        if let Some((fd, relative)) = anchored(path) {
+            return open_at(fd, &relative, libc::O_PATH)?.metadata();
+        }
+    }
+    fs::metadata(path)
+}
+
+/// The target of the link at `path`, as [fs::read_link].
+pub fn read_link(path: &Path) -> io::Result<PathBuf> {
+    #[cfg(target_os = "linux")]
+    {
+        if let Some((fd, relative)) = anchored(path) {
+            return read_link_at(fd, &relative);
+        }
+    }
+    fs::read_link(path)
+}
+
+/// Opens the file at `relative` from the directory descriptor `fd` with `openat`, or a
+/// duplicate of `fd` itself when `relative` is empty.
+#[cfg(target_os = "linux")]
+fn open_at(fd: i32, relative: &Path, flags: libc::c_int) -> io::Result<fs::File> {
+    use std::os::unix::io::FromRawFd;
+
+    let opened = if relative.as_os_str().is_empty() {
+        unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 0) }
+    } else {
+        let relative = c_path(relative)?;
+        unsafe { libc::openat(fd, relative.as_ptr(), flags | libc::O_CLOEXEC) }
+    };
+    if opened < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { fs::File::from_raw_fd(opened) })
+}
+
+/// The target of the link at `relative` from the directory descriptor `fd`, with `readlinkat`.
+#[cfg(target_os = "linux")]
+fn read_link_at(fd: i32, relative: &Path) -> io::Result<PathBuf> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let relative = c_path(relative)?;
+    let mut buffer = vec![0u8; 256];
+    loop {
+        let length = unsafe {
+            libc::readlinkat(
+                fd,
+                relative.as_ptr(),
+                buffer.as_mut_ptr() as *mut libc::c_char,
+                buffer.len(),
+            )
+        };
+        if length < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // The target may have been truncated to the size of the buffer.
+        if (length as usize) < buffer.len() {
+            buffer.truncate(length as usize);
+            return Ok(PathBuf::from(OsStr::from_bytes(&buffer)));
+        }
+        let size = buffer.len() * 2;
+        buffer.resize(size, 0);
+    }
+}
+
+#[cfg(unix)]
+fn c_path(path: &Path) -> io::Result<std::ffi::CString> {
+    use std::os::unix::ffi::OsStrExt;
+
+    std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
+
+/// An entry of a directory read by [read_dir].
+pub struct DirEntry {
+    path: PathBuf,
+    kind: EntryKind,
+}
+
+enum EntryKind {
+    Std(fs::DirEntry),
+    /// The name of the `DT_` constant of the type reported along the entry.
+    Raw(&'static str),
+}
+
+impl DirEntry {
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    pub fn file_name(&self) -> OsString {
+        match &self.kind {
+            EntryKind::Std(entry) => entry.file_name(),
+            EntryKind::Raw(_) => self.path.file_name().unwrap_or_default().to_os_string(),
+        }
+    }
+
+    /// Whether the entry is a directory, from the type reported along it when it is known.
+    pub fn is_dir(&self) -> io::Result<bool> {
+        match &self.kind {
+            EntryKind::Std(entry) => entry.file_type().map(|file_type| file_type.is_dir()),
+            EntryKind::Raw("unknown") => symlink_metadata(&self.path).map(|m| m.is_dir()),
+            EntryKind::Raw(kind) => Ok(*kind == "dir"),
+        }
+    }
+}
+
+/// Reads the entries of the directory `dir`, as [fs::read_dir] does but for the directories under
+/// an [fd_label], read from their descriptor. The `.` and `..` entries are left out.
+pub fn read_dir(dir: &Path) -> io::Result<Box<dyn Iterator<Item = io::Result<DirEntry>>>> {
+    #[cfg(target_os = "linux")]
+    {
+        if let Some((fd, relative)) = anchored(dir) {
+            let entries = read_fd(fd, &relative)?
+                .into_iter()
+                .filter(|entry| entry.name != "." && entry.name != "..");
+            let dir = dir.to_path_buf();
+            return Ok(Box::new(entries.map(move |entry| {
+                Ok(DirEntry {
+                    path: dir.join(&entry.name),
+                    kind: EntryKind::Raw(entry.kind),
+                })
+            })));
+        }
+    }
+
+    let entries = fs::read_dir(dir)?;
+    Ok(Box::new(entries.map(|entry| {
+        entry.map(|entry| DirEntry {
+            path: entry.path(),
+            kind: EntryKind::Std(entry),
+        })
+    })))
+}
+
+/// The name of the `DT_` constant of the type of a file, from its metadata.
+fn kind_of(file_type: &fs::FileType) -> &'static str {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+
+        if file_type.is_fifo() {
+            return "fifo";
+        } else if file_type.is_char_device() {
+            return "chr";
+        } else if file_type.is_block_device() {
+            return "blk";
+        } else if file_type.is_socket() {
+            return "sock";
+        }
+    }
+    if file_type.is_dir() {
+        "dir"
+    } else if file_type.is_symlink() {
+        "lnk"
+    } else if file_type.is_file() {
+        "reg"
+    } else {
+        "unknown"
+    }
+}
+
+/// Reads the entries of `dir` with `readdir`, keeping the type it reports, which the standard
+/// library replaces by the one of the metadata when it is unknown.
+#[cfg(unix)]
+fn read(dir: &Path) -> io::Result<Vec<RawEntry>> {
+    #[cfg(target_os = "linux")]
+    {
+        if let Some((fd, relative)) = anchored(dir) {
+            return read_fd(fd, &relative);
+        }
+    }
+
+    let path = c_path(dir)?;
+    let handle = unsafe { libc::opendir(path.as_ptr()) };
+    if handle.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(read_handle(handle))
+}
+
+/// Reads the entries of the directory at `relative` from the directory descriptor `fd`, from its
+/// start. The directory of `fd` itself is read through a duplicate of it, so that it stays open.
+#[cfg(target_os = "linux")]
+fn read_fd(fd: i32, relative: &Path) -> io::Result<Vec<RawEntry>> {
+    use std::os::unix::io::IntoRawFd;
+
+    let fd = open_at(fd, relative, libc::O_RDONLY | libc::O_DIRECTORY)?.into_raw_fd();
+    let handle = unsafe { libc::fdopendir(fd) };
+    if handle.is_null() {
+        let err = io::Error::last_os_error();
+        unsafe { libc::close(fd) };
+        return Err(err);
+    }
+    // The duplicate shares the offset of the descriptor, which may have been read already.
+    unsafe { libc::rewinddir(handle) };
+    Ok(read_handle(handle))
+}
+
+/// Reads the entries of the directory stream `handle`, then closes it.
+#[cfg(unix)]
+fn read_handle(handle: *mut libc::DIR) -> Vec<RawEntry> {
+    use std::ffi::{CStr, OsStr};
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut entries = Vec::new();
+    loop {
+        let entry = unsafe { libc::readdir(handle) };
+        if entry.is_null() {
+            break;
+        }
+        let entry = unsafe { &*entry };
+        let name = unsafe { CStr::from_ptr(entry.d_name.as_ptr()) };
+        let kind = match entry.d_type {
+            libc::DT_FIFO => "fifo",
+            libc::DT_CHR => "chr",
+            libc::DT_DIR => "dir",
+            libc::DT_BLK => "blk",
+            libc::DT_REG => "reg",
+            libc::DT_LNK => "lnk",
+            libc::DT_SOCK => "sock",
+            _ => "unknown",
+        };
+        entries.push(RawEntry {
+            name: OsStr::from_bytes(name.to_bytes()).to_os_string(),
+            kind,
+            inode: entry.d_ino as u64,
+        });
+    }
+    unsafe { libc::closedir(handle) };
+    entries
+}
+
+/// Reads the entries of `dir`, with the type the directory listing reports. The inodes are not
+/// known on Windows.
+#[cfg(windows)]
+fn read(dir: &Path) -> io::Result<Vec<RawEntry>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let kind = match entry.file_type() {
+            Ok(file_type) => kind_of(&file_type),
+            Err(_) => "unknown",
+        };
+        entries.push(RawEntry {
+            name: entry.file_name(),
+            kind,
+            inode: 0,
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod test {
+    use super::debug_readdir;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_debug_readdir() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        fs::write(tmp_dir.path().join("file"), "").unwrap();
+        fs::create_dir(tmp_dir.path().join("dir")).unwrap();
+
+        let output = debug_readdir(tmp_dir.path(), false).unwrap();
+        let mut lines: Vec<(&str, &str, &str)> = output
+            .lines()
+            .map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                (fields[0], fields[2], fields[3])
+            })
+            .collect();
+        lines.sort_by_key(|line| line.2);
+        // The file systems which do not report the types are told apart by the stat column.
+        for (_, stat, name) in lines.iter().filter(|line| line.0 != "unknown") {
+            assert_eq!("ok", *stat, "{}", name);
+        }
+        let names: Vec<&str> = lines.iter().map(|line| line.2).collect();
+        assert_eq!(vec![".", "..", "dir", "file"], names);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_read_dir_fd() {
+        use super::{fd_label, metadata, read_dir, read_link, symlink_metadata};
+        use std::os::unix::io::AsRawFd;
+        use std::path::PathBuf;
+
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        fs::write(tmp_dir.path().join("file"), "").unwrap();
+        fs::create_dir(tmp_dir.path().join("dir")).unwrap();
+        fs::write(tmp_dir.path().join("dir/inner"), "inner").unwrap();
+        std::os::unix::fs::symlink("dir/inner", tmp_dir.path().join("link")).unwrap();
+        let dir = fs::File::open(tmp_dir.path()).unwrap();
+        let path = fd_label(dir.as_raw_fd()).unwrap();
+        assert_eq!(PathBuf::from(format!("fd:{}", dir.as_raw_fd())), path);
+        // The files are looked up from the descriptor, not from the path it was opened from.
+        let moved = tmp_dir.path().with_extension("moved");
+        fs::rename(tmp_dir.path(), &moved).unwrap();
+
+        let mut entries: Vec<(String, bool)> = read_dir(&path)
+            .unwrap()
+            .map(|entry| {
+                let entry = entry.unwrap();
+                let name = entry.file_name().to_string_lossy().to_string();
+                assert_eq!(path.join(&name), entry.path());
+                (name, entry.is_dir().unwrap())
+            })
+            .collect();
+        entries.sort();
+        assert_eq!(
+            vec![
+                ("dir".to_string(), true),
+                ("file".to_string(), false),
+                ("link".to_string(), false)
+            ],
+            entries
+        );
+        // The descriptor is read from its start each time.
+        assert_eq!(3, read_dir(&path).unwrap().count());
+        assert_eq!(1, read_dir(&path.join("dir")).unwrap().count());
+
+        assert!(metadata(&path).unwrap().is_dir());
+        assert_eq!(5, metadata(&path.join("link")).unwrap().len());
+        assert!(symlink_metadata(&path.join("link"))
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(
+            PathBuf::from("dir/inner"),
+            read_link(&path.join("link")).unwrap()
+        );
+        assert!(read_link(&path.join("file")).is_err());
+        assert!(metadata(&path.join("missing")).is_err());
+
+        let file = fs::File::open(moved.join("file")).unwrap();
+        assert!(fd_label(file.as_raw_fd()).is_err());
+        fs::rename(&moved, tmp_dir.path()).unwrap();
+    }
+
+    #[test]
+    fn test_debug_readdir_missing() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        assert!(debug_readdir(&tmp_dir.path().join("missing"), false).is_err());
+    }
+}
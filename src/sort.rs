@@ -1,33 +1,62 @@
-use crate::flags::{DirGrouping, Flags, SortColumn, SortOrder};
-use crate::meta::Meta;
+use crate::flags::{Collation, DirGrouping, Flags, SortCase, SortColumn, SortOrder};
+use crate::meta::{FileType, Meta};
 use human_sort::compare;
+use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::ffi::CString;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
 
 pub type SortFn = fn(&Meta, &Meta) -> Ordering;
 
 pub fn assemble_sorters(flags: &Flags) -> Vec<(SortOrder, SortFn)> {
     let mut sorters: Vec<(SortOrder, SortFn)> = vec![];
-    match flags.sorting.dir_grouping {
-        DirGrouping::First => {
-            sorters.push((SortOrder::Default, with_dirs_first));
-        }
-        DirGrouping::Last => {
-            sorters.push((SortOrder::Reverse, with_dirs_first));
-        }
-        DirGrouping::None => {}
-    };
+    sorters.extend(dir_grouping_sorter(flags));
     let other_sort = match flags.sorting.column {
-        SortColumn::Name => by_name,
+        SortColumn::Name => match (flags.sorting.collation, flags.sorting.case) {
+            (Collation::Bytes, SortCase::Sensitive) => by_name_case_sensitive,
+            (Collation::Bytes, SortCase::Insensitive) => by_name,
+            (Collation::Bytes, SortCase::Smart) => by_name_smart_case,
+            (Collation::Locale, case) => {
+                use_locale_collation();
+                match case {
+                    SortCase::Sensitive => by_name_case_sensitive_collated,
+                    SortCase::Insensitive => by_name_collated,
+                    SortCase::Smart => by_name_smart_case_collated,
+                }
+            }
+        },
         SortColumn::Size => by_size,
         SortColumn::Time => by_date,
+        SortColumn::Created => by_created,
         SortColumn::Version => by_version,
+        SortColumn::INode => by_inode,
+        SortColumn::Links => by_links,
         SortColumn::Extension => by_extension,
         SortColumn::GitStatus => by_git_status,
+        SortColumn::GitRepo => by_git_repo,
     };
     sorters.push((flags.sorting.order, other_sort));
     sorters
 }
 
+/// The sorter keeping the directories together before or after the files, if they are grouped.
+fn dir_grouping_sorter(flags: &Flags) -> Option<(SortOrder, SortFn)> {
+    let order = match flags.sorting.dir_grouping {
+        DirGrouping::First => SortOrder::Default,
+        DirGrouping::Last => SortOrder::Reverse,
+        DirGrouping::None => return None,
+    };
+    let sorter: SortFn = if flags.sorting.group_dir_symlinks.0 {
+        with_dirs_first
+    } else {
+        with_real_dirs_first
+    };
+    Some((order, sorter))
+}
+
 pub fn by_meta(sorters: &[(SortOrder, SortFn)], a: &Meta, b: &Meta) -> Ordering {
     for (direction, sorter) in sorters.iter() {
         match (sorter)(a, b) {
@@ -43,10 +72,137 @@ pub fn by_meta(sorters: &[(SortOrder, SortFn)], a: &Meta, b: &Meta) -> Ordering
     Ordering::Equal
 }
 
+/// The most bytes read from the output of a sort command, a longer output being an error.
+pub const SORT_CMD_MAX_OUTPUT: u64 = 16 * 1024 * 1024;
+
+/// The bytes of `path` as the commands read them, even when they are not valid UTF-8.
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Cow::Borrowed(path.as_os_str().as_bytes())
+}
+
+#[cfg(windows)]
+fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+    Cow::Owned(path.to_string_lossy().into_owned().into_bytes())
+}
+
+/// Runs the shell `command` with the paths of the `metas` on its standard input, one per line,
+/// and returns the sort key it printed for each of them, in the same order.
+///
+/// # Errors
+///
+/// This returns the reason as a message when the command can not be run, exits with a failure,
+/// prints more than [SORT_CMD_MAX_OUTPUT] bytes or not one line per path.
+pub fn command_keys(command: &str, metas: &[Meta]) -> Result<Vec<String>, String> {
+    let mut input = Vec::new();
+    for meta in metas {
+        input.extend_from_slice(&path_bytes(&meta.path));
+        input.push(b'\n');
+    }
+
+    let mut child = shell(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| err.to_string())?;
+
+    // The input is written from another thread, so a command printing its keys before reading
+    // all the paths can not block on a full pipe. A command not reading them is not an error by
+    // itself, its output is checked instead.
+    let mut stdin = child.stdin.take().expect("the stdin of the child is piped");
+    let writer = thread::spawn(move || stdin.write_all(&input));
+
+    let mut output = Vec::new();
+    let read = child
+        .stdout
+        .take()
+        .expect("the stdout of the child is piped")
+        .take(SORT_CMD_MAX_OUTPUT + 1)
+        .read_to_end(&mut output);
+    if output.len() as u64 > SORT_CMD_MAX_OUTPUT {
+        let _ = child.kill();
+    }
+    let status = child.wait().map_err(|err| err.to_string())?;
+    let _ = writer.join();
+
+    read.map_err(|err| err.to_string())?;
+    if output.len() as u64 > SORT_CMD_MAX_OUTPUT {
+        return Err(format!(
+            "it printed more than {} bytes",
+            SORT_CMD_MAX_OUTPUT
+        ));
+    }
+    if !status.success() {
+        return Err(format!("it exited with {}", status));
+    }
+
+    let keys: Vec<String> = String::from_utf8_lossy(&output)
+        .lines()
+        .map(String::from)
+        .collect();
+    if keys.len() != metas.len() {
+        return Err(format!(
+            "it printed {} keys for {} paths",
+            keys.len(),
+            metas.len()
+        ));
+    }
+    Ok(keys)
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+/// Sorts the `metas` by their `keys`, as returned by [command_keys], in the order set by the
+/// flags. The directories stay grouped, and the files with the same key are sorted by the
+/// `sorters`.
+pub fn by_keys(
+    flags: &Flags,
+    sorters: &[(SortOrder, SortFn)],
+    metas: &mut Vec<Meta>,
+    keys: Vec<String>,
+) {
+    let grouping = dir_grouping_sorter(flags);
+    let directed = |order: SortOrder, ordering: Ordering| match order {
+        SortOrder::Default => ordering,
+        SortOrder::Reverse => ordering.reverse(),
+    };
+
+    let mut keyed: Vec<(String, Meta)> = keys.into_iter().zip(metas.drain(..)).collect();
+    keyed.sort_by(|(key_a, a), (key_b, b)| {
+        grouping
+            .map_or(Ordering::Equal, |(order, sorter)| {
+                directed(order, sorter(a, b))
+            })
+            .then_with(|| directed(flags.sorting.order, key_a.cmp(key_b)))
+            .then_with(|| by_meta(sorters, a, b))
+    });
+    metas.extend(keyed.into_iter().map(|(_, meta)| meta));
+}
+
 fn with_dirs_first(a: &Meta, b: &Meta) -> Ordering {
     b.file_type.is_dirlike().cmp(&a.file_type.is_dirlike())
 }
 
+/// Sorts the directories first, the symlinks to directories going with the files.
+fn with_real_dirs_first(a: &Meta, b: &Meta) -> Ordering {
+    let is_dir = |meta: &Meta| matches!(meta.file_type, FileType::Directory { .. });
+    is_dir(b).cmp(&is_dir(a))
+}
+
 fn by_size(a: &Meta, b: &Meta) -> Ordering {
     b.size.get_bytes().cmp(&a.size.get_bytes())
 }
@@ -55,10 +211,67 @@ fn by_name(a: &Meta, b: &Meta) -> Ordering {
     a.name.cmp(&b.name)
 }
 
+fn by_name_case_sensitive(a: &Meta, b: &Meta) -> Ordering {
+    a.name.name.cmp(&b.name.name)
+}
+
+fn by_name_smart_case(a: &Meta, b: &Meta) -> Ordering {
+    by_name(a, b).then_with(|| by_name_case_sensitive(a, b))
+}
+
+/// Sets the collation rules of `strcoll` from the `LC_COLLATE` (or `LC_ALL`, `LANG`) variable of
+/// the environment, the process starting with the "C" locale which compares the bytes.
+fn use_locale_collation() {
+    unsafe {
+        libc::setlocale(libc::LC_COLLATE, b"\0".as_ptr() as *const libc::c_char);
+    }
+}
+
+/// Compares `a` and `b` with the collation rules of the locale.
+fn collate(a: &str, b: &str) -> Ordering {
+    match (CString::new(a), CString::new(b)) {
+        (Ok(a), Ok(b)) => unsafe { libc::strcoll(a.as_ptr(), b.as_ptr()) }.cmp(&0),
+        _ => a.cmp(b),
+    }
+}
+
+fn by_name_collated(a: &Meta, b: &Meta) -> Ordering {
+    collate(&a.name.name.to_lowercase(), &b.name.name.to_lowercase())
+}
+
+fn by_name_case_sensitive_collated(a: &Meta, b: &Meta) -> Ordering {
+    collate(&a.name.name, &b.name.name)
+}
+
+fn by_name_smart_case_collated(a: &Meta, b: &Meta) -> Ordering {
+    by_name_collated(a, b).then_with(|| by_name_case_sensitive_collated(a, b))
+}
+
 fn by_date(a: &Meta, b: &Meta) -> Ordering {
     b.date.cmp(&a.date).then(a.name.cmp(&b.name))
 }
 
+/// Sorts the files created last first, then the ones whose creation time is unknown.
+fn by_created(a: &Meta, b: &Meta) -> Ordering {
+    b.created.cmp(&a.created).then(a.name.cmp(&b.name))
+}
+
+/// Sorts by increasing inode number, so that the hard links to a file follow each other.
+fn by_inode(a: &Meta, b: &Meta) -> Ordering {
+    a.inode
+        .index()
+        .cmp(&b.inode.index())
+        .then(a.name.cmp(&b.name))
+}
+
+/// Sorts the files with the most hard links first.
+fn by_links(a: &Meta, b: &Meta) -> Ordering {
+    b.links
+        .count()
+        .cmp(&a.links.count())
+        .then(a.name.cmp(&b.name))
+}
+
 fn by_version(a: &Meta, b: &Meta) -> Ordering {
     compare(&a.name.name, &b.name.name)
 }
@@ -71,9 +284,20 @@ fn by_git_status(a: &Meta, b: &Meta) -> Ordering {
     a.git_status.cmp(&b.git_status)
 }
 
+/// Sorts the dirty repositories first, then the clean ones, then the files which are not one.
+fn by_git_repo(a: &Meta, b: &Meta) -> Ordering {
+    let rank = |meta: &Meta| match &meta.git_repo {
+        Some(repo) if repo.dirty => 0,
+        Some(_) => 1,
+        None => 2,
+    };
+    rank(a).cmp(&rank(b))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::flags::sorting::GroupDirSymlinks;
     use crate::flags::Flags;
     use std::fs::{create_dir, File};
     use std::process::Command;
@@ -300,4 +524,231 @@ mod tests {
         let sorter = assemble_sorters(&flags);
         assert_eq!(by_meta(&sorter, &meta_b, &meta_c), Ordering::Less);
     }
+
+    #[test]
+    fn test_sort_assemble_sorters_by_git_repo() {
+        use crate::meta::GitRepo;
+
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path = tmp_dir.path().join("dir");
+        create_dir(&path).expect("failed to create dir");
+        let meta = Meta::from_path(&path, false).expect("failed to get meta");
+        let repo = |name: &str, dirty: Option<bool>| {
+            let mut meta = meta.clone();
+            meta.name.name = name.to_string();
+            meta.git_repo = dirty.map(|dirty| GitRepo {
+                branch: "main".to_string(),
+                dirty,
+            });
+            meta
+        };
+        let mut metas = [
+            repo("plain", None),
+            repo("clean", Some(false)),
+            repo("dirty", Some(true)),
+        ];
+
+        let mut flags = Flags::default();
+        flags.sorting.column = SortColumn::GitRepo;
+        let sorters = assemble_sorters(&flags);
+        metas.sort_by(|a, b| by_meta(&sorters, a, b));
+        let names: Vec<&str> = metas.iter().map(|meta| meta.name.name.as_str()).collect();
+        assert_eq!(vec!["dirty", "clean", "plain"], names);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sort_assemble_sorters_by_inode_and_links() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+
+        let path_a = tmp_dir.path().join("a");
+        File::create(&path_a).expect("failed to create file");
+        let path_b = tmp_dir.path().join("b");
+        File::create(&path_b).expect("failed to create file");
+        std::fs::hard_link(&path_b, tmp_dir.path().join("c")).expect("failed to create link");
+        let meta_a = Meta::from_path(&path_a, false).expect("failed to get meta");
+        let meta_b = Meta::from_path(&path_b, false).expect("failed to get meta");
+        let meta_c = Meta::from_path(&tmp_dir.path().join("c"), false).expect("failed to get meta");
+
+        let mut flags = Flags::default();
+        flags.sorting.column = SortColumn::INode;
+        let sorters = assemble_sorters(&flags);
+        assert_eq!(
+            meta_a.inode.index().cmp(&meta_b.inode.index()),
+            by_meta(&sorters, &meta_a, &meta_b)
+        );
+        // The hard links to the same file are sorted by name
+        assert_eq!(Ordering::Greater, by_meta(&sorters, &meta_c, &meta_b));
+
+        flags.sorting.column = SortColumn::Links;
+        let sorters = assemble_sorters(&flags);
+        assert_eq!(Ordering::Greater, by_meta(&sorters, &meta_a, &meta_b));
+        assert_eq!(Ordering::Greater, by_meta(&sorters, &meta_c, &meta_b));
+    }
+
+    #[test]
+    fn test_sort_assemble_sorters_by_created() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+
+        let path = tmp_dir.path().join("file");
+        File::create(&path).expect("failed to create file");
+        let meta = Meta::from_path(&path, false).expect("failed to get meta");
+        let created = |name: &str, secs: Option<u64>| {
+            let mut meta = meta.clone();
+            meta.name.name = name.to_string();
+            meta.created = secs.map(|secs| {
+                crate::meta::Date::from(
+                    std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs),
+                )
+            });
+            meta
+        };
+        let mut metas = [
+            created("unknown", None),
+            created("old", Some(1_000)),
+            created("new", Some(2_000)),
+        ];
+
+        let mut flags = Flags::default();
+        flags.sorting.column = SortColumn::Created;
+        let sorters = assemble_sorters(&flags);
+        metas.sort_by(|a, b| by_meta(&sorters, a, b));
+        let names: Vec<&str> = metas.iter().map(|meta| meta.name.name.as_str()).collect();
+        assert_eq!(vec!["new", "old", "unknown"], names);
+    }
+
+    #[test]
+    fn test_sort_assemble_sorters_by_name_case() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+
+        let path = tmp_dir.path().join("file");
+        File::create(&path).expect("failed to create file");
+        let meta = Meta::from_path(&path, false).expect("failed to get meta");
+        // The names are only compared, so they do not need to exist.
+        let named = |name: &str| {
+            let mut meta = meta.clone();
+            meta.name.name = name.to_string();
+            meta
+        };
+        let (lower_a, upper_a, upper_b) = (named("a"), named("A"), named("B"));
+
+        let mut flags = Flags::default();
+        let sorter = assemble_sorters(&flags);
+        assert_eq!(by_meta(&sorter, &lower_a, &upper_b), Ordering::Less);
+        assert_eq!(by_meta(&sorter, &lower_a, &upper_a), Ordering::Equal);
+
+        flags.sorting.case = SortCase::Sensitive;
+        let sorter = assemble_sorters(&flags);
+        assert_eq!(by_meta(&sorter, &lower_a, &upper_b), Ordering::Greater);
+
+        flags.sorting.case = SortCase::Smart;
+        let sorter = assemble_sorters(&flags);
+        assert_eq!(by_meta(&sorter, &lower_a, &upper_b), Ordering::Less);
+        assert_eq!(by_meta(&sorter, &lower_a, &upper_a), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_sort_assemble_sorters_by_name_collated() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+
+        let path = tmp_dir.path().join("file");
+        File::create(&path).expect("failed to create file");
+        let meta = Meta::from_path(&path, false).expect("failed to get meta");
+        let named = |name: &str| {
+            let mut meta = meta.clone();
+            meta.name.name = name.to_string();
+            meta
+        };
+        let (lower_a, upper_a, upper_b) = (named("a"), named("A"), named("B"));
+
+        // The order of the letters with and without case is the same in every locale.
+        let mut flags = Flags::default();
+        flags.sorting.collation = Collation::Locale;
+        let sorter = assemble_sorters(&flags);
+        assert_eq!(by_meta(&sorter, &lower_a, &upper_b), Ordering::Less);
+        assert_eq!(by_meta(&sorter, &lower_a, &upper_a), Ordering::Equal);
+
+        flags.sorting.case = SortCase::Smart;
+        let sorter = assemble_sorters(&flags);
+        assert_eq!(by_meta(&sorter, &lower_a, &upper_b), Ordering::Less);
+        assert_ne!(by_meta(&sorter, &lower_a, &upper_a), Ordering::Equal);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_command_keys() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let metas: Vec<Meta> = ["a", "b"]
+            .iter()
+            .map(|name| {
+                let path = tmp_dir.path().join(name);
+                File::create(&path).expect("failed to create file");
+                Meta::from_path(&path, false).expect("failed to get meta")
+            })
+            .collect();
+
+        assert_eq!(
+            Ok(vec!["2".to_string(), "1".to_string()]),
+            command_keys("awk '{ print 3 - NR }'", &metas)
+        );
+        assert_eq!(
+            Err("it printed 1 keys for 2 paths".to_string()),
+            command_keys("echo key", &metas)
+        );
+        assert!(command_keys("cat; exit 1", &metas).is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_sort_assemble_sorters_dir_symlinks() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path_dir = tmp_dir.path().join("zzz");
+        create_dir(&path_dir).expect("failed to create dir");
+        let path_link = tmp_dir.path().join("yyy");
+        std::os::unix::fs::symlink(&path_dir, &path_link).expect("failed to create symlink");
+        let path_file = tmp_dir.path().join("aaa");
+        File::create(&path_file).expect("failed to create file");
+        let mut metas: Vec<Meta> = [&path_file, &path_link, &path_dir]
+            .iter()
+            .map(|path| Meta::from_path(path, false).expect("failed to get meta"))
+            .collect();
+
+        let mut flags = Flags::default();
+        flags.sorting.dir_grouping = DirGrouping::First;
+        let sorters = assemble_sorters(&flags);
+        metas.sort_by(|a, b| by_meta(&sorters, a, b));
+        let names: Vec<&str> = metas.iter().map(|meta| meta.name.name.as_str()).collect();
+        assert_eq!(vec!["yyy", "zzz", "aaa"], names);
+
+        flags.sorting.group_dir_symlinks = GroupDirSymlinks(false);
+        let sorters = assemble_sorters(&flags);
+        metas.sort_by(|a, b| by_meta(&sorters, a, b));
+        let names: Vec<&str> = metas.iter().map(|meta| meta.name.name.as_str()).collect();
+        assert_eq!(vec!["zzz", "aaa", "yyy"], names);
+    }
+
+    #[test]
+    fn test_by_keys_with_dirs_first() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let path_file = tmp_dir.path().join("file");
+        File::create(&path_file).expect("failed to create file");
+        let path_dir = tmp_dir.path().join("dir");
+        create_dir(&path_dir).expect("failed to create dir");
+        let path_other = tmp_dir.path().join("other");
+        File::create(&path_other).expect("failed to create file");
+        let mut metas: Vec<Meta> = [&path_file, &path_dir, &path_other]
+            .iter()
+            .map(|path| Meta::from_path(path, false).expect("failed to get meta"))
+            .collect();
+
+        let mut flags = Flags::default();
+        flags.sorting.dir_grouping = DirGrouping::First;
+        flags.sorting.order = SortOrder::Reverse;
+        let sorters = assemble_sorters(&flags);
+        let keys = vec!["1".to_string(), "3".to_string(), "2".to_string()];
+        by_keys(&flags, &sorters, &mut metas, keys);
+
+        let names: Vec<&str> = metas.iter().map(|meta| meta.name.name.as_str()).collect();
+        assert_eq!(vec!["dir", "other", "file"], names);
+    }
 }
@@ -0,0 +1,297 @@
+//! The statistics of the files of a directory, counted and summed by extension or by top level
+//! directory, to tell what takes the space in it.
+
+use crate::color::{Colors, Elem};
+use crate::core::ExitCode;
+use crate::flags::{Flags, SizeFlag, SortOrder};
+use crate::meta::{self, FileType, Meta, Size};
+use crate::print_error;
+
+use std::path::{Component, Path};
+use unicode_width::UnicodeWidthStr;
+
+/// The widest bar, for the group taking the most space.
+const BAR_WIDTH: usize = 30;
+
+/// How the files are grouped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grouping {
+    /// By their extension in lower case, compound as `tar.gz` when it is known, the files without
+    /// one being grouped under "(none)".
+    Extension,
+    /// By the top level directory they are in, the files of the directory itself being grouped
+    /// under ".".
+    Directory,
+}
+
+impl Grouping {
+    /// Get a `Grouping` from the value of the "by" argument of the stats command.
+    pub fn from_arg_str(value: &str) -> Self {
+        match value {
+            "directory" => Self::Directory,
+            _ => Self::Extension,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Extension => "Extension",
+            Self::Directory => "Directory",
+        }
+    }
+
+    /// The group of the file of `meta`, found at `path` relative to the walked directory.
+    fn key(self, meta: &Meta, path: &Path) -> String {
+        match self {
+            Self::Extension => match meta::name::compound_extension(&meta.name.name) {
+                Some(extension) => extension.to_lowercase(),
+                None => "(none)".to_string(),
+            },
+            Self::Directory => match path.components().next() {
+                Some(Component::Normal(dir)) if path.components().nth(1).is_some() => {
+                    dir.to_string_lossy().to_string()
+                }
+                _ => ".".to_string(),
+            },
+        }
+    }
+}
+
+/// The number of files of a group and their total size.
+#[derive(Debug, PartialEq, Eq)]
+struct Group {
+    key: String,
+    files: usize,
+    bytes: u64,
+}
+
+/// Counts the regular files found in `dir`, walked up to `depth` levels down as for the listing,
+/// and sums their sizes by [Grouping]. The groups are printed in a table, the largest first, with
+/// a bar showing their share of the largest one, followed by the total.
+pub fn stats(
+    dir: &Path,
+    depth: usize,
+    grouping: Grouping,
+    flags: &Flags,
+    colors: &Colors,
+) -> String {
+    let mut groups = collect(dir, depth, grouping, flags);
+    groups.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.key.cmp(&b.key)));
+    if flags.sorting.order == SortOrder::Reverse {
+        groups.reverse();
+    }
+
+    let size_text = |bytes: u64| {
+        let size = Size::new(bytes);
+        let separator = if flags.size == SizeFlag::Default {
+            " "
+        } else {
+            ""
+        };
+        format!(
+            "{}{}{}",
+            size.value_string(flags),
+            separator,
+            size.unit_string(flags)
+        )
+    };
+    let total = Group {
+        key: "total".to_string(),
+        files: groups.iter().map(|group| group.files).sum(),
+        bytes: groups.iter().map(|group| group.bytes).sum(),
+    };
+    let [key_width, files_width, size_width] = groups.iter().chain(Some(&total)).fold(
+        [grouping.label().width(), "Files".len(), "Size".len()],
+        |[key_width, files_width, size_width], group| {
+            [
+                key_width.max(group.key.width()),
+                files_width.max(group.files.to_string().len()),
+                size_width.max(size_text(group.bytes).width()),
+            ]
+        },
+    );
+    let largest = groups.iter().map(|group| group.bytes).max().unwrap_or(0);
+
+    let mut output = String::new();
+    let header = format!(
+        "{}{}  {:>files_width$}  {:>size_width$}",
+        grouping.label(),
+        " ".repeat(key_width - grouping.label().width()),
+        "Files",
+        "Size",
+        files_width = files_width,
+        size_width = size_width
+    );
+    output += &colors.colorize(header, &Elem::Header).to_string();
+    output.push('\n');
+
+    let row = |group: &Group, with_bar: bool| {
+        let size = Size::new(group.bytes);
+        let text = size_text(group.bytes);
+        let mut row = format!(
+            "{}{}  {:>files_width$}  {}",
+            group.key,
+            " ".repeat(key_width - group.key.width()),
+            group.files,
+            " ".repeat(size_width - text.width()),
+            files_width = files_width
+        );
+        row += &size.paint(colors, flags, text).to_string();
+        let bar = bar_length(group.bytes, largest);
+        if with_bar && bar > 0 {
+            row.push_str("  ");
+            row += &size
+                .paint(colors, flags, "\u{2588}".repeat(bar))
+                .to_string();
+        }
+        row.push('\n');
+        row
+    };
+    for group in &groups {
+        output += &row(group, true);
+    }
+    output += &row(&total, false);
+
+    output
+}
+
+/// The groups of the regular files of `dir`, in no particular order.
+fn collect(dir: &Path, depth: usize, grouping: Grouping, flags: &Flags) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    for meta in meta::walk(dir, depth, flags) {
+        let meta = match meta {
+            Ok(meta) => meta,
+            Err((path, err)) => {
+                print_error!("{}: {}.", path.display(), err);
+                ExitCode::MinorIssue.report();
+                continue;
+            }
+        };
+        if !matches!(meta.file_type, FileType::File { .. }) {
+            continue;
+        }
+        let path = meta.path.strip_prefix(dir).unwrap_or(&meta.path);
+        let key = grouping.key(&meta, path);
+        let bytes = meta.size.get_bytes();
+        match groups.iter_mut().find(|group| group.key == key) {
+            Some(group) => {
+                group.files += 1;
+                group.bytes += bytes;
+            }
+            None => groups.push(Group {
+                key,
+                files: 1,
+                bytes,
+            }),
+        }
+    }
+    groups
+}
+
+/// The length of the bar of a group of `bytes`, the `largest` group having the widest one. A group
+/// which is not empty has a bar, however small it is.
+fn bar_length(bytes: u64, largest: u64) -> usize {
+    if bytes == 0 || largest == 0 {
+        return 0;
+    }
+    let length = (bytes as f64 / largest as f64 * BAR_WIDTH as f64).round() as usize;
+    length.max(1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{bar_length, collect, stats, Group, Grouping, BAR_WIDTH};
+    use crate::app;
+    use crate::color::{self, Colors};
+    use crate::config_file::Config;
+    use crate::flags::Flags;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn flags() -> Flags {
+        let matches = app::build().get_matches_from_safe(vec!["lsd"]).unwrap();
+        Flags::configure_from(&matches, &Config::with_none()).unwrap()
+    }
+
+    #[test]
+    fn test_collect() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        fs::create_dir_all(tmp_dir.path().join("src/sub")).unwrap();
+        fs::write(tmp_dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(tmp_dir.path().join("src/sub/lib.RS"), "mod a;").unwrap();
+        fs::write(tmp_dir.path().join("Makefile"), "all:").unwrap();
+        fs::write(tmp_dir.path().join("backup.tar.gz"), "gz").unwrap();
+
+        let flags = flags();
+        let mut groups = collect(tmp_dir.path(), usize::MAX, Grouping::Extension, &flags);
+        groups.sort_by(|a, b| a.key.cmp(&b.key));
+        assert_eq!(
+            vec![
+                Group {
+                    key: "(none)".to_string(),
+                    files: 1,
+                    bytes: 4
+                },
+                Group {
+                    key: "rs".to_string(),
+                    files: 2,
+                    bytes: 18
+                },
+                Group {
+                    key: "tar.gz".to_string(),
+                    files: 1,
+                    bytes: 2
+                },
+            ],
+            groups
+        );
+
+        let mut groups = collect(tmp_dir.path(), usize::MAX, Grouping::Directory, &flags);
+        groups.sort_by(|a, b| a.key.cmp(&b.key));
+        assert_eq!(
+            vec![
+                Group {
+                    key: ".".to_string(),
+                    files: 2,
+                    bytes: 6
+                },
+                Group {
+                    key: "src".to_string(),
+                    files: 2,
+                    bytes: 18
+                },
+            ],
+            groups
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        fs::write(tmp_dir.path().join("a.txt"), "12345678").unwrap();
+        fs::write(tmp_dir.path().join("b.md"), "1234").unwrap();
+
+        let output = stats(
+            tmp_dir.path(),
+            usize::MAX,
+            Grouping::Extension,
+            &flags(),
+            &Colors::new(color::Theme::NoColor),
+        );
+        let bar = |length| "\u{2588}".repeat(length);
+        let expected = [
+            "Extension  Files  Size\n".to_string(),
+            format!("txt            1   8 B  {}\n", bar(BAR_WIDTH)),
+            format!("md             1   4 B  {}\n", bar(BAR_WIDTH / 2)),
+            "total          2  12 B\n".to_string(),
+        ];
+        assert_eq!(expected.concat(), output);
+    }
+
+    #[test]
+    fn test_bar_length() {
+        assert_eq!(BAR_WIDTH, bar_length(10, 10));
+        assert_eq!(1, bar_length(1, 1000));
+        assert_eq!(0, bar_length(0, 1000));
+    }
+}
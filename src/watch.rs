@@ -0,0 +1,459 @@
+//! The waiting for the listed files to change, to list them again in the watch mode. The changes
+//! are notified by inotify on Linux, and looked for by comparing the metadata of the files
+//! elsewhere, or when inotify can not be used. The entries which changed between two listings
+//! are highlighted for a few of them.
+
+use crate::color::Elem;
+use crate::meta::{FileType, Meta};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// The delay letting a burst of changes end before the files are listed again.
+const SETTLE_DELAY: Duration = Duration::from_millis(100);
+
+/// The delay between two looks at the files, when their changes are not notified.
+const POLL_DELAY: Duration = Duration::from_secs(1);
+
+/// The number of listings an entry which changed is highlighted in, the one where the change is
+/// seen included.
+const HIGHLIGHT_LISTINGS: usize = 3;
+
+/// The modification date and the length of the watched paths and of their entries, sorted by
+/// path, or [None] for the ones which can not be read.
+type Snapshot = Vec<(PathBuf, Option<(SystemTime, u64)>)>;
+
+/// The watch of the listed files, started before they are listed so that the changes made while
+/// listing them are not missed.
+pub struct Watcher {
+    watched: Vec<PathBuf>,
+    #[cfg(target_os = "linux")]
+    inotify: Option<inotify::Inotify>,
+    /// The state of the files when the watch started, when their changes are not notified.
+    first: Option<Snapshot>,
+}
+
+impl Watcher {
+    /// Starts watching the `paths`, and the directories found in them up to `depth` levels down,
+    /// for an entry being created, removed, renamed, or having its content or attributes modified.
+    pub fn new(paths: &[PathBuf], depth: usize) -> Self {
+        let watched = watched_paths(paths, depth);
+
+        #[cfg(target_os = "linux")]
+        {
+            let inotify = inotify::Inotify::watch(&watched);
+            let first = if inotify.is_none() {
+                Some(snapshot(&watched))
+            } else {
+                None
+            };
+            Self {
+                watched,
+                inotify,
+                first,
+            }
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let first = Some(snapshot(&watched));
+            Self { watched, first }
+        }
+    }
+
+    /// Blocks until one of the watched files changes, the changes made since the watch started
+    /// included.
+    pub fn wait(self) {
+        #[cfg(target_os = "linux")]
+        {
+            if let Some(inotify) = &self.inotify {
+                if inotify.wait() {
+                    thread::sleep(SETTLE_DELAY);
+                    return;
+                }
+            }
+        }
+
+        let watched = self.watched;
+        let first = self.first.unwrap_or_else(|| snapshot(&watched));
+        loop {
+            thread::sleep(POLL_DELAY);
+            if snapshot(&watched) != first {
+                return;
+            }
+        }
+    }
+}
+
+/// How an entry changed since the previous listing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Created,
+    Modified,
+    Deleted,
+}
+
+impl Change {
+    /// The element the name of the entry is painted as.
+    pub fn elem(self) -> Elem {
+        match self {
+            Self::Created => Elem::WatchCreated,
+            Self::Modified => Elem::WatchModified,
+            Self::Deleted => Elem::WatchDeleted,
+        }
+    }
+}
+
+/// The entries listed the previous time, and the ones which changed in the last listings.
+#[derive(Default)]
+pub struct Changes {
+    /// The entries of the previous listing, without their content, or [None] before the first
+    /// one.
+    previous: Option<HashMap<PathBuf, Meta>>,
+    /// The entries which changed, with the number of listings they are still highlighted in.
+    highlighted: HashMap<PathBuf, (Change, usize)>,
+    /// The entries deleted, as they were last listed, shown until they are not highlighted any
+    /// more.
+    deleted: HashMap<PathBuf, Meta>,
+}
+
+impl Changes {
+    /// Sets the [change](Meta::change) of the listed `metas` and of their content, compared to
+    /// the previous listing. Nothing is highlighted in the first one. When `show_deleted`, the
+    /// entries deleted are put back in the content of their directory, to be struck through.
+    pub fn annotate(&mut self, metas: &mut Vec<Meta>, show_deleted: bool) {
+        for (_, listings) in self.highlighted.values_mut() {
+            *listings -= 1;
+        }
+        self.highlighted.retain(|_, (_, listings)| *listings > 0);
+        let highlighted = &self.highlighted;
+        self.deleted
+            .retain(|path, _| highlighted.contains_key(path));
+
+        let mut current = HashMap::new();
+        collect(metas, &mut current);
+
+        if let Some(previous) = &self.previous {
+            for (path, meta) in &current {
+                let change = match previous.get(path) {
+                    None => Change::Created,
+                    // A directory is modified whenever one of its entries is created or deleted.
+                    Some(old)
+                        if !matches!(meta.file_type, FileType::Directory { .. })
+                            && (old.date != meta.date
+                                || old.size.get_bytes() != meta.size.get_bytes()) =>
+                    {
+                        Change::Modified
+                    }
+                    Some(_) => continue,
+                };
+                self.highlighted
+                    .insert(path.clone(), (change, HIGHLIGHT_LISTINGS));
+                self.deleted.remove(path);
+            }
+            for (path, meta) in previous {
+                if !current.contains_key(path) {
+                    self.highlighted
+                        .insert(path.clone(), (Change::Deleted, HIGHLIGHT_LISTINGS));
+                    self.deleted.insert(path.clone(), meta.clone());
+                }
+            }
+        }
+        self.previous = Some(current);
+
+        let deleted = if show_deleted {
+            Some(&self.deleted)
+        } else {
+            None
+        };
+        mark(metas, &self.highlighted, deleted);
+    }
+}
+
+/// Puts the `metas` and their content, without its own content, in `entries`.
+fn collect(metas: &mut Vec<Meta>, entries: &mut HashMap<PathBuf, Meta>) {
+    for meta in metas {
+        let content = meta.content.take();
+        entries.insert(meta.path.clone(), meta.clone());
+        meta.content = content;
+        if let Some(content) = &mut meta.content {
+            collect(content, entries);
+        }
+    }
+}
+
+/// Sets the change of the `metas` and of their content from the `highlighted` ones, putting the
+/// `deleted` entries back in the content of their directory.
+fn mark(
+    metas: &mut Vec<Meta>,
+    highlighted: &HashMap<PathBuf, (Change, usize)>,
+    deleted: Option<&HashMap<PathBuf, Meta>>,
+) {
+    for meta in metas {
+        meta.change = highlighted.get(&meta.path).map(|(change, _)| *change);
+        if let Some(content) = &mut meta.content {
+            mark(content, highlighted, deleted);
+            if let Some(deleted) = deleted {
+                let parent = Some(meta.path.as_path());
+                content.extend(
+                    deleted
+                        .iter()
+                        .filter(|(path, _)| path.parent() == parent)
+                        .map(|(_, entry)| Meta {
+                            change: Some(Change::Deleted),
+                            ..entry.clone()
+                        }),
+                );
+            }
+        }
+    }
+}
+
+/// The `paths`, followed by the directories found in them up to `depth` levels down. The symlinks
+/// to directories are not followed.
+fn watched_paths(paths: &[PathBuf], depth: usize) -> Vec<PathBuf> {
+    let mut watched = Vec::new();
+    let mut pending: Vec<(PathBuf, usize)> = paths
+        .iter()
+        .rev()
+        .map(|path| (path.clone(), depth))
+        .collect();
+    while let Some((path, depth)) = pending.pop() {
+        if depth > 1 {
+            if let Ok(entries) = path.read_dir() {
+                for entry in entries.flatten() {
+                    if entry.file_type().map(|kind| kind.is_dir()).unwrap_or(false) {
+                        pending.push((entry.path(), depth - 1));
+                    }
+                }
+            }
+        }
+        watched.push(path);
+    }
+    watched
+}
+
+/// The [Snapshot] of the `watched` paths.
+fn snapshot(watched: &[PathBuf]) -> Snapshot {
+    let state = |path: &PathBuf| {
+        let metadata = path.symlink_metadata().ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    };
+
+    let mut snapshot = Vec::new();
+    for path in watched {
+        snapshot.push((path.clone(), state(path)));
+        if let Ok(entries) = path.read_dir() {
+            for entry in entries.flatten() {
+                let entry = entry.path();
+                let entry_state = state(&entry);
+                snapshot.push((entry, entry_state));
+            }
+        }
+    }
+    snapshot.sort();
+    snapshot
+}
+
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::PathBuf;
+
+    /// The events telling that a watched file, or an entry of a watched directory, changed.
+    const MASK: u32 = libc::IN_ATTRIB
+        | libc::IN_CREATE
+        | libc::IN_DELETE
+        | libc::IN_DELETE_SELF
+        | libc::IN_MODIFY
+        | libc::IN_MOVE_SELF
+        | libc::IN_MOVED_FROM
+        | libc::IN_MOVED_TO;
+
+    /// An inotify instance watching some paths, closed when dropped.
+    pub struct Inotify(libc::c_int);
+
+    impl Inotify {
+        /// Starts watching the `watched` paths. This returns [None] if none of them can be
+        /// watched.
+        pub fn watch(watched: &[PathBuf]) -> Option<Self> {
+            let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+            if fd < 0 {
+                return None;
+            }
+            let inotify = Self(fd);
+
+            let mut added = false;
+            for path in watched {
+                if let Ok(path) = CString::new(path.as_os_str().as_bytes()) {
+                    added |= unsafe { libc::inotify_add_watch(fd, path.as_ptr(), MASK) } >= 0;
+                }
+            }
+
+            if added {
+                Some(inotify)
+            } else {
+                None
+            }
+        }
+
+        /// Blocks until a change of the watched paths is notified, the ones made since they are
+        /// watched included. This returns false when the wait fails.
+        pub fn wait(&self) -> bool {
+            // The events are only waited for, the files being listed again whatever they are.
+            let mut buffer = [0u8; 4096];
+            let read = unsafe {
+                libc::read(
+                    self.0,
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                )
+            };
+            read > 0
+        }
+    }
+
+    impl Drop for Inotify {
+        fn drop(&mut self) {
+            unsafe { libc::close(self.0) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{snapshot, watched_paths, Change, Changes, Watcher, HIGHLIGHT_LISTINGS};
+    use crate::flags::Flags;
+    use crate::meta::{MemoryBudget, Meta};
+    use std::fs;
+    use std::path::Path;
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_watched_paths() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        fs::create_dir_all(tmp_dir.path().join("a/b")).unwrap();
+        fs::write(tmp_dir.path().join("file"), "").unwrap();
+        let paths = vec![tmp_dir.path().to_path_buf()];
+
+        assert_eq!(paths, watched_paths(&paths, 1));
+        let mut watched = watched_paths(&paths, 2);
+        watched.sort();
+        assert_eq!(
+            vec![tmp_dir.path().to_path_buf(), tmp_dir.path().join("a")],
+            watched
+        );
+        assert_eq!(3, watched_paths(&paths, usize::MAX).len());
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let paths = vec![tmp_dir.path().to_path_buf()];
+        let first = snapshot(&paths);
+        assert_eq!(first, snapshot(&paths));
+
+        fs::write(tmp_dir.path().join("file"), "").unwrap();
+        assert_ne!(first, snapshot(&paths));
+    }
+
+    #[test]
+    fn test_wait() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let file = tmp_dir.path().join("file");
+        let watcher = Watcher::new(&[tmp_dir.path().to_path_buf()], 1);
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(200));
+            fs::write(file, "changed").unwrap();
+        });
+
+        watcher.wait();
+        writer.join().unwrap();
+        assert!(tmp_dir.path().join("file").exists());
+    }
+
+    #[test]
+    fn test_wait_for_change_before_waiting() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        let watcher = Watcher::new(&[tmp_dir.path().to_path_buf()], 1);
+
+        // As when the file changes while the listing is printed.
+        fs::write(tmp_dir.path().join("file"), "changed").unwrap();
+        watcher.wait();
+    }
+
+    fn list(path: &Path) -> Vec<Meta> {
+        let mut meta = Meta::from_path(path, false).unwrap();
+        meta.content = meta
+            .recurse_into(1, &Flags::default(), None, &MemoryBudget::new(None))
+            .unwrap();
+        vec![meta]
+    }
+
+    fn changes(metas: &[Meta]) -> Vec<(String, Option<Change>)> {
+        let mut changes: Vec<_> = metas[0]
+            .content
+            .iter()
+            .flatten()
+            .map(|meta| (meta.name.name.clone(), meta.change))
+            .collect();
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+        changes
+    }
+
+    #[test]
+    fn test_annotate() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        fs::write(tmp_dir.path().join("deleted"), "").unwrap();
+        fs::write(tmp_dir.path().join("modified"), "").unwrap();
+        fs::write(tmp_dir.path().join("same"), "").unwrap();
+        let mut changes_seen = Changes::default();
+
+        let mut metas = list(tmp_dir.path());
+        changes_seen.annotate(&mut metas, true);
+        assert!(changes(&metas).iter().all(|(_, change)| change.is_none()));
+
+        fs::remove_file(tmp_dir.path().join("deleted")).unwrap();
+        fs::write(tmp_dir.path().join("modified"), "modified").unwrap();
+        fs::write(tmp_dir.path().join("created"), "").unwrap();
+        let expected = vec![
+            ("created".to_string(), Some(Change::Created)),
+            ("deleted".to_string(), Some(Change::Deleted)),
+            ("modified".to_string(), Some(Change::Modified)),
+            ("same".to_string(), None),
+        ];
+        for _ in 0..HIGHLIGHT_LISTINGS {
+            let mut metas = list(tmp_dir.path());
+            changes_seen.annotate(&mut metas, true);
+            assert_eq!(expected, changes(&metas));
+        }
+
+        let mut metas = list(tmp_dir.path());
+        changes_seen.annotate(&mut metas, true);
+        assert_eq!(
+            vec![
+                ("created".to_string(), None),
+                ("modified".to_string(), None),
+                ("same".to_string(), None),
+            ],
+            changes(&metas)
+        );
+    }
+
+    #[test]
+    fn test_annotate_without_deleted() {
+        let tmp_dir = tempdir().expect("failed to create temp dir");
+        fs::write(tmp_dir.path().join("deleted"), "").unwrap();
+        let mut changes_seen = Changes::default();
+        changes_seen.annotate(&mut list(tmp_dir.path()), false);
+
+        fs::remove_file(tmp_dir.path().join("deleted")).unwrap();
+        let mut metas = list(tmp_dir.path());
+        changes_seen.annotate(&mut metas, false);
+        assert!(changes(&metas).is_empty());
+    }
+}